@@ -14,7 +14,7 @@ use vector::{
     event::{Event, Value},
     test_util::runtime,
 };
-use vrl::prelude::*;
+use vrl::{prelude::*, VrlRuntime};
 
 criterion_group!(
     name = benches;
@@ -56,6 +56,46 @@ fn benchmark_remap(c: &mut Criterion) {
                     timezone: TimeZone::default(),
                     drop_on_error: true,
                     drop_on_abort: true,
+                    state: None,
+                    module_paths: vec![],
+                    runtime: VrlRuntime::Ast,
+                },
+                &Default::default(),
+            )
+            .unwrap(),
+        );
+
+        let event = {
+            let mut event = Event::from("augment me");
+            event.as_mut_log().insert("copy_from", "buz".to_owned());
+            event
+        };
+
+        b.iter_batched(
+            || event.clone(),
+            |event| add_fields_runner(&mut tform, event),
+            BatchSize::SmallInput,
+        );
+    });
+
+    group.bench_function("add_fields/remap_vm", |b| {
+        let mut tform: Box<dyn FunctionTransform> = Box::new(
+            Remap::new(
+                RemapConfig {
+                    source: Some(
+                        indoc! {r#".foo = "bar"
+                            .bar = "baz"
+                            .copy = string!(.copy_from)
+                        "#}
+                        .to_string(),
+                    ),
+                    file: None,
+                    timezone: TimeZone::default(),
+                    drop_on_error: true,
+                    drop_on_abort: true,
+                    state: None,
+                    module_paths: vec![],
+                    runtime: VrlRuntime::Vm,
                 },
                 &Default::default(),
             )
@@ -122,6 +162,9 @@ fn benchmark_remap(c: &mut Criterion) {
                     timezone: TimeZone::default(),
                     drop_on_error: true,
                     drop_on_abort: true,
+                    state: None,
+                    module_paths: vec![],
+                    runtime: VrlRuntime::Ast,
                 },
                 &Default::default(),
             )
@@ -196,6 +239,9 @@ fn benchmark_remap(c: &mut Criterion) {
                 timezone: TimeZone::default(),
                 drop_on_error: true,
                 drop_on_abort: true,
+                state: None,
+                module_paths: vec![],
+                runtime: VrlRuntime::Ast,
             }, &Default::default())
             .unwrap(),
         );
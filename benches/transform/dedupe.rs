@@ -42,7 +42,13 @@ fn dedupe(c: &mut Criterion) {
                 fields: Some(FieldMatchConfig::IgnoreFields(vec![String::from(
                     "message",
                 )])),
-                cache: CacheConfig { num_events: 4 },
+                cache: CacheConfig {
+                    num_events: 4,
+                    ttl_secs: None,
+                    max_bytes: None,
+                    persist: false,
+                },
+                data_dir: None,
             },
         },
         // Modification of previous where field "message" is matched.
@@ -51,7 +57,13 @@ fn dedupe(c: &mut Criterion) {
             input: fixed_stream.clone(),
             dedupe_config: DedupeConfig {
                 fields: Some(FieldMatchConfig::MatchFields(vec![String::from("message")])),
-                cache: CacheConfig { num_events: 4 },
+                cache: CacheConfig {
+                    num_events: 4,
+                    ttl_secs: None,
+                    max_bytes: None,
+                    persist: false,
+                },
+                data_dir: None,
             },
         },
         // Measurement where ignore fields do not exist in the event.
@@ -59,7 +71,12 @@ fn dedupe(c: &mut Criterion) {
             slug: "field_ignore_dne",
             input: fixed_stream.clone(),
             dedupe_config: DedupeConfig {
-                cache: CacheConfig { num_events: 4 },
+                cache: CacheConfig {
+                    num_events: 4,
+                    ttl_secs: None,
+                    max_bytes: None,
+                    persist: false,
+                },
                 fields: Some(FieldMatchConfig::IgnoreFields(vec![
                     String::from("abcde"),
                     String::from("eabcd"),
@@ -67,6 +84,7 @@ fn dedupe(c: &mut Criterion) {
                     String::from("cdeab"),
                     String::from("bcdea"),
                 ])),
+                data_dir: None,
             },
         },
         // Modification of previous where match fields do not exist in the
@@ -75,7 +93,12 @@ fn dedupe(c: &mut Criterion) {
             slug: "field_match_dne",
             input: fixed_stream.clone(),
             dedupe_config: DedupeConfig {
-                cache: CacheConfig { num_events: 4 },
+                cache: CacheConfig {
+                    num_events: 4,
+                    ttl_secs: None,
+                    max_bytes: None,
+                    persist: false,
+                },
                 fields: Some(FieldMatchConfig::MatchFields(vec![
                     String::from("abcde"),
                     String::from("eabcd"),
@@ -83,6 +106,7 @@ fn dedupe(c: &mut Criterion) {
                     String::from("cdeab"),
                     String::from("bcdea"),
                 ])),
+                data_dir: None,
             },
         },
     ] {
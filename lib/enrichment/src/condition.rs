@@ -0,0 +1,269 @@
+//! Matching of table values against search conditions, shared by the table
+//! implementations so every table interprets conditions the same way.
+use std::net::IpAddr;
+
+use vrl_core::Value;
+
+use crate::{Case, Condition};
+
+impl<'a> Condition<'a> {
+    /// The field of the table the condition applies to.
+    pub fn field(&self) -> &'a str {
+        match self {
+            Condition::Equals { field, .. }
+            | Condition::BetweenDates { field, .. }
+            | Condition::BetweenNumbers { field, .. }
+            | Condition::RangeContains { field, .. }
+            | Condition::CidrContains { field, .. }
+            | Condition::PrefixOf { field, .. } => field,
+        }
+    }
+
+    /// The kind of range index that can be used to search for this condition, if any.
+    pub fn range_kind(&self) -> Option<RangeKind> {
+        match self {
+            Condition::RangeContains { .. } => Some(RangeKind::Range),
+            Condition::CidrContains { .. } => Some(RangeKind::Cidr),
+            Condition::PrefixOf { .. } => Some(RangeKind::Prefix),
+            _ => None,
+        }
+    }
+
+    /// Does the value held by the table for the condition's field match the condition?
+    pub fn matches(&self, case: Case, value: &Value) -> bool {
+        match self {
+            Condition::Equals { value: wanted, .. } => match (case, value, wanted) {
+                (Case::Insensitive, Value::Bytes(bytes1), Value::Bytes(bytes2)) => {
+                    match (std::str::from_utf8(bytes1), std::str::from_utf8(bytes2)) {
+                        (Ok(s1), Ok(s2)) => s1.to_lowercase() == s2.to_lowercase(),
+                        (Err(_), Err(_)) => bytes1 == bytes2,
+                        _ => false,
+                    }
+                }
+                (_, value1, value2) => value1 == value2,
+            },
+            Condition::BetweenDates { from, to, .. } => match value {
+                Value::Timestamp(date) => from <= date && date <= to,
+                _ => false,
+            },
+            Condition::BetweenNumbers { from, to, .. } => {
+                as_number(value).map_or(false, |number| *from <= number && number <= *to)
+            }
+            Condition::RangeContains { value: number, .. } => {
+                parse_range(value).map_or(false, |(low, high)| low <= *number && *number <= high)
+            }
+            Condition::CidrContains { value: ip, .. } => {
+                Cidr::parse(value).map_or(false, |cidr| cidr.contains(*ip))
+            }
+            Condition::PrefixOf { value: full, .. } => match value {
+                Value::Bytes(prefix) => match case {
+                    Case::Sensitive => full.as_bytes().starts_with(prefix),
+                    Case::Insensitive => full
+                        .to_lowercase()
+                        .starts_with(&String::from_utf8_lossy(prefix).to_lowercase()),
+                },
+                _ => false,
+            },
+        }
+    }
+}
+
+/// The kinds of conditions which match a value held by the table against a range of values, which
+/// tables can build dedicated indexes for with `Table::add_range_index`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RangeKind {
+    /// The table holds numeric ranges, searched with `Condition::RangeContains`.
+    Range,
+    /// The table holds networks in CIDR notation, searched with `Condition::CidrContains`.
+    Cidr,
+    /// The table holds prefixes, searched with `Condition::PrefixOf`.
+    Prefix,
+}
+
+/// Returns the value as a number, parsing it if it is a string.
+pub fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Integer(number) => Some(*number as f64),
+        Value::Float(number) => Some(number.into_inner()),
+        Value::Bytes(bytes) => std::str::from_utf8(bytes).ok()?.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Parses a numeric range held by a table, either a single number or two numbers separated by a
+/// dash such as `8000-8099`. Both ends of the range are inclusive.
+pub fn parse_range(value: &Value) -> Option<(f64, f64)> {
+    match value {
+        Value::Bytes(bytes) => {
+            let range = std::str::from_utf8(bytes).ok()?.trim();
+            // The first character may be the sign of the lower bound.
+            match range
+                .char_indices()
+                .skip(1)
+                .find(|(_, c)| *c == '-')
+                .map(|(idx, _)| idx)
+            {
+                Some(idx) => {
+                    let low = range[..idx].trim().parse().ok()?;
+                    let high = range[idx + 1..].trim().parse().ok()?;
+                    Some((low, high))
+                }
+                None => range.parse().ok().map(|number| (number, number)),
+            }
+        }
+        value => as_number(value).map(|number| (number, number)),
+    }
+}
+
+/// A network in CIDR notation, such as `10.0.0.0/8`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Cidr {
+    v6: bool,
+    network: u128,
+    prefix_len: u8,
+}
+
+impl Cidr {
+    /// Parses a network held by a table. A single address is treated as a network containing
+    /// only that address.
+    pub fn parse(value: &Value) -> Option<Self> {
+        let cidr = match value {
+            Value::Bytes(bytes) => std::str::from_utf8(bytes).ok()?.trim(),
+            _ => return None,
+        };
+        let (ip, prefix_len) = match cidr.split_once('/') {
+            Some((ip, prefix_len)) => (ip.parse().ok()?, Some(prefix_len.parse().ok()?)),
+            None => (cidr.parse().ok()?, None),
+        };
+
+        let (v6, bits, max_len) = address_bits(ip);
+        let prefix_len = prefix_len.unwrap_or(max_len);
+        if prefix_len > max_len {
+            return None;
+        }
+
+        Some(Self {
+            v6,
+            network: mask(bits, prefix_len, max_len),
+            prefix_len,
+        })
+    }
+
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    pub fn is_ipv6(&self) -> bool {
+        self.v6
+    }
+
+    /// The network address, as an integer.
+    pub fn network(&self) -> u128 {
+        self.network
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        let (v6, bits, max_len) = address_bits(ip);
+        v6 == self.v6 && mask(bits, self.prefix_len, max_len) == self.network
+    }
+
+    /// Returns the network address of the network with the given prefix length containing the
+    /// address, as an integer. This is the `network` of every `Cidr` of that prefix length which
+    /// contains the address.
+    pub fn network_of(ip: IpAddr, prefix_len: u8) -> u128 {
+        let (_, bits, max_len) = address_bits(ip);
+        mask(bits, prefix_len.min(max_len), max_len)
+    }
+}
+
+/// Returns whether the address is IPv6, its bits, and the number of bits in the address.
+fn address_bits(ip: IpAddr) -> (bool, u128, u8) {
+    match ip {
+        IpAddr::V4(ip) => (false, u128::from(u32::from(ip)), 32),
+        IpAddr::V6(ip) => (true, u128::from(ip), 128),
+    }
+}
+
+/// Keeps only the first `prefix_len` bits of an address which is `max_len` bits long.
+fn mask(bits: u128, prefix_len: u8, max_len: u8) -> u128 {
+    if prefix_len == 0 {
+        0
+    } else {
+        let host_bits = u32::from(max_len - prefix_len);
+        bits >> host_bits << host_bits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(ip: &str) -> IpAddr {
+        ip.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_ranges() {
+        assert_eq!(Some((8000.0, 8099.0)), parse_range(&"8000-8099".into()));
+        assert_eq!(Some((-10.0, -5.0)), parse_range(&"-10 - -5".into()));
+        assert_eq!(Some((22.0, 22.0)), parse_range(&"22".into()));
+        assert_eq!(Some((22.0, 22.0)), parse_range(&22.into()));
+        assert_eq!(None, parse_range(&"ssh".into()));
+    }
+
+    #[test]
+    fn cidr_contains() {
+        let cidr = Cidr::parse(&"10.0.0.0/8".into()).unwrap();
+        assert!(cidr.contains(ip("10.1.2.3")));
+        assert!(!cidr.contains(ip("11.1.2.3")));
+        assert!(!cidr.contains(ip("::a01:203")));
+        assert_eq!(cidr.network(), Cidr::network_of(ip("10.200.0.1"), 8));
+
+        let cidr = Cidr::parse(&"2001:db8::/32".into()).unwrap();
+        assert!(cidr.contains(ip("2001:db8::1")));
+        assert!(!cidr.contains(ip("2001:db9::1")));
+
+        assert!(Cidr::parse(&"0.0.0.0/0".into())
+            .unwrap()
+            .contains(ip("1.2.3.4")));
+        assert!(Cidr::parse(&"1.2.3.4".into())
+            .unwrap()
+            .contains(ip("1.2.3.4")));
+        assert_eq!(None, Cidr::parse(&"10.0.0.0/33".into()));
+        assert_eq!(None, Cidr::parse(&"nope/8".into()));
+    }
+
+    #[test]
+    fn matches_conditions() {
+        let condition = Condition::PrefixOf {
+            field: "path",
+            value: "/API/users".to_string(),
+        };
+        assert!(condition.matches(Case::Insensitive, &"/api".into()));
+        assert!(!condition.matches(Case::Sensitive, &"/api".into()));
+        assert!(condition.matches(Case::Sensitive, &"/API/".into()));
+
+        let condition = Condition::BetweenNumbers {
+            field: "port",
+            from: 1.0,
+            to: 1024.0,
+        };
+        assert!(condition.matches(Case::Sensitive, &"443".into()));
+        assert!(condition.matches(Case::Sensitive, &1024.into()));
+        assert!(!condition.matches(Case::Sensitive, &8080.into()));
+
+        let condition = Condition::RangeContains {
+            field: "ports",
+            value: 8080.0,
+        };
+        assert!(condition.matches(Case::Sensitive, &"8000-8099".into()));
+        assert!(!condition.matches(Case::Sensitive, &"9000-9099".into()));
+
+        let condition = Condition::CidrContains {
+            field: "network",
+            value: ip("192.168.1.20"),
+        };
+        assert!(condition.matches(Case::Sensitive, &"192.168.0.0/16".into()));
+        assert!(!condition.matches(Case::Sensitive, &"192.169.0.0/16".into()));
+    }
+}
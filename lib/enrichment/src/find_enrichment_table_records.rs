@@ -166,6 +166,7 @@ mod tests {
     use crate::test_util::{
         get_table_registry, get_table_registry_with_tables, DummyEnrichmentTable,
     };
+    use crate::RangeKind;
     use chrono::{TimeZone as _, Utc};
     use shared::{btreemap, TimeZone};
     use std::sync::{Arc, Mutex};
@@ -250,4 +251,52 @@ mod tests {
         let indexes = indexes.lock().unwrap();
         assert_eq!(vec![vec!["field1".to_string()]], *indexes);
     }
+
+    #[test]
+    fn add_indexes_with_ranges() {
+        let indexes = Arc::new(Mutex::new(Vec::new()));
+        let dummy = DummyEnrichmentTable::new_with_index(indexes.clone());
+
+        let registry = get_table_registry_with_tables(vec![("dummy1".to_string(), dummy.clone())]);
+
+        let mut func = FindEnrichmentTableRecordsFn {
+            table: "dummy1".to_string(),
+            condition: btreemap! {
+                "field1" =>  expression::Literal::from("value"),
+                "field2" => expression::Container::new(expression::Variant::Object(btreemap! {
+                    "cidr_contains" => expression::Literal::from("192.168.0.1"),
+                }.into())),
+                "field3" => expression::Container::new(expression::Variant::Object(btreemap! {
+                    "from" => expression::Literal::from(1),
+                    "to" => expression::Literal::from(10),
+                }.into())),
+                // Only object literals search for ranges, anything else is matched exactly.
+                "field4" => expression::Container::new(expression::Variant::Group(
+                    expression::Group::new(expression::Container::new(expression::Variant::Object(btreemap! {
+                        "prefix_of" => expression::Literal::from("abc"),
+                    }.into())).into()),
+                )),
+            },
+            index: None,
+            select: None,
+            case_sensitive: Case::Sensitive,
+            enrichment_tables: registry.as_readonly(),
+        };
+
+        let mut compiler = state::Compiler::new();
+        compiler.set_external_context(Some(Box::new(registry)));
+
+        assert_eq!(Ok(()), func.update_state(&mut compiler));
+        assert_eq!(Some(IndexHandle(0)), func.index);
+
+        let indexes = indexes.lock().unwrap();
+        assert_eq!(
+            vec![vec!["field1".to_string(), "field4".to_string()]],
+            *indexes
+        );
+        assert_eq!(
+            vec![("field2".to_string(), RangeKind::Cidr)],
+            dummy.range_indexes()
+        );
+    }
 }
@@ -1,3 +1,4 @@
+mod condition;
 pub mod find_enrichment_table_records;
 pub mod get_enrichment_table_record;
 pub mod tables;
@@ -6,9 +7,10 @@ pub mod tables;
 mod test_util;
 mod vrl_util;
 use dyn_clone::DynClone;
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, net::IpAddr};
 use vrl_core::Value;
 
+pub use condition::{as_number, parse_range, Cidr, RangeKind};
 pub use tables::{TableRegistry, TableSearch};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -24,9 +26,18 @@ pub enum Condition<'a> {
         from: chrono::DateTime<chrono::Utc>,
         to: chrono::DateTime<chrono::Utc>,
     },
+    /// The number in the field is between from and to (inclusive).
+    BetweenNumbers { field: &'a str, from: f64, to: f64 },
+    /// The field holds a numeric range, such as `8000-8099`, which contains the value.
+    RangeContains { field: &'a str, value: f64 },
+    /// The field holds a network in CIDR notation, such as `10.0.0.0/8`, which contains the
+    /// address.
+    CidrContains { field: &'a str, value: IpAddr },
+    /// The field holds a prefix of the value.
+    PrefixOf { field: &'a str, value: String },
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Case {
     Sensitive,
     Insensitive,
@@ -65,6 +76,40 @@ pub trait Table: DynClone {
     /// # Errors
     /// Errors if the fields are not in the table.
     fn add_index(&mut self, case: Case, fields: &[&str]) -> Result<IndexHandle, String>;
+
+    /// Hints to the enrichment table that the field is going to be searched with conditions of the
+    /// given kind, so the ranges it holds can be indexed in advance. The index is used for any
+    /// search with such a condition on the field. Tables that can't index ranges ignore the hint.
+    ///
+    /// # Errors
+    /// Errors if the field is not in the table.
+    fn add_range_index(
+        &mut self,
+        _case: Case,
+        _field: &str,
+        _kind: RangeKind,
+    ) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Returns true if the source the table was loaded from has changed since it was loaded.
+    fn needs_reload(&self) -> bool {
+        false
+    }
+
+    /// Loads a fresh copy of the table from its source. The new table has the same indexes as
+    /// this one, so any `IndexHandle` returned by `add_index` remains valid for it.
+    ///
+    /// # Errors
+    /// Errors if the source could not be loaded or the table does not support reloading.
+    fn reload(&self) -> Result<Box<dyn Table + Send + Sync>, String> {
+        Err("table does not support reloading".to_string())
+    }
+
+    /// The number of rows held by the table, if known.
+    fn row_count(&self) -> Option<usize> {
+        None
+    }
 }
 
 dyn_clone::clone_trait_object!(Table);
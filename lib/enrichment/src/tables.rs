@@ -28,8 +28,15 @@
 //! `TableRegistry::as_readonly`. This returns a cheaply clonable struct that
 //! implements `vrl:EnrichmentTableSearch` through with the enrichment tables
 //! can be searched.
+//!
+//! ## Reloading
+//!
+//! Tables whose source changes whilst Vector is running can be reloaded with
+//! `TableRegistry::reload_changed`. The new data is loaded alongside the
+//! current data and then swapped into the `ArcSwap`, so searches are never
+//! blocked and the rest of the tables are left untouched.
 
-use crate::Case;
+use crate::{Case, RangeKind};
 
 use super::{Condition, IndexHandle, Table};
 use arc_swap::ArcSwap;
@@ -39,10 +46,14 @@ use std::sync::{Arc, Mutex};
 /// A hashmap of name => implementation of an enrichment table.
 type TableMap = HashMap<String, Box<dyn Table + Send + Sync>>;
 
+/// The tables once they have finished loading. Each table is held in an `Arc`
+/// so that a single table can be swapped out without copying the others.
+type ReadonlyTableMap = HashMap<String, Arc<dyn Table + Send + Sync>>;
+
 #[derive(Clone, Default)]
 pub struct TableRegistry {
     loading: Arc<Mutex<Option<TableMap>>>,
-    tables: Arc<ArcSwap<Option<TableMap>>>,
+    tables: Arc<ArcSwap<Option<ReadonlyTableMap>>>,
 }
 
 impl TableRegistry {
@@ -69,8 +80,8 @@ impl TableRegistry {
     /// Once loading is complete, the data is swapped out of `loading` and we
     /// return to a single copy of the tables.
     ///
-    /// TODO This function currently does nothing to reload the the underlying
-    /// data should it have changed in the enrichment source.
+    /// Changes to the underlying data of existing tables are picked up
+    /// separately by `reload_changed`.
     ///
     /// # Panics
     ///
@@ -83,7 +94,7 @@ impl TableRegistry {
             tables.extend(
                 existing
                     .iter()
-                    .map(|(key, value)| (key.clone(), value.clone())),
+                    .map(|(key, value)| (key.clone(), dyn_clone::clone_box(&**value))),
             );
         }
         match *loading {
@@ -102,10 +113,66 @@ impl TableRegistry {
     /// Panics if the Mutex is poisoned.
     pub fn finish_load(&self) {
         let mut tables_lock = self.loading.lock().unwrap();
-        let tables = tables_lock.take();
+        let tables = tables_lock.take().map(|tables| {
+            tables
+                .into_iter()
+                .map(|(key, table)| (key, Arc::from(table)))
+                .collect()
+        });
         self.tables.swap(Arc::new(tables));
     }
 
+    /// Reloads every table whose source has changed since it was loaded, and
+    /// atomically swaps the new tables in. Searches that are in progress
+    /// carry on with the previous data.
+    ///
+    /// Returns the name of each table that needed reloading along with
+    /// either the number of rows it now holds, if known, or the error that
+    /// prevented it from being reloaded. Tables that fail to reload keep
+    /// their previous data.
+    pub fn reload_changed(&self) -> Vec<(String, Result<Option<usize>, String>)> {
+        let reloads = match **self.tables.load() {
+            Some(ref tables) => tables
+                .iter()
+                .filter(|(_, table)| table.needs_reload())
+                .map(|(key, table)| (key.clone(), Arc::clone(table), table.reload()))
+                .collect::<Vec<_>>(),
+            None => return Vec::new(),
+        };
+
+        let mut results = Vec::with_capacity(reloads.len());
+        let mut reloaded = Vec::new();
+        for (key, previous, reload) in reloads {
+            match reload {
+                Ok(table) => {
+                    results.push((key.clone(), Ok(table.row_count())));
+                    reloaded.push((key, previous, Arc::<dyn Table + Send + Sync>::from(table)));
+                }
+                Err(error) => results.push((key, Err(error))),
+            }
+        }
+
+        if !reloaded.is_empty() {
+            self.tables.rcu(|tables| {
+                let mut tables = (**tables).clone();
+                if let Some(ref mut tables) = tables {
+                    // The tables may have been swapped out whilst reloading, in which case the
+                    // reloaded copy is already stale and is dropped.
+                    for (key, previous, table) in &reloaded {
+                        if let Some(current) = tables.get_mut(key) {
+                            if same_table(current, previous) {
+                                *current = Arc::clone(table);
+                            }
+                        }
+                    }
+                }
+                tables
+            });
+        }
+
+        results
+    }
+
     /// Return a list of the available tables that we can write to.
     ///
     /// This only works in the writing stage and will acquire a lock to retrieve
@@ -146,6 +213,31 @@ impl TableRegistry {
         }
     }
 
+    /// Adds a range index for the given field to the given Enrichment Table.
+    ///
+    /// If we are in the reading stage, this function will error.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the Mutex is poisoned.
+    pub fn add_range_index(
+        &mut self,
+        table: &str,
+        case: Case,
+        field: &str,
+        kind: RangeKind,
+    ) -> Result<(), String> {
+        let mut locked = self.loading.lock().unwrap();
+
+        match *locked {
+            None => Err("finish_load has been called".to_string()),
+            Some(ref mut tables) => match tables.get_mut(table) {
+                None => Err(format!("table '{}' not loaded", table)),
+                Some(table) => table.add_range_index(case, field, kind),
+            },
+        }
+    }
+
     /// Returns a cheaply clonable struct through that provides lock free read
     /// access to the enrichment tables.
     pub fn as_readonly(&self) -> TableSearch {
@@ -153,6 +245,12 @@ impl TableRegistry {
     }
 }
 
+/// Whether both point at the same table. Only the data pointers are compared, as the vtables for
+/// the same type can differ between codegen units.
+fn same_table(a: &Arc<dyn Table + Send + Sync>, b: &Arc<dyn Table + Send + Sync>) -> bool {
+    Arc::as_ptr(a) as *const () == Arc::as_ptr(b) as *const ()
+}
+
 impl std::fmt::Debug for TableRegistry {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         fmt_enrichment_table(f, "TableRegistry", &self.tables)
@@ -163,7 +261,7 @@ impl std::fmt::Debug for TableRegistry {
 /// `vrl::EnrichmentTableSearch` trait. Cloning this object is designed to be
 /// cheap. The underlying data will be shared by all clones.
 #[derive(Clone, Default)]
-pub struct TableSearch(Arc<ArcSwap<Option<TableMap>>>);
+pub struct TableSearch(Arc<ArcSwap<Option<ReadonlyTableMap>>>);
 
 impl TableSearch {
     /// Search the given table to find the data.
@@ -221,7 +319,7 @@ impl std::fmt::Debug for TableSearch {
 fn fmt_enrichment_table(
     f: &mut std::fmt::Formatter<'_>,
     name: &'static str,
-    tables: &Arc<ArcSwap<Option<TableMap>>>,
+    tables: &Arc<ArcSwap<Option<ReadonlyTableMap>>>,
 ) -> std::fmt::Result {
     let tables = tables.load();
     match **tables {
@@ -246,6 +344,7 @@ mod tests {
     use super::*;
     use crate::test_util::DummyEnrichmentTable;
     use shared::btreemap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
     use vrl_core::Value;
 
     #[test]
@@ -370,4 +469,147 @@ mod tests {
 
         assert_eq!(vec!["dummy1".to_string(), "dummy2".to_string()], table_ids,);
     }
+
+    /// A table that reloads itself whenever `latest` moves past its version.
+    #[derive(Clone)]
+    struct VersionedTable {
+        version: usize,
+        latest: Arc<AtomicUsize>,
+        /// A registry to load the tables of again whilst reloading, as a config reload would.
+        reload_registry: Option<TableRegistry>,
+    }
+
+    impl Table for VersionedTable {
+        fn find_table_row(
+            &self,
+            case: Case,
+            condition: &[Condition],
+            select: Option<&[String]>,
+            index: Option<IndexHandle>,
+        ) -> Result<BTreeMap<String, Value>, String> {
+            self.find_table_rows(case, condition, select, index)
+                .map(|mut rows| rows.remove(0))
+        }
+
+        fn find_table_rows(
+            &self,
+            _case: Case,
+            _condition: &[Condition],
+            _select: Option<&[String]>,
+            _index: Option<IndexHandle>,
+        ) -> Result<Vec<BTreeMap<String, Value>>, String> {
+            Ok(vec![btreemap! { "version" => self.version }])
+        }
+
+        fn add_index(&mut self, _case: Case, _fields: &[&str]) -> Result<IndexHandle, String> {
+            Ok(IndexHandle(0))
+        }
+
+        fn needs_reload(&self) -> bool {
+            self.latest.load(Ordering::Relaxed) != self.version
+        }
+
+        fn reload(&self) -> Result<Box<dyn Table + Send + Sync>, String> {
+            if let Some(registry) = &self.reload_registry {
+                registry.load(HashMap::new());
+                registry.finish_load();
+            }
+
+            match self.latest.load(Ordering::Relaxed) {
+                usize::MAX => Err("broken".to_string()),
+                version => Ok(Box::new(VersionedTable {
+                    version,
+                    latest: Arc::clone(&self.latest),
+                    reload_registry: None,
+                })),
+            }
+        }
+
+        fn row_count(&self) -> Option<usize> {
+            Some(1)
+        }
+    }
+
+    #[test]
+    fn reloads_changed_tables() {
+        let latest = Arc::new(AtomicUsize::new(0));
+        let mut tables: TableMap = HashMap::new();
+        tables.insert(
+            "versioned".to_string(),
+            Box::new(VersionedTable {
+                version: 0,
+                latest: Arc::clone(&latest),
+                reload_registry: None,
+            }),
+        );
+        tables.insert("dummy1".to_string(), Box::new(DummyEnrichmentTable::new()));
+
+        let registry = super::TableRegistry::default();
+        // Nothing can be reloaded until loading has finished.
+        assert!(registry.reload_changed().is_empty());
+
+        registry.load(tables);
+        registry.finish_load();
+        let tables_search = registry.as_readonly();
+        let find_version = || {
+            tables_search
+                .find_table_row("versioned", Case::Sensitive, &[], None, None)
+                .unwrap()
+                .remove("version")
+                .unwrap()
+        };
+
+        assert!(registry.reload_changed().is_empty());
+        assert_eq!(Value::from(0), find_version());
+
+        latest.store(1, Ordering::Relaxed);
+        assert_eq!(
+            vec![("versioned".to_string(), Ok(Some(1)))],
+            registry.reload_changed()
+        );
+        assert_eq!(Value::from(1), find_version());
+        assert!(registry.reload_changed().is_empty());
+
+        // A failed reload keeps the previous data.
+        latest.store(usize::MAX, Ordering::Relaxed);
+        assert_eq!(
+            vec![("versioned".to_string(), Err("broken".to_string()))],
+            registry.reload_changed()
+        );
+        assert_eq!(Value::from(1), find_version());
+    }
+
+    #[test]
+    fn drops_reloads_of_replaced_tables() {
+        let latest = Arc::new(AtomicUsize::new(0));
+        let registry = super::TableRegistry::default();
+        let mut tables: TableMap = HashMap::new();
+        tables.insert(
+            "versioned".to_string(),
+            Box::new(VersionedTable {
+                version: 0,
+                latest: Arc::clone(&latest),
+                reload_registry: Some(registry.clone()),
+            }),
+        );
+        registry.load(tables);
+        registry.finish_load();
+
+        // The tables are loaded again whilst reloading, so the reloaded table is already stale
+        // and the newly loaded one is kept.
+        latest.store(1, Ordering::Relaxed);
+        assert_eq!(
+            vec![("versioned".to_string(), Ok(Some(1)))],
+            registry.reload_changed()
+        );
+        assert_eq!(
+            Value::from(0),
+            registry
+                .as_readonly()
+                .find_table_row("versioned", Case::Sensitive, &[], None, None)
+                .unwrap()
+                .remove("version")
+                .unwrap()
+        );
+    }
 }
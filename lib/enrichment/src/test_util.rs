@@ -1,4 +1,4 @@
-use crate::{Case, Condition, IndexHandle, Table, TableRegistry};
+use crate::{Case, Condition, IndexHandle, RangeKind, Table, TableRegistry};
 use shared::btreemap;
 use std::{
     collections::{BTreeMap, HashMap},
@@ -10,6 +10,7 @@ use vrl_core::Value;
 pub(crate) struct DummyEnrichmentTable {
     data: BTreeMap<String, Value>,
     indexes: Arc<Mutex<Vec<Vec<String>>>>,
+    range_indexes: Arc<Mutex<Vec<(String, RangeKind)>>>,
 }
 
 impl DummyEnrichmentTable {
@@ -23,8 +24,14 @@ impl DummyEnrichmentTable {
                 "field".to_string() => Value::from("result"),
             },
             indexes,
+            range_indexes: Arc::new(Mutex::new(Vec::new())),
         }
     }
+
+    /// The fields and kinds of the range indexes added so far, by this table or its clones.
+    pub(crate) fn range_indexes(&self) -> Vec<(String, RangeKind)> {
+        self.range_indexes.lock().unwrap().clone()
+    }
 }
 
 impl Table for DummyEnrichmentTable {
@@ -53,6 +60,14 @@ impl Table for DummyEnrichmentTable {
         indexes.push(fields.iter().map(|s| (*s).to_string()).collect());
         Ok(IndexHandle(indexes.len() - 1))
     }
+
+    fn add_range_index(&mut self, _case: Case, field: &str, kind: RangeKind) -> Result<(), String> {
+        self.range_indexes
+            .lock()
+            .unwrap()
+            .push((field.to_string(), kind));
+        Ok(())
+    }
 }
 
 /// Create a table registry with dummy data
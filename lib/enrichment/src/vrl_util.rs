@@ -6,7 +6,7 @@ use vrl_core::{
     prelude::*,
 };
 
-use crate::{Case, Condition, IndexHandle, TableRegistry};
+use crate::{as_number, Case, Condition, IndexHandle, RangeKind, TableRegistry};
 
 #[derive(Debug)]
 pub enum Error {
@@ -40,35 +40,125 @@ impl DiagnosticError for Error {
     }
 }
 
+/// The keys of the condition objects that search for a range held by the table.
+const RANGE_CONTAINS: &str = "range_contains";
+const CIDR_CONTAINS: &str = "cidr_contains";
+const PREFIX_OF: &str = "prefix_of";
+
+/// Returns the kind of range condition given by the key of a condition object, if any.
+fn range_kind(key: &str) -> Option<RangeKind> {
+    match key {
+        RANGE_CONTAINS => Some(RangeKind::Range),
+        CIDR_CONTAINS => Some(RangeKind::Cidr),
+        PREFIX_OF => Some(RangeKind::Prefix),
+        _ => None,
+    }
+}
+
+/// How a condition searches the table.
+///
+/// This is decided from the expression of the condition when the program is compiled, as the
+/// indexes of the table are added then: only object literals such as `{ "from": .., "to": .. }`
+/// or `{ "cidr_contains": .. }` search for ranges, any other value is matched exactly, even if it
+/// turns out to be such an object at runtime.
+enum ConditionKind<'a> {
+    Equals,
+    Between {
+        from: &'a expression::Expr,
+        to: &'a expression::Expr,
+    },
+    Range {
+        kind: RangeKind,
+        value: &'a expression::Expr,
+    },
+}
+
+fn condition_kind(value: &expression::Expr) -> ConditionKind<'_> {
+    let map = match value {
+        expression::Expr::Container(expression::Container {
+            variant: expression::Variant::Object(map),
+        }) => map,
+        _ => return ConditionKind::Equals,
+    };
+
+    if let (Some(from), Some(to)) = (map.get("from"), map.get("to")) {
+        return ConditionKind::Between { from, to };
+    }
+
+    match map.iter().next() {
+        Some((key, value)) if map.len() == 1 => match range_kind(key) {
+            Some(kind) => ConditionKind::Range { kind, value },
+            None => ConditionKind::Equals,
+        },
+        _ => ConditionKind::Equals,
+    }
+}
+
 /// Evaluates the condition object to search the enrichment tables with.
 pub(crate) fn evaluate_condition<'a>(
     ctx: &mut Context,
     key: &'a str,
     value: &expression::Expr,
 ) -> Result<Condition<'a>> {
-    let value = value.resolve(ctx)?;
-
-    Ok(match value {
-        Value::Object(map) if map.contains_key("from") && map.contains_key("to") => {
-            Condition::BetweenDates {
-                field: key,
-                from: *map
-                    .get("from")
-                    .expect("should contain from")
-                    .as_timestamp()
-                    .ok_or("from in condition must be a timestamp")?,
-                to: *map
-                    .get("to")
-                    .expect("should contain to")
-                    .as_timestamp()
-                    .ok_or("to in condition must be a timestamp")?,
+    Ok(match condition_kind(value) {
+        ConditionKind::Equals => Condition::Equals {
+            field: key,
+            value: value.resolve(ctx)?,
+        },
+        ConditionKind::Between { from, to } => {
+            let from = from.resolve(ctx)?;
+            let to = to.resolve(ctx)?;
+            match (&from, &to) {
+                (Value::Timestamp(from), Value::Timestamp(to)) => Condition::BetweenDates {
+                    field: key,
+                    from: *from,
+                    to: *to,
+                },
+                (Value::Integer(_) | Value::Float(_), Value::Integer(_) | Value::Float(_)) => {
+                    Condition::BetweenNumbers {
+                        field: key,
+                        from: as_number(&from).expect("from should be a number"),
+                        to: as_number(&to).expect("to should be a number"),
+                    }
+                }
+                _ => {
+                    return Err(
+                        "from and to in condition must both be timestamps or numbers".into(),
+                    )
+                }
+            }
+        }
+        ConditionKind::Range { kind, value } => {
+            let value = value.resolve(ctx)?;
+            match kind {
+                RangeKind::Range => Condition::RangeContains {
+                    field: key,
+                    value: as_number(&value)
+                        .ok_or("range_contains in condition must be a number")?,
+                },
+                RangeKind::Cidr => Condition::CidrContains {
+                    field: key,
+                    value: value
+                        .as_bytes()
+                        .and_then(|ip| std::str::from_utf8(ip).ok()?.parse().ok())
+                        .ok_or("cidr_contains in condition must be an IP address")?,
+                },
+                RangeKind::Prefix => Condition::PrefixOf {
+                    field: key,
+                    value: value
+                        .as_bytes()
+                        .map(|value| String::from_utf8_lossy(value).into_owned())
+                        .ok_or("prefix_of in condition must be a string")?,
+                },
             }
         }
-        _ => Condition::Equals { field: key, value },
     })
 }
 
 /// Add an index for the given condition to the given enrichment table.
+///
+/// The fields searched for a range held by the table get a range index of their own, the fields
+/// searched for exact matches are indexed together.
 pub(crate) fn add_index(
     state: &mut state::Compiler,
     tablename: &str,
@@ -79,15 +169,16 @@ pub(crate) fn add_index(
 
     match registry {
         Some(ref mut table) => {
-            let fields = condition
-                .iter()
-                .filter_map(|(field, value)| match value {
-                    expression::Expr::Container(expression::Container {
-                        variant: expression::Variant::Object(map),
-                    }) if map.contains_key("from") && map.contains_key("to") => None,
-                    _ => Some(field.as_ref()),
-                })
-                .collect::<Vec<_>>();
+            let mut fields = Vec::new();
+            for (field, value) in condition {
+                match condition_kind(value) {
+                    ConditionKind::Equals => fields.push(field.as_ref()),
+                    ConditionKind::Between { .. } => {}
+                    ConditionKind::Range { kind, .. } => {
+                        table.add_range_index(tablename, case, field, kind)?
+                    }
+                }
+            }
             let index = table.add_index(tablename, case, &fields)?;
 
             Ok(index)
@@ -96,3 +187,103 @@ pub(crate) fn add_index(
         None => unreachable!("enrichment tables aren't loaded"),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::{btreemap, TimeZone};
+
+    fn object(map: BTreeMap<&str, expression::Literal>) -> expression::Expr {
+        let map = map
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value.into()))
+            .collect::<BTreeMap<_, _>>();
+        expression::Container::new(expression::Variant::Object(map.into())).into()
+    }
+
+    fn evaluate(value: &expression::Expr) -> Result<Condition<'static>> {
+        let tz = TimeZone::default();
+        let mut object: Value = BTreeMap::new().into();
+        let mut runtime_state = vrl_core::state::Runtime::default();
+        let mut ctx = Context::new(&mut object, &mut runtime_state, &tz);
+
+        evaluate_condition(&mut ctx, "field", value)
+    }
+
+    #[test]
+    fn evaluates_range_conditions() {
+        assert_eq!(
+            Ok(Condition::CidrContains {
+                field: "field",
+                value: "192.168.0.1".parse().unwrap(),
+            }),
+            evaluate(&object(btreemap! {
+                "cidr_contains" => expression::Literal::from("192.168.0.1"),
+            }))
+        );
+        assert_eq!(
+            Ok(Condition::RangeContains {
+                field: "field",
+                value: 5.0,
+            }),
+            evaluate(&object(btreemap! {
+                "range_contains" => expression::Literal::from(5),
+            }))
+        );
+        assert_eq!(
+            Ok(Condition::PrefixOf {
+                field: "field",
+                value: "abc".to_string(),
+            }),
+            evaluate(&object(btreemap! {
+                "prefix_of" => expression::Literal::from("abc"),
+            }))
+        );
+        assert_eq!(
+            Ok(Condition::BetweenNumbers {
+                field: "field",
+                from: 1.0,
+                to: 10.0,
+            }),
+            evaluate(&object(btreemap! {
+                "from" => expression::Literal::from(1),
+                "to" => expression::Literal::from(10),
+            }))
+        );
+    }
+
+    #[test]
+    fn evaluates_non_literal_objects_as_equals() {
+        let value = object(btreemap! {
+            "cidr_contains" => expression::Literal::from("192.168.0.1"),
+        });
+        let group =
+            expression::Container::new(expression::Variant::Group(expression::Group::new(value)))
+                .into();
+
+        assert_eq!(
+            Ok(Condition::Equals {
+                field: "field",
+                value: value!({ "cidr_contains": "192.168.0.1" }),
+            }),
+            evaluate(&group)
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_range_values() {
+        assert_eq!(
+            Err("cidr_contains in condition must be an IP address".into()),
+            evaluate(&object(btreemap! {
+                "cidr_contains" => expression::Literal::from("not an ip"),
+            }))
+        );
+        assert_eq!(
+            Err("from and to in condition must both be timestamps or numbers".into()),
+            evaluate(&object(btreemap! {
+                "from" => expression::Literal::from(1),
+                "to" => expression::Literal::from("10"),
+            }))
+        );
+    }
+}
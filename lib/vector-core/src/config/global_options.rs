@@ -38,6 +38,10 @@ pub struct GlobalOptions {
     pub timezone: TimeZone,
     #[serde(skip_serializing_if = "crate::serde::skip_serializing_if_default")]
     pub proxy: ProxyConfig,
+    /// How often enrichment tables are checked for changes to their source,
+    /// 10 seconds if not set.
+    #[serde(skip_serializing_if = "crate::serde::skip_serializing_if_default")]
+    pub enrichment_tables_reload_interval_secs: Option<u64>,
 }
 
 impl GlobalOptions {
@@ -1,17 +1,16 @@
 #![deny(missing_docs)]
 
-use super::{BatchNotifier, EventFinalizer, EventFinalizers, EventStatus};
+use super::{BatchNotifier, EventFinalizer, EventFinalizers, EventStatus, Value};
 use crate::ByteSizeOf;
 use getset::{Getters, Setters};
 use serde::{Deserialize, Serialize};
 use shared::EventDataEq;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 /// The top-level metadata structure contained by both `struct Metric`
 /// and `struct LogEvent` types.
-#[derive(
-    Clone, Debug, Default, Deserialize, Getters, PartialEq, PartialOrd, Serialize, Setters,
-)]
+#[derive(Clone, Debug, Deserialize, Getters, PartialEq, PartialOrd, Serialize, Setters)]
 pub struct EventMetadata {
     /// Used to store the datadog API from sources to sinks
     #[getset(get = "pub", set = "pub")]
@@ -19,6 +18,29 @@ pub struct EventMetadata {
     datadog_api_key: Option<Arc<str>>,
     #[serde(default, skip)]
     finalizers: EventFinalizers,
+    /// Arbitrary data traveling with the event without being part of it, such
+    /// as the context of the source, read and written by VRL programs through
+    /// metadata paths (`%foo`). This is always an object.
+    ///
+    /// It isn't part of the encoded event, so it's dropped when the event goes
+    /// through a disk buffer or is sent to another Vector instance.
+    #[getset(get = "pub")]
+    #[serde(skip, default = "empty_value")]
+    value: Value,
+}
+
+fn empty_value() -> Value {
+    Value::Map(BTreeMap::new())
+}
+
+impl Default for EventMetadata {
+    fn default() -> Self {
+        Self {
+            datadog_api_key: None,
+            finalizers: EventFinalizers::default(),
+            value: empty_value(),
+        }
+    }
 }
 
 impl ByteSizeOf for EventMetadata {
@@ -26,7 +48,7 @@ impl ByteSizeOf for EventMetadata {
         // NOTE we don't count the `str` here because it's allocated somewhere
         // else. We're just moving around the pointer, which is already captured
         // by `ByteSizeOf::size_of`.
-        self.finalizers.allocated_bytes()
+        self.finalizers.allocated_bytes() + self.value.allocated_bytes()
     }
 }
 
@@ -44,11 +66,29 @@ impl EventMetadata {
 
     /// Merge the other `EventMetadata` into this.
     /// If a Datadog API key is not set in `self`, the one from `other` will be used.
+    /// Metadata values not set in `self` are taken from `other`.
     pub fn merge(&mut self, other: Self) {
         self.finalizers.merge(other.finalizers);
         if self.datadog_api_key.is_none() {
             self.datadog_api_key = other.datadog_api_key;
         }
+        if let (Value::Map(values), Value::Map(other)) = (&mut self.value, other.value) {
+            for (key, value) in other {
+                values.entry(key).or_insert(value);
+            }
+        }
+    }
+
+    /// Get a mutable reference to the metadata value, which must be kept an
+    /// object.
+    pub(crate) fn value_mut(&mut self) -> &mut Value {
+        &mut self.value
+    }
+
+    /// Replace the metadata value with the given object.
+    pub fn with_value(mut self, value: BTreeMap<String, Value>) -> Self {
+        self.value = Value::Map(value);
+        self
     }
 
     /// Update the finalizer(s) status.
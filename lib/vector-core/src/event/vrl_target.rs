@@ -49,6 +49,20 @@ impl VrlTarget {
             }
         }
     }
+
+    fn metadata(&self) -> &EventMetadata {
+        match self {
+            VrlTarget::LogEvent(_, metadata) => metadata,
+            VrlTarget::Metric(metric) => metric.metadata(),
+        }
+    }
+
+    fn metadata_mut(&mut self) -> &mut EventMetadata {
+        match self {
+            VrlTarget::LogEvent(_, metadata) => metadata,
+            VrlTarget::Metric(metric) => metric.metadata_mut(),
+        }
+    }
 }
 
 impl vrl_core::Target for VrlTarget {
@@ -248,6 +262,52 @@ impl vrl_core::Target for VrlTarget {
             }
         }
     }
+
+    fn insert_metadata(&mut self, path: &LookupBuf, value: vrl_core::Value) -> Result<(), String> {
+        let value = Value::from(value);
+        let metadata = self.metadata_mut().value_mut();
+
+        if path.is_root() {
+            return match value {
+                Value::Map(_) => {
+                    *metadata = value;
+                    Ok(())
+                }
+                _ => Err(MetadataPathError::RootNotObject.to_string()),
+            };
+        }
+
+        metadata
+            .insert(path.clone(), value)
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+
+    fn get_metadata(&self, path: &LookupBuf) -> Result<Option<vrl_core::Value>, String> {
+        self.metadata()
+            .value()
+            .get(path)
+            .map(|val| val.map(|val| val.clone().into()))
+            .map_err(|err| err.to_string())
+    }
+
+    fn remove_metadata(
+        &mut self,
+        path: &LookupBuf,
+        compact: bool,
+    ) -> Result<Option<vrl_core::Value>, String> {
+        let metadata = self.metadata_mut().value_mut();
+
+        if path.is_root() {
+            let value = std::mem::replace(metadata, Value::Map(BTreeMap::new()));
+            return Ok(Some(value.into()));
+        }
+
+        metadata
+            .remove(path, compact)
+            .map(|val| val.map(Into::into))
+            .map_err(|err| err.to_string())
+    }
 }
 
 impl From<Event> for VrlTarget {
@@ -292,6 +352,12 @@ enum MetricPathError<'a> {
     InvalidPath { path: &'a str, expected: &'a str },
 }
 
+#[derive(Debug, Snafu)]
+enum MetadataPathError {
+    #[snafu(display("cannot set metadata root path to a value that isn't an object"))]
+    RootNotObject,
+}
+
 #[cfg(test)]
 mod test {
     use super::super::{metric::MetricTags, MetricValue};
@@ -609,6 +675,47 @@ mod test {
         }
     }
 
+    #[test]
+    fn metadata() {
+        let metadata = EventMetadata::default().with_value(btreemap! {
+            "kafka" => Value::from(btreemap! { "offset" => 10 }),
+        });
+        let metric = Metric::new_with_metadata(
+            "zub",
+            MetricKind::Absolute,
+            MetricValue::Counter { value: 1.23 },
+            metadata.clone(),
+        );
+
+        for event in vec![
+            Event::Log(LogEvent::new_with_metadata(metadata)),
+            Event::Metric(metric),
+        ] {
+            let mut target = VrlTarget::new(event);
+            let offset = LookupBuf::from_str("kafka.offset").unwrap();
+            let topic = LookupBuf::from_str("kafka.topic").unwrap();
+
+            assert_eq!(target.get_metadata(&offset), Ok(Some(10.into())));
+            assert_eq!(target.insert_metadata(&topic, "logs".into()), Ok(()));
+            assert_eq!(target.remove_metadata(&offset, true), Ok(Some(10.into())));
+            assert_eq!(
+                target.get_metadata(&LookupBuf::root()),
+                Ok(Some(
+                    btreemap! { "kafka" => btreemap! { "topic" => "logs" } }.into()
+                ))
+            );
+            assert!(target
+                .insert_metadata(&LookupBuf::root(), "not an object".into())
+                .is_err());
+
+            let event = target.into_events().next().unwrap();
+            assert_eq!(
+                event.metadata().value(),
+                &Value::from(btreemap! { "kafka" => btreemap! { "topic" => "logs" } })
+            );
+        }
+    }
+
     #[test]
     fn metric_all_fields() {
         let metric = Metric::new(
@@ -18,6 +18,10 @@ pub enum ExpandType {
 #[cfg(feature = "vrl")]
 #[derive(Debug, Default)]
 pub struct TransformContext {
+    /// The ID of the transform being built, when it is built to run in a
+    /// topology. Transforms keeping data in the data directory name their
+    /// subdirectory after it.
+    pub key: Option<String>,
     pub globals: GlobalOptions,
     pub enrichment_tables: enrichment::TableRegistry,
 }
@@ -25,13 +29,14 @@ pub struct TransformContext {
 #[cfg(not(feature = "vrl"))]
 #[derive(Debug, Default)]
 pub struct TransformContext {
+    /// The ID of the transform being built, when it is built to run in a
+    /// topology. Transforms keeping data in the data directory name their
+    /// subdirectory after it.
+    pub key: Option<String>,
     pub globals: GlobalOptions,
 }
 
 impl TransformContext {
-    // clippy allow avoids an issue where vrl is flagged off and `globals` is
-    // the sole field in the struct
-    #[allow(clippy::needless_update)]
     pub fn new_with_globals(globals: GlobalOptions) -> Self {
         Self {
             globals,
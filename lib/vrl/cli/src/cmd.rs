@@ -1,6 +1,6 @@
 #[cfg(feature = "repl")]
 use super::repl;
-use super::Error;
+use super::{test, Error};
 use shared::TimeZone;
 use std::collections::BTreeMap;
 use std::fs::File;
@@ -8,11 +8,14 @@ use std::io::{self, Read};
 use std::iter::IntoIterator;
 use std::path::PathBuf;
 use structopt::StructOpt;
-use vrl::{diagnostic::Formatter, state, Program, Runtime, Target, Value};
+use vrl::{diagnostic::Formatter, state, Program, Runtime, Target, TargetValue, Value};
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "VRL", about = "Vector Remap Language CLI")]
 pub struct Opts {
+    #[structopt(subcommand)]
+    command: Option<Command>,
+
     /// The VRL program to execute. The program ".foo = true", for example, sets the event object's
     /// `foo` field to `true`.
     #[structopt(name = "PROGRAM")]
@@ -34,6 +37,23 @@ pub struct Opts {
     /// The timezone used to parse dates.
     #[structopt(short = "tz", long)]
     timezone: Option<String>,
+
+    /// A directory the modules imported by the program are looked up in. Can be given multiple
+    /// times, in which case the directories are searched in order.
+    #[structopt(long = "module-path", parse(from_os_str), number_of_values = 1)]
+    module_paths: Vec<PathBuf>,
+}
+
+#[derive(Debug, StructOpt)]
+enum Command {
+    /// Run the VRL programs of a directory against their test cases, and report the branches of
+    /// the programs the test cases don't cover.
+    ///
+    /// The test cases of a program `foo.vrl` are the `foo.input.json` or `foo.<case>.input.json`
+    /// files next to it, holding the event object to run the program on, paired with the
+    /// expected outcome: the `foo.<case>.output.json` event object, the message of the
+    /// `foo.<case>.error` file, or an empty `foo.<case>.abort` file.
+    Test(test::Opts),
 }
 
 impl Opts {
@@ -62,13 +82,7 @@ impl Opts {
             None => read(io::stdin()),
         }?;
 
-        match input.as_str() {
-            "" => Ok(vec![Value::Object(BTreeMap::default())]),
-            _ => input
-                .lines()
-                .map(|line| Ok(serde_to_vrl(serde_json::from_str(line)?)))
-                .collect::<Result<Vec<Value>, Error>>(),
-        }
+        parse_objects(&input)
     }
 
     fn should_open_repl(&self) -> bool {
@@ -77,6 +91,10 @@ impl Opts {
 }
 
 pub fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    if let Some(Command::Test(opts)) = &opts.command {
+        return test::cmd(opts);
+    }
+
     match run(opts) {
         Ok(_) => exitcode::OK,
         Err(err) => {
@@ -102,14 +120,18 @@ fn run(opts: &Opts) -> Result<(), Error> {
     } else {
         let objects = opts.read_into_objects()?;
         let source = opts.read_program()?;
-        let program = vrl::compile(&source, &stdlib::all(), None).map_err(|diagnostics| {
-            Error::Parse(Formatter::new(&source, diagnostics).colored().to_string())
-        })?;
+        let mut state = state::Compiler::new();
+        state.set_module_paths(opts.module_paths.clone());
+
+        let program = vrl::compile_with_state(&source, &stdlib::all(), &mut state).map_err(
+            |diagnostics| Error::Parse(Formatter::new(&source, diagnostics).colored().to_string()),
+        )?;
 
-        for mut object in objects {
-            let result = execute(&mut object, &program, &tz).map(|v| {
+        for object in objects {
+            let mut target = TargetValue::new(object);
+            let result = execute(&mut target, &program, &tz).map(|v| {
                 if opts.print_object {
-                    object.to_string()
+                    target.value.to_string()
                 } else {
                     v.to_string()
                 }
@@ -147,7 +169,19 @@ fn execute(
         .map_err(Error::Runtime)
 }
 
-fn serde_to_vrl(value: serde_json::Value) -> Value {
+/// Parses the event objects of the input, JSON events being one per line. An empty input holds a
+/// single empty object.
+pub(crate) fn parse_objects(input: &str) -> Result<Vec<Value>, Error> {
+    match input {
+        "" => Ok(vec![Value::Object(BTreeMap::default())]),
+        _ => input
+            .lines()
+            .map(|line| Ok(serde_to_vrl(serde_json::from_str(line)?)))
+            .collect::<Result<Vec<Value>, Error>>(),
+    }
+}
+
+pub(crate) fn serde_to_vrl(value: serde_json::Value) -> Value {
     use serde_json::Value;
 
     match value {
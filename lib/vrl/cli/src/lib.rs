@@ -1,6 +1,9 @@
 pub mod cmd;
 #[cfg(feature = "repl")]
 mod repl;
+#[cfg(feature = "repl")]
+mod table;
+pub mod test;
 
 pub use cmd::{cmd, Opts};
 
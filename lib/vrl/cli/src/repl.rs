@@ -1,8 +1,11 @@
+use crate::cmd::parse_objects;
+use crate::table::FileTable;
+use enrichment::TableRegistry;
 use indoc::indoc;
 use lazy_static::lazy_static;
 use prettytable::{format, Cell, Row, Table};
 use regex::Regex;
-use rustyline::completion::Completer;
+use rustyline::completion::{Completer, FilenameCompleter, Pair};
 use rustyline::error::ReadlineError;
 use rustyline::highlight::{Highlighter, MatchingBracketHighlighter};
 use rustyline::hint::{Hinter, HistoryHinter};
@@ -10,7 +13,12 @@ use rustyline::validate::{self, ValidationResult, Validator};
 use rustyline::{Context, Editor, Helper};
 use shared::TimeZone;
 use std::borrow::Cow::{self, Borrowed, Owned};
-use vrl::{diagnostic::Formatter, state, value, Runtime, Target, Value};
+use std::collections::{BTreeMap, HashMap};
+use vrl::type_def::{Field, KindInfo, TypeKind};
+use vrl::{
+    diagnostic::Formatter, state, value, Function, Index, Program, Runtime, Target, TargetValue,
+    TypeDef, Value,
+};
 
 // Create a list of all possible error values for potential docs lookup
 lazy_static! {
@@ -36,14 +44,29 @@ const RESERVED_TERMS: &[&str] = &[
     "help funcs",
     "help fs",
     "help docs",
+    "events",
+    "event",
+    "load",
+    "table",
+    "type",
 ];
 
-pub(crate) fn run(mut objects: Vec<Value>, timezone: &TimeZone) {
+pub(crate) fn run(objects: Vec<Value>, timezone: &TimeZone) {
+    let mut objects: Vec<TargetValue> = objects.into_iter().map(TargetValue::new).collect();
     let mut index = 0;
     let func_docs_regex = Regex::new(r"^help\sdocs\s(\w{1,})$").unwrap();
     let error_docs_regex = Regex::new(r"^help\serror\s(\w{1,})$").unwrap();
 
+    // The arguments of the commands below can't start with `=`, so that variables with the same
+    // name as a command can still be assigned to and compared.
+    let type_regex = Regex::new(r"^type\s+([^=\s].*)$").unwrap();
+    let load_regex = Regex::new(r"^load\s+([^=\s]\S*)$").unwrap();
+    let table_regex = Regex::new(r"^table\s+(\w+)\s+(\S+)$").unwrap();
+    let event_regex = Regex::new(r"^event\s+(\d+)$").unwrap();
+
+    let mut tables = HashMap::new();
     let mut compiler_state = state::Compiler::default();
+    compiler_state.set_external_context(Some(Box::new(TableRegistry::default())));
     let mut rt = Runtime::new(state::Runtime::default());
     let mut rl = Editor::<Repl>::new();
     rl.set_helper(Some(Repl::new()));
@@ -51,6 +74,13 @@ pub(crate) fn run(mut objects: Vec<Value>, timezone: &TimeZone) {
     println!("{}", BANNER_TEXT);
 
     loop {
+        if let Some(helper) = rl.helper_mut() {
+            helper.update(
+                objects.get(index).map(|target| &target.value),
+                &compiler_state,
+            );
+        }
+
         let readline = rl.readline("$ ");
         match readline.as_deref() {
             Ok(line) if line == "exit" || line == "quit" => break,
@@ -63,19 +93,72 @@ pub(crate) fn run(mut objects: Vec<Value>, timezone: &TimeZone) {
             Ok(line) if error_docs_regex.is_match(line) => show_error_docs(line, &error_docs_regex),
             // Capture "help docs <func_name>"
             Ok(line) if func_docs_regex.is_match(line) => show_func_docs(line, &func_docs_regex),
+            Ok(line) if line == "events" => {
+                println!("event {} of {}\n", index + 1, objects.len());
+            }
+            // Capture "type <expression>"
+            Ok(line) if type_regex.is_match(line) => {
+                rl.add_history_entry(line);
+
+                // Unwrap is okay, as the regex guarantees the match
+                let source = type_regex.captures(line).unwrap().get(1).unwrap().as_str();
+
+                // The expression is only type-checked, so any variables it assigns to aren't
+                // defined for the expressions that follow.
+                let mut type_state = compiler_state.copy_without_external_context();
+                if let Some(registry) = compiler_state.get_external_context::<TableRegistry>() {
+                    type_state.set_external_context(Some(Box::new(registry.clone())));
+                }
+
+                match compile(source, &mut type_state) {
+                    Ok(program) => println!("{}\n", program_type(&program, &type_state)),
+                    Err(err) => println!("{}\n", err),
+                }
+            }
+            // Capture "load <file>"
+            Ok(line) if load_regex.is_match(line) => {
+                rl.add_history_entry(line);
+
+                let path = load_regex.captures(line).unwrap().get(1).unwrap().as_str();
+                match load_objects(path) {
+                    Ok(loaded) => {
+                        println!("loaded {} events from {}\n", loaded.len(), path);
+                        objects = loaded.into_iter().map(TargetValue::new).collect();
+                        index = 0;
+                    }
+                    Err(err) => println!("unable to load events from {}: {}\n", path, err),
+                }
+            }
+            // Capture "table <name> <file>"
+            Ok(line) if table_regex.is_match(line) => {
+                rl.add_history_entry(line);
+
+                let captures = table_regex.captures(line).unwrap();
+                let name = captures.get(1).unwrap().as_str();
+                let path = captures.get(2).unwrap().as_str();
+                match FileTable::load(path) {
+                    Ok(table) => {
+                        println!("loaded table {} with {} rows\n", name, table.len());
+                        tables.insert(name.to_owned(), table);
+                        compiler_state
+                            .set_external_context(Some(Box::new(table_registry(&tables))));
+                    }
+                    Err(err) => println!("unable to load table from {}: {}\n", path, err),
+                }
+            }
             Ok(line) => {
                 rl.add_history_entry(line);
 
                 let command = match line {
                     "next" => {
                         // allow adding one new object at a time
-                        if index < objects.len() && objects.last() != Some(&Value::Null) {
+                        if index < objects.len() && !last_is_empty(&objects) {
                             index = index.saturating_add(1);
                         }
 
                         // add new object
                         if index == objects.len() {
-                            objects.push(Value::Null)
+                            objects.push(TargetValue::new(Value::Null))
                         }
 
                         "."
@@ -84,12 +167,31 @@ pub(crate) fn run(mut objects: Vec<Value>, timezone: &TimeZone) {
                         index = index.saturating_sub(1);
 
                         // remove empty last object
-                        if objects.last() == Some(&Value::Null) {
+                        if last_is_empty(&objects) {
                             let _ = objects.pop();
                         }
 
                         "."
                     }
+                    // Capture "event <number>"
+                    line if event_regex.is_match(line) => {
+                        let number = event_regex.captures(line).unwrap().get(1).unwrap().as_str();
+                        match number.parse::<usize>() {
+                            Ok(number) if number >= 1 && number <= objects.len() => {
+                                index = number - 1;
+                            }
+                            _ => {
+                                println!(
+                                    "event {} not found, {} events are loaded\n",
+                                    number,
+                                    objects.len()
+                                );
+                                continue;
+                            }
+                        }
+
+                        "."
+                    }
                     "" => continue,
                     _ => line,
                 };
@@ -126,27 +228,133 @@ fn resolve(
     state: &mut state::Compiler,
     timezone: &TimeZone,
 ) -> Result<Value, String> {
-    let mut empty = value!({});
+    let mut empty = TargetValue::new(value!({}));
     let object = match object {
         None => &mut empty as &mut dyn Target,
         Some(object) => object,
     };
 
-    let program = match vrl::compile_with_state(program, &stdlib::all(), state) {
-        Ok(program) => program,
-        Err(diagnostics) => return Err(Formatter::new(program, diagnostics).colored().to_string()),
-    };
+    let program = compile(program, state)?;
 
     runtime
         .resolve(object, &program, timezone)
         .map_err(|err| err.to_string())
 }
 
+/// Returns whether the last object was added by `next` and not assigned to since.
+fn last_is_empty(objects: &[TargetValue]) -> bool {
+    objects.last().map(|target| &target.value) == Some(&Value::Null)
+}
+
+fn compile(source: &str, state: &mut state::Compiler) -> Result<Program, String> {
+    // The enrichment table functions index the tables they search while they're compiled, which
+    // the tables only allow while they're loading.
+    let registry = state.get_external_context::<TableRegistry>().cloned();
+    if let Some(registry) = &registry {
+        registry.load(HashMap::new());
+    }
+
+    let program = vrl::compile_with_state(source, &functions(), state);
+
+    if let Some(registry) = &registry {
+        registry.finish_load();
+    }
+
+    program.map_err(|diagnostics| Formatter::new(source, diagnostics).colored().to_string())
+}
+
+fn functions() -> Vec<Box<dyn Function>> {
+    let mut functions = stdlib::all();
+    functions.extend(enrichment::vrl_functions());
+    functions
+}
+
+fn table_registry(tables: &HashMap<String, FileTable>) -> TableRegistry {
+    let registry = TableRegistry::default();
+    registry.load(
+        tables
+            .iter()
+            .map(|(name, table)| {
+                (
+                    name.clone(),
+                    Box::new(table.clone()) as Box<dyn enrichment::Table + Send + Sync>,
+                )
+            })
+            .collect(),
+    );
+    registry.finish_load();
+    registry
+}
+
+fn load_objects(path: &str) -> Result<Vec<Value>, String> {
+    let input = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+
+    parse_objects(&input).map_err(|err| err.to_string())
+}
+
+/// Returns the type of the value the program resolves to, which is the type of its last
+/// expression.
+fn program_type(program: &Program, state: &state::Compiler) -> String {
+    let type_def = program
+        .last()
+        .map(|expression| expression.type_def(state))
+        .unwrap_or_else(|| TypeDef::new().null());
+
+    kind_info_to_string(&type_def.kind)
+}
+
+fn kind_info_to_string(kind: &KindInfo) -> String {
+    match kind {
+        KindInfo::Unknown => "any".to_owned(),
+        KindInfo::Known(kinds) if kinds.is_empty() => "never".to_owned(),
+        KindInfo::Known(kinds) => kinds
+            .iter()
+            .map(type_kind_to_string)
+            .collect::<Vec<_>>()
+            .join(" | "),
+    }
+}
+
+fn type_kind_to_string(kind: &TypeKind) -> String {
+    match kind {
+        TypeKind::Array(elements) => {
+            let elements = elements
+                .iter()
+                .map(|(index, kind)| match index {
+                    Index::Index(index) => format!("{}: {}", index, kind_info_to_string(kind)),
+                    Index::Any => format!("*: {}", kind_info_to_string(kind)),
+                })
+                .collect::<Vec<_>>();
+
+            format!("[{}]", elements.join(", "))
+        }
+        TypeKind::Object(fields) if fields.is_empty() => "{}".to_owned(),
+        TypeKind::Object(fields) => {
+            let fields = fields
+                .iter()
+                .map(|(field, kind)| match field {
+                    Field::Field(name) => format!("{:?}: {}", name, kind_info_to_string(kind)),
+                    Field::Any => format!("*: {}", kind_info_to_string(kind)),
+                })
+                .collect::<Vec<_>>();
+
+            format!("{{ {} }}", fields.join(", "))
+        }
+        kind => kind.to_kind().as_str().to_owned(),
+    }
+}
+
 struct Repl {
     highlighter: MatchingBracketHighlighter,
     history_hinter: HistoryHinter,
+    filename_completer: FilenameCompleter,
     colored_prompt: String,
     hints: Vec<&'static str>,
+    function_names: Vec<&'static str>,
+    /// The paths of the current event object.
+    paths: Vec<String>,
+    /// The variables assigned so far.
+    variables: Vec<String>,
 }
 
 impl Repl {
@@ -154,23 +362,106 @@ impl Repl {
         Self {
             highlighter: MatchingBracketHighlighter::new(),
             history_hinter: HistoryHinter {},
+            filename_completer: FilenameCompleter::new(),
             colored_prompt: "$ ".to_owned(),
             hints: initial_hints(),
+            function_names: functions().iter().map(|f| f.identifier()).collect(),
+            paths: Vec::new(),
+            variables: Vec::new(),
         }
     }
+
+    /// Updates the paths and variables offered as completions.
+    fn update(&mut self, object: Option<&Value>, state: &state::Compiler) {
+        self.paths.clear();
+        if let Some(Value::Object(fields)) = object {
+            object_paths(&mut self.paths, "", fields);
+        }
+
+        self.variables = state.variable_names().map(Into::into).collect();
+        self.variables.sort();
+    }
 }
 
 fn initial_hints() -> Vec<&'static str> {
-    stdlib::all()
+    functions()
         .into_iter()
         .map(|f| f.identifier())
         .chain(RESERVED_TERMS.iter().copied())
         .collect()
 }
 
+/// Collects the paths of the fields of the object, nested objects included, prefixed by the path
+/// of the object itself.
+fn object_paths(paths: &mut Vec<String>, prefix: &str, fields: &BTreeMap<String, Value>) {
+    for (key, value) in fields {
+        let path = if key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            format!("{}.{}", prefix, key)
+        } else {
+            format!("{}.{:?}", prefix, key)
+        };
+
+        paths.push(path.clone());
+        if let Value::Object(fields) = value {
+            object_paths(paths, &path, fields);
+        }
+    }
+}
+
 impl Helper for Repl {}
 impl Completer for Repl {
-    type Candidate = String;
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        lazy_static! {
+            static ref FILE_ARGUMENT: Regex = Regex::new(r"^(load|table\s+\w+)\s+\S*$").unwrap();
+        }
+
+        // The files of the events and enrichment tables to load
+        if FILE_ARGUMENT.is_match(&line[..pos]) {
+            return self.filename_completer.complete(line, pos, ctx);
+        }
+
+        let start = line[..pos]
+            .rfind(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.'))
+            .map_or(0, |index| index + 1);
+        let word = &line[start..pos];
+
+        if word.is_empty() {
+            return Ok((pos, Vec::new()));
+        }
+
+        let candidates: Vec<&str> = if word.starts_with('.') {
+            self.paths.iter().map(String::as_str).collect()
+        } else {
+            let commands = if start == 0 { RESERVED_TERMS } else { &[] };
+
+            self.function_names
+                .iter()
+                .copied()
+                .chain(self.variables.iter().map(String::as_str))
+                .chain(commands.iter().copied())
+                .collect()
+        };
+
+        let mut matches = candidates
+            .into_iter()
+            .filter(|candidate| candidate.starts_with(word))
+            .map(|candidate| Pair {
+                display: candidate.to_owned(),
+                replacement: candidate.to_owned(),
+            })
+            .collect::<Vec<_>>();
+        matches.sort_by(|a, b| a.display.cmp(&b.display));
+        matches.dedup_by(|a, b| a.display == b.display);
+
+        Ok((start, matches))
+    }
 }
 
 impl Hinter for Repl {
@@ -184,7 +475,7 @@ impl Hinter for Repl {
         let mut hints: Vec<String> = Vec::new();
 
         // Add all function names to the hints
-        let mut func_names = stdlib::all()
+        let mut func_names = functions()
             .iter()
             .map(|f| f.identifier().into())
             .collect::<Vec<String>>();
@@ -271,7 +562,7 @@ fn print_function_list() {
 
     let mut func_table = Table::new();
     func_table.set_format(table_format);
-    stdlib::all()
+    functions()
         .chunks(num_columns)
         .map(|funcs| {
             // Because it's possible that some chunks are only partial, e.g. have only two Some(_)
@@ -312,7 +603,7 @@ fn show_func_docs(line: &str, pattern: &Regex) {
     let matches = pattern.captures(line).unwrap();
     let func_name = matches.get(1).unwrap().as_str();
 
-    if stdlib::all().iter().any(|f| f.identifier() == func_name) {
+    if functions().iter().any(|f| f.identifier() == func_name) {
         let func_url = format!("{}/functions/#{}", DOCS_URL, func_name);
         open_url(&func_url);
     } else {
@@ -335,13 +626,20 @@ fn show_error_docs(line: &str, pattern: &Regex) {
 
 const HELP_TEXT: &str = indoc! {r#"
     VRL REPL commands:
-      help functions     Display a list of currently available VRL functions (aliases: ["help funcs", "help fs"])
-      help docs          Navigate to the VRL docs on the Vector website
-      help docs <func>   Navigate to the VRL docs for the specified function
-      help error <code>  Navigate to the docs for a specific error code
-      next               Load the next object or create a new one
-      prev               Load the previous object
-      exit               Terminate the program
+      help functions       Display a list of currently available VRL functions (aliases: ["help funcs", "help fs"])
+      help docs            Navigate to the VRL docs on the Vector website
+      help docs <func>     Navigate to the VRL docs for the specified function
+      help error <code>    Navigate to the docs for a specific error code
+      next                 Load the next object or create a new one
+      prev                 Load the previous object
+      event <number>       Load the object with the given number, starting from 1
+      events               Display the number of the current object and the number of objects
+      load <file>          Replace the objects with the JSON objects of a file, one per line
+      table <name> <file>  Load a CSV file as the enrichment table with the given name
+      type <expression>    Display the type of an expression without resolving it
+      exit                 Terminate the program
+
+    Function names, variables and paths of the current object are completed with [tab].
 "#};
 
 const BANNER_TEXT: &str = indoc! {r#"
@@ -375,9 +673,130 @@ const BANNER_TEXT: &str = indoc! {r#"
     >   help              Learn more about VRL
     >   next              Load the next object or create a new one
     >   prev              Load the previous object
+    >   type              Display the type of an expression
     >   exit              Terminate the program
     >
     > Any other value is resolved to a VRL expression.
     >
     > Try it out now by typing `.` and hitting [enter] to see the result.
 "#};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustyline::history::History;
+    use std::{collections::BTreeSet, fs};
+    use vrl::type_def::Index;
+
+    fn complete(repl: &Repl, line: &str) -> (usize, Vec<String>) {
+        let history = History::new();
+        let ctx = Context::new(&history);
+        let (start, candidates) = repl.complete(line, line.len(), &ctx).unwrap();
+
+        (
+            start,
+            candidates
+                .into_iter()
+                .map(|candidate| candidate.replacement)
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn completes_paths_functions_variables_and_commands() {
+        let mut state = state::Compiler::new();
+        compile("status_code = 200", &mut state).unwrap();
+
+        let mut repl = Repl::new();
+        // `Completer::update` has the same name.
+        Repl::update(
+            &mut repl,
+            Some(&value!({ "status": "ok", "message": { "status": 200 } })),
+            &state,
+        );
+
+        assert_eq!(
+            complete(&repl, "upcase(.st"),
+            (7, vec![".status".to_owned()])
+        );
+        assert_eq!(
+            complete(&repl, ".message.s"),
+            (0, vec![".message.status".to_owned()])
+        );
+        assert_eq!(
+            complete(&repl, "x = status_"),
+            (4, vec!["status_code".to_owned()])
+        );
+        assert_eq!(complete(&repl, "upca"), (0, vec!["upcase".to_owned()]));
+
+        // Commands are only completed at the start of the line.
+        assert_eq!(complete(&repl, "loa"), (0, vec!["load".to_owned()]));
+        assert_eq!(complete(&repl, "x = loa"), (4, Vec::new()));
+        assert_eq!(complete(&repl, "x = "), (4, Vec::new()));
+    }
+
+    #[test]
+    fn completes_file_names_of_load_and_table() {
+        let dir = std::env::temp_dir().join(format!("vrl-repl-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("events.json"), "{}").unwrap();
+
+        let repl = Repl::new();
+        let prefix = format!("{}/ev", dir.display());
+        let file = dir.join("events.json").display().to_string();
+        for line in &[
+            format!("load {}", prefix),
+            format!("table geoip {}", prefix),
+        ] {
+            let (_, candidates) = complete(&repl, line);
+            assert_eq!(candidates, vec![file.clone()], "{}", line);
+        }
+
+        // Anywhere else, file names aren't completed.
+        assert_eq!(
+            complete(&repl, &format!("x = {}", prefix)).1,
+            Vec::<String>::new()
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn quotes_paths_of_fields_with_special_characters() {
+        let object = value!({ "a": { "b c": { "d": 1 } }, "e_1": 2 });
+        let mut paths = Vec::new();
+        object_paths(&mut paths, "", object.as_object().unwrap());
+
+        assert_eq!(paths, vec![".a", r#".a."b c""#, r#".a."b c".d"#, ".e_1"]);
+    }
+
+    #[test]
+    fn formats_nested_kinds() {
+        let kind =
+            |kinds: Vec<TypeKind>| KindInfo::Known(kinds.into_iter().collect::<BTreeSet<_>>());
+
+        let object = TypeKind::Object(
+            vec![
+                (Field::from("message"), kind(vec![TypeKind::Bytes])),
+                (Field::Any, kind(vec![TypeKind::Integer, TypeKind::Null])),
+            ]
+            .into_iter()
+            .collect(),
+        );
+        let array = TypeKind::Array(
+            vec![
+                (Index::Index(0), kind(vec![object])),
+                (Index::Any, kind(vec![TypeKind::Array(BTreeMap::new())])),
+            ]
+            .into_iter()
+            .collect(),
+        );
+
+        assert_eq!(
+            kind_info_to_string(&kind(vec![array, TypeKind::Object(BTreeMap::new())])),
+            r#"[*: [], 0: { *: integer | null, "message": string }] | {}"#
+        );
+        assert_eq!(kind_info_to_string(&KindInfo::Unknown), "any");
+        assert_eq!(kind_info_to_string(&kind(Vec::new())), "never");
+    }
+}
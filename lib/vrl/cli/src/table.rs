@@ -0,0 +1,101 @@
+use enrichment::{Case, Condition, IndexHandle, Table};
+use std::collections::BTreeMap;
+use std::path::Path;
+use vrl::Value;
+
+/// An enrichment table holding the rows of a CSV file, so the enrichment table functions can be
+/// tried out in the REPL.
+///
+/// The first row of the file holds the names of the columns, and every value is read as a string.
+/// The table isn't indexed, every search scans all of its rows.
+#[derive(Clone, Debug)]
+pub(crate) struct FileTable {
+    headers: Vec<String>,
+    rows: Vec<Vec<Value>>,
+}
+
+impl FileTable {
+    pub(crate) fn load(path: impl AsRef<Path>) -> Result<Self, csv::Error> {
+        let mut reader = csv::Reader::from_path(path)?;
+
+        let headers = reader.headers()?.iter().map(Into::into).collect();
+        let rows = reader
+            .records()
+            .map(|record| Ok(record?.iter().map(Value::from).collect()))
+            .collect::<Result<_, csv::Error>>()?;
+
+        Ok(Self { headers, rows })
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    fn column(&self, field: &str) -> Option<usize> {
+        self.headers.iter().position(|header| header == field)
+    }
+
+    fn matches(&self, case: Case, condition: &[Condition<'_>], row: &[Value]) -> bool {
+        condition.iter().all(|condition| {
+            self.column(condition.field())
+                .and_then(|column| row.get(column))
+                .map_or(false, |value| condition.matches(case, value))
+        })
+    }
+
+    fn select(&self, select: Option<&[String]>, row: &[Value]) -> BTreeMap<String, Value> {
+        self.headers
+            .iter()
+            .zip(row)
+            .filter(|(header, _)| select.map_or(true, |select| select.contains(header)))
+            .map(|(header, value)| (header.clone(), value.clone()))
+            .collect()
+    }
+}
+
+impl Table for FileTable {
+    fn find_table_row<'a>(
+        &self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&[String]>,
+        index: Option<IndexHandle>,
+    ) -> Result<BTreeMap<String, Value>, String> {
+        let mut rows = self
+            .find_table_rows(case, condition, select, index)?
+            .into_iter();
+
+        match (rows.next(), rows.next()) {
+            (Some(row), None) => Ok(row),
+            (Some(_), Some(_)) => Err("more than one row found".to_string()),
+            (None, _) => Err("no rows found".to_string()),
+        }
+    }
+
+    fn find_table_rows<'a>(
+        &self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&[String]>,
+        _index: Option<IndexHandle>,
+    ) -> Result<Vec<BTreeMap<String, Value>>, String> {
+        Ok(self
+            .rows
+            .iter()
+            .filter(|row| self.matches(case, condition, row))
+            .map(|row| self.select(select, row))
+            .collect())
+    }
+
+    fn add_index(&mut self, _case: Case, fields: &[&str]) -> Result<IndexHandle, String> {
+        // Nothing is indexed, but the fields are checked like any other table would.
+        match fields.iter().find(|field| self.column(field).is_none()) {
+            Some(field) => Err(format!("field(s) '{}' missing from dataset", field)),
+            None => Ok(IndexHandle(0)),
+        }
+    }
+
+    fn row_count(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
@@ -0,0 +1,597 @@
+//! Runs the VRL programs of a directory against their test cases.
+//!
+//! The test cases of a program `foo.vrl` are the files next to it named
+//! `foo.input.json` or `foo.<case>.input.json`, each holding the event object
+//! the program runs on. Each input is paired with the expected outcome of the
+//! program, one of:
+//!
+//! - `foo.<case>.output.json`, the event object after the program ran,
+//! - `foo.<case>.error`, the message of the error the program fails with,
+//! - `foo.<case>.abort`, an empty file, for programs expected to abort.
+
+use super::cmd::serde_to_vrl;
+use super::Error;
+use shared::TimeZone;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use structopt::StructOpt;
+use vrl::{diagnostic::Formatter, state, Program, Runtime, TargetValue, Terminate, Value};
+
+const INPUT_EXTENSION: &str = ".input.json";
+
+#[derive(Debug, StructOpt)]
+pub struct Opts {
+    /// The directory to look up VRL programs and their test cases in, recursively.
+    #[structopt(name = "DIR", parse(from_os_str))]
+    dir: PathBuf,
+
+    /// The timezone used to parse dates.
+    #[structopt(short = "tz", long)]
+    timezone: Option<String>,
+
+    /// A directory the modules imported by the programs are looked up in. Can be given multiple
+    /// times, in which case the directories are searched in order.
+    #[structopt(long = "module-path", parse(from_os_str), number_of_values = 1)]
+    module_paths: Vec<PathBuf>,
+}
+
+impl Opts {
+    fn timezone(&self) -> Result<TimeZone, Error> {
+        if let Some(ref tz) = self.timezone {
+            TimeZone::parse(tz)
+                .ok_or_else(|| Error::Parse(format!("unable to parse timezone: {}", tz)))
+        } else {
+            Ok(TimeZone::default())
+        }
+    }
+}
+
+pub fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    match run(opts) {
+        Ok(true) => exitcode::OK,
+        Ok(false) => exitcode::SOFTWARE,
+        Err(err) => {
+            eprintln!("{}", err);
+            exitcode::SOFTWARE
+        }
+    }
+}
+
+/// Runs all test cases, returning whether they all passed.
+fn run(opts: &Opts) -> Result<bool, Error> {
+    let timezone = opts.timezone()?;
+
+    let mut programs = vec![];
+    find_programs(&opts.dir, &mut programs)?;
+    programs.sort();
+
+    let mut passed = 0;
+    let mut failed = 0;
+
+    for path in programs {
+        let cases = find_cases(&path)?;
+        println!("{}", path.display());
+
+        let source = fs::read_to_string(&path)?;
+        let mut state = state::Compiler::new();
+        state.set_module_paths(opts.module_paths.clone());
+
+        let program = match vrl::compile_with_state(&source, &stdlib::all(), &mut state) {
+            Ok(program) => program,
+            Err(diagnostics) => {
+                println!("  compilation failed");
+                println!("{}", Formatter::new(&source, diagnostics).colored());
+                failed += cases.len().max(1);
+                continue;
+            }
+        };
+
+        if cases.is_empty() {
+            println!("  no test cases");
+            continue;
+        }
+
+        let mut state = state::Runtime::default();
+        state.record_branches();
+        let mut runtime = Runtime::new(state);
+
+        for case in cases {
+            match case.run(&mut runtime, &program, &timezone) {
+                Ok(()) => {
+                    println!("  {} ... ok", case.name);
+                    passed += 1;
+                }
+                Err(mismatches) => {
+                    println!("  {} ... FAILED", case.name);
+                    for mismatch in mismatches {
+                        println!("    {}", mismatch);
+                    }
+                    failed += 1;
+                }
+            }
+        }
+
+        report_coverage(&source, &program, runtime.state());
+    }
+
+    println!();
+    println!("{} passed, {} failed", passed, failed);
+
+    Ok(failed == 0)
+}
+
+fn find_programs(dir: &Path, programs: &mut Vec<PathBuf>) -> Result<(), Error> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            find_programs(&path, programs)?;
+        } else if path.extension().map_or(false, |ext| ext == "vrl") {
+            programs.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Finds the test cases of the program at the given path, sorted by name.
+fn find_cases(program: &Path) -> Result<Vec<Case>, Error> {
+    let dir = program.parent().unwrap_or_else(|| Path::new("."));
+    let stem = match program.file_stem().and_then(|stem| stem.to_str()) {
+        Some(stem) => stem,
+        None => return Ok(vec![]),
+    };
+
+    let mut cases = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let file_name = match path.file_name().and_then(|name| name.to_str()) {
+            Some(file_name) => file_name,
+            None => continue,
+        };
+
+        let base = match file_name.strip_suffix(INPUT_EXTENSION) {
+            Some(base) => base,
+            None => continue,
+        };
+
+        let name = match base.strip_prefix(stem) {
+            Some("") => stem.to_owned(),
+            Some(case) => match case.strip_prefix('.') {
+                Some(case) if !case.contains('.') => case.to_owned(),
+                _ => continue,
+            },
+            None => continue,
+        };
+
+        let base = path.with_file_name(base);
+        cases.push(Case {
+            name,
+            input: path,
+            base,
+        });
+    }
+
+    cases.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(cases)
+}
+
+struct Case {
+    name: String,
+
+    /// The file holding the event object.
+    input: PathBuf,
+
+    /// The path of the expectation files, without their extension.
+    base: PathBuf,
+}
+
+enum Outcome {
+    Output(Value),
+    Error(String),
+    Abort,
+}
+
+impl Case {
+    /// Runs the program on the input of the case, returning how the outcome
+    /// differs from the expected one, if it does.
+    fn run(
+        &self,
+        runtime: &mut Runtime,
+        program: &Program,
+        timezone: &TimeZone,
+    ) -> Result<(), Vec<String>> {
+        let (mut target, expected) = match (read_json(&self.input), self.expected()) {
+            (Ok(object), Ok(expected)) => (TargetValue::new(object), expected),
+            (Err(err), _) | (_, Err(err)) => return Err(vec![err.to_string()]),
+        };
+
+        let result = runtime.resolve(&mut target, program, timezone);
+        runtime.clear();
+
+        let got = match result {
+            Ok(_) => Outcome::Output(target.value),
+            Err(Terminate::Error(error)) => Outcome::Error(error.to_string()),
+            Err(Terminate::Abort(_)) => Outcome::Abort,
+        };
+
+        let mut mismatches = vec![];
+        match (expected, got) {
+            (Outcome::Output(want), Outcome::Output(got)) => {
+                diff(&mut String::new(), &want, &got, &mut mismatches)
+            }
+            (Outcome::Error(want), Outcome::Error(got)) if want.trim() == got.trim() => {}
+            (Outcome::Abort, Outcome::Abort) => {}
+            (want, got) => mismatches.push(format!(
+                "expected {}, got {}",
+                want.describe(),
+                got.describe()
+            )),
+        }
+
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(mismatches)
+        }
+    }
+
+    fn expected(&self) -> Result<Outcome, Error> {
+        let path = |extension: &str| {
+            let mut path = self.base.clone().into_os_string();
+            path.push(extension);
+            PathBuf::from(path)
+        };
+
+        let output = path(".output.json");
+        if output.exists() {
+            return read_json(&output).map(Outcome::Output);
+        }
+
+        let error = path(".error");
+        if error.exists() {
+            return Ok(Outcome::Error(fs::read_to_string(error)?));
+        }
+
+        if path(".abort").exists() {
+            return Ok(Outcome::Abort);
+        }
+
+        Err(Error::Parse(format!(
+            "no expected outcome: one of {:?}, {:?} or {:?} must exist",
+            output,
+            error,
+            path(".abort")
+        )))
+    }
+}
+
+impl Outcome {
+    fn describe(&self) -> String {
+        match self {
+            Outcome::Output(value) => format!("output {}", value),
+            Outcome::Error(error) => format!("error {:?}", error.trim()),
+            Outcome::Abort => "abort".to_owned(),
+        }
+    }
+}
+
+fn read_json(path: &Path) -> Result<Value, Error> {
+    let json = fs::read_to_string(path)?;
+
+    Ok(serde_to_vrl(serde_json::from_str(&json)?))
+}
+
+/// Describes how the value at the given path differs from the expected one.
+fn diff(path: &mut String, want: &Value, got: &Value, mismatches: &mut Vec<String>) {
+    let len = path.len();
+    let display = |path: &str| if path.is_empty() { "." } else { path }.to_owned();
+
+    match (want, got) {
+        (Value::Object(want), Value::Object(got)) => {
+            let keys = want.keys().chain(got.keys()).collect::<BTreeSet<_>>();
+
+            for key in keys {
+                path.push('.');
+                path.push_str(key);
+
+                match (want.get(key), got.get(key)) {
+                    (Some(want), Some(got)) => diff(path, want, got, mismatches),
+                    (Some(want), None) => {
+                        mismatches.push(format!("{}: expected {}, got nothing", path, want))
+                    }
+                    (None, Some(got)) => mismatches.push(format!("{}: unexpected {}", path, got)),
+                    (None, None) => {}
+                }
+
+                path.truncate(len);
+            }
+        }
+        (Value::Array(want), Value::Array(got)) if want.len() == got.len() => {
+            for (index, (want, got)) in want.iter().zip(got.iter()).enumerate() {
+                path.push_str(&format!("[{}]", index));
+                diff(path, want, got, mismatches);
+                path.truncate(len);
+            }
+        }
+        (want, got) if want != got => {
+            mismatches.push(format!("{}: expected {}, got {}", display(path), want, got))
+        }
+        _ => {}
+    }
+}
+
+/// The branches of the `if` statements of a program taken by its test cases.
+#[derive(Debug, PartialEq)]
+struct Coverage {
+    taken: usize,
+    total: usize,
+
+    /// The branches never taken, by the line of their `if` statement.
+    not_taken: BTreeMap<usize, Vec<&'static str>>,
+}
+
+/// Finds out which branches of the program the test cases took, if it has
+/// any.
+fn coverage(source: &str, program: &Program, state: &state::Runtime) -> Option<Coverage> {
+    let branches = program.branches();
+    if branches.is_empty() {
+        return None;
+    }
+
+    let mut not_taken = BTreeMap::new();
+    for (index, span) in branches.iter().enumerate() {
+        let line = source[..span.start()].matches('\n').count() + 1;
+
+        for &(predicate, branch) in &[(true, "if"), (false, "else")] {
+            if state.branch_taken(index, predicate) != Some(true) {
+                not_taken.entry(line).or_insert_with(Vec::new).push(branch);
+            }
+        }
+    }
+
+    let total = branches.len() * 2;
+    let taken = total - not_taken.values().map(Vec::len).sum::<usize>();
+
+    Some(Coverage {
+        taken,
+        total,
+        not_taken,
+    })
+}
+
+/// Prints the branches of the `if` statements of the program that none of
+/// the test cases took.
+fn report_coverage(source: &str, program: &Program, state: &state::Runtime) {
+    let coverage = match coverage(source, program, state) {
+        Some(coverage) => coverage,
+        None => return,
+    };
+
+    println!(
+        "  coverage: {} of {} branches taken",
+        coverage.taken, coverage.total
+    );
+
+    for (line, branches) in coverage.not_taken {
+        for branch in branches {
+            println!("    line {}: `{}` branch never taken", line, branch);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vrl::value;
+
+    /// A directory of test files, removed once dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path =
+                std::env::temp_dir().join(format!("vrl-cli-test-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&path);
+            fs::create_dir_all(&path).unwrap();
+            Self(path)
+        }
+
+        fn write(&self, file: &str, contents: &str) -> PathBuf {
+            let path = self.0.join(file);
+            fs::write(&path, contents).unwrap();
+            path
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    const SOURCE: &str = indoc::indoc! {r#"
+        if .a == 1 {
+            .b = 1
+        } else {
+            .b = 2
+        }
+        if .c == 1 {
+            abort
+        }
+    "#};
+
+    fn compile(source: &str) -> Program {
+        vrl::compile(source, &stdlib::all(), None).unwrap()
+    }
+
+    fn runtime() -> Runtime {
+        let mut state = state::Runtime::default();
+        state.record_branches();
+        Runtime::new(state)
+    }
+
+    fn case(dir: &TempDir, name: &str) -> Case {
+        find_cases(&dir.0.join("foo.vrl"))
+            .unwrap()
+            .into_iter()
+            .find(|case| case.name == name)
+            .unwrap()
+    }
+
+    #[test]
+    fn find_cases_by_name() {
+        let dir = TempDir::new("find-cases");
+        let program = dir.write("foo.vrl", SOURCE);
+        for file in &[
+            "foo.input.json",
+            "foo.b.input.json",
+            "foo.a.input.json",
+            "foo.a.output.json",
+            "foo.a.b.input.json",
+            "foobar.input.json",
+            "bar.input.json",
+        ] {
+            dir.write(file, "{}");
+        }
+
+        let cases = find_cases(&program).unwrap();
+        let names = cases
+            .iter()
+            .map(|case| case.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["a", "b", "foo"]);
+
+        assert_eq!(cases[0].input, dir.0.join("foo.a.input.json"));
+        assert_eq!(cases[0].base, dir.0.join("foo.a"));
+        assert_eq!(cases[2].base, dir.0.join("foo"));
+    }
+
+    #[test]
+    fn expected_outcome() {
+        let dir = TempDir::new("expected");
+        dir.write("foo.vrl", SOURCE);
+        for case in &["output", "error", "abort", "missing"] {
+            dir.write(&format!("foo.{}.input.json", case), "{}");
+        }
+        dir.write("foo.output.output.json", r#"{ "b": 1 }"#);
+        dir.write("foo.error.error", "something failed\n");
+        dir.write("foo.abort.abort", "");
+
+        assert!(matches!(
+            case(&dir, "output").expected(),
+            Ok(Outcome::Output(value)) if value == value!({ "b": 1 })
+        ));
+        assert!(matches!(
+            case(&dir, "error").expected(),
+            Ok(Outcome::Error(error)) if error == "something failed\n"
+        ));
+        assert!(matches!(case(&dir, "abort").expected(), Ok(Outcome::Abort)));
+
+        let error = case(&dir, "missing").expected().err().unwrap();
+        assert!(error.to_string().contains("no expected outcome"));
+    }
+
+    #[test]
+    fn run_case() {
+        let dir = TempDir::new("run");
+        dir.write("foo.vrl", SOURCE);
+        dir.write("foo.ok.input.json", r#"{ "a": 1 }"#);
+        dir.write("foo.ok.output.json", r#"{ "a": 1, "b": 1 }"#);
+        dir.write("foo.wrong.input.json", r#"{ "a": 2 }"#);
+        dir.write("foo.wrong.output.json", r#"{ "a": 2, "b": 1 }"#);
+        dir.write("foo.abort.input.json", r#"{ "c": 1 }"#);
+        dir.write("foo.abort.abort", "");
+        dir.write("foo.error.input.json", r#"{ "c": 1 }"#);
+        dir.write("foo.error.error", "something failed");
+
+        let program = compile(SOURCE);
+        let mut runtime = runtime();
+        let timezone = TimeZone::default();
+        let mut run = |name| case(&dir, name).run(&mut runtime, &program, &timezone);
+
+        assert_eq!(run("ok"), Ok(()));
+        assert_eq!(run("abort"), Ok(()));
+        assert_eq!(run("wrong"), Err(vec![".b: expected 1, got 2".to_owned()]));
+        assert_eq!(
+            run("error"),
+            Err(vec![
+                r#"expected error "something failed", got abort"#.to_owned()
+            ])
+        );
+    }
+
+    #[test]
+    fn diff_values() {
+        let want = value!({
+            "a": 1,
+            "b": { "c": [1, 2] },
+            "d": [1],
+            "e": true,
+        });
+        let got = value!({
+            "a": 2,
+            "b": { "c": [1, 3] },
+            "d": [1, 2],
+            "f": null,
+        });
+
+        let mut mismatches = vec![];
+        diff(&mut String::new(), &want, &got, &mut mismatches);
+        assert_eq!(
+            mismatches,
+            vec![
+                ".a: expected 1, got 2",
+                ".b.c[1]: expected 2, got 3",
+                ".d: expected [1], got [1, 2]",
+                ".e: expected true, got nothing",
+                ".f: unexpected null",
+            ]
+        );
+
+        let mut mismatches = vec![];
+        diff(
+            &mut String::new(),
+            &value!(1),
+            &value!("1"),
+            &mut mismatches,
+        );
+        assert_eq!(mismatches, vec![r#".: expected 1, got "1""#]);
+
+        let mut mismatches = vec![];
+        diff(&mut String::new(), &want, &want, &mut mismatches);
+        assert!(mismatches.is_empty());
+    }
+
+    #[test]
+    fn coverage_across_cases() {
+        let program = compile(SOURCE);
+        let mut runtime = runtime();
+        let timezone = TimeZone::default();
+
+        for object in [value!({ "a": 1 }), value!({ "a": 2 })].iter().cloned() {
+            let mut target = TargetValue::new(object);
+            runtime.resolve(&mut target, &program, &timezone).unwrap();
+            runtime.clear();
+        }
+
+        assert_eq!(
+            coverage(SOURCE, &program, runtime.state()),
+            Some(Coverage {
+                taken: 3,
+                total: 4,
+                not_taken: vec![(6, vec!["if"])].into_iter().collect(),
+            })
+        );
+    }
+
+    #[test]
+    fn coverage_without_branches() {
+        let source = ".a = 1";
+        let program = compile(source);
+
+        assert_eq!(coverage(source, &program, runtime().state()), None);
+    }
+}
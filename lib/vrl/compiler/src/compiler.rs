@@ -1,10 +1,14 @@
 use crate::expression::*;
-use crate::{Function, Program, State, Value};
+use crate::function::{user, UserFunction};
+use crate::module;
+use crate::{Function, Program, State, TypeDef, Value};
 use chrono::{TimeZone, Utc};
 use diagnostic::DiagnosticError;
 use ordered_float::NotNan;
 use parser::ast::{self, AssignmentOp, Node};
+use std::collections::HashMap;
 use std::convert::TryFrom;
+use std::path::PathBuf;
 
 pub type Errors = Vec<Box<dyn DiagnosticError>>;
 
@@ -14,6 +18,14 @@ pub struct Compiler<'a> {
     errors: Errors,
     fallible: bool,
     abortable: bool,
+
+    /// The modules being imported, from the outermost one, used to detect
+    /// cyclic imports.
+    imports: Vec<(String, PathBuf)>,
+
+    /// The spans of the `if` statements of the program, not including the
+    /// ones of imported modules.
+    branches: Vec<diagnostic::Span>,
 }
 
 impl<'a> Compiler<'a> {
@@ -24,6 +36,8 @@ impl<'a> Compiler<'a> {
             errors: vec![],
             fallible: false,
             abortable: false,
+            imports: vec![],
+            branches: vec![],
         }
     }
 
@@ -42,9 +56,22 @@ impl<'a> Compiler<'a> {
             expressions,
             fallible: self.fallible,
             abortable: self.abortable,
+            branches: self.branches,
         })
     }
 
+    /// Compiles the root expressions of a program into a block, instead of a
+    /// program, used to compile function closures from source.
+    pub(crate) fn compile_block_source(mut self, ast: parser::Program) -> Result<Block, Errors> {
+        let exprs = self.compile_root_exprs(ast);
+
+        if !self.errors.is_empty() {
+            return Err(self.errors);
+        }
+
+        Ok(Block::new(exprs))
+    }
+
     fn compile_root_exprs(
         &mut self,
         nodes: impl IntoIterator<Item = Node<ast::RootExpr>>,
@@ -67,6 +94,14 @@ impl<'a> Compiler<'a> {
 
                         Some(expr)
                     }
+                    FunctionDefinition(node) => {
+                        self.compile_function_definition(node);
+                        None
+                    }
+                    Import(node) => {
+                        self.compile_import(node);
+                        None
+                    }
                     Error(err) => {
                         self.handle_parser_error(err);
                         None
@@ -76,6 +111,157 @@ impl<'a> Compiler<'a> {
             .collect()
     }
 
+    /// Compiles an imported module, which can only define functions and
+    /// import other modules.
+    fn compile_module(&mut self, ast: parser::Program) {
+        use ast::RootExpr::*;
+
+        for node in ast {
+            let span = node.span();
+
+            match node.into_inner() {
+                Expr(_) => {
+                    let err = module::Error::UnexpectedExpression { span };
+                    self.errors.push(Box::new(err));
+                }
+                FunctionDefinition(node) => self.compile_function_definition(node),
+                Import(node) => self.compile_import(node),
+                Error(err) => self.handle_parser_error(err),
+            }
+        }
+    }
+
+    fn compile_function_definition(&mut self, node: Node<ast::FunctionDefinition>) {
+        let ast::FunctionDefinition {
+            ident,
+            parameters,
+            block,
+        } = node.into_inner();
+
+        let (ident_span, ident) = ident.take();
+
+        if self.fns.iter().any(|f| f.identifier() == ident.as_ref())
+            || self.state.user_function(&ident).is_some()
+        {
+            let err = user::Error::Redefined { ident_span, ident };
+            self.errors.push(Box::new(err));
+            return;
+        }
+
+        let mut variables = HashMap::new();
+        let mut idents = Vec::with_capacity(parameters.len());
+        for node in parameters {
+            let (parameter_span, parameter) = node.take();
+
+            if variables.contains_key(&parameter) {
+                let err = user::Error::DuplicateParameter {
+                    parameter_span,
+                    ident: parameter,
+                };
+                self.errors.push(Box::new(err));
+                return;
+            }
+
+            let details = assignment::Details {
+                type_def: TypeDef::new().unknown().infallible(),
+                value: None,
+            };
+
+            variables.insert(parameter.clone(), details);
+            idents.push(parameter);
+        }
+
+        // The body is compiled with only the parameters in scope, and nothing
+        // known about the target, as the function can be called from
+        // anywhere.
+        let variables = self.state.swap_variables(variables);
+        let target = self.state.swap_target(None);
+        self.state.take_target_updated();
+
+        let block = self.compile_block(block);
+        let type_def = block.type_def(self.state);
+
+        let updates_target = self.state.take_target_updated();
+        self.state.swap_target(target);
+        self.state.swap_variables(variables);
+
+        let function = UserFunction::new(ident, idents, block, type_def, updates_target);
+        self.state.insert_user_function(function);
+    }
+
+    fn compile_import(&mut self, node: Node<ast::Import>) {
+        let (span, ast::Import { module }) = node.take();
+        let module = module.into_inner();
+
+        let path = match module::find(&module, self.state.module_paths()) {
+            Some(path) => path,
+            None => {
+                let paths = self.state.module_paths().to_vec();
+                let err = module::Error::NotFound {
+                    span,
+                    module,
+                    paths,
+                };
+                self.errors.push(Box::new(err));
+                return;
+            }
+        };
+
+        let canonical = module::canonical(&path);
+        if let Some(position) = self.imports.iter().position(|(_, p)| p == &canonical) {
+            let modules = self.imports[position..]
+                .iter()
+                .map(|(module, _)| module.clone())
+                .chain(std::iter::once(module))
+                .collect();
+
+            let err = module::Error::Cycle { span, modules };
+            self.errors.push(Box::new(err));
+            return;
+        }
+
+        // Modules imported before, by the program or another module, already
+        // defined their functions.
+        if !self.state.insert_module(&canonical) {
+            return;
+        }
+
+        let source = match module::read(&path) {
+            Ok(source) => source,
+            Err(error) => {
+                let err = module::Error::Read { span, path, error };
+                self.errors.push(Box::new(err));
+                return;
+            }
+        };
+
+        let mut imports = self.imports.clone();
+        imports.push((module.clone(), canonical));
+
+        // The errors in the module are reported against its own source.
+        let errors = match parser::parse(&source) {
+            Ok(ast) => {
+                let mut compiler = Compiler::new(self.fns, self.state);
+                compiler.imports = imports;
+                compiler.compile_module(ast);
+
+                self.fallible |= compiler.fallible;
+                self.abortable |= compiler.abortable;
+                compiler.errors
+            }
+            Err(err) => vec![Box::new(err) as _],
+        };
+
+        if !errors.is_empty() {
+            let err = module::Error::Compilation {
+                span,
+                module,
+                errors: module::describe(&path, &source, &errors),
+            };
+            self.errors.push(Box::new(err));
+        }
+    }
+
     fn compile_exprs(&mut self, nodes: impl IntoIterator<Item = Node<ast::Expr>>) -> Vec<Expr> {
         nodes
             .into_iter()
@@ -160,11 +346,14 @@ impl<'a> Compiler<'a> {
     }
 
     fn compile_if_statement(&mut self, node: Node<ast::IfStatement>) -> IfStatement {
-        let ast::IfStatement {
-            predicate,
-            consequent,
-            alternative,
-        } = node.into_inner();
+        let (
+            span,
+            ast::IfStatement {
+                predicate,
+                consequent,
+                alternative,
+            },
+        ) = node.take();
 
         let predicate = match self.compile_predicate(predicate) {
             Ok(v) => v,
@@ -177,10 +366,18 @@ impl<'a> Compiler<'a> {
         let consequent = self.compile_block(consequent);
         let alternative = alternative.map(|block| self.compile_block(block));
 
+        let branch = if self.imports.is_empty() {
+            self.branches.push(span);
+            Some(self.branches.len() - 1)
+        } else {
+            None
+        };
+
         IfStatement {
             predicate,
             consequent,
             alternative,
+            branch,
         }
     }
 
@@ -308,6 +505,7 @@ impl<'a> Compiler<'a> {
 
         match node.into_inner() {
             External => Target::External,
+            Metadata => Target::Metadata,
             Internal(ident) => {
                 let variable = self.compile_variable(Node::new(span, ident));
                 Target::Internal(variable)
@@ -329,6 +527,7 @@ impl<'a> Compiler<'a> {
             ident,
             abort_on_error,
             arguments,
+            closure,
         } = node.into_inner();
 
         let arguments = arguments
@@ -336,22 +535,60 @@ impl<'a> Compiler<'a> {
             .map(|node| Node::new(node.span(), self.compile_function_argument(node)))
             .collect();
 
+        let (closure_variables, closure_block) = match closure {
+            Some(node) => {
+                let (span, ast::FunctionClosure { variables, block }) = node.take();
+                (Some(Node::new(span, variables)), Some(block))
+            }
+            None => (None, None),
+        };
+
         if abort_on_error {
             self.fallible = true;
         }
 
-        FunctionCall::new(
+        if let Some(function) = self.state.user_function(&ident).cloned() {
+            return FunctionCall::user_defined(
+                call_span,
+                ident,
+                abort_on_error,
+                arguments,
+                closure_variables.map(|variables| variables.span()),
+                function,
+                self.state,
+            )
+            .unwrap_or_else(|err| {
+                self.errors.push(Box::new(err));
+                FunctionCall::noop()
+            });
+        }
+
+        let builder = match function_call::Builder::new(
             call_span,
             ident,
             abort_on_error,
             arguments,
+            closure_variables,
             self.fns,
             self.state,
-        )
-        .unwrap_or_else(|err| {
-            self.errors.push(Box::new(err));
-            FunctionCall::noop()
-        })
+        ) {
+            Ok(builder) => builder,
+            Err(err) => {
+                self.errors.push(Box::new(err));
+                return FunctionCall::noop();
+            }
+        };
+
+        // The closure block is compiled with the closure variables in scope.
+        let closure_block =
+            closure_block.map(|block| Node::new(block.span(), self.compile_block(block)));
+
+        builder
+            .compile(self.state, closure_block)
+            .unwrap_or_else(|err| {
+                self.errors.push(Box::new(err));
+                FunctionCall::noop()
+            })
     }
 
     fn compile_function_argument(&mut self, node: Node<ast::FunctionArgument>) -> FunctionArgument {
@@ -1,4 +1,4 @@
-use crate::{Context, Span, State, TypeDef, Value};
+use crate::{Context, Span, State, TypeDef, Value, Vm};
 use diagnostic::{DiagnosticError, Label, Note};
 use dyn_clone::{clone_trait_object, DynClone};
 use std::fmt;
@@ -81,6 +81,16 @@ pub trait Expression: Send + Sync + fmt::Debug + DynClone {
     fn format(&self) -> Option<String> {
         None
     }
+
+    /// Compile the expression to bytecode, to be run by the [`Vm`].
+    ///
+    /// This method is executed at compile-time.
+    ///
+    /// Only the expressions of the language itself can be compiled, function
+    /// implementations are resolved through their function call.
+    fn compile_to_vm(&self, _vm: &mut Vm) -> Result<(), String> {
+        Err(format!("can't compile expression to bytecode: {:?}", self))
+    }
 }
 
 clone_trait_object!(Expression);
@@ -180,6 +190,24 @@ impl Expression for Expr {
             Abort(v) => v.type_def(state),
         }
     }
+
+    fn compile_to_vm(&self, vm: &mut Vm) -> Result<(), String> {
+        use Expr::*;
+
+        match self {
+            Literal(v) => v.compile_to_vm(vm),
+            Container(v) => v.compile_to_vm(vm),
+            IfStatement(v) => v.compile_to_vm(vm),
+            Op(v) => v.compile_to_vm(vm),
+            Assignment(v) => v.compile_to_vm(vm),
+            Query(v) => v.compile_to_vm(vm),
+            FunctionCall(v) => v.compile_to_vm(vm),
+            Variable(v) => v.compile_to_vm(vm),
+            Noop(v) => v.compile_to_vm(vm),
+            Unary(v) => v.compile_to_vm(vm),
+            Abort(v) => v.compile_to_vm(vm),
+        }
+    }
 }
 
 impl fmt::Display for Expr {
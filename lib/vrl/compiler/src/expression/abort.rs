@@ -1,4 +1,5 @@
 use crate::expression::{ExpressionError, Resolved};
+use crate::vm::{OpCode, Vm};
 use crate::{Context, Expression, Span, State, TypeDef};
 use std::fmt;
 
@@ -21,6 +22,11 @@ impl Expression for Abort {
     fn type_def(&self, _: &State) -> TypeDef {
         TypeDef::new().infallible().null()
     }
+
+    fn compile_to_vm(&self, vm: &mut Vm) -> Result<(), String> {
+        vm.emit(OpCode::Abort(self.span));
+        Ok(())
+    }
 }
 
 impl fmt::Display for Abort {
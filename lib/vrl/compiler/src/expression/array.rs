@@ -1,4 +1,5 @@
 use crate::expression::{Expr, Resolved};
+use crate::vm::{OpCode, Vm};
 use crate::{Context, Expression, State, TypeDef, Value};
 use std::{fmt, ops::Deref};
 
@@ -51,6 +52,19 @@ impl Expression for Array {
 
         TypeDef::new().array(type_defs).with_fallibility(fallible)
     }
+
+    fn compile_to_vm(&self, vm: &mut Vm) -> Result<(), String> {
+        let start = vm.position();
+
+        for expr in &self.inner {
+            expr.compile_to_vm(vm)?;
+        }
+
+        vm.emit(OpCode::CreateArray(self.inner.len()));
+        vm.fold(start);
+
+        Ok(())
+    }
 }
 
 impl fmt::Display for Array {
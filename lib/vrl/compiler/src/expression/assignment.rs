@@ -1,8 +1,9 @@
-use crate::expression::{Expr, Literal, Resolved};
+use crate::expression::{Expr, ExpressionError, Literal, Resolved};
 use crate::parser::{
     ast::{self, Ident},
     Node,
 };
+use crate::vm::{OpCode, Vm};
 use crate::{Context, Expression, Span, State, TypeDef, Value};
 use diagnostic::{DiagnosticError, Label, Note};
 use lookup::LookupBuf;
@@ -152,6 +153,10 @@ impl Expression for Assignment {
     fn type_def(&self, state: &State) -> TypeDef {
         self.variant.type_def(state)
     }
+
+    fn compile_to_vm(&self, vm: &mut Vm) -> Result<(), String> {
+        self.variant.compile_to_vm(vm)
+    }
 }
 
 impl fmt::Display for Assignment {
@@ -185,6 +190,7 @@ pub enum Target {
     Noop,
     Internal(Ident, Option<LookupBuf>),
     External(Option<LookupBuf>),
+    Metadata(LookupBuf),
 }
 
 impl Target {
@@ -238,10 +244,27 @@ impl Target {
 
                 state.update_target(details);
             }
+
+            // The type of metadata isn't tracked, as it's mostly set outside
+            // of the program.
+            Metadata(_) => {}
+        }
+    }
+
+    /// Emit the assignment of the value at the top of the stack, keeping it
+    /// on the stack.
+    fn compile_to_vm(&self, vm: &mut Vm) {
+        if !matches!(self, Target::Noop) {
+            let index = vm.add_target(self);
+            vm.emit(OpCode::SetTarget(index));
         }
     }
 
-    fn insert(&self, value: Value, ctx: &mut Context) {
+    /// Inserts the value into the target.
+    ///
+    /// This fails if the metadata of the target can't be written to, such as
+    /// when the program runs against a target that doesn't support metadata.
+    pub(crate) fn insert(&self, value: Value, ctx: &mut Context) -> Result<(), ExpressionError> {
         use Target::*;
 
         match self {
@@ -251,7 +274,10 @@ impl Target {
                 // without any path appended and return early.
                 let path = match path {
                     Some(path) => path,
-                    None => return ctx.state_mut().insert_variable(ident.clone(), value),
+                    None => {
+                        ctx.state_mut().insert_variable(ident.clone(), value);
+                        return Ok(());
+                    }
                 };
 
                 // Update existing variable using the provided path, or create a
@@ -269,7 +295,11 @@ impl Target {
                     .target_mut()
                     .insert(path.as_ref().unwrap_or(&LookupBuf::root()), value);
             }
+
+            Metadata(path) => ctx.target_mut().insert_metadata(path, value)?,
         }
+
+        Ok(())
     }
 }
 
@@ -283,6 +313,7 @@ impl fmt::Display for Target {
             Internal(ident, None) => ident.fmt(f),
             External(Some(path)) => write!(f, ".{}", path),
             External(None) => f.write_str("."),
+            Metadata(path) => write!(f, "%{}", path),
         }
     }
 }
@@ -297,6 +328,7 @@ impl fmt::Debug for Target {
             Internal(ident, _) => write!(f, "Internal({})", ident),
             External(Some(path)) => write!(f, "External({})", path),
             External(_) => f.write_str("External(.)"),
+            Metadata(path) => write!(f, "Metadata({})", path),
         }
     }
 }
@@ -320,6 +352,7 @@ impl TryFrom<ast::AssignmentTarget> for Target {
                 match target {
                     ast::QueryTarget::Internal(ident) => Internal(ident, Some(path)),
                     ast::QueryTarget::External => External(Some(path)),
+                    ast::QueryTarget::Metadata => Metadata(path),
                     _ => {
                         return Err(Error {
                             variant: ErrorVariant::InvalidTarget(span),
@@ -366,7 +399,7 @@ where
         let value = match self {
             Single { target, expr } => {
                 let value = expr.resolve(ctx)?;
-                target.insert(value.clone(), ctx);
+                target.insert(value.clone(), ctx)?;
                 value
             }
             Infallible {
@@ -376,14 +409,14 @@ where
                 default,
             } => match expr.resolve(ctx) {
                 Ok(value) => {
-                    ok.insert(value.clone(), ctx);
-                    err.insert(Value::Null, ctx);
+                    ok.insert(value.clone(), ctx)?;
+                    err.insert(Value::Null, ctx)?;
                     value
                 }
                 Err(error) => {
-                    ok.insert(default.clone(), ctx);
+                    ok.insert(default.clone(), ctx)?;
                     let value = Value::from(error.to_string());
-                    err.insert(value.clone(), ctx);
+                    err.insert(value.clone(), ctx)?;
                     value
                 }
             },
@@ -400,6 +433,44 @@ where
             Infallible { expr, .. } => expr.type_def(state).infallible(),
         }
     }
+
+    fn compile_to_vm(&self, vm: &mut Vm) -> Result<(), String> {
+        use Variant::*;
+
+        match self {
+            Single { target, expr } => {
+                expr.compile_to_vm(vm)?;
+                target.compile_to_vm(vm);
+            }
+            Infallible {
+                ok,
+                err,
+                expr,
+                default,
+            } => {
+                let handler = vm.emit_jump(OpCode::PushHandler);
+                expr.compile_to_vm(vm)?;
+                vm.emit(OpCode::PopHandler);
+
+                ok.compile_to_vm(vm);
+                vm.emit_constant(Value::Null);
+                err.compile_to_vm(vm);
+                vm.emit_pop();
+                let end_jump = vm.emit_jump(OpCode::Jump);
+
+                vm.patch_jump(handler);
+                vm.emit_constant(default.clone());
+                ok.compile_to_vm(vm);
+                vm.emit_pop();
+                vm.emit(OpCode::PushError);
+                err.compile_to_vm(vm);
+
+                vm.patch_jump(end_jump);
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl<T, U> fmt::Display for Variant<T, U>
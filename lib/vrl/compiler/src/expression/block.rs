@@ -1,5 +1,5 @@
 use crate::expression::{Expr, Resolved};
-use crate::{Context, Expression, State, TypeDef, Value};
+use crate::{Context, Expression, State, TypeDef, Value, Vm};
 use std::fmt;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -42,6 +42,22 @@ impl Expression for Block {
 
         type_def.with_fallibility(fallible)
     }
+
+    fn compile_to_vm(&self, vm: &mut Vm) -> Result<(), String> {
+        if self.inner.is_empty() {
+            vm.emit_constant(Value::Null);
+        }
+
+        for (index, expr) in self.inner.iter().enumerate() {
+            if index > 0 {
+                vm.emit_pop();
+            }
+
+            expr.compile_to_vm(vm)?;
+        }
+
+        Ok(())
+    }
 }
 
 impl fmt::Display for Block {
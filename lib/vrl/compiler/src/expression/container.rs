@@ -1,5 +1,5 @@
 use crate::expression::{Array, Block, Group, Object, Resolved, Value};
-use crate::{Context, Expression, State, TypeDef};
+use crate::{Context, Expression, State, TypeDef, Vm};
 use std::fmt;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -54,6 +54,17 @@ impl Expression for Container {
             Object(v) => v.type_def(state),
         }
     }
+
+    fn compile_to_vm(&self, vm: &mut Vm) -> Result<(), String> {
+        use Variant::*;
+
+        match &self.variant {
+            Group(v) => v.compile_to_vm(vm),
+            Block(v) => v.compile_to_vm(vm),
+            Array(v) => v.compile_to_vm(vm),
+            Object(v) => v.compile_to_vm(vm),
+        }
+    }
 }
 
 impl fmt::Display for Container {
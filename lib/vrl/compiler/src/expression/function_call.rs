@@ -1,10 +1,18 @@
-use crate::expression::{levenstein, ExpressionError, FunctionArgument, Noop};
-use crate::function::{ArgumentList, FunctionCompileContext, Parameter};
+use crate::expression::{
+    assignment, levenstein, Block, Expr, ExpressionError, FunctionArgument, Noop,
+};
+use crate::function::{
+    closure, user::UserFunctionFn, ArgumentList, FunctionClosure, FunctionCompileContext,
+    Parameter, UserFunction,
+};
 use crate::parser::{Ident, Node};
+use crate::vm::{OpCode, Vm};
 use crate::{value::Kind, Context, Expression, Function, Resolved, Span, State, TypeDef};
 
 use diagnostic::{DiagnosticError, Label, Note, Urls};
+use std::collections::HashMap;
 use std::fmt;
+use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct FunctionCall {
@@ -24,16 +32,41 @@ pub struct FunctionCall {
     arguments_dbg: Vec<String>,
 
     // used for equality check
-    ident: &'static str,
+    ident: String,
 }
 
-impl FunctionCall {
-    pub fn new(
+/// A function call with its arguments checked, waiting for its closure, if
+/// any, to be compiled.
+///
+/// While the builder is alive, the closure variables are defined in the
+/// compiler state, so that the closure block can be compiled with them in
+/// scope. Building the function call restores any variables they shadow.
+pub(crate) struct Builder<'a> {
+    abort_on_error: bool,
+    call_span: Span,
+    ident_span: Span,
+    function: &'a dyn Function,
+    list: ArgumentList,
+    maybe_fallible_arguments: bool,
+    arguments_fmt: Vec<String>,
+    arguments_dbg: Vec<String>,
+    closure: Option<ClosureBuilder>,
+}
+
+struct ClosureBuilder {
+    variables: Vec<Ident>,
+    output: Kind,
+    shadowed: Vec<(Ident, Option<assignment::Details>)>,
+}
+
+impl<'a> Builder<'a> {
+    pub(crate) fn new(
         call_span: Span,
         ident: Node<Ident>,
         abort_on_error: bool,
         arguments: Vec<Node<FunctionArgument>>,
-        funcs: &[Box<dyn Function>],
+        closure_variables: Option<Node<Vec<Node<Ident>>>>,
+        funcs: &'a [Box<dyn Function>],
         state: &mut State,
     ) -> Result<Self, Error> {
         let (ident_span, ident) = ident.take();
@@ -78,6 +111,10 @@ impl FunctionCall {
         let mut index = 0;
         let mut list = ArgumentList::default();
 
+        // The type definitions of the arguments, used to define the types of
+        // the closure variables.
+        let mut type_defs = HashMap::new();
+
         let arguments_fmt = arguments
             .iter()
             .map(|arg| arg.to_string())
@@ -116,11 +153,16 @@ impl FunctionCall {
             .ok_or_else(|| Error::UnknownKeyword {
                 keyword_span: argument.keyword_span().expect("exists"),
                 ident_span,
-                keywords: function.parameters().iter().map(|p| p.keyword).collect(),
+                keywords: function
+                    .parameters()
+                    .iter()
+                    .map(|p| p.keyword.to_owned())
+                    .collect(),
             })?;
 
             // Check if the argument is of the expected type.
-            let expr_kind = argument.type_def(state).kind();
+            let type_def = argument.type_def(state);
+            let expr_kind = type_def.kind();
             let param_kind = parameter.kind();
 
             if !param_kind.intersects(expr_kind) {
@@ -138,12 +180,13 @@ impl FunctionCall {
             }
 
             // Check if the argument is infallible.
-            if argument.type_def(state).is_fallible() {
+            if type_def.is_fallible() {
                 return Err(Error::FallibleArgument {
                     expr_span: argument.span(),
                 });
             }
 
+            type_defs.insert(parameter.keyword, type_def);
             list.insert(parameter.keyword, argument.into_inner());
         }
 
@@ -157,21 +200,110 @@ impl FunctionCall {
             .try_for_each(|(i, p)| -> Result<_, _> {
                 Err(Error::MissingArgument {
                     call_span,
-                    keyword: p.keyword,
+                    keyword: p.keyword.to_owned(),
                     position: i,
                 })
             })?;
 
+        // Check the closure matches the one the function accepts.
+        let closure = match (function.closure(), closure_variables) {
+            (None, None) => None,
+            (None, Some(variables)) => {
+                return Err(Error::UnexpectedClosure {
+                    ident_span,
+                    closure_span: variables.span(),
+                })
+            }
+            (Some(definition), None) => {
+                return Err(Error::MissingClosure {
+                    call_span,
+                    example: closure_example(function.identifier(), &definition),
+                })
+            }
+            (Some(definition), Some(variables)) => {
+                let (closure_span, variables) = variables.take();
+
+                if variables.len() != definition.variables.len() {
+                    return Err(Error::ClosureArityMismatch {
+                        ident_span,
+                        closure_span,
+                        expected: definition.variables.len(),
+                        supplied: variables.len(),
+                    });
+                }
+
+                Some(ClosureBuilder::new(
+                    &definition,
+                    variables,
+                    &type_defs,
+                    state,
+                ))
+            }
+        };
+
+        Ok(Self {
+            abort_on_error,
+            call_span,
+            ident_span,
+            function: function.as_ref(),
+            list,
+            maybe_fallible_arguments,
+            arguments_fmt,
+            arguments_dbg,
+            closure,
+        })
+    }
+
+    /// Compiles the function call, with the closure block compiled while the
+    /// closure variables were in scope.
+    pub(crate) fn compile(
+        mut self,
+        state: &mut State,
+        closure_block: Option<Node<Block>>,
+    ) -> Result<FunctionCall, Error> {
+        let call_span = self.call_span;
+        let ident_span = self.ident_span;
+        let abort_on_error = self.abort_on_error;
+
+        if let Some(closure) = self.closure.take() {
+            let (block_span, block) = closure_block.expect("closure block compiled").take();
+            let type_def = block.type_def(state);
+            let (variables, output) = closure.restore(state);
+
+            if type_def.is_fallible() {
+                return Err(Error::FallibleClosure { block_span });
+            }
+
+            // Closures are type-checked the same way as arguments: a block
+            // that only partially matches the expected kind makes the function
+            // call fallible, and fails at runtime if it resolves to a value of
+            // the wrong kind.
+            let kind = type_def.kind();
+            if !output.intersects(kind) {
+                return Err(Error::InvalidClosureKind {
+                    block_span,
+                    expected: output,
+                    got: kind,
+                });
+            } else if !output.contains(kind) {
+                self.maybe_fallible_arguments = true;
+            }
+
+            self.list
+                .set_closure(FunctionClosure::new(variables, block, type_def, output));
+        }
+
         let compile_ctx = FunctionCompileContext { span: call_span };
 
-        let mut expr = function
-            .compile(state, &compile_ctx, list)
+        let mut expr = self
+            .function
+            .compile(state, &compile_ctx, self.list)
             .map_err(|error| Error::Compilation { call_span, error })?;
 
         // Asking for an infallible function to abort on error makes no sense.
         // We consider this an error at compile-time, because it makes the
         // resulting program incorrectly convey this function call might fail.
-        if abort_on_error && !maybe_fallible_arguments && !expr.type_def(state).is_fallible() {
+        if abort_on_error && !self.maybe_fallible_arguments && !expr.type_def(state).is_fallible() {
             return Err(Error::AbortInfallible {
                 ident_span,
                 abort_span: Span::new(ident_span.end(), ident_span.end() + 1),
@@ -184,17 +316,115 @@ impl FunctionCall {
             error: err.to_string(),
         })?;
 
-        Ok(Self {
+        Ok(FunctionCall {
             abort_on_error,
             expr,
-            maybe_fallible_arguments,
+            maybe_fallible_arguments: self.maybe_fallible_arguments,
             span: call_span,
-            arguments_fmt,
-            arguments_dbg,
-            ident: function.identifier(),
+            arguments_fmt: self.arguments_fmt,
+            arguments_dbg: self.arguments_dbg,
+            ident: self.function.identifier().to_owned(),
         })
     }
+}
+
+impl ClosureBuilder {
+    /// Defines the closure variables in the compiler state, typed after the
+    /// arguments the function calls the closure with.
+    fn new(
+        definition: &closure::Definition,
+        variables: Vec<Node<Ident>>,
+        type_defs: &HashMap<&'static str, TypeDef>,
+        state: &mut State,
+    ) -> Self {
+        let kind_of = |keyword: &str| {
+            type_defs
+                .get(keyword)
+                .map(TypeDef::kind)
+                .unwrap_or_else(Kind::all)
+        };
 
+        let input = type_defs.get(definition.input).cloned().unwrap_or_default();
+
+        let mut key = Kind::empty();
+        if input.has_kind(Kind::Object) {
+            key |= Kind::Bytes;
+        }
+        if input.has_kind(Kind::Array) {
+            key |= Kind::Integer;
+        }
+
+        let variables = variables
+            .into_iter()
+            .map(|node| node.into_inner())
+            .collect::<Vec<_>>();
+
+        let shadowed = variables
+            .iter()
+            .zip(definition.variables)
+            .map(|(ident, variable)| {
+                let type_def = match variable {
+                    closure::Variable::Key => key.into(),
+                    closure::Variable::Value => input.element_type_def(),
+                    closure::Variable::Accumulator(keyword) => kind_of(*keyword).into(),
+                };
+
+                let details = assignment::Details {
+                    type_def,
+                    value: None,
+                };
+
+                let previous = state.remove_variable(ident);
+                state.insert_variable(ident.clone(), details);
+                (ident.clone(), previous)
+            })
+            .collect();
+
+        let output = match definition.output {
+            closure::Output::Kind(kind) => Kind::new(kind),
+            closure::Output::Parameter(keyword) => kind_of(keyword),
+        };
+
+        Self {
+            variables,
+            output,
+            shadowed,
+        }
+    }
+
+    /// Restores the variables shadowed by the closure variables.
+    fn restore(self, state: &mut State) -> (Vec<Ident>, Kind) {
+        for (ident, previous) in self.shadowed {
+            match previous {
+                Some(details) => state.insert_variable(ident, details),
+                None => {
+                    state.remove_variable(&ident);
+                }
+            }
+        }
+
+        (self.variables, self.output)
+    }
+}
+
+/// An example of how to call a function with its closure, used in
+/// diagnostics.
+fn closure_example(ident: &str, definition: &closure::Definition) -> String {
+    let variables = definition
+        .variables
+        .iter()
+        .map(|variable| match variable {
+            closure::Variable::Key => "key",
+            closure::Variable::Value => "value",
+            closure::Variable::Accumulator(_) => "accumulator",
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!("{}(...) -> |{}| {{ ... }}", ident, variables)
+}
+
+impl FunctionCall {
     pub fn noop() -> Self {
         let expr = Box::new(Noop) as _;
 
@@ -205,17 +435,140 @@ impl FunctionCall {
             span: Span::default(),
             arguments_fmt: vec![],
             arguments_dbg: vec![],
-            ident: "noop",
+            ident: "noop".to_owned(),
+        }
+    }
+
+    /// Builds a call to a function defined by the program, or by one of the
+    /// modules it imports.
+    ///
+    /// The arguments are checked the same way as for any other function, all
+    /// parameters of the function being required and accepting any value.
+    pub(crate) fn user_defined(
+        call_span: Span,
+        ident: Node<Ident>,
+        abort_on_error: bool,
+        arguments: Vec<Node<FunctionArgument>>,
+        closure_span: Option<Span>,
+        function: Arc<UserFunction>,
+        state: &mut State,
+    ) -> Result<Self, Error> {
+        let (ident_span, ident) = ident.take();
+
+        if let Some(closure_span) = closure_span {
+            return Err(Error::UnexpectedClosure {
+                ident_span,
+                closure_span,
+            });
+        }
+
+        let parameters = function.parameters();
+        if arguments.len() > parameters.len() {
+            let arguments_span = {
+                let start = arguments.first().unwrap().span().start();
+                let end = arguments.last().unwrap().span().end();
+
+                Span::new(start, end)
+            };
+
+            return Err(Error::WrongNumberOfArgs {
+                arguments_span,
+                max: parameters.len(),
+            });
+        }
+
+        let arguments_fmt = arguments
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect::<Vec<_>>();
+
+        let arguments_dbg = arguments
+            .iter()
+            .map(|arg| format!("{:?}", arg))
+            .collect::<Vec<_>>();
+
+        // Keeps track of positional argument indices, the same way as for
+        // other functions.
+        let mut index = 0;
+        let mut list: Vec<Option<Expr>> = vec![None; parameters.len()];
+
+        for node in arguments {
+            let argument = node.into_inner();
+
+            let position = match argument.keyword() {
+                None => {
+                    index += 1;
+                    Some(index - 1).filter(|position| *position < parameters.len())
+                }
+                Some(k) => parameters
+                    .iter()
+                    .position(|parameter| parameter.as_ref() == k)
+                    .map(|position| {
+                        if position == index {
+                            index += 1;
+                        }
+
+                        position
+                    }),
+            }
+            .ok_or_else(|| Error::UnknownKeyword {
+                keyword_span: argument.keyword_span().expect("exists"),
+                ident_span,
+                keywords: parameters.iter().map(|p| p.to_string()).collect(),
+            })?;
+
+            if argument.type_def(state).is_fallible() {
+                return Err(Error::FallibleArgument {
+                    expr_span: argument.span(),
+                });
+            }
+
+            list[position] = Some(argument.into_inner());
+        }
+
+        let arguments = list
+            .into_iter()
+            .enumerate()
+            .map(|(position, argument)| {
+                argument.ok_or_else(|| Error::MissingArgument {
+                    call_span,
+                    keyword: parameters[position].to_string(),
+                    position,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if abort_on_error && !function.type_def().is_fallible() {
+            return Err(Error::AbortInfallible {
+                ident_span,
+                abort_span: Span::new(ident_span.end(), ident_span.end() + 1),
+            });
         }
+
+        let mut expr = Box::new(UserFunctionFn::new(function, arguments)) as Box<dyn Expression>;
+        expr.update_state(state).map_err(|err| Error::UpdateState {
+            call_span,
+            error: err.to_string(),
+        })?;
+
+        Ok(FunctionCall {
+            abort_on_error,
+            expr,
+            maybe_fallible_arguments: false,
+            span: call_span,
+            arguments_fmt,
+            arguments_dbg,
+            ident: ident.as_ref().to_owned(),
+        })
     }
 }
 
 impl Expression for FunctionCall {
     fn resolve(&self, ctx: &mut Context) -> Resolved {
         self.expr.resolve(ctx).map_err(|err| match err {
-            ExpressionError::Abort { .. } => {
-                panic!("abort errors must only be defined by `abort` statement")
-            }
+            // Only the `abort` statement aborts, which can be part of the body
+            // of a user-defined function.
+            err @ ExpressionError::Abort { .. } => err,
             ExpressionError::Error {
                 message,
                 mut labels,
@@ -307,6 +660,13 @@ impl Expression for FunctionCall {
 
         type_def
     }
+
+    fn compile_to_vm(&self, vm: &mut Vm) -> Result<(), String> {
+        let index = vm.add_expression(Box::new(self.clone()));
+        vm.emit(OpCode::Call(index));
+
+        Ok(())
+    }
 }
 
 impl fmt::Display for FunctionCall {
@@ -372,13 +732,13 @@ pub enum Error {
     UnknownKeyword {
         keyword_span: Span,
         ident_span: Span,
-        keywords: Vec<&'static str>,
+        keywords: Vec<String>,
     },
 
     #[error("missing function argument")]
     MissingArgument {
         call_span: Span,
-        keyword: &'static str,
+        keyword: String,
         position: usize,
     },
 
@@ -407,6 +767,33 @@ pub enum Error {
 
     #[error("error updating state {}", error)]
     UpdateState { call_span: Span, error: String },
+
+    #[error("unexpected closure")]
+    UnexpectedClosure {
+        ident_span: Span,
+        closure_span: Span,
+    },
+
+    #[error("missing function closure")]
+    MissingClosure { call_span: Span, example: String },
+
+    #[error("wrong number of closure variables")]
+    ClosureArityMismatch {
+        ident_span: Span,
+        closure_span: Span,
+        expected: usize,
+        supplied: usize,
+    },
+
+    #[error("invalid closure return type")]
+    InvalidClosureKind {
+        block_span: Span,
+        expected: Kind,
+        got: Kind,
+    },
+
+    #[error("fallible closure")]
+    FallibleClosure { block_span: Span },
 }
 
 impl DiagnosticError for Error {
@@ -423,6 +810,11 @@ impl DiagnosticError for Error {
             InvalidArgumentKind { .. } => 110,
             FallibleArgument { .. } => 630,
             UpdateState { .. } => 640,
+            UnexpectedClosure { .. } => 109,
+            MissingClosure { .. } => 120,
+            ClosureArityMismatch { .. } => 112,
+            InvalidClosureKind { .. } => 113,
+            FallibleClosure { .. } => 631,
         }
     }
 
@@ -576,6 +968,75 @@ impl DiagnosticError for Error {
                 format!("an error occurred updating the compiler state: {}", error),
                 call_span,
             )],
+
+            UnexpectedClosure {
+                ident_span,
+                closure_span,
+            } => vec![
+                Label::primary("unexpected closure", closure_span),
+                Label::context("this function does not accept a closure", ident_span),
+            ],
+
+            MissingClosure { call_span, example } => vec![
+                Label::primary("this function expects a closure", call_span),
+                Label::context(format!("example: {}", example), call_span),
+            ],
+
+            ClosureArityMismatch {
+                ident_span,
+                closure_span,
+                expected,
+                supplied,
+            } => {
+                let variables = |n: usize| if n == 1 { "variable" } else { "variables" };
+
+                vec![
+                    Label::primary(
+                        format!("this closure has {} {}", supplied, variables(*supplied)),
+                        closure_span,
+                    ),
+                    Label::context(
+                        format!(
+                            "this function expects a closure with {} {}",
+                            expected,
+                            variables(*expected)
+                        ),
+                        ident_span,
+                    ),
+                ]
+            }
+
+            InvalidClosureKind {
+                block_span,
+                expected,
+                got,
+            } => {
+                let kind_str = |kind: &Kind| {
+                    if kind.is_any() {
+                        kind.to_string()
+                    } else if !kind.is_many() {
+                        format!(r#"the exact type {}"#, kind)
+                    } else {
+                        format!("one of {}", kind)
+                    }
+                };
+
+                vec![
+                    Label::primary(
+                        format!("this block resolves to {}", kind_str(got)),
+                        block_span,
+                    ),
+                    Label::context(
+                        format!("but the closure must resolve to {}", kind_str(expected)),
+                        block_span,
+                    ),
+                ]
+            }
+
+            FallibleClosure { block_span } => vec![
+                Label::primary("this closure can fail", block_span),
+                Label::context("handle the errors inside the closure", block_span),
+            ],
         }
     }
 
@@ -587,7 +1048,16 @@ impl DiagnosticError for Error {
                 "function arguments".to_owned(),
                 Urls::expression_docs_url("#arguments"),
             )],
-            AbortInfallible { .. } | FallibleArgument { .. } => vec![Note::SeeErrorDocs],
+            AbortInfallible { .. } | FallibleArgument { .. } | FallibleClosure { .. } => {
+                vec![Note::SeeErrorDocs]
+            }
+            UnexpectedClosure { .. }
+            | MissingClosure { .. }
+            | ClosureArityMismatch { .. }
+            | InvalidClosureKind { .. } => vec![Note::SeeDocs(
+                "function closures".to_owned(),
+                Urls::expression_docs_url("#closures"),
+            )],
             InvalidArgumentKind {
                 function_ident,
                 abort_on_error,
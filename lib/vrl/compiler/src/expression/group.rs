@@ -1,5 +1,5 @@
 use crate::expression::{Expr, Resolved};
-use crate::{Context, Expression, State, TypeDef};
+use crate::{Context, Expression, State, TypeDef, Vm};
 use std::fmt;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -23,6 +23,10 @@ impl Expression for Group {
     fn type_def(&self, state: &State) -> TypeDef {
         self.inner.type_def(state)
     }
+
+    fn compile_to_vm(&self, vm: &mut Vm) -> Result<(), String> {
+        self.inner.compile_to_vm(vm)
+    }
 }
 
 impl fmt::Display for Group {
@@ -1,4 +1,5 @@
 use crate::expression::{Block, Expr, Literal, Predicate, Resolved};
+use crate::vm::{OpCode, Vm};
 use crate::{Context, Expression, State, TypeDef, Value};
 use std::fmt;
 
@@ -7,6 +8,12 @@ pub struct IfStatement {
     pub predicate: Predicate,
     pub consequent: Block,
     pub alternative: Option<Block>,
+
+    /// The index of the statement in [`Program::branches`], if it's part of
+    /// the program rather than an imported module.
+    ///
+    /// [`Program::branches`]: crate::Program::branches
+    pub branch: Option<usize>,
 }
 
 impl IfStatement {
@@ -21,6 +28,7 @@ impl IfStatement {
             predicate,
             consequent,
             alternative: None,
+            branch: None,
         }
     }
 }
@@ -29,6 +37,10 @@ impl Expression for IfStatement {
     fn resolve(&self, ctx: &mut Context) -> Resolved {
         let predicate = self.predicate.resolve(ctx)?.try_boolean()?;
 
+        if let Some(branch) = self.branch {
+            ctx.state_mut().take_branch(branch, predicate);
+        }
+
         match predicate {
             true => self.consequent.resolve(ctx),
             false => self
@@ -47,6 +59,39 @@ impl Expression for IfStatement {
             Some(alternative) => type_def.merge(alternative.type_def(state)),
         }
     }
+
+    fn compile_to_vm(&self, vm: &mut Vm) -> Result<(), String> {
+        let start = vm.position();
+        self.predicate.compile_to_vm(vm)?;
+
+        // Only the branch taken by a constant predicate is needed.
+        if let Some(Value::Boolean(predicate)) = vm.constant_at(start).cloned() {
+            vm.truncate(start);
+
+            return match (predicate, &self.alternative) {
+                (true, _) => self.consequent.compile_to_vm(vm),
+                (false, Some(alternative)) => alternative.compile_to_vm(vm),
+                (false, None) => {
+                    vm.emit_constant(Value::Null);
+                    Ok(())
+                }
+            };
+        }
+
+        let alternative_jump = vm.emit_jump(OpCode::JumpIfFalse);
+        self.consequent.compile_to_vm(vm)?;
+        let end_jump = vm.emit_jump(OpCode::Jump);
+
+        vm.patch_jump(alternative_jump);
+        match &self.alternative {
+            Some(alternative) => alternative.compile_to_vm(vm)?,
+            None => vm.emit_constant(Value::Null),
+        }
+
+        vm.patch_jump(end_jump);
+
+        Ok(())
+    }
 }
 
 impl fmt::Display for IfStatement {
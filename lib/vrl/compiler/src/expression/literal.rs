@@ -1,5 +1,5 @@
 use crate::expression::Resolved;
-use crate::{value::Regex, Context, Expression, Span, State, TypeDef, Value};
+use crate::{value::Regex, Context, Expression, Span, State, TypeDef, Value, Vm};
 use bytes::Bytes;
 use chrono::{DateTime, SecondsFormat, Utc};
 use diagnostic::{DiagnosticError, Label, Note, Urls};
@@ -85,6 +85,11 @@ impl Expression for Literal {
 
         type_def.infallible()
     }
+
+    fn compile_to_vm(&self, vm: &mut Vm) -> Result<(), String> {
+        vm.emit_constant(self.to_value());
+        Ok(())
+    }
 }
 
 impl fmt::Display for Literal {
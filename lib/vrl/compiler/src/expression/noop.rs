@@ -1,5 +1,5 @@
 use crate::expression::Resolved;
-use crate::{Context, Expression, State, TypeDef, Value};
+use crate::{Context, Expression, State, TypeDef, Value, Vm};
 use std::fmt;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -13,6 +13,11 @@ impl Expression for Noop {
     fn type_def(&self, _: &State) -> TypeDef {
         TypeDef::new().null().infallible()
     }
+
+    fn compile_to_vm(&self, vm: &mut Vm) -> Result<(), String> {
+        vm.emit_constant(Value::Null);
+        Ok(())
+    }
 }
 
 impl fmt::Display for Noop {
@@ -1,5 +1,6 @@
 use crate::expression::{Expr, Noop, Resolved};
 use crate::parser::Node;
+use crate::vm::{OpCode, Vm};
 use crate::{value::Kind, Context, Expression, Span, State, TypeDef};
 use diagnostic::{DiagnosticError, Label, Note, Urls};
 use std::fmt;
@@ -44,6 +45,16 @@ impl Expression for Not {
     fn type_def(&self, state: &State) -> TypeDef {
         self.inner.type_def(state).boolean()
     }
+
+    fn compile_to_vm(&self, vm: &mut Vm) -> std::result::Result<(), String> {
+        let start = vm.position();
+
+        self.inner.compile_to_vm(vm)?;
+        vm.emit(OpCode::Not);
+        vm.fold(start);
+
+        Ok(())
+    }
 }
 
 impl fmt::Display for Not {
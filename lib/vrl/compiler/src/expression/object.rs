@@ -1,4 +1,5 @@
 use crate::expression::{Expr, Resolved};
+use crate::vm::{OpCode, Vm};
 use crate::{Context, Expression, State, TypeDef, Value};
 use std::collections::BTreeMap;
 use std::{fmt, ops::Deref};
@@ -52,6 +53,20 @@ impl Expression for Object {
 
         TypeDef::new().object(type_defs).with_fallibility(fallible)
     }
+
+    fn compile_to_vm(&self, vm: &mut Vm) -> Result<(), String> {
+        let start = vm.position();
+
+        for expr in self.inner.values() {
+            expr.compile_to_vm(vm)?;
+        }
+
+        let keys = vm.add_object_keys(self.inner.keys().cloned().collect());
+        vm.emit(OpCode::CreateObject(keys));
+        vm.fold(start);
+
+        Ok(())
+    }
 }
 
 impl fmt::Display for Object {
@@ -1,5 +1,6 @@
 use crate::expression::{self, Expr, Noop, Resolved};
 use crate::parser::{ast, Node};
+use crate::vm::{OpCode, Vm};
 use crate::{value, Context, Expression, State, TypeDef, Value};
 use diagnostic::{DiagnosticError, Label, Note, Span, Urls};
 use std::fmt;
@@ -235,6 +236,69 @@ impl Expression for Op {
                 .scalar(K::Integer | K::Float),
         }
     }
+
+    fn compile_to_vm(&self, vm: &mut Vm) -> Result<(), String> {
+        use ast::Opcode::*;
+
+        let start = vm.position();
+
+        match self.opcode {
+            Err => {
+                let handler = vm.emit_jump(OpCode::PushHandler);
+                self.lhs.compile_to_vm(vm)?;
+                vm.emit(OpCode::PopHandler);
+                let end_jump = vm.emit_jump(OpCode::Jump);
+
+                vm.patch_jump(handler);
+                self.rhs.compile_to_vm(vm)?;
+                vm.patch_jump(end_jump);
+            }
+            Or => {
+                self.lhs.compile_to_vm(vm)?;
+                let short_circuit = vm.emit_jump(OpCode::ShortCircuitOr);
+
+                let handler = vm.emit_jump(OpCode::PushHandler);
+                self.rhs.compile_to_vm(vm)?;
+                vm.emit(OpCode::PopHandler);
+                let end_jump = vm.emit_jump(OpCode::Jump);
+
+                vm.patch_jump(handler);
+                vm.emit(OpCode::RaiseOr);
+                vm.patch_jump(short_circuit);
+                vm.patch_jump(end_jump);
+            }
+            And => {
+                self.lhs.compile_to_vm(vm)?;
+                let short_circuit = vm.emit_jump(OpCode::ShortCircuitAnd);
+                self.rhs.compile_to_vm(vm)?;
+                vm.emit(OpCode::And);
+                vm.patch_jump(short_circuit);
+            }
+            opcode => {
+                self.lhs.compile_to_vm(vm)?;
+                self.rhs.compile_to_vm(vm)?;
+                vm.emit(match opcode {
+                    Mul => OpCode::Multiply,
+                    Div => OpCode::Divide,
+                    Add => OpCode::Add,
+                    Sub => OpCode::Subtract,
+                    Rem => OpCode::Remainder,
+                    Eq => OpCode::Equal,
+                    Ne => OpCode::NotEqual,
+                    Gt => OpCode::Greater,
+                    Ge => OpCode::GreaterEqual,
+                    Lt => OpCode::Less,
+                    Le => OpCode::LessEqual,
+                    Merge => OpCode::Merge,
+                    Err | Or | And => unreachable!("compiled above"),
+                });
+            }
+        }
+
+        vm.fold(start);
+
+        Ok(())
+    }
 }
 
 impl fmt::Display for Op {
@@ -721,6 +785,7 @@ mod tests {
                         predicate: Predicate::new_unchecked(vec![Literal::from(true).into()]),
                         consequent: Block::new(vec![Literal::from("string").into()]),
                         alternative: None,
+                        branch: None,
                     }.into()),
                 rhs: Box::new(Literal::from("another string").into()),
                 opcode: Or,
@@ -734,7 +799,8 @@ mod tests {
                     IfStatement {
                         predicate: Predicate::new_unchecked(vec![Literal::from(true).into()]),
                         consequent: Block::new(vec![Literal::from("string").into()]),
-                        alternative:  Some(Block::new(vec![Literal::from(42).into()]))
+                        alternative:  Some(Block::new(vec![Literal::from(42).into()])),
+                        branch: None,
                 }.into()),
                 rhs: Box::new(Literal::from("another string").into()),
                 opcode: Or,
@@ -1,6 +1,6 @@
 use crate::expression::{Block, Expr, Resolved};
 use crate::parser::Node;
-use crate::{value::Kind, Context, Expression, Span, State, TypeDef, Value};
+use crate::{value::Kind, Context, Expression, Span, State, TypeDef, Value, Vm};
 use diagnostic::{DiagnosticError, Label, Note, Urls};
 use std::fmt;
 
@@ -59,6 +59,22 @@ impl Expression for Predicate {
 
         type_def.with_fallibility(fallible)
     }
+
+    fn compile_to_vm(&self, vm: &mut Vm) -> std::result::Result<(), String> {
+        if self.inner.is_empty() {
+            vm.emit_constant(Value::Null);
+        }
+
+        for (index, expr) in self.inner.iter().enumerate() {
+            if index > 0 {
+                vm.emit_pop();
+            }
+
+            expr.compile_to_vm(vm)?;
+        }
+
+        Ok(())
+    }
 }
 
 impl fmt::Display for Predicate {
@@ -1,5 +1,6 @@
 use crate::expression::{assignment, Container, FunctionCall, Resolved, Variable};
 use crate::parser::ast::Ident;
+use crate::vm::{OpCode, Vm};
 use crate::{Context, Expression, State, TypeDef, Value};
 use lookup::LookupBuf;
 use std::collections::BTreeMap;
@@ -31,6 +32,10 @@ impl Query {
         matches!(self.target, Target::External)
     }
 
+    pub fn is_metadata(&self) -> bool {
+        matches!(self.target, Target::Metadata)
+    }
+
     pub fn variable_ident(&self) -> Option<&Ident> {
         match &self.target {
             Target::Internal(v) => Some(v.ident()),
@@ -48,11 +53,14 @@ impl Query {
 
     pub fn delete_type_def(&self, state: &mut State) {
         if self.is_external() {
-            if let Some(ref mut target) = state.target().as_mut() {
-                let value = target.value.clone();
-                let type_def = target.type_def.remove_path(&self.path);
+            match state.target().as_mut() {
+                Some(ref mut target) => {
+                    let value = target.value.clone();
+                    let type_def = target.type_def.remove_path(&self.path);
 
-                state.update_target(assignment::Details { type_def, value })
+                    state.update_target(assignment::Details { type_def, value })
+                }
+                None => state.mark_target_updated(),
             }
         }
     }
@@ -71,6 +79,12 @@ impl Expression for Query {
                     .flatten()
                     .unwrap_or(Value::Null))
             }
+            Metadata => {
+                return Ok(ctx
+                    .target()
+                    .get_metadata(&self.path)?
+                    .unwrap_or(Value::Null))
+            }
             Internal(variable) => variable.resolve(ctx)?,
             FunctionCall(call) => call.resolve(ctx)?,
             Container(container) => container.resolve(ctx)?,
@@ -112,11 +126,49 @@ impl Expression for Query {
                 }
             }
 
+            // Metadata is set at runtime, by sources and other transforms, so
+            // nothing is known about its type.
+            Metadata => TypeDef::new().unknown().infallible(),
+
             Internal(variable) => variable.type_def(state).at_path(self.path.clone()),
             FunctionCall(call) => call.type_def(state).at_path(self.path.clone()),
             Container(container) => container.type_def(state).at_path(self.path.clone()),
         }
     }
+
+    fn compile_to_vm(&self, vm: &mut Vm) -> Result<(), String> {
+        use Target::*;
+
+        let start = vm.position();
+        let path = vm.add_path(&self.path);
+
+        match &self.target {
+            External => {
+                vm.emit(OpCode::GetTarget(path));
+            }
+            Metadata => {
+                vm.emit(OpCode::GetMetadata(path));
+            }
+
+            // Only the queried part of the variable is cloned.
+            Internal(variable) => {
+                let variable = vm.add_variable(variable.ident());
+                vm.emit(OpCode::GetVariablePath(variable, path));
+            }
+            FunctionCall(call) => {
+                call.compile_to_vm(vm)?;
+                vm.emit(OpCode::GetPath(path));
+            }
+            Container(container) => {
+                container.compile_to_vm(vm)?;
+                vm.emit(OpCode::GetPath(path));
+            }
+        }
+
+        vm.fold(start);
+
+        Ok(())
+    }
 }
 
 impl fmt::Display for Query {
@@ -135,6 +187,7 @@ impl fmt::Debug for Query {
 pub enum Target {
     Internal(Variable),
     External,
+    Metadata,
     FunctionCall(FunctionCall),
     Container(Container),
 }
@@ -146,6 +199,7 @@ impl fmt::Display for Target {
         match self {
             Internal(v) => v.fmt(f),
             External => write!(f, "."),
+            Metadata => write!(f, "%"),
             FunctionCall(v) => v.fmt(f),
             Container(v) => v.fmt(f),
         }
@@ -159,6 +213,7 @@ impl fmt::Debug for Target {
         match self {
             Internal(v) => write!(f, "Internal({:?})", v),
             External => f.write_str("External"),
+            Metadata => f.write_str("Metadata"),
             FunctionCall(v) => v.fmt(f),
             Container(v) => v.fmt(f),
         }
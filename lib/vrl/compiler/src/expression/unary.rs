@@ -1,5 +1,5 @@
 use crate::expression::{Not, Resolved};
-use crate::{Context, Expression, State, TypeDef};
+use crate::{Context, Expression, State, TypeDef, Vm};
 use std::fmt;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -34,6 +34,14 @@ impl Expression for Unary {
             Not(v) => v.type_def(state),
         }
     }
+
+    fn compile_to_vm(&self, vm: &mut Vm) -> Result<(), String> {
+        use Variant::*;
+
+        match &self.variant {
+            Not(v) => v.compile_to_vm(vm),
+        }
+    }
 }
 
 impl fmt::Display for Unary {
@@ -1,5 +1,6 @@
 use crate::expression::{levenstein, Resolved};
 use crate::parser::ast::Ident;
+use crate::vm::{OpCode, Vm};
 use crate::{Context, Expression, Span, State, TypeDef, Value};
 
 use diagnostic::{DiagnosticError, Label};
@@ -57,6 +58,12 @@ impl Expression for Variable {
             .map(|d| d.type_def)
             .unwrap_or_else(|| TypeDef::new().null().infallible())
     }
+
+    fn compile_to_vm(&self, vm: &mut Vm) -> Result<(), String> {
+        let index = vm.add_variable(&self.ident);
+        vm.emit(OpCode::GetVariable(index));
+        Ok(())
+    }
 }
 
 impl fmt::Display for Variable {
@@ -8,6 +8,12 @@ use diagnostic::{DiagnosticError, Label, Note};
 use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 
+pub mod closure;
+pub mod user;
+
+pub use closure::FunctionClosure;
+pub use user::UserFunction;
+
 pub type Compiled = Result<Box<dyn Expression>, Box<dyn DiagnosticError>>;
 
 pub trait Function: Sync + fmt::Debug {
@@ -53,6 +59,14 @@ pub trait Function: Sync + fmt::Debug {
     fn parameters(&self) -> &'static [Parameter] {
         &[]
     }
+
+    /// The closure the function accepts, if any.
+    ///
+    /// Functions accepting a closure require one to be passed in, and receive
+    /// it through [`ArgumentList::required_closure`].
+    fn closure(&self) -> Option<closure::Definition> {
+        None
+    }
 }
 
 // -----------------------------------------------------------------------------
@@ -101,7 +115,10 @@ impl Parameter {
 // -----------------------------------------------------------------------------
 
 #[derive(Debug, Default)]
-pub struct ArgumentList(HashMap<&'static str, Expr>);
+pub struct ArgumentList {
+    arguments: HashMap<&'static str, Expr>,
+    closure: Option<FunctionClosure>,
+}
 
 impl ArgumentList {
     pub fn optional(&mut self, keyword: &'static str) -> Option<Box<dyn Expression>> {
@@ -246,16 +263,35 @@ impl ArgumentList {
         Ok(required(self.optional_array(keyword)?))
     }
 
+    pub fn optional_closure(&mut self) -> Option<FunctionClosure> {
+        self.closure.take()
+    }
+
+    pub fn required_closure(&mut self) -> FunctionClosure {
+        required(self.optional_closure())
+    }
+
     pub(crate) fn keywords(&self) -> Vec<&'static str> {
-        self.0.keys().copied().collect::<Vec<_>>()
+        self.arguments.keys().copied().collect::<Vec<_>>()
     }
 
     pub(crate) fn insert(&mut self, k: &'static str, v: Expr) {
-        self.0.insert(k, v);
+        self.arguments.insert(k, v);
+    }
+
+    pub(crate) fn set_closure(&mut self, closure: FunctionClosure) {
+        self.closure = Some(closure);
+    }
+
+    /// Passes the given closure in with the arguments, for the tests of
+    /// functions accepting a closure.
+    pub fn with_closure(mut self, closure: FunctionClosure) -> Self {
+        self.set_closure(closure);
+        self
     }
 
     fn optional_expr(&mut self, keyword: &'static str) -> Option<Expr> {
-        self.0.remove(keyword)
+        self.arguments.remove(keyword)
     }
 
     fn required_expr(&mut self, keyword: &'static str) -> Expr {
@@ -269,11 +305,13 @@ fn required<T>(argument: Option<T>) -> T {
 
 impl From<HashMap<&'static str, Value>> for ArgumentList {
     fn from(map: HashMap<&'static str, Value>) -> Self {
-        Self(
-            map.into_iter()
+        Self {
+            arguments: map
+                .into_iter()
                 .map(|(k, v)| (k, v.into_expr()))
                 .collect::<HashMap<_, _>>(),
-        )
+            closure: None,
+        }
     }
 }
 
@@ -291,7 +329,10 @@ impl From<Vec<Node<FunctionArgument>>> for ArgumentList {
             })
             .collect::<HashMap<_, _>>();
 
-        Self(arguments)
+        Self {
+            arguments,
+            closure: None,
+        }
     }
 }
 
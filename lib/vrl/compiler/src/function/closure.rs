@@ -0,0 +1,161 @@
+use crate::expression::{assignment, Block, Resolved};
+use crate::parser::Ident;
+use crate::value::{self, Kind};
+use crate::{Context, Expression, TypeDef, Value};
+
+/// The closure a function accepts.
+///
+/// The compiler uses this definition to give the closure variables their type
+/// definition, and to check the type of the value the closure resolves to.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Definition {
+    /// The keyword of the parameter holding the array or object the function
+    /// calls the closure for.
+    pub input: &'static str,
+
+    /// The variables the closure is called with, in order.
+    pub variables: &'static [Variable],
+
+    /// The type kind(s) the closure has to resolve to.
+    pub output: Output,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Variable {
+    /// The key of an object element, or the index of an array element.
+    Key,
+
+    /// The value of an element.
+    Value,
+
+    /// The value the closure resolved to for the previous element, starting
+    /// with the argument passed in for the given parameter.
+    Accumulator(&'static str),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Output {
+    /// The closure resolves to one of the given type kinds.
+    Kind(u16),
+
+    /// The closure resolves to one of the type kinds of the argument passed
+    /// in for the given parameter.
+    Parameter(&'static str),
+}
+
+/// A compiled closure, passed to the function it belongs to.
+///
+/// Functions only call closures for the elements of an array or object they
+/// already resolved, which can't grow while they iterate over it, and the
+/// language has no other way to loop or recurse, so programs using closures
+/// are still guaranteed to terminate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionClosure {
+    variables: Vec<Ident>,
+    block: Block,
+    type_def: TypeDef,
+    output: Kind,
+}
+
+impl FunctionClosure {
+    pub(crate) fn new(
+        variables: Vec<Ident>,
+        block: Block,
+        type_def: TypeDef,
+        output: Kind,
+    ) -> Self {
+        Self {
+            variables,
+            block,
+            type_def,
+            output,
+        }
+    }
+
+    /// Compiles a closure from the source of its block, with the given
+    /// variables in scope.
+    ///
+    /// This is meant for the tests of functions accepting a closure, which
+    /// pass it in through [`ArgumentList::with_closure`]. The block can't call
+    /// any functions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the block fails to parse or compile.
+    ///
+    /// [`ArgumentList::with_closure`]: crate::function::ArgumentList::with_closure
+    pub fn from_source(variables: &[(&str, TypeDef)], source: &str, output: Kind) -> Self {
+        let ast = ::parser::parse(source).expect("closure block parses");
+
+        let mut state = crate::State::default();
+        let variables = variables
+            .iter()
+            .map(|(name, type_def)| {
+                let ident = Ident::new(*name);
+                let details = assignment::Details {
+                    type_def: type_def.clone(),
+                    value: None,
+                };
+
+                state.insert_variable(ident.clone(), details);
+                ident
+            })
+            .collect::<Vec<_>>();
+
+        let block = crate::compiler::Compiler::new(&[], &mut state)
+            .compile_block_source(ast)
+            .unwrap_or_else(|errors| panic!("closure block compiles: {:?}", errors));
+        let type_def = block.type_def(&state);
+
+        Self::new(variables, block, type_def, output)
+    }
+
+    /// The type definition of the value the closure resolves to.
+    ///
+    /// This is the type definition of the closure block as compiled, with the
+    /// closure variables in scope.
+    pub fn type_def(&self) -> &TypeDef {
+        &self.type_def
+    }
+
+    /// Runs the closure with its variables set to the given values.
+    ///
+    /// Any variables the closure variables shadow are restored afterwards. The
+    /// closure fails if it resolves to a value of a kind the function doesn't
+    /// accept, which the compiler allows for when the block only partially
+    /// matches the expected kind.
+    pub fn run(&self, ctx: &mut Context, values: Vec<Value>) -> Resolved {
+        let shadowed = self
+            .variables
+            .iter()
+            .zip(values)
+            .map(|(ident, value)| {
+                let previous = ctx.state_mut().remove_variable(ident);
+                ctx.state_mut().insert_variable(ident.clone(), value);
+                (ident, previous)
+            })
+            .collect::<Vec<_>>();
+
+        let result = self.block.resolve(ctx);
+
+        for (ident, previous) in shadowed {
+            match previous {
+                Some(value) => ctx.state_mut().insert_variable(ident.clone(), value),
+                None => {
+                    ctx.state_mut().remove_variable(ident);
+                }
+            }
+        }
+
+        let value = result?;
+        if !self.output.contains(value.kind()) {
+            return Err(value::Error::Expected {
+                got: value.kind(),
+                expected: self.output,
+            }
+            .into());
+        }
+
+        Ok(value)
+    }
+}
@@ -0,0 +1,155 @@
+use crate::expression::{Block, Expr, ExpressionError, Resolved};
+use crate::parser::Ident;
+use crate::{Context, Expression, Span, State, TypeDef};
+use diagnostic::{DiagnosticError, Label, Note, Urls};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A function defined by the program, or by one of the modules it imports.
+///
+/// The body of the function is compiled once, where the function is defined,
+/// with its parameters typed as any value and nothing known about the target,
+/// as the function can be called from anywhere. The body only sees its own
+/// variables, not those of the caller.
+///
+/// A function can only call the functions defined before it, so it can't
+/// recurse, and programs using it are still guaranteed to terminate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserFunction {
+    ident: Ident,
+    parameters: Vec<Ident>,
+    block: Block,
+    type_def: TypeDef,
+    updates_target: bool,
+}
+
+impl UserFunction {
+    pub(crate) fn new(
+        ident: Ident,
+        parameters: Vec<Ident>,
+        block: Block,
+        type_def: TypeDef,
+        updates_target: bool,
+    ) -> Self {
+        Self {
+            ident,
+            parameters,
+            block,
+            type_def,
+            updates_target,
+        }
+    }
+
+    pub fn ident(&self) -> &Ident {
+        &self.ident
+    }
+
+    pub fn parameters(&self) -> &[Ident] {
+        &self.parameters
+    }
+
+    /// The type definition of the value the function resolves to.
+    pub fn type_def(&self) -> &TypeDef {
+        &self.type_def
+    }
+}
+
+/// A call to a [`UserFunction`], with one argument for each of its
+/// parameters, in order.
+#[derive(Debug, Clone)]
+pub(crate) struct UserFunctionFn {
+    function: Arc<UserFunction>,
+    arguments: Vec<Expr>,
+}
+
+impl UserFunctionFn {
+    pub(crate) fn new(function: Arc<UserFunction>, arguments: Vec<Expr>) -> Self {
+        Self {
+            function,
+            arguments,
+        }
+    }
+}
+
+impl Expression for UserFunctionFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let variables = self
+            .function
+            .parameters
+            .iter()
+            .zip(&self.arguments)
+            .map(|(ident, argument)| Ok((ident.clone(), argument.resolve(ctx)?)))
+            .collect::<Result<HashMap<_, _>, ExpressionError>>()?;
+
+        let caller = ctx.state_mut().swap_variables(variables);
+        let result = self.function.block.resolve(ctx);
+        ctx.state_mut().swap_variables(caller);
+
+        result
+    }
+
+    fn type_def(&self, _: &State) -> TypeDef {
+        self.function.type_def.clone()
+    }
+
+    /// What the caller knows about the target no longer holds once it's
+    /// modified by the function.
+    fn update_state(&mut self, state: &mut State) -> Result<(), ExpressionError> {
+        if self.function.updates_target {
+            state.swap_target(None);
+            state.mark_target_updated();
+        }
+
+        Ok(())
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("function already defined")]
+    Redefined { ident_span: Span, ident: Ident },
+
+    #[error("duplicate function parameter")]
+    DuplicateParameter { parameter_span: Span, ident: Ident },
+}
+
+impl DiagnosticError for Error {
+    fn code(&self) -> usize {
+        use Error::*;
+
+        match self {
+            Redefined { .. } => 114,
+            DuplicateParameter { .. } => 115,
+        }
+    }
+
+    fn labels(&self) -> Vec<Label> {
+        use Error::*;
+
+        match self {
+            Redefined { ident_span, ident } => vec![
+                Label::primary(
+                    format!(r#"a function named "{}" already exists"#, ident),
+                    ident_span,
+                ),
+                Label::context("give the function a different name", ident_span),
+            ],
+            DuplicateParameter {
+                parameter_span,
+                ident,
+            } => vec![Label::primary(
+                format!(r#"parameter "{}" is already defined"#, ident),
+                parameter_span,
+            )],
+        }
+    }
+
+    fn notes(&self) -> Vec<Note> {
+        vec![Note::SeeDocs(
+            "function definitions".to_owned(),
+            Urls::expression_docs_url("#function-definition"),
+        )]
+    }
+}
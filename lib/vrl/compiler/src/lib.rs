@@ -1,5 +1,6 @@
 mod compiler;
 mod context;
+mod module;
 mod program;
 mod target;
 mod test_util;
@@ -9,6 +10,7 @@ pub mod function;
 pub mod state;
 pub mod type_def;
 pub mod value;
+pub mod vm;
 
 pub(crate) use diagnostic::Span;
 pub(crate) use state::Compiler as State;
@@ -17,9 +19,10 @@ pub use context::Context;
 pub use expression::{Expression, ExpressionError, Resolved};
 pub use function::{Function, Parameter};
 pub use program::Program;
-pub use target::Target;
+pub use target::{Target, TargetValue};
 pub use type_def::TypeDef;
 pub use value::Value;
+pub use vm::Vm;
 
 pub use paste::paste;
 
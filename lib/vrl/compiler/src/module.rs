@@ -0,0 +1,201 @@
+use crate::Span;
+use diagnostic::{DiagnosticError, Label, Note, Urls};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The extension of module files, added to the name of an imported module
+/// if it doesn't have it.
+const EXTENSION: &str = "vrl";
+
+/// Finds the file of the given module in the first of the module paths
+/// that has it.
+pub(crate) fn find(module: &str, paths: &[PathBuf]) -> Option<PathBuf> {
+    let mut file = PathBuf::from(module);
+    if file
+        .extension()
+        .map_or(true, |extension| extension != EXTENSION)
+    {
+        file = PathBuf::from(format!("{}.{}", module, EXTENSION));
+    }
+
+    paths
+        .iter()
+        .map(|path| path.join(&file))
+        .find(|path| path.is_file())
+}
+
+/// Modules are told apart by their canonical path, so that importing the
+/// same module through different names or module paths is detected.
+pub(crate) fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_owned())
+}
+
+pub(crate) fn read(path: &Path) -> io::Result<String> {
+    fs::read_to_string(path)
+}
+
+/// Describes the errors found in a module, one line for each error, pointing
+/// to where in the module the error is, followed by its primary labels.
+///
+/// Compiling the module on its own shows the full diagnostics.
+pub(crate) fn describe(
+    path: &Path,
+    source: &str,
+    errors: &[Box<dyn DiagnosticError>],
+) -> Vec<String> {
+    let mut lines = vec![];
+
+    for error in errors {
+        let labels = error.labels();
+        let location = labels
+            .iter()
+            .find(|label| label.primary)
+            .or_else(|| labels.first())
+            .map(|label| {
+                let (line, column) = position(source, label.span.start());
+                format!("{}:{}:{}", path.display(), line, column)
+            })
+            .unwrap_or_else(|| path.display().to_string());
+
+        lines.push(format!(
+            "{}: error[E{}]: {}",
+            location,
+            error.code(),
+            error.message()
+        ));
+
+        for label in labels.iter().filter(|label| label.primary) {
+            lines.push(format!("    {}", label.message));
+        }
+    }
+
+    lines
+}
+
+/// The one-based line and column of the given byte offset in the source.
+fn position(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset.min(source.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rsplit('\n')
+        .next()
+        .map_or(0, |line| line.chars().count())
+        + 1;
+
+    (line, column)
+}
+
+// -----------------------------------------------------------------------------
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("module not found")]
+    NotFound {
+        span: Span,
+        module: String,
+        paths: Vec<PathBuf>,
+    },
+
+    #[error("unable to read module")]
+    Read {
+        span: Span,
+        path: PathBuf,
+        error: io::Error,
+    },
+
+    #[error("cyclic module import")]
+    Cycle { span: Span, modules: Vec<String> },
+
+    #[error(r#"error in module "{}""#, module)]
+    Compilation {
+        span: Span,
+        module: String,
+        errors: Vec<String>,
+    },
+
+    #[error("unexpected expression in module")]
+    UnexpectedExpression { span: Span },
+}
+
+impl DiagnosticError for Error {
+    fn code(&self) -> usize {
+        use Error::*;
+
+        match self {
+            NotFound { .. } | Read { .. } => 116,
+            Cycle { .. } => 117,
+            Compilation { .. } => 118,
+            UnexpectedExpression { .. } => 119,
+        }
+    }
+
+    fn labels(&self) -> Vec<Label> {
+        use Error::*;
+
+        match self {
+            NotFound {
+                span,
+                module,
+                paths,
+            } => {
+                let mut labels = vec![Label::primary(
+                    format!(r#"module "{}" not found"#, module),
+                    span,
+                )];
+
+                if paths.is_empty() {
+                    labels.push(Label::context("no module paths are configured", span));
+                } else {
+                    let paths = paths
+                        .iter()
+                        .map(|path| format!(r#""{}""#, path.display()))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+
+                    labels.push(Label::context(
+                        format!("modules are looked up in: {}", paths),
+                        span,
+                    ));
+                }
+
+                labels
+            }
+            Read { span, path, error } => vec![Label::primary(
+                format!(r#"unable to read "{}": {}"#, path.display(), error),
+                span,
+            )],
+            Cycle { span, modules } => vec![
+                Label::primary("this import leads back to itself", span),
+                Label::context(format!("modules imported: {}", modules.join(" -> ")), span),
+            ],
+            Compilation { span, module, .. } => vec![Label::primary(
+                format!(r#"module "{}" failed to compile"#, module),
+                span,
+            )],
+            UnexpectedExpression { span } => vec![
+                Label::primary("unexpected expression", span),
+                Label::context(
+                    "modules can only define functions and import other modules",
+                    span,
+                ),
+            ],
+        }
+    }
+
+    fn notes(&self) -> Vec<Note> {
+        use Error::*;
+
+        let mut notes = match self {
+            Compilation { errors, .. } => errors.iter().cloned().map(Note::Basic).collect(),
+            _ => vec![],
+        };
+
+        notes.push(Note::SeeDocs(
+            "modules".to_owned(),
+            Urls::expression_docs_url("#import"),
+        ));
+
+        notes
+    }
+}
@@ -1,4 +1,5 @@
-use crate::Expression;
+use crate::{Expression, Vm};
+use diagnostic::Span;
 use std::iter::IntoIterator;
 use std::ops::Deref;
 
@@ -7,6 +8,7 @@ pub struct Program {
     pub(crate) expressions: Vec<Box<dyn Expression>>,
     pub(crate) fallible: bool,
     pub(crate) abortable: bool,
+    pub(crate) branches: Vec<Span>,
 }
 
 impl Program {
@@ -25,6 +27,34 @@ impl Program {
     pub fn can_abort(&self) -> bool {
         self.abortable
     }
+
+    /// Returns the spans of the `if` statements of the program, in the order
+    /// they're compiled.
+    ///
+    /// When resolving the program, the runtime state records which branches
+    /// of these statements are taken, if asked to with
+    /// [`state::Runtime::record_branches`](crate::state::Runtime::record_branches).
+    pub fn branches(&self) -> &[Span] {
+        &self.branches
+    }
+
+    /// Compile the program to bytecode, to be run by the [`Vm`] instead of
+    /// resolving each expression.
+    ///
+    /// The [`Vm`] resolves to the same values and errors as the expressions.
+    pub fn compile_to_vm(&self) -> Result<Vm, String> {
+        let mut vm = Vm::default();
+
+        for (index, expr) in self.expressions.iter().enumerate() {
+            if index > 0 {
+                vm.emit_pop();
+            }
+
+            expr.compile_to_vm(&mut vm)?;
+        }
+
+        Ok(vm)
+    }
 }
 
 impl IntoIterator for Program {
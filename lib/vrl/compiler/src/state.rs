@@ -1,6 +1,12 @@
 use crate::expression::assignment;
+use crate::function::UserFunction;
 use crate::{parser::ast::Ident, TypeDef, Value};
-use std::{any::Any, collections::HashMap};
+use std::{
+    any::Any,
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 /// The state held by the compiler.
 ///
@@ -14,6 +20,25 @@ pub struct Compiler {
     /// stored internal variable type definitions
     variables: HashMap<Ident, assignment::Details>,
 
+    /// Whether the target was assigned to or deleted from, whether or not its
+    /// type definition is known.
+    ///
+    /// This is used to find out if the body of a user-defined function
+    /// modifies the target.
+    target_updated: bool,
+
+    /// functions defined by the program, or by the modules it imports
+    user_functions: HashMap<Ident, Arc<UserFunction>>,
+
+    /// The directories modules are imported from, in order of precedence.
+    module_paths: Vec<PathBuf>,
+
+    /// The files of the modules imported so far.
+    ///
+    /// Modules are only compiled the first time they are imported, their
+    /// functions are available to the whole program from then on.
+    modules: HashSet<PathBuf>,
+
     /// context passed between the client program and a VRL function.
     external_context: Option<Box<dyn Any>>,
 
@@ -50,6 +75,11 @@ impl Compiler {
         self.variables.keys()
     }
 
+    /// Returns the names of the variables assigned so far.
+    pub fn variable_names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.variables.keys().map(|ident| ident.as_ref())
+    }
+
     pub(crate) fn variable(&self, ident: &Ident) -> Option<&assignment::Details> {
         self.variables.get(ident)
     }
@@ -58,12 +88,70 @@ impl Compiler {
         self.variables.insert(ident, details);
     }
 
+    pub(crate) fn remove_variable(&mut self, ident: &Ident) -> Option<assignment::Details> {
+        self.variables.remove(ident)
+    }
+
+    /// Replaces all variables, returning the ones that were defined.
+    pub(crate) fn swap_variables(
+        &mut self,
+        variables: HashMap<Ident, assignment::Details>,
+    ) -> HashMap<Ident, assignment::Details> {
+        std::mem::replace(&mut self.variables, variables)
+    }
+
     pub(crate) fn target(&self) -> Option<&assignment::Details> {
         self.target.as_ref()
     }
 
     pub(crate) fn update_target(&mut self, details: assignment::Details) {
         self.target = Some(details);
+        self.target_updated = true;
+    }
+
+    /// Replaces the target type definition, returning the previous one.
+    pub(crate) fn swap_target(
+        &mut self,
+        target: Option<assignment::Details>,
+    ) -> Option<assignment::Details> {
+        std::mem::replace(&mut self.target, target)
+    }
+
+    /// Records that the target was modified, when its type definition isn't
+    /// known.
+    pub(crate) fn mark_target_updated(&mut self) {
+        self.target_updated = true;
+    }
+
+    /// Returns whether the target was modified since the last call, and
+    /// resets it.
+    pub(crate) fn take_target_updated(&mut self) -> bool {
+        std::mem::take(&mut self.target_updated)
+    }
+
+    pub(crate) fn user_function(&self, ident: &Ident) -> Option<&Arc<UserFunction>> {
+        self.user_functions.get(ident)
+    }
+
+    pub(crate) fn insert_user_function(&mut self, function: UserFunction) {
+        self.user_functions
+            .insert(function.ident().clone(), Arc::new(function));
+    }
+
+    /// Sets the directories `import` statements look up modules in, in order
+    /// of precedence.
+    pub fn set_module_paths(&mut self, paths: Vec<PathBuf>) {
+        self.module_paths = paths;
+    }
+
+    pub(crate) fn module_paths(&self) -> &[PathBuf] {
+        &self.module_paths
+    }
+
+    /// Records the module at the given path as imported, returning `false` if
+    /// it already was.
+    pub(crate) fn insert_module(&mut self, path: &Path) -> bool {
+        self.modules.insert(path.to_owned())
     }
 
     /// Take a snapshot of the current state of the compiler.
@@ -76,19 +164,37 @@ impl Compiler {
         let snapshot = Self {
             target,
             variables,
-            external_context: None,
-            snapshot: None,
+            ..Default::default()
         };
 
         self.snapshot = Some(Box::new(snapshot));
     }
 
     /// Roll back the compiler state to a previously stored snapshot.
+    ///
+    /// Only the target and variables are rolled back, the functions and
+    /// modules defined so far are kept.
     pub(crate) fn rollback(&mut self) {
-        if let Some(mut snapshot) = self.snapshot.take() {
-            let context = snapshot.external_context.take();
-            *self = *snapshot;
-            self.external_context = context;
+        if let Some(snapshot) = self.snapshot.take() {
+            self.target = snapshot.target;
+            self.variables = snapshot.variables;
+        }
+    }
+
+    /// Copies the state, without its external context, which can't be cloned.
+    ///
+    /// Compiling a program against the copy leaves this state as it was, which
+    /// is useful to type-check a program without running it.
+    pub fn copy_without_external_context(&self) -> Self {
+        Self {
+            target: self.target.clone(),
+            variables: self.variables.clone(),
+            target_updated: self.target_updated,
+            user_functions: self.user_functions.clone(),
+            module_paths: self.module_paths.clone(),
+            modules: self.modules.clone(),
+            external_context: None,
+            snapshot: None,
         }
     }
 
@@ -123,6 +229,13 @@ impl Compiler {
 pub struct Runtime {
     /// The [`Value`] stored in each variable.
     variables: HashMap<Ident, Value>,
+
+    /// The branches of the program taken so far, by their index in
+    /// [`Program::branches`](crate::Program::branches) and whether the
+    /// predicate was true, if coverage is recorded.
+    ///
+    /// Unlike the variables, these are kept across runs of the program.
+    branches_taken: Option<HashSet<(usize, bool)>>,
 }
 
 impl Runtime {
@@ -145,4 +258,35 @@ impl Runtime {
     pub(crate) fn insert_variable(&mut self, ident: Ident, value: Value) {
         self.variables.insert(ident, value);
     }
+
+    pub(crate) fn remove_variable(&mut self, ident: &Ident) -> Option<Value> {
+        self.variables.remove(ident)
+    }
+
+    /// Replaces all variables, returning the ones that were set.
+    pub(crate) fn swap_variables(
+        &mut self,
+        variables: HashMap<Ident, Value>,
+    ) -> HashMap<Ident, Value> {
+        std::mem::replace(&mut self.variables, variables)
+    }
+
+    /// Starts recording the branches taken when resolving the program.
+    pub fn record_branches(&mut self) {
+        self.branches_taken.get_or_insert_with(HashSet::new);
+    }
+
+    /// Returns whether the given branch was taken, or `None` if branches
+    /// aren't recorded.
+    pub fn branch_taken(&self, branch: usize, predicate: bool) -> Option<bool> {
+        self.branches_taken
+            .as_ref()
+            .map(|taken| taken.contains(&(branch, predicate)))
+    }
+
+    pub(crate) fn take_branch(&mut self, branch: usize, predicate: bool) {
+        if let Some(taken) = &mut self.branches_taken {
+            taken.insert((branch, predicate));
+        }
+    }
 }
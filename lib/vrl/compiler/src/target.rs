@@ -1,5 +1,6 @@
 use crate::Value;
 use lookup::LookupBuf;
+use std::collections::BTreeMap;
 
 /// Any target object you want to remap using VRL has to implement this trait.
 pub trait Target: std::fmt::Debug {
@@ -51,4 +52,127 @@ pub trait Target: std::fmt::Debug {
     /// If `compact` is true, after deletion, if an empty object or array is
     /// left behind, it should be removed as well, cascading up to the root.
     fn remove(&mut self, path: &LookupBuf, compact: bool) -> Result<Option<Value>, String>;
+
+    /// Insert a given [`Value`] in the metadata of the target, such as `%foo`.
+    ///
+    /// Metadata travels with the target, but isn't part of it. The root of the
+    /// metadata is always an object.
+    ///
+    /// Targets without metadata return an error.
+    fn insert_metadata(&mut self, _path: &LookupBuf, _value: Value) -> Result<(), String> {
+        Err("target does not support metadata".to_owned())
+    }
+
+    /// Get a value from the metadata of the target, or `None` if no value is
+    /// found.
+    ///
+    /// See [`Target::insert_metadata`] for more details.
+    fn get_metadata(&self, _path: &LookupBuf) -> Result<Option<Value>, String> {
+        Err("target does not support metadata".to_owned())
+    }
+
+    /// Remove the given path from the metadata of the target.
+    ///
+    /// See [`Target::remove`] for more details.
+    fn remove_metadata(
+        &mut self,
+        _path: &LookupBuf,
+        _compact: bool,
+    ) -> Result<Option<Value>, String> {
+        Err("target does not support metadata".to_owned())
+    }
+}
+
+/// A [`Value`] together with its metadata, for programs that run outside of
+/// an event, such as in the CLI and the VRL tests, so that they can still use
+/// metadata paths.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TargetValue {
+    /// The value queried by the paths of the program, such as `.foo`.
+    pub value: Value,
+
+    /// The metadata queried by the metadata paths of the program, such as
+    /// `%foo`. This is always an object.
+    pub metadata: Value,
+}
+
+impl TargetValue {
+    /// Creates a target for the given value, without any metadata.
+    pub fn new(value: Value) -> Self {
+        Self {
+            value,
+            metadata: Value::Object(BTreeMap::new()),
+        }
+    }
+}
+
+impl Target for TargetValue {
+    fn insert(&mut self, path: &LookupBuf, value: Value) -> Result<(), String> {
+        self.value.insert(path, value)
+    }
+
+    fn get(&self, path: &LookupBuf) -> Result<Option<Value>, String> {
+        self.value.get(path)
+    }
+
+    fn remove(&mut self, path: &LookupBuf, compact: bool) -> Result<Option<Value>, String> {
+        self.value.remove(path, compact)
+    }
+
+    fn insert_metadata(&mut self, path: &LookupBuf, value: Value) -> Result<(), String> {
+        if path.is_root() && !value.is_object() {
+            return Err("cannot set metadata root path to a value that isn't an object".to_owned());
+        }
+
+        self.metadata.insert(path, value)
+    }
+
+    fn get_metadata(&self, path: &LookupBuf) -> Result<Option<Value>, String> {
+        self.metadata.get(path)
+    }
+
+    fn remove_metadata(
+        &mut self,
+        path: &LookupBuf,
+        compact: bool,
+    ) -> Result<Option<Value>, String> {
+        self.metadata.remove(path, compact)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value;
+    use lookup::SegmentBuf;
+
+    #[test]
+    fn target_value_metadata() {
+        let mut target = TargetValue::new(value!({foo: "event"}));
+        let foo = LookupBuf::from(SegmentBuf::from("foo"));
+
+        assert_eq!(target.get_metadata(&foo), Ok(None));
+        assert_eq!(target.insert_metadata(&foo, value!("metadata")), Ok(()));
+        assert_eq!(target.get_metadata(&foo), Ok(Some(value!("metadata"))));
+        assert_eq!(target.get(&foo), Ok(Some(value!("event"))));
+        assert_eq!(
+            target.remove_metadata(&foo, false),
+            Ok(Some(value!("metadata")))
+        );
+        assert_eq!(target.metadata, value!({}));
+    }
+
+    #[test]
+    fn target_value_metadata_root_not_object() {
+        let mut target = TargetValue::new(value!({}));
+
+        assert!(target
+            .insert_metadata(&LookupBuf::root(), value!(true))
+            .is_err());
+        assert_eq!(
+            target.insert_metadata(&LookupBuf::root(), value!({foo: true})),
+            Ok(())
+        );
+        assert_eq!(target.metadata, value!({foo: true}));
+    }
 }
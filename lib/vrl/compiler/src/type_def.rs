@@ -863,6 +863,30 @@ impl TypeDef {
         self
     }
 
+    /// Returns the type definition of the values held by the arrays and objects in this type
+    /// definition, across all their indexes and fields.
+    ///
+    /// Only the top-level kinds of the values are kept.
+    pub fn element_type_def(&self) -> Self {
+        fn kind(info: &KindInfo) -> Kind {
+            match info {
+                KindInfo::Unknown => Kind::all(),
+                KindInfo::Known(set) => set.iter().fold(Kind::empty(), |acc, k| acc | k.to_kind()),
+            }
+        }
+
+        let kind = match &self.kind {
+            KindInfo::Unknown => Kind::all(),
+            KindInfo::Known(set) => set.iter().fold(Kind::empty(), |acc, k| match k {
+                TypeKind::Array(array) => array.values().fold(acc, |acc, v| acc | kind(v)),
+                TypeKind::Object(object) => object.values().fold(acc, |acc, v| acc | kind(v)),
+                _ => acc,
+            }),
+        };
+
+        kind.into()
+    }
+
     #[inline]
     pub fn is_unknown(&self) -> bool {
         matches!(self.kind, KindInfo::Unknown)
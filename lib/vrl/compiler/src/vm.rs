@@ -0,0 +1,681 @@
+//! A stack-based virtual machine to run compiled programs.
+//!
+//! Instead of walking the tree of [`Expression`]s for each event, a program
+//! can be compiled once into a flat list of [`OpCode`]s, which the [`Vm`]
+//! executes. This avoids the dynamic dispatch for every expression, and the
+//! cloning of intermediate values, such as whole variables to query a single
+//! field of them.
+//!
+//! Expressions that only depend on constants are folded into a single
+//! constant at compile-time. Function calls resolve the expression the
+//! function compiled its (statically checked) arguments into, so that each
+//! function behaves the same as when walking the tree.
+
+use crate::expression::{assignment, ExpressionError, Resolved};
+use crate::parser::ast::Ident;
+use crate::{state, value, Context, Expression, Span, Value};
+use lookup::LookupBuf;
+use shared::TimeZone;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpCode {
+    /// Push the constant at the given index.
+    Constant(usize),
+
+    /// Discard the value at the top of the stack.
+    Pop,
+
+    /// Push the value of the variable at the given index.
+    GetVariable(usize),
+
+    /// Push the value at the given path (second index) of the variable at the
+    /// given index (first index).
+    GetVariablePath(usize, usize),
+
+    /// Push the value at the given path of the target.
+    GetTarget(usize),
+
+    /// Push the value at the given path of the target's metadata.
+    GetMetadata(usize),
+
+    /// Replace the value at the top of the stack with the value at the given
+    /// path of it.
+    GetPath(usize),
+
+    /// Assign the value at the top of the stack to the assignment target at
+    /// the given index, keeping the value on the stack.
+    SetTarget(usize),
+
+    /// Assign the value at the top of the stack to the assignment target at
+    /// the given index, and discard it.
+    SetTargetPop(usize),
+
+    /// Negate the boolean at the top of the stack.
+    Not,
+
+    Multiply,
+    Divide,
+    Add,
+    Subtract,
+    Remainder,
+    And,
+    Equal,
+    NotEqual,
+    Greater,
+    GreaterEqual,
+    Less,
+    LessEqual,
+    Merge,
+
+    /// If the value at the top of the stack is `null` or `false`, replace it
+    /// with `false` and jump to the given instruction.
+    ShortCircuitAnd(usize),
+
+    /// If the value at the top of the stack is neither `null` nor `false`,
+    /// keep it and jump to the given instruction, otherwise discard it.
+    ShortCircuitOr(usize),
+
+    /// Pop the boolean at the top of the stack, and jump to the given
+    /// instruction if it's `false`.
+    JumpIfFalse(usize),
+
+    /// Jump to the given instruction.
+    Jump(usize),
+
+    /// Handle any error raised until the matching `PopHandler` by jumping to
+    /// the given instruction, with the stack as it is now.
+    PushHandler(usize),
+
+    /// Stop handling errors with the last pushed handler.
+    PopHandler,
+
+    /// Raise the handled error again, as the error of an `||` operation.
+    RaiseOr,
+
+    /// Push the message of the handled error.
+    PushError,
+
+    /// Replace the given number of values at the top of the stack with an
+    /// array of them.
+    CreateArray(usize),
+
+    /// Replace the values at the top of the stack with an object, using the
+    /// keys at the given index.
+    CreateObject(usize),
+
+    /// Push the resolved value of the expression at the given index.
+    Call(usize),
+
+    /// Abort the program.
+    Abort(Span),
+}
+
+impl OpCode {
+    /// Returns whether the instruction only depends on the values on the
+    /// stack, which allows it to be run at compile-time.
+    ///
+    /// Multiplications and remainders aren't run at compile-time, as some
+    /// operands make them panic or allocate unbounded strings, which must
+    /// only happen if the program actually runs them.
+    fn is_foldable(&self) -> bool {
+        use OpCode::*;
+
+        !matches!(
+            self,
+            GetVariable(..)
+                | GetVariablePath(..)
+                | GetTarget(..)
+                | GetMetadata(..)
+                | SetTarget(..)
+                | SetTargetPop(..)
+                | Multiply
+                | Remainder
+                | Call(..)
+                | Abort(..)
+        )
+    }
+}
+
+/// A program compiled to bytecode.
+#[derive(Debug, Clone, Default)]
+pub struct Vm {
+    instructions: Vec<OpCode>,
+    constants: Vec<Value>,
+    variables: Vec<Ident>,
+    paths: Vec<LookupBuf>,
+    targets: Vec<assignment::Target>,
+    object_keys: Vec<Vec<String>>,
+    expressions: Vec<Box<dyn Expression>>,
+
+    /// The last instruction a jump was patched to land on.
+    jump_target: Option<usize>,
+}
+
+#[derive(Debug, Default)]
+struct Machine {
+    ip: usize,
+    stack: Vec<Value>,
+    handlers: Vec<Handler>,
+    error: Option<ExpressionError>,
+}
+
+#[derive(Debug)]
+struct Handler {
+    ip: usize,
+    depth: usize,
+}
+
+impl Vm {
+    /// Get the compiled instructions.
+    pub fn instructions(&self) -> &[OpCode] {
+        &self.instructions
+    }
+
+    /// Get the constants used by the instructions.
+    pub fn constants(&self) -> &[Value] {
+        &self.constants
+    }
+
+    /// Run the program to completion, resolving to the value of its last
+    /// expression.
+    pub fn run(&self, ctx: &mut Context) -> Resolved {
+        let mut machine = Machine::default();
+
+        self.execute(&mut machine, ctx)
+    }
+
+    pub(crate) fn emit(&mut self, instruction: OpCode) -> usize {
+        self.instructions.push(instruction);
+        self.instructions.len() - 1
+    }
+
+    pub(crate) fn emit_constant(&mut self, value: Value) {
+        self.constants.push(value);
+
+        let index = self.constants.len() - 1;
+        self.emit(OpCode::Constant(index));
+    }
+
+    /// Discard the value at the top of the stack, which is merged into the
+    /// assignment that produced it, if any.
+    pub(crate) fn emit_pop(&mut self) {
+        let len = self.instructions.len();
+
+        match self.instructions.last().copied() {
+            Some(OpCode::SetTarget(index)) if self.jump_target != Some(len) => {
+                self.instructions[len - 1] = OpCode::SetTargetPop(index)
+            }
+            _ => {
+                self.emit(OpCode::Pop);
+            }
+        }
+    }
+
+    /// Emit a jump, or an instruction holding a jump target, to be patched
+    /// with [`Vm::patch_jump`] once its target is known.
+    pub(crate) fn emit_jump(&mut self, jump: fn(usize) -> OpCode) -> usize {
+        self.emit(jump(usize::MAX))
+    }
+
+    /// Make the jump at the given instruction land on the next emitted
+    /// instruction.
+    pub(crate) fn patch_jump(&mut self, at: usize) {
+        use OpCode::*;
+
+        let target = self.instructions.len();
+
+        self.instructions[at] = match self.instructions[at] {
+            ShortCircuitAnd(_) => ShortCircuitAnd(target),
+            ShortCircuitOr(_) => ShortCircuitOr(target),
+            JumpIfFalse(_) => JumpIfFalse(target),
+            Jump(_) => Jump(target),
+            PushHandler(_) => PushHandler(target),
+            instruction => unreachable!("not a jump: {:?}", instruction),
+        };
+
+        self.jump_target = Some(target);
+    }
+
+    pub(crate) fn add_variable(&mut self, ident: &Ident) -> usize {
+        match self.variables.iter().position(|v| v == ident) {
+            Some(index) => index,
+            None => {
+                self.variables.push(ident.clone());
+                self.variables.len() - 1
+            }
+        }
+    }
+
+    pub(crate) fn add_path(&mut self, path: &LookupBuf) -> usize {
+        match self.paths.iter().position(|p| p == path) {
+            Some(index) => index,
+            None => {
+                self.paths.push(path.clone());
+                self.paths.len() - 1
+            }
+        }
+    }
+
+    pub(crate) fn add_target(&mut self, target: &assignment::Target) -> usize {
+        match self.targets.iter().position(|t| t == target) {
+            Some(index) => index,
+            None => {
+                self.targets.push(target.clone());
+                self.targets.len() - 1
+            }
+        }
+    }
+
+    pub(crate) fn add_object_keys(&mut self, keys: Vec<String>) -> usize {
+        self.object_keys.push(keys);
+        self.object_keys.len() - 1
+    }
+
+    pub(crate) fn add_expression(&mut self, expr: Box<dyn Expression>) -> usize {
+        self.expressions.push(expr);
+        self.expressions.len() - 1
+    }
+
+    /// The number of emitted instructions, used to mark the start of an
+    /// expression's instructions.
+    pub(crate) fn position(&self) -> usize {
+        self.instructions.len()
+    }
+
+    /// Get the value of the expression starting at the given instruction, if
+    /// it's a constant.
+    pub(crate) fn constant_at(&self, start: usize) -> Option<&Value> {
+        match self.instructions.get(start..)? {
+            [OpCode::Constant(index)] => self.constants.get(*index),
+            _ => None,
+        }
+    }
+
+    /// Remove the instructions starting at the given position.
+    pub(crate) fn truncate(&mut self, start: usize) {
+        self.instructions.truncate(start);
+    }
+
+    /// Replace the instructions of the expression starting at the given
+    /// instruction with a constant, if they only depend on constants.
+    ///
+    /// The instructions are kept if they fail, to fail the same way at
+    /// runtime.
+    pub(crate) fn fold(&mut self, start: usize) {
+        let instructions = &self.instructions[start..];
+
+        if instructions.len() < 2 || !instructions.iter().all(OpCode::is_foldable) {
+            return;
+        }
+
+        let mut machine = Machine {
+            ip: start,
+            ..Default::default()
+        };
+
+        let mut target = Value::Null;
+        let mut state = state::Runtime::default();
+        let timezone = TimeZone::default();
+        let mut ctx = Context::new(&mut target, &mut state, &timezone);
+
+        if let Ok(value) = self.execute(&mut machine, &mut ctx) {
+            self.truncate(start);
+            self.emit_constant(value);
+        }
+    }
+
+    fn execute(&self, machine: &mut Machine, ctx: &mut Context) -> Resolved {
+        while let Some(&instruction) = self.instructions.get(machine.ip) {
+            machine.ip += 1;
+
+            if let Err(error) = self.step(instruction, machine, ctx) {
+                match machine.handlers.pop() {
+                    Some(Handler { ip, depth }) => {
+                        machine.stack.truncate(depth);
+                        machine.error = Some(error);
+                        machine.ip = ip;
+                    }
+                    None => return Err(error),
+                }
+            }
+        }
+
+        Ok(machine.stack.pop().unwrap_or(Value::Null))
+    }
+
+    fn step(
+        &self,
+        instruction: OpCode,
+        machine: &mut Machine,
+        ctx: &mut Context,
+    ) -> Result<(), ExpressionError> {
+        use OpCode::*;
+
+        match instruction {
+            Constant(index) => machine.stack.push(self.constants[index].clone()),
+            Pop => {
+                machine.pop()?;
+            }
+            GetVariable(index) => {
+                let value = ctx
+                    .state()
+                    .variable(&self.variables[index])
+                    .cloned()
+                    .unwrap_or(Value::Null);
+
+                machine.stack.push(value)
+            }
+            GetVariablePath(index, path) => {
+                let value = ctx
+                    .state()
+                    .variable(&self.variables[index])
+                    .and_then(|value| value.get_by_path(&self.paths[path]))
+                    .cloned()
+                    .unwrap_or(Value::Null);
+
+                machine.stack.push(value)
+            }
+            GetTarget(path) => {
+                let value = ctx
+                    .target()
+                    .get(&self.paths[path])
+                    .ok()
+                    .flatten()
+                    .unwrap_or(Value::Null);
+
+                machine.stack.push(value)
+            }
+            GetMetadata(path) => {
+                let value = ctx
+                    .target()
+                    .get_metadata(&self.paths[path])?
+                    .unwrap_or(Value::Null);
+
+                machine.stack.push(value)
+            }
+            GetPath(path) => {
+                let value = machine.pop()?;
+                let value = value
+                    .get_by_path(&self.paths[path])
+                    .cloned()
+                    .unwrap_or(Value::Null);
+
+                machine.stack.push(value)
+            }
+            SetTarget(index) => {
+                let value = machine.peek()?.clone();
+                self.targets[index].insert(value, ctx)?;
+            }
+            SetTargetPop(index) => {
+                let value = machine.pop()?;
+                self.targets[index].insert(value, ctx)?;
+            }
+            Not => {
+                let value = machine.pop()?;
+                machine.stack.push((!value.try_boolean()?).into())
+            }
+            Multiply => machine.binary(Value::try_mul)?,
+            Divide => machine.binary(Value::try_div)?,
+            Add => machine.binary(Value::try_add)?,
+            Subtract => machine.binary(Value::try_sub)?,
+            Remainder => machine.binary(Value::try_rem)?,
+            And => machine.binary(Value::try_and)?,
+            Equal => machine.binary(|lhs, rhs| Ok(lhs.eq_lossy(&rhs).into()))?,
+            NotEqual => machine.binary(|lhs, rhs| Ok((!lhs.eq_lossy(&rhs)).into()))?,
+            Greater => machine.binary(Value::try_gt)?,
+            GreaterEqual => machine.binary(Value::try_ge)?,
+            Less => machine.binary(Value::try_lt)?,
+            LessEqual => machine.binary(Value::try_le)?,
+            Merge => machine.binary(Value::try_merge)?,
+            ShortCircuitAnd(target) => {
+                let value = machine.peek_mut()?;
+
+                if matches!(value, Value::Null | Value::Boolean(false)) {
+                    *value = false.into();
+                    machine.ip = target;
+                }
+            }
+            ShortCircuitOr(target) => {
+                if matches!(machine.peek()?, Value::Null | Value::Boolean(false)) {
+                    machine.pop()?;
+                } else {
+                    machine.ip = target;
+                }
+            }
+            JumpIfFalse(target) => {
+                if !machine.pop()?.try_boolean()? {
+                    machine.ip = target;
+                }
+            }
+            Jump(target) => machine.ip = target,
+            PushHandler(ip) => {
+                let depth = machine.stack.len();
+                machine.handlers.push(Handler { ip, depth })
+            }
+            PopHandler => {
+                machine.handlers.pop();
+            }
+            RaiseOr => {
+                let error = machine.take_error()?;
+                return Err(value::Error::Or(error).into());
+            }
+            PushError => {
+                let error = machine.take_error()?;
+                machine.stack.push(error.to_string().into())
+            }
+            CreateArray(len) => {
+                let start = machine.split_point(len)?;
+                let values = machine.stack.split_off(start);
+
+                machine.stack.push(Value::Array(values))
+            }
+            CreateObject(index) => {
+                let keys = &self.object_keys[index];
+                let start = machine.split_point(keys.len())?;
+                let values = machine.stack.split_off(start);
+                let object = keys.iter().cloned().zip(values).collect::<BTreeMap<_, _>>();
+
+                machine.stack.push(Value::Object(object))
+            }
+            Call(index) => {
+                let value = self.expressions[index].resolve(ctx)?;
+                machine.stack.push(value)
+            }
+            Abort(span) => return Err(ExpressionError::Abort { span }),
+        }
+
+        Ok(())
+    }
+}
+
+impl Machine {
+    fn pop(&mut self) -> Result<Value, ExpressionError> {
+        self.stack.pop().ok_or_else(|| "stack underflow".into())
+    }
+
+    fn peek(&self) -> Result<&Value, ExpressionError> {
+        self.stack.last().ok_or_else(|| "stack underflow".into())
+    }
+
+    fn peek_mut(&mut self) -> Result<&mut Value, ExpressionError> {
+        self.stack
+            .last_mut()
+            .ok_or_else(|| "stack underflow".into())
+    }
+
+    fn split_point(&self, len: usize) -> Result<usize, ExpressionError> {
+        self.stack
+            .len()
+            .checked_sub(len)
+            .ok_or_else(|| "stack underflow".into())
+    }
+
+    fn take_error(&mut self) -> Result<ExpressionError, ExpressionError> {
+        self.error.take().ok_or_else(|| "no error to handle".into())
+    }
+
+    fn binary(
+        &mut self,
+        op: impl FnOnce(Value, Value) -> Result<Value, value::Error>,
+    ) -> Result<(), ExpressionError> {
+        let rhs = self.pop()?;
+        let lhs = self.pop()?;
+
+        self.stack.push(op(lhs, rhs)?);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Program;
+    use shared::btreemap;
+
+    fn compile(source: &str) -> Program {
+        let ast = parser::parse(source).unwrap_or_else(|err| panic!("{}: {:?}", source, err));
+
+        crate::compile(ast, &[])
+            .unwrap_or_else(|errors| panic!("{}", diagnostic::Formatter::new(source, errors)))
+    }
+
+    fn resolve(program: &Program, mut target: Value) -> (Result<Value, String>, Value) {
+        let mut state = state::Runtime::default();
+        let timezone = TimeZone::default();
+        let mut ctx = Context::new(&mut target, &mut state, &timezone);
+
+        let result = program
+            .iter()
+            .map(|expr| expr.resolve(&mut ctx))
+            .collect::<Result<Vec<_>, _>>()
+            .map(|mut values| values.pop().unwrap_or(Value::Null))
+            .map_err(|err| format!("{:?}", err));
+
+        (result, target)
+    }
+
+    fn run(vm: &Vm, mut target: Value) -> (Result<Value, String>, Value) {
+        let mut state = state::Runtime::default();
+        let timezone = TimeZone::default();
+        let mut ctx = Context::new(&mut target, &mut state, &timezone);
+
+        let result = vm.run(&mut ctx).map_err(|err| format!("{:?}", err));
+
+        (result, target)
+    }
+
+    #[test]
+    fn same_as_resolving_expressions() {
+        let cases = [
+            "1 + 2 * 3 - 4 % 3",
+            ".a = 1\n.b = .a + 1\n.c = .b * 2.5",
+            "x = 5\nx > 3 && x < 10",
+            ".a = null || \"default\"",
+            ".a = false || null || 3",
+            ".missing && true ?? \"error\"",
+            "x = 1\nx == 1.0",
+            "x = \"a\"\nx != \"b\"",
+            "x = {\"a\": {\"b\": [1, 2, 3]}}\nx.a.b[1]",
+            "x = [1, {\"a\": 2}]\nx[1].a = 3\nx",
+            "{\"a\": .foo, \"b\": [.bar, 2]}",
+            "{\"a\": 1} | {\"b\": 2}",
+            "[1, 2, 3][2]",
+            "if .foo == \"bar\" { 1 } else { 2 }",
+            "if .bar == \"bar\" { 1 }",
+            "if false { 1 } else if .foo == \"bar\" { 2 } else { 3 }",
+            "!(.foo == \"bar\")",
+            ".a = 10 / .count ?? 0",
+            ".a = 10 / 0 ?? (1 / 0 ?? 2)",
+            "ok, err = 10 / .count\n[ok, err]",
+            "ok, err = 10 / 0\n[ok, err]",
+            "_, err = 10 / 0\nerr",
+            "ok, _ = 10 / 0\nok",
+            "x = .foo\n.foo = \"baz\"\nx",
+            "%custom = \"set\"\n%custom",
+            ". = {\"replaced\": true}\n.",
+            "del = 1\n{ del; 2 }",
+            "fn first(value) { value.a }\nfirst({\"a\": .count})",
+            "fn fail(value) { 10 / value }\n_, err = fail(0)\nerr",
+            "fn fail(value) { 10 / value }\nfail!(0)",
+            "_, err = (1 / 0 ?? false) || (2 / 0)\nerr",
+            "_, err = (1 / 0 ?? false) || .count / 0\nerr",
+            ".foo = 1\nabort",
+            "if .count > 0 { abort }\n.unreachable = true",
+            "ok, err = { .a = 1; 10 / 0 }\n[ok, err, .a]",
+        ];
+
+        let target = Value::from(btreemap! {
+            "foo" => "bar",
+            "count" => 5,
+        });
+
+        for source in &cases {
+            let program = compile(source);
+            let vm = program.compile_to_vm().unwrap();
+
+            assert_eq!(
+                run(&vm, target.clone()),
+                resolve(&program, target.clone()),
+                "{}: {:?}",
+                source,
+                vm.instructions()
+            );
+        }
+    }
+
+    #[test]
+    fn fold_constants() {
+        let cases = [
+            ("1 + 2 - 3.5", Value::from(-0.5)),
+            ("!(1 > 2)", true.into()),
+            ("false || \"default\"", "default".into()),
+            ("null && true", false.into()),
+            ("1 / 0 ?? 4", 4.into()),
+            ("if 2 > 1 { \"yes\" } else { \"no\" }", "yes".into()),
+            ("[1, [2, 3]][1][0]", 2.into()),
+            ("{\"a\": 1 + 1}.a", 2.into()),
+        ];
+
+        for (source, want) in cases.iter() {
+            let vm = compile(source).compile_to_vm().unwrap();
+
+            match vm.instructions() {
+                [OpCode::Constant(index)] => {
+                    assert_eq!(&vm.constants()[*index], want, "{}", source)
+                }
+                instructions => panic!("{} isn't folded: {:?}", source, instructions),
+            }
+        }
+    }
+
+    #[test]
+    fn keep_failing_constants() {
+        let vm = compile("_, err = 1 / 0").compile_to_vm().unwrap();
+        assert!(vm.instructions().contains(&OpCode::Divide));
+
+        // Remainders panic for some operands, so only run them when needed.
+        let vm = compile("if 1 > 2 { 1.5 % 0.0 } else { 1 }")
+            .compile_to_vm()
+            .unwrap();
+        assert!(matches!(vm.instructions(), [OpCode::Constant(_)]));
+
+        let vm = compile("1.5 % 0.0").compile_to_vm().unwrap();
+        assert!(vm.instructions().contains(&OpCode::Remainder));
+    }
+
+    #[test]
+    fn merge_discarded_assignments() {
+        let vm = compile(".a = 1\n.b = 2").compile_to_vm().unwrap();
+
+        assert_eq!(
+            vm.instructions(),
+            &[
+                OpCode::Constant(0),
+                OpCode::SetTargetPop(0),
+                OpCode::Constant(1),
+                OpCode::SetTarget(1),
+            ]
+        );
+    }
+}
@@ -2,10 +2,12 @@ pub mod prelude;
 mod runtime;
 
 pub use compiler::{
-    function, state, type_def::Index, value, Context, Expression, Function, Program, Target, Value,
+    function, state,
+    type_def::{self, Index},
+    value, vm, Context, Expression, Function, Program, Target, TargetValue, TypeDef, Value, Vm,
 };
 pub use diagnostic;
-pub use runtime::{Runtime, RuntimeResult, Terminate};
+pub use runtime::{Runtime, RuntimeResult, Terminate, VrlRuntime};
 use std::any::Any;
 
 /// Compile a given source into the final [`Program`].
@@ -24,7 +24,9 @@ pub use std::fmt;
 
 // commonly used function types
 
-pub use compiler::function::{ArgumentList, Compiled, Example, FunctionCompileContext, Parameter};
+pub use compiler::function::{
+    closure, ArgumentList, Compiled, Example, FunctionClosure, FunctionCompileContext, Parameter,
+};
 
 // commonly used macros
 pub use compiler::{
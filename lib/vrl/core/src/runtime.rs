@@ -1,8 +1,9 @@
-use crate::{state, Context, Program, Target, Value};
+use crate::{state, Context, Program, Target, Value, Vm};
 use compiler::ExpressionError;
 use lookup::LookupBuf;
+use serde::{Deserialize, Serialize};
 use shared::TimeZone;
-use std::{error::Error, fmt};
+use std::{error::Error, fmt, str::FromStr};
 
 pub type RuntimeResult = Result<Value, Terminate>;
 
@@ -12,6 +13,35 @@ pub struct Runtime {
     root_lookup: LookupBuf,
 }
 
+/// The way programs are run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VrlRuntime {
+    /// Resolve each expression of the [`Program`].
+    Ast,
+
+    /// Run the [`Program`] compiled to bytecode by the [`Vm`].
+    Vm,
+}
+
+impl Default for VrlRuntime {
+    fn default() -> Self {
+        Self::Ast
+    }
+}
+
+impl FromStr for VrlRuntime {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ast" => Ok(Self::Ast),
+            "vm" => Ok(Self::Vm),
+            _ => Err("runtime must be one of: ast, vm"),
+        }
+    }
+}
+
 /// The error raised if the runtime is terminated.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Terminate {
@@ -57,6 +87,10 @@ impl Runtime {
         self.state.clear();
     }
 
+    pub fn state(&self) -> &state::Runtime {
+        &self.state
+    }
+
     /// Given the provided [`Target`], resolve the provided [`Program`] to
     /// completion.
     pub fn resolve(
@@ -65,46 +99,63 @@ impl Runtime {
         program: &Program,
         timezone: &TimeZone,
     ) -> RuntimeResult {
-        // Validate that the path is an object.
-        //
-        // VRL technically supports any `Value` object as the root, but the
-        // assumption is people are expected to use it to query objects.
-        match target.get(&self.root_lookup) {
-            Ok(Some(Value::Object(_))) => {}
-            Ok(Some(value)) => {
-                return Err(Terminate::Error(
-                    format!(
-                        "target must be a valid object, got {}: {}",
-                        value.kind(),
-                        value
-                    )
-                    .into(),
-                ))
-            }
-            Ok(None) => {
-                return Err(Terminate::Error(
-                    "expected target object, got nothing".to_owned().into(),
-                ))
-            }
-            Err(err) => {
-                return Err(Terminate::Error(
-                    format!("error querying target object: {}", err).into(),
-                ))
-            }
-        };
+        self.validate_target(target)?;
 
         let mut context = Context::new(target, &mut self.state, timezone);
 
         let mut values = program
             .iter()
-            .map(|expr| {
-                expr.resolve(&mut context).map_err(|err| match err {
-                    ExpressionError::Abort { .. } => Terminate::Abort(err),
-                    err @ ExpressionError::Error { .. } => Terminate::Error(err),
-                })
-            })
+            .map(|expr| expr.resolve(&mut context).map_err(terminate))
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(values.pop().unwrap_or(Value::Null))
     }
+
+    /// Given the provided [`Target`], run the [`Program`] compiled to the
+    /// provided [`Vm`] to completion.
+    ///
+    /// This resolves to the same result as [`Runtime::resolve`].
+    pub fn run_vm(
+        &mut self,
+        target: &mut dyn Target,
+        vm: &Vm,
+        timezone: &TimeZone,
+    ) -> RuntimeResult {
+        self.validate_target(target)?;
+
+        let mut context = Context::new(target, &mut self.state, timezone);
+
+        vm.run(&mut context).map_err(terminate)
+    }
+
+    fn validate_target(&self, target: &dyn Target) -> Result<(), Terminate> {
+        // Validate that the path is an object.
+        //
+        // VRL technically supports any `Value` object as the root, but the
+        // assumption is people are expected to use it to query objects.
+        match target.get(&self.root_lookup) {
+            Ok(Some(Value::Object(_))) => Ok(()),
+            Ok(Some(value)) => Err(Terminate::Error(
+                format!(
+                    "target must be a valid object, got {}: {}",
+                    value.kind(),
+                    value
+                )
+                .into(),
+            )),
+            Ok(None) => Err(Terminate::Error(
+                "expected target object, got nothing".to_owned().into(),
+            )),
+            Err(err) => Err(Terminate::Error(
+                format!("error querying target object: {}", err).into(),
+            )),
+        }
+    }
+}
+
+fn terminate(err: ExpressionError) -> Terminate {
+    match err {
+        ExpressionError::Abort { .. } => Terminate::Abort(err),
+        err @ ExpressionError::Error { .. } => Terminate::Error(err),
+    }
 }
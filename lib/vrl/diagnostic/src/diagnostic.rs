@@ -120,7 +120,7 @@ impl From<Diagnostic> for diagnostic::Diagnostic<()> {
         let mut notes = diag.notes.to_vec();
 
         // not all codes have a page on the site yet
-        if diag.code >= 100 && diag.code <= 110 {
+        if diag.code >= 100 && diag.code <= 120 {
             notes.push(Note::SeeCodeDocs(diag.code));
         }
 
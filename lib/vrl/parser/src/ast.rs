@@ -164,6 +164,8 @@ impl IntoIterator for Program {
 #[derive(PartialEq)]
 pub enum RootExpr {
     Expr(Node<Expr>),
+    FunctionDefinition(Node<FunctionDefinition>),
+    Import(Node<Import>),
 
     /// A special expression that is returned if a given expression could not be
     /// parsed. This allows the parser to continue on to the next expression.
@@ -176,6 +178,8 @@ impl fmt::Debug for RootExpr {
 
         let value = match self {
             Expr(v) => format!("{:?}", v),
+            FunctionDefinition(v) => format!("{:?}", v),
+            Import(v) => format!("{:?}", v),
             Error(v) => format!("{:?}", v),
         };
 
@@ -189,11 +193,84 @@ impl fmt::Display for RootExpr {
 
         match self {
             Expr(v) => v.fmt(f),
+            FunctionDefinition(v) => v.fmt(f),
+            Import(v) => v.fmt(f),
             Error(v) => v.fmt(f),
         }
     }
 }
 
+// -----------------------------------------------------------------------------
+// function definition
+// -----------------------------------------------------------------------------
+
+/// A named function defined by the program.
+///
+/// Function definitions are only allowed at the root of a program or module.
+#[derive(Clone, PartialEq)]
+pub struct FunctionDefinition {
+    pub ident: Node<Ident>,
+    pub parameters: Vec<Node<Ident>>,
+    pub block: Node<Block>,
+}
+
+impl fmt::Display for FunctionDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "fn {}(", self.ident)?;
+
+        let mut iter = self.parameters.iter().peekable();
+        while let Some(parameter) = iter.next() {
+            parameter.fmt(f)?;
+
+            if iter.peek().is_some() {
+                f.write_str(", ")?;
+            }
+        }
+
+        write!(f, ") {}", self.block)
+    }
+}
+
+impl fmt::Debug for FunctionDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FunctionDefinition({}(", self.ident)?;
+
+        let mut iter = self.parameters.iter().peekable();
+        while let Some(parameter) = iter.next() {
+            parameter.fmt(f)?;
+
+            if iter.peek().is_some() {
+                f.write_str(", ")?;
+            }
+        }
+
+        write!(f, "): {:?})", self.block)
+    }
+}
+
+// -----------------------------------------------------------------------------
+// import
+// -----------------------------------------------------------------------------
+
+/// An import of the functions defined in a module, by the name of its file
+/// on the module search path.
+#[derive(Clone, PartialEq)]
+pub struct Import {
+    pub module: Node<String>,
+}
+
+impl fmt::Display for Import {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, r#"import "{}""#, self.module)
+    }
+}
+
+impl fmt::Debug for Import {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Import({:?})", self.module)
+    }
+}
+
 // -----------------------------------------------------------------------------
 // expression
 // -----------------------------------------------------------------------------
@@ -901,6 +978,7 @@ impl fmt::Debug for Query {
 pub enum QueryTarget {
     Internal(Ident),
     External,
+    Metadata,
     FunctionCall(FunctionCall),
     Container(Container),
 }
@@ -912,6 +990,7 @@ impl fmt::Display for QueryTarget {
         match self {
             Internal(v) => v.fmt(f),
             External => write!(f, "."),
+            Metadata => write!(f, "%"),
             FunctionCall(v) => v.fmt(f),
             Container(v) => v.fmt(f),
         }
@@ -925,6 +1004,7 @@ impl fmt::Debug for QueryTarget {
         match self {
             Internal(v) => write!(f, "Internal({:?})", v),
             External => f.write_str("External"),
+            Metadata => f.write_str("Metadata"),
             FunctionCall(v) => v.fmt(f),
             Container(v) => v.fmt(f),
         }
@@ -944,6 +1024,7 @@ pub struct FunctionCall {
     pub ident: Node<Ident>,
     pub abort_on_error: bool,
     pub arguments: Vec<Node<FunctionArgument>>,
+    pub closure: Option<Node<FunctionClosure>>,
 }
 
 impl fmt::Display for FunctionCall {
@@ -960,7 +1041,13 @@ impl fmt::Display for FunctionCall {
             }
         }
 
-        f.write_str(")")
+        f.write_str(")")?;
+
+        if let Some(closure) = &self.closure {
+            write!(f, " {}", closure)?;
+        }
+
+        Ok(())
     }
 }
 
@@ -980,7 +1067,13 @@ impl fmt::Debug for FunctionCall {
             }
         }
 
-        f.write_str("))")
+        f.write_str(")")?;
+
+        if let Some(closure) = &self.closure {
+            write!(f, " {:?}", closure)?;
+        }
+
+        f.write_str(")")
     }
 }
 
@@ -1016,6 +1109,50 @@ impl fmt::Debug for FunctionArgument {
     }
 }
 
+/// A closure passed to a function call.
+///
+/// The function calls the closure with the variables bound to its own values,
+/// such as the key and value of each element of an object it iterates over.
+#[derive(Clone, PartialEq)]
+pub struct FunctionClosure {
+    pub variables: Vec<Node<Ident>>,
+    pub block: Node<Block>,
+}
+
+impl fmt::Display for FunctionClosure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("-> |")?;
+
+        let mut iter = self.variables.iter().peekable();
+        while let Some(variable) = iter.next() {
+            variable.fmt(f)?;
+
+            if iter.peek().is_some() {
+                f.write_str(", ")?;
+            }
+        }
+
+        write!(f, "| {}", self.block)
+    }
+}
+
+impl fmt::Debug for FunctionClosure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Closure(")?;
+
+        let mut iter = self.variables.iter().peekable();
+        while let Some(variable) = iter.next() {
+            variable.fmt(f)?;
+
+            if iter.peek().is_some() {
+                f.write_str(", ")?;
+            }
+        }
+
+        write!(f, ": {:?})", self.block)
+    }
+}
+
 // -----------------------------------------------------------------------------
 // unary
 // -----------------------------------------------------------------------------
@@ -215,6 +215,14 @@ pub struct Lexer<'input> {
     ///   ~~~~~~~~~~  0..10
     ///    ~~~~       1..5
     rquery_indices: Vec<usize>,
+
+    /// Whether the next token is expected to start an operand, which is the
+    /// case at the start of an expression and after an operator, but not
+    /// after an identifier, literal or closing delimiter.
+    ///
+    /// A `%` only starts a metadata path where an operand is expected, so that
+    /// `a%b` is still the remainder of `a` and `b`.
+    operand_expected: bool,
 }
 
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
@@ -246,6 +254,8 @@ pub enum Token<S> {
     False,
     True,
     Abort,
+    Fn,
+    Import,
 
     // tokens
     Colon,
@@ -266,6 +276,8 @@ pub enum Token<S> {
     MergeEquals,
     Bang,
     Question,
+    Arrow,
+    Percent,
 
     /// The {L,R}Query token is an "instruction" token. It does not represent
     /// any character in the source, instead it represents the start or end of a
@@ -276,6 +288,7 @@ pub enum Token<S> {
     /// ```text
     /// .          => LQuery, Dot, RQuery
     /// .foo       => LQuery, Dot, Ident, RQuery
+    /// %foo       => LQuery, Percent, Ident, RQuery
     /// foo.bar[2] => LQuery, Ident, Dot, Ident, LBracket, Integer, RBracket, RQuery
     /// foo().bar  => LQuery, FunctionCall, LParen, RParen, Dot, Ident, RQuery
     /// [1].foo    => LQuery, LBracket, Integer, RBracket, Dot, Ident, RQuery
@@ -331,6 +344,8 @@ impl<S> Token<S> {
             Null => Null,
             True => True,
             Abort => Abort,
+            Fn => Fn,
+            Import => Import,
 
             // tokens
             Colon => Colon,
@@ -351,11 +366,38 @@ impl<S> Token<S> {
             MergeEquals => MergeEquals,
             Bang => Bang,
             Question => Question,
+            Percent => Percent,
+            Arrow => Arrow,
 
             LQuery => LQuery,
             RQuery => RQuery,
         }
     }
+
+    /// Whether the token can end an operand, in which case the token that
+    /// follows it is an operator, not the start of another operand.
+    fn ends_operand(&self) -> bool {
+        use self::Token::*;
+
+        matches!(
+            self,
+            Identifier(_)
+                | PathField(_)
+                | StringLiteral(_)
+                | IntegerLiteral(_)
+                | FloatLiteral(_)
+                | RegexLiteral(_)
+                | TimestampLiteral(_)
+                | ReservedIdentifier(_)
+                | Null
+                | False
+                | True
+                | RBrace
+                | RBracket
+                | RParen
+                | RQuery
+        )
+    }
 }
 
 impl<S> fmt::Display for Token<S>
@@ -385,6 +427,8 @@ where
             Null => "Null",
             True => "True",
             Abort => "Abort",
+            Fn => "Fn",
+            Import => "Import",
 
             // tokens
             Colon => "Colon",
@@ -405,6 +449,8 @@ where
             MergeEquals => "MergeEquals",
             Bang => "Bang",
             Question => "Question",
+            Percent => "Percent",
+            Arrow => "Arrow",
 
             LQuery => "LQuery",
             RQuery => "RQuery",
@@ -426,6 +472,8 @@ impl<'input> Token<&'input str> {
             "false" => False,
             "null" => Null,
             "abort" => Abort,
+            "fn" => Fn,
+            "import" => Import,
 
             // reserved identifiers
             "array" | "bool" | "boolean" | "break" | "continue" | "do" | "emit" | "float"
@@ -465,6 +513,18 @@ impl<'input> Iterator for Lexer<'input> {
     type Item = SpannedResult<'input, usize>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        let result = self.next_token();
+
+        if let Some(Ok((_, token, _))) = &result {
+            self.operand_expected = !token.ends_operand();
+        }
+
+        result
+    }
+}
+
+impl<'input> Lexer<'input> {
+    fn next_token(&mut self) -> Option<SpannedResult<'input, usize>> {
         use Token::*;
 
         loop {
@@ -525,6 +585,10 @@ impl<'input> Iterator for Lexer<'input> {
                         Some(Ok(self.token(start, Bang)))
                     }
 
+                    '%' if self.operand_expected && self.test_peek(is_metadata_path_start) => {
+                        Some(Ok(self.token(start, Percent)))
+                    }
+
                     '#' => {
                         self.take_until(start, |ch| ch == '\n');
                         continue;
@@ -843,6 +907,13 @@ impl<'input> Lexer<'input> {
                 }
 
                 '.' if last_char.is_none() => valid = true,
+                '%' if last_char.is_none() && self.operand_expected => {
+                    // a metadata path, unless this is the remainder operator
+                    match chars.peek() {
+                        Some((_, ch)) if is_metadata_path_start(*ch) => valid = true,
+                        _ => break,
+                    }
+                }
                 '.' if last_char == Some(')') => valid = true,
                 '.' if last_char == Some('}') => valid = true,
                 '.' if last_char == Some(']') => valid = true,
@@ -990,6 +1061,7 @@ impl<'input> Lexer<'input> {
             "=" => Token::Equals,
             "|=" => Token::MergeEquals,
             "?" => Token::Question,
+            "->" => Token::Arrow,
             op => Token::Operator(op),
         };
 
@@ -1045,6 +1117,7 @@ impl<'input> Lexer<'input> {
             open_brackets: 0,
             open_parens: 0,
             rquery_indices: vec![],
+            operand_expected: true,
         }
     }
 
@@ -1129,11 +1202,17 @@ fn is_ident_continue(ch: char) -> bool {
 
 fn is_query_start(ch: char) -> bool {
     match ch {
-        '.' | '{' | '[' => true,
+        '.' | '{' | '[' | '%' => true,
         ch => is_ident_start(ch),
     }
 }
 
+/// A `%` followed by one of these characters starts a metadata path, such as
+/// `%foo` or `%"foo bar"`, instead of being the remainder operator.
+fn is_metadata_path_start(ch: char) -> bool {
+    ch == '"' || is_ident_start(ch)
+}
+
 fn is_digit(ch: char) -> bool {
     ch.is_digit(10)
 }
@@ -1381,6 +1460,59 @@ mod test {
         );
     }
 
+    #[test]
+    fn function_call_closure() {
+        test(
+            data(r#"foo(x) -> |k, v| { v }"#),
+            vec![
+                (r#"~~~                   "#, FunctionCall("foo")),
+                (r#"   ~                  "#, LParen),
+                (r#"    ~                 "#, Identifier("x")),
+                (r#"     ~                "#, RParen),
+                (r#"       ~~             "#, Arrow),
+                (r#"          ~           "#, Operator("|")),
+                (r#"           ~          "#, Identifier("k")),
+                (r#"            ~         "#, Comma),
+                (r#"              ~       "#, Identifier("v")),
+                (r#"               ~      "#, Operator("|")),
+                (r#"                 ~    "#, LBrace),
+                (r#"                   ~  "#, Identifier("v")),
+                (r#"                     ~"#, RBrace),
+            ],
+        );
+    }
+
+    #[test]
+    fn function_definition() {
+        test(
+            data(r#"fn foo(x) { x }"#),
+            vec![
+                (r#"~~             "#, Fn),
+                (r#"   ~~~         "#, FunctionCall("foo")),
+                (r#"      ~        "#, LParen),
+                (r#"       ~       "#, Identifier("x")),
+                (r#"        ~      "#, RParen),
+                (r#"          ~    "#, LBrace),
+                (r#"            ~  "#, Identifier("x")),
+                (r#"              ~"#, RBrace),
+            ],
+        );
+    }
+
+    #[test]
+    fn import() {
+        use StringLiteral as S;
+        use Token::StringLiteral as L;
+
+        test(
+            data(r#"import "foo""#),
+            vec![
+                (r#"~~~~~~      "#, Import),
+                (r#"       ~~~~~"#, L(S::Escaped("foo"))),
+            ],
+        );
+    }
+
     #[test]
     fn single_query() {
         test(
@@ -1420,6 +1552,89 @@ mod test {
         );
     }
 
+    #[test]
+    fn metadata_query() {
+        use StringLiteral as S;
+        use Token::StringLiteral as L;
+
+        test(
+            data(r#"%foo.bar, %"foo bar""#),
+            vec![
+                (r#"~                   "#, LQuery),
+                (r#"~                   "#, Percent),
+                (r#" ~~~                "#, Identifier("foo")),
+                (r#"    ~               "#, Dot),
+                (r#"     ~~~            "#, Identifier("bar")),
+                (r#"       ~            "#, RQuery),
+                (r#"        ~           "#, Comma),
+                (r#"          ~         "#, LQuery),
+                (r#"          ~         "#, Percent),
+                (r#"           ~~~~~~~~~"#, L(S::Escaped("foo bar"))),
+                (r#"                   ~"#, RQuery),
+            ],
+        );
+    }
+
+    #[test]
+    fn remainder_operator() {
+        test(
+            data(r#"foo % 2"#),
+            vec![
+                (r#"~~~    "#, Identifier("foo")),
+                (r#"    ~  "#, Operator("%")),
+                (r#"      ~"#, IntegerLiteral(2)),
+            ],
+        );
+    }
+
+    #[test]
+    fn remainder_operator_without_spaces() {
+        test(
+            data(r#"a%b"#),
+            vec![
+                (r#"~  "#, Identifier("a")),
+                (r#" ~ "#, Operator("%")),
+                (r#"  ~"#, Identifier("b")),
+            ],
+        );
+
+        test(
+            data(r#".a%b"#),
+            vec![
+                (r#"~   "#, LQuery),
+                (r#"~   "#, Dot),
+                (r#" ~  "#, Identifier("a")),
+                (r#" ~  "#, RQuery),
+                (r#"  ~ "#, Operator("%")),
+                (r#"   ~"#, Identifier("b")),
+            ],
+        );
+
+        test(
+            data(r#"a %b"#),
+            vec![
+                (r#"~   "#, Identifier("a")),
+                (r#"  ~ "#, Operator("%")),
+                (r#"   ~"#, Identifier("b")),
+            ],
+        );
+    }
+
+    #[test]
+    fn metadata_query_after_operator() {
+        test(
+            data(r#"a % %b"#),
+            vec![
+                (r#"~     "#, Identifier("a")),
+                (r#"  ~   "#, Operator("%")),
+                (r#"    ~ "#, LQuery),
+                (r#"    ~ "#, Percent),
+                (r#"     ~"#, Identifier("b")),
+                (r#"     ~"#, RQuery),
+            ],
+        );
+    }
+
     #[test]
     fn ampersat_in_query() {
         test(
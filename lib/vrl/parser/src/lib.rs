@@ -64,3 +64,25 @@ pub fn parse_literal(input: impl AsRef<str>) -> Result<Literal, Error> {
 pub mod test {
     pub use super::parser::TestParser as Parser;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remainder_without_spaces() {
+        let cases = [
+            ("a%b", "a % b"),
+            (".a%b", ".a % b"),
+            ("a %b", "a % b"),
+            ("a % %b", "a % %b"),
+            ("%a%b", "%a % b"),
+        ];
+
+        for (source, want) in cases.iter() {
+            let program = parse(source).unwrap_or_else(|err| panic!("{}: {}", source, err));
+
+            assert_eq!(program.to_string().trim(), *want, "{}", source);
+        }
+    }
+}
@@ -142,7 +142,8 @@ prop_compose! {
             arguments: params.into_iter().map(|p| node(FunctionArgument {
                 ident: None,
                 expr: node(Expr::Variable(node(p)))
-            })).collect()
+            })).collect(),
+            closure: None,
         }
     }
 }
@@ -260,6 +261,7 @@ fn expr() -> impl Strategy<Value = Expr> {
                                 })
                             })
                             .collect(),
+                        closure: None,
                     }))
                 }
             ),
@@ -267,6 +269,53 @@ fn expr() -> impl Strategy<Value = Expr> {
     })
 }
 
+/// Source code of expressions using a fixed set of variables and target
+/// paths, so that most of them compile.
+fn source_opcode() -> impl Strategy<Value = Opcode> {
+    prop_oneof![
+        Just(Opcode::Add),
+        Just(Opcode::Sub),
+        Just(Opcode::Or),
+        Just(Opcode::Div),
+        Just(Opcode::And),
+        Just(Opcode::Err),
+        Just(Opcode::Ne),
+        Just(Opcode::Eq),
+        Just(Opcode::Ge),
+        Just(Opcode::Gt),
+        Just(Opcode::Le),
+        Just(Opcode::Lt),
+        Just(Opcode::Merge),
+    ]
+}
+
+fn source_expr() -> impl Strategy<Value = String> {
+    // Integers stay small and `*` and `%` are left out, as overflowing
+    // operations and remainders of zero panic in both runtimes.
+    let leaf = prop_oneof![
+        prop_oneof![string_literal(), float_literal()].prop_map(|v| v.to_string()),
+        (0..100i64).prop_map(|v| v.to_string()),
+        prop_oneof![Just("null"), Just("true"), Just("false")].prop_map(String::from),
+        prop_oneof![Just("a"), Just("b"), Just("c")].prop_map(String::from),
+        prop_oneof![Just(".foo"), Just(".bar"), Just(".baz.qux[0]")].prop_map(String::from),
+    ];
+
+    leaf.prop_recursive(3, 32, 3, |inner| {
+        prop_oneof![
+            (inner.clone(), source_opcode(), inner.clone())
+                .prop_map(|(l, o, r)| format!("({} {} {})", l, o, r)),
+            (inner.clone(), inner.clone(), inner.clone(), inner.clone())
+                .prop_map(|(l, r, c, a)| format!("if {} == {} {{ {} }} else {{ {} }}", l, r, c, a)),
+            prop::collection::vec(inner.clone(), 0..3).prop_map(|v| format!("[{}]", v.join(", "))),
+            (inner.clone(), inner.clone())
+                .prop_map(|(a, b)| format!("{{ \"a\": {}, \"b\": {} }}", a, b)),
+            inner.clone().prop_map(|v| format!("!({} == true)", v)),
+            inner.clone().prop_map(|v| format!("(b = {})", v)),
+            inner.prop_map(|v| format!("(.foo = {})", v)),
+        ]
+    })
+}
+
 fn program(expr: Expr) -> Program {
     Program(vec![node(RootExpr::Expr(node(expr)))])
 }
@@ -283,6 +332,49 @@ proptest! {
                    "{}", source);
     }
 
+    #[test]
+    fn vm_resolves_like_expressions(exprs in prop::collection::vec(source_expr(), 1..4)) {
+        use vrl::{state, Runtime, Value};
+
+        let compiles = |source: &str| vrl::compile(source, &[], None).is_ok();
+
+        // Only statements that compile are relevant, either handling their
+        // errors or not, depending on whether they can fail.
+        let mut source = "a = 1\nb = \"two\"\nc = [3.0]\nerr = null\n".to_owned();
+        for expr in exprs {
+            let fallible = format!("{}_, err = {}\n", source, expr);
+            let infallible = format!("{}{}\n", source, expr);
+
+            if compiles(&fallible) {
+                source = fallible;
+            } else if compiles(&infallible) {
+                source = infallible;
+            }
+        }
+        source.push_str("[a, b, c, err]");
+
+        let program = vrl::compile(&source, &[], None).unwrap();
+        let vm = program.compile_to_vm().unwrap();
+
+        let target: Value = serde_json::from_str(
+            r#"{ "foo": "bar", "bar": 1, "baz": { "qux": [true] } }"#,
+        ).unwrap();
+        let timezone = Default::default();
+
+        let mut ast_target = target.clone();
+        let ast_result = Runtime::new(state::Runtime::default())
+            .resolve(&mut ast_target, &program, &timezone)
+            .map_err(|err| err.to_string());
+
+        let mut vm_target = target;
+        let vm_result = Runtime::new(state::Runtime::default())
+            .run_vm(&mut vm_target, &vm, &timezone)
+            .map_err(|err| err.to_string());
+
+        assert_eq!(ast_result, vm_result, "{}", source);
+        assert_eq!(ast_target, vm_target, "{}", source);
+    }
+
     #[test]
     fn if_parses(expr in if_statement()) {
         let expr = program(expr);
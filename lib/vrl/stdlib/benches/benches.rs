@@ -12,18 +12,31 @@ criterion_group!(
     targets = assert,
               assert_eq,
               ceil,
+              community_id,
               compact,
               contains,
+              crc32,
               decode_base64,
+              decode_gzip,
               decode_percent,
+              decode_zlib,
+              decrypt,
               // TODO: Cannot pass a Path to bench_function
               //del,
               downcase,
+              encode_base16,
               encode_base64,
+              encode_csv,
+              encode_gzip,
               encode_key_value,
               encode_json,
               encode_logfmt,
               encode_percent,
+              encode_protobuf,
+              encode_xml,
+              encode_yaml,
+              encode_zlib,
+              encrypt,
               ends_with,
               // TODO: Cannot pass a Path to bench_function
               //exists
@@ -36,17 +49,24 @@ criterion_group!(
               get,
               get_env_var,
               get_hostname,
+              hmac,
               includes,
               ip_aton,
               ip_cidr_contains,
+              ip_is_loopback,
+              ip_is_multicast,
+              ip_is_private,
               ip_ntoa,
               ip_subnet,
               ip_to_ipv6,
+              ip_version,
               ipv6_to_ipv4,
               is_array,
               is_boolean,
               is_float,
               is_integer,
+              is_ipv4,
+              is_ipv6,
               is_null,
               is_nullish,
               is_object,
@@ -68,6 +88,7 @@ criterion_group!(
               parse_aws_alb_log,
               parse_aws_cloudwatch_log_subscription_message,
               parse_aws_vpc_flow_log,
+              parse_cef,
               parse_common_log,
               parse_csv,
               parse_duration,
@@ -75,9 +96,12 @@ criterion_group!(
               parse_grok,
               parse_key_value,
               parse_klog,
+              parse_leef,
               parse_int,
               parse_json,
               parse_nginx_log,
+              parse_port_name,
+              parse_protobuf,
               parse_query_string,
               parse_regex,
               parse_regex_all,
@@ -88,6 +112,7 @@ criterion_group!(
               parse_url,
               parse_user_agent,
               parse_xml,
+              parse_yaml,
               push,
               redact,
               remove,
@@ -120,7 +145,8 @@ criterion_group!(
               //unnest
               // TODO: value is dynamic so we cannot assert equality
               //uuidv4,
-              upcase
+              upcase,
+              xxhash
 );
 criterion_main!(benches);
 
@@ -160,6 +186,32 @@ bench_function! {
     }
 }
 
+bench_function! {
+    community_id => vrl_stdlib::CommunityId;
+
+    tcp {
+        args: func_args![
+            source_ip: "128.232.110.120",
+            destination_ip: "66.35.250.204",
+            protocol: 6,
+            source_port: 34855,
+            destination_port: 80,
+        ],
+        want: Ok("1:LQU9qZlK+B5F3KDmev6m5PMibrg="),
+    }
+
+    icmp {
+        args: func_args![
+            source_ip: "192.168.0.89",
+            destination_ip: "192.168.0.1",
+            protocol: 1,
+            source_port: 8,
+            destination_port: 0,
+        ],
+        want: Ok("1:X0snYXpgwiv9TZtqg64sgzUn6Dk="),
+    }
+}
+
 bench_function! {
     compact => vrl_stdlib::Compact;
 
@@ -192,6 +244,15 @@ bench_function! {
     }
 }
 
+bench_function! {
+    crc32 => vrl_stdlib::Crc32;
+
+    literal {
+        args: func_args![value: "foo"],
+        want: Ok(2356372769i64),
+    }
+}
+
 bench_function! {
     decode_base64 => vrl_stdlib::DecodeBase64;
 
@@ -201,6 +262,15 @@ bench_function! {
     }
 }
 
+bench_function! {
+    decode_gzip => vrl_stdlib::DecodeGzip;
+
+    literal {
+        args: func_args![value: &b"\x1f\x8b\x08\x00\x00\x00\x00\x00\x00\xff+\xc8IM,NUH\xcdK\xceOIU\xc8M\x05\x00\x8e\x11\xe2\xf7\x10\x00\x00\x00"[..]],
+        want: Ok("please encode me"),
+    }
+}
+
 bench_function! {
     decode_percent => vrl_stdlib::DecodePercent;
 
@@ -210,6 +280,39 @@ bench_function! {
     }
 }
 
+bench_function! {
+    decode_zlib => vrl_stdlib::DecodeZlib;
+
+    literal {
+        args: func_args![value: &b"x\x9c+\xc8IM,NUH\xcdK\xceOIU\xc8M\x05\x004E\x05\xfb"[..]],
+        want: Ok("please encode me"),
+    }
+}
+
+bench_function! {
+    decrypt => vrl_stdlib::Decrypt;
+
+    aes_256_cbc_pkcs7 {
+        args: func_args![
+            ciphertext: &b"'\xb3\x82\xc6\x8e\x04kp\x8c1\xb9\x10rP\xa0\xb5"[..],
+            algorithm: "AES-256-CBC-PKCS7",
+            key: "01234567890123456789012345678912",
+            iv: "1234567890123456",
+        ],
+        want: Ok("data"),
+    }
+
+    aes_256_gcm {
+        args: func_args![
+            ciphertext: &b"\xb4\x0b\xad\x1f\xa3|*t\xcd?w\xf2\xe5\x92\x8d=\xba\r\xdc\\"[..],
+            algorithm: "AES-256-GCM",
+            key: "01234567890123456789012345678912",
+            iv: "123456789012",
+        ],
+        want: Ok("data"),
+    }
+}
+
 bench_function! {
     downcase => vrl_stdlib::Downcase;
 
@@ -219,6 +322,15 @@ bench_function! {
     }
 }
 
+bench_function! {
+    encode_base16 => vrl_stdlib::EncodeBase16;
+
+    literal {
+        args: func_args![value: "some string value"],
+        want: Ok("736f6d6520737472696e672076616c7565"),
+    }
+}
+
 bench_function! {
     encode_base64 => vrl_stdlib::EncodeBase64;
 
@@ -228,6 +340,24 @@ bench_function! {
     }
 }
 
+bench_function! {
+    encode_csv => vrl_stdlib::EncodeCsv;
+
+    literal {
+        args: func_args![value: value!(["foo", 1, true, "foo \", bar"])],
+        want: Ok(r#"foo,1,true,"foo "", bar""#),
+    }
+}
+
+bench_function! {
+    encode_gzip => vrl_stdlib::EncodeGzip;
+
+    literal {
+        args: func_args![value: "please encode me"],
+        want: Ok(&b"\x1f\x8b\x08\x00\x00\x00\x00\x00\x00\xff+\xc8IM,NUH\xcdK\xceOIU\xc8M\x05\x00\x8e\x11\xe2\xf7\x10\x00\x00\x00"[..]),
+    }
+}
+
 bench_function! {
     encode_key_value => vrl_stdlib::EncodeKeyValue;
 
@@ -329,6 +459,70 @@ bench_function! {
     }
 }
 
+bench_function! {
+    encode_protobuf => vrl_stdlib::EncodeProtobuf;
+
+    message {
+        args: func_args![
+            value: value!({name: "Someone", id: 1234, email: "someone@example.com"}),
+            desc_file: "tests/data/protobuf/test_protobuf.desc",
+            message_type: "test_protobuf.Person",
+        ],
+        want: Ok(&b"\x0a\x07Someone\x10\xd2\x09\x1a\x13someone@example.com"[..]),
+    }
+}
+
+bench_function! {
+    encode_xml => vrl_stdlib::EncodeXml;
+
+    object {
+        args: func_args![value: value!({"book": {"@category": "CHILDREN", "title": {"@lang": "en", "text": "Harry Potter"}, "year": 2005}})],
+        want: Ok(r#"<book category="CHILDREN"><title lang="en">Harry Potter</title><year>2005</year></book>"#),
+    }
+}
+
+bench_function! {
+    encode_yaml => vrl_stdlib::EncodeYaml;
+
+    map {
+        args: func_args![value: value![{"field": "value"}]],
+        want: Ok("field: value"),
+    }
+}
+
+bench_function! {
+    encode_zlib => vrl_stdlib::EncodeZlib;
+
+    literal {
+        args: func_args![value: "please encode me"],
+        want: Ok(&b"x\x9c+\xc8IM,NUH\xcdK\xceOIU\xc8M\x05\x004E\x05\xfb"[..]),
+    }
+}
+
+bench_function! {
+    encrypt => vrl_stdlib::Encrypt;
+
+    aes_256_cbc_pkcs7 {
+        args: func_args![
+            plaintext: "data",
+            algorithm: "AES-256-CBC-PKCS7",
+            key: "01234567890123456789012345678912",
+            iv: "1234567890123456",
+        ],
+        want: Ok(&b"'\xb3\x82\xc6\x8e\x04kp\x8c1\xb9\x10rP\xa0\xb5"[..]),
+    }
+
+    aes_256_gcm {
+        args: func_args![
+            plaintext: "data",
+            algorithm: "AES-256-GCM",
+            key: "01234567890123456789012345678912",
+            iv: "123456789012",
+        ],
+        want: Ok(&b"\xb4\x0b\xad\x1f\xa3|*t\xcd?w\xf2\xe5\x92\x8d=\xba\r\xdc\\"[..]),
+    }
+}
+
 bench_function! {
     ends_with => vrl_stdlib::EndsWith;
 
@@ -457,6 +651,15 @@ bench_function! {
     }
 }
 
+bench_function! {
+    hmac => vrl_stdlib::Hmac;
+
+    default {
+        args: func_args![value: "The quick brown fox jumps over the lazy dog", key: "key"],
+        want: Ok("f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"),
+    }
+}
+
 bench_function! {
     includes => vrl_stdlib::Includes;
 
@@ -508,6 +711,48 @@ bench_function! {
     }
 }
 
+bench_function! {
+    ip_is_loopback => vrl_stdlib::IpIsLoopback;
+
+    ipv4 {
+        args: func_args![value: "127.0.0.1"],
+        want: Ok(true),
+    }
+
+    ipv6 {
+        args: func_args![value: "::1"],
+        want: Ok(true),
+    }
+}
+
+bench_function! {
+    ip_is_multicast => vrl_stdlib::IpIsMulticast;
+
+    ipv4 {
+        args: func_args![value: "224.0.0.251"],
+        want: Ok(true),
+    }
+
+    ipv6 {
+        args: func_args![value: "ff02::fb"],
+        want: Ok(true),
+    }
+}
+
+bench_function! {
+    ip_is_private => vrl_stdlib::IpIsPrivate;
+
+    ipv4 {
+        args: func_args![value: "192.168.10.32"],
+        want: Ok(true),
+    }
+
+    ipv6 {
+        args: func_args![value: "fd12:3456:789a:1::1"],
+        want: Ok(true),
+    }
+}
+
 bench_function! {
     ip_ntoa => vrl_stdlib::IpNtoa;
 
@@ -555,6 +800,20 @@ bench_function! {
     }
 }
 
+bench_function! {
+    ip_version => vrl_stdlib::IpVersion;
+
+    ipv4 {
+        args: func_args![value: "192.168.0.1"],
+        want: Ok(4),
+    }
+
+    ipv6 {
+        args: func_args![value: "2404:6800:4003:c02::64"],
+        want: Ok(6),
+    }
+}
+
 bench_function! {
     ipv6_to_ipv4 => vrl_stdlib::Ipv6ToIpV4;
 
@@ -625,6 +884,34 @@ bench_function! {
     }
 }
 
+bench_function! {
+    is_ipv4 => vrl_stdlib::IsIpv4;
+
+    ipv4 {
+        args: func_args![value: "192.168.0.1"],
+        want: Ok(true),
+    }
+
+    ipv6 {
+        args: func_args![value: "2404:6800:4003:c02::64"],
+        want: Ok(false),
+    }
+}
+
+bench_function! {
+    is_ipv6 => vrl_stdlib::IsIpv6;
+
+    ipv6 {
+        args: func_args![value: "2404:6800:4003:c02::64"],
+        want: Ok(true),
+    }
+
+    ipv4 {
+        args: func_args![value: "192.168.0.1"],
+        want: Ok(false),
+    }
+}
+
 bench_function! {
     is_null => vrl_stdlib::IsNull;
 
@@ -1160,6 +1447,26 @@ bench_function! {
     }
 }
 
+bench_function! {
+    parse_cef => vrl_stdlib::ParseCef;
+
+    default {
+        args: func_args![value: "CEF:0|Security|threatmanager|1.0|100|worm stopped|10|src=10.0.0.1 dst=2.1.2.2 msg=Detected a threat"],
+        want: Ok(value!({
+            "cefVersion": "0",
+            "deviceVendor": "Security",
+            "deviceProduct": "threatmanager",
+            "deviceVersion": "1.0",
+            "deviceEventClassId": "100",
+            "name": "worm stopped",
+            "severity": "10",
+            "src": "10.0.0.1",
+            "dst": "2.1.2.2",
+            "msg": "Detected a threat",
+        })),
+    }
+}
+
 bench_function! {
     parse_common_log => vrl_stdlib::ParseCommonLog;
 
@@ -1306,6 +1613,24 @@ bench_function! {
     }
 }
 
+bench_function! {
+    parse_leef => vrl_stdlib::ParseLeef;
+
+    default {
+        args: func_args![value: "LEEF:1.0|Microsoft|MSExchange|4.0 SP1|15345|src=192.0.2.0\tdst=172.50.123.1\tsev=5"],
+        want: Ok(value!({
+            "leefVersion": "1.0",
+            "vendor": "Microsoft",
+            "productName": "MSExchange",
+            "productVersion": "4.0 SP1",
+            "eventId": "15345",
+            "src": "192.0.2.0",
+            "dst": "172.50.123.1",
+            "sev": "5",
+        })),
+    }
+}
+
 bench_function! {
     parse_nginx_log => vrl_stdlib::ParseNginxLog;
 
@@ -1349,6 +1674,33 @@ bench_function! {
     }
 }
 
+bench_function! {
+    parse_port_name => vrl_stdlib::ParsePortName;
+
+    name {
+        args: func_args![value: "https"],
+        want: Ok(443),
+    }
+
+    number {
+        args: func_args![value: "8443"],
+        want: Ok(8443),
+    }
+}
+
+bench_function! {
+    parse_protobuf => vrl_stdlib::ParseProtobuf;
+
+    message {
+        args: func_args![
+            value: &b"\x0a\x07Someone\x10\xd2\x09\x1a\x13someone@example.com"[..],
+            desc_file: "tests/data/protobuf/test_protobuf.desc",
+            message_type: "test_protobuf.Person",
+        ],
+        want: Ok(value!({name: "Someone", id: 1234, email: "someone@example.com"})),
+    }
+}
+
 bench_function! {
     parse_query_string => vrl_stdlib::ParseQueryString;
 
@@ -1724,6 +2076,28 @@ bench_function! {
         args: func_args![ value: "true" ],
         want: Err("unable to parse xml: unknown token at 1:1")
     }
+
+    windows_event {
+        args: func_args![
+            value: r#"<Event><System><EventID>4624</EventID><Channel>Security</Channel></System><EventData><Data Name="TargetUserName">bob</Data><Data Name="LogonType">3</Data></EventData></Event>"#,
+            windows_event: true,
+        ],
+        want: Ok(value!({
+            "Event": {
+                "System": { "EventID": 4624, "Channel": "Security" },
+                "EventData": { "TargetUserName": "bob", "LogonType": 3 },
+            }
+        }))
+    }
+}
+
+bench_function! {
+    parse_yaml => vrl_stdlib::ParseYaml;
+
+    map {
+        args: func_args![value: "field: value\nlist:\n  - 1\n  - true"],
+        want: Ok(value!({field: "value", list: [1, true]})),
+    }
 }
 
 bench_function! {
@@ -2196,3 +2570,12 @@ bench_function! {
         want: Ok("FOO")
     }
 }
+
+bench_function! {
+    xxhash => vrl_stdlib::Xxhash;
+
+    default {
+        args: func_args![value: "foo"],
+        want: Ok(3728699739546630719i64),
+    }
+}
@@ -0,0 +1,374 @@
+use ::sha1::{Digest, Sha1};
+use std::convert::TryFrom;
+use std::net::IpAddr;
+use vrl::prelude::*;
+
+const ICMP: u8 = 1;
+const TCP: u8 = 6;
+const UDP: u8 = 17;
+const ICMPV6: u8 = 58;
+const SCTP: u8 = 132;
+
+#[derive(Clone, Copy, Debug)]
+pub struct CommunityId;
+
+impl Function for CommunityId {
+    fn identifier(&self) -> &'static str {
+        "community_id"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "source_ip",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "destination_ip",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "protocol",
+                kind: kind::INTEGER,
+                required: true,
+            },
+            Parameter {
+                keyword: "source_port",
+                kind: kind::INTEGER,
+                required: false,
+            },
+            Parameter {
+                keyword: "destination_port",
+                kind: kind::INTEGER,
+                required: false,
+            },
+            Parameter {
+                keyword: "seed",
+                kind: kind::INTEGER,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "TCP",
+                source: r#"community_id!(source_ip: "128.232.110.120", destination_ip: "66.35.250.204", protocol: 6, source_port: 34855, destination_port: 80)"#,
+                result: Ok("1:LQU9qZlK+B5F3KDmev6m5PMibrg="),
+            },
+            Example {
+                title: "other direction",
+                source: r#"community_id!(source_ip: "66.35.250.204", destination_ip: "128.232.110.120", protocol: 6, source_port: 80, destination_port: 34855)"#,
+                result: Ok("1:LQU9qZlK+B5F3KDmev6m5PMibrg="),
+            },
+            Example {
+                title: "seed",
+                source: r#"community_id!(source_ip: "128.232.110.120", destination_ip: "66.35.250.204", protocol: 6, source_port: 34855, destination_port: 80, seed: 1)"#,
+                result: Ok("1:3V71V58M3Ksw/yuFALMcW0LAHvc="),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let source_ip = arguments.required("source_ip");
+        let destination_ip = arguments.required("destination_ip");
+        let protocol = arguments.required("protocol");
+        let source_port = arguments.optional("source_port");
+        let destination_port = arguments.optional("destination_port");
+        let seed = arguments.optional("seed");
+
+        Ok(Box::new(CommunityIdFn {
+            source_ip,
+            destination_ip,
+            protocol,
+            source_port,
+            destination_port,
+            seed,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CommunityIdFn {
+    source_ip: Box<dyn Expression>,
+    destination_ip: Box<dyn Expression>,
+    protocol: Box<dyn Expression>,
+    source_port: Option<Box<dyn Expression>>,
+    destination_port: Option<Box<dyn Expression>>,
+    seed: Option<Box<dyn Expression>>,
+}
+
+impl Expression for CommunityIdFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let source_ip = resolve_ip(self.source_ip.as_ref(), ctx)?;
+        let destination_ip = resolve_ip(self.destination_ip.as_ref(), ctx)?;
+
+        let protocol = self.protocol.resolve(ctx)?.try_integer()?;
+        let protocol = u8::try_from(protocol)
+            .map_err(|_| format!("protocol must be between 0 and 255, got {}", protocol))?;
+
+        let ports = match (&self.source_port, &self.destination_port) {
+            (Some(source), Some(destination)) => Some((
+                resolve_u16("source_port", source.as_ref(), ctx)?,
+                resolve_u16("destination_port", destination.as_ref(), ctx)?,
+            )),
+            (None, None) => None,
+            _ => return Err("source_port and destination_port must be given together".into()),
+        };
+
+        let seed = match &self.seed {
+            Some(seed) => resolve_u16("seed", seed.as_ref(), ctx)?,
+            None => 0,
+        };
+
+        let flow = Flow {
+            source: (source_ip, ports.map(|(port, _)| port)),
+            destination: (destination_ip, ports.map(|(_, port)| port)),
+            protocol,
+        };
+
+        Ok(flow.community_id(seed)?.into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().bytes()
+    }
+}
+
+fn resolve_ip(expr: &dyn Expression, ctx: &mut Context) -> Result<IpAddr> {
+    let value = expr.resolve(ctx)?;
+
+    Ok(value
+        .try_bytes_utf8_lossy()?
+        .parse()
+        .map_err(|err| format!("unable to parse IP address: {}", err))?)
+}
+
+fn resolve_u16(name: &str, expr: &dyn Expression, ctx: &mut Context) -> Result<u16> {
+    let value = expr.resolve(ctx)?.try_integer()?;
+
+    Ok(u16::try_from(value)
+        .map_err(|_| format!("{} must be between 0 and 65535, got {}", name, value))?)
+}
+
+/// The endpoints and protocol of a network flow.
+struct Flow {
+    source: (IpAddr, Option<u16>),
+    destination: (IpAddr, Option<u16>),
+    protocol: u8,
+}
+
+impl Flow {
+    /// Calculates the version 1 Community ID of the flow, which is the same
+    /// for both of its directions.
+    ///
+    /// For ICMP and ICMPv6, the ports are the message type and code.
+    fn community_id(self, seed: u16) -> std::result::Result<String, String> {
+        let Flow {
+            mut source,
+            mut destination,
+            protocol,
+        } = self;
+
+        let mut one_way = false;
+        if let (ICMP, Some(kind)) | (ICMPV6, Some(kind)) = (protocol, source.1) {
+            // ICMP messages with a matching reply, such as echo requests, use
+            // the reply type as destination port so both directions are the
+            // same flow.
+            match icmp_reply_type(protocol, kind) {
+                Some(reply) => destination.1 = Some(reply),
+                None => one_way = true,
+            }
+        }
+
+        if !one_way && source > destination {
+            std::mem::swap(&mut source, &mut destination);
+        }
+
+        let mut hasher = Sha1::new();
+        hasher.update(seed.to_be_bytes());
+
+        match (source.0, destination.0) {
+            (IpAddr::V4(source), IpAddr::V4(destination)) => {
+                hasher.update(source.octets());
+                hasher.update(destination.octets());
+            }
+            (IpAddr::V6(source), IpAddr::V6(destination)) => {
+                hasher.update(source.octets());
+                hasher.update(destination.octets());
+            }
+            _ => return Err("source_ip and destination_ip must be of the same IP version".into()),
+        }
+
+        // The protocol is followed by a byte of padding.
+        hasher.update([protocol, 0]);
+
+        if let (Some(source), Some(destination)) = (source.1, destination.1) {
+            if matches!(protocol, ICMP | TCP | UDP | ICMPV6 | SCTP) {
+                hasher.update(source.to_be_bytes());
+                hasher.update(destination.to_be_bytes());
+            }
+        }
+
+        Ok(format!("1:{}", base64::encode(hasher.finalize())))
+    }
+}
+
+fn icmp_reply_type(protocol: u8, kind: u16) -> Option<u16> {
+    let reply = match (protocol, kind) {
+        // Echo, router, timestamp, information and address mask messages.
+        (ICMP, 8) => 0,
+        (ICMP, 0) => 8,
+        (ICMP, 10) => 9,
+        (ICMP, 9) => 10,
+        (ICMP, 13) => 14,
+        (ICMP, 14) => 13,
+        (ICMP, 15) => 16,
+        (ICMP, 16) => 15,
+        (ICMP, 17) => 18,
+        (ICMP, 18) => 17,
+
+        // Echo, multicast listener, router, neighbor, node information and
+        // home agent address discovery messages.
+        (ICMPV6, 128) => 129,
+        (ICMPV6, 129) => 128,
+        (ICMPV6, 130) => 131,
+        (ICMPV6, 131) => 130,
+        (ICMPV6, 133) => 134,
+        (ICMPV6, 134) => 133,
+        (ICMPV6, 135) => 136,
+        (ICMPV6, 136) => 135,
+        (ICMPV6, 139) => 140,
+        (ICMPV6, 140) => 139,
+        (ICMPV6, 144) => 145,
+        (ICMPV6, 145) => 144,
+        _ => return None,
+    };
+
+    Some(reply)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        community_id => CommunityId;
+
+        tcp {
+            args: func_args![
+                source_ip: "128.232.110.120",
+                destination_ip: "66.35.250.204",
+                protocol: 6,
+                source_port: 34855,
+                destination_port: 80,
+            ],
+            want: Ok("1:LQU9qZlK+B5F3KDmev6m5PMibrg="),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        udp {
+            args: func_args![
+                source_ip: "1.2.3.4",
+                destination_ip: "5.6.7.8",
+                protocol: 17,
+                source_port: 53,
+                destination_port: 53,
+            ],
+            want: Ok("1:XtZWkJ8rNa7zoxie2V3yS1soMxY="),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        icmp_request {
+            args: func_args![
+                source_ip: "192.168.0.89",
+                destination_ip: "192.168.0.1",
+                protocol: 1,
+                source_port: 8,
+                destination_port: 0,
+            ],
+            want: Ok("1:X0snYXpgwiv9TZtqg64sgzUn6Dk="),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        icmp_reply {
+            args: func_args![
+                source_ip: "192.168.0.1",
+                destination_ip: "192.168.0.89",
+                protocol: 1,
+                source_port: 0,
+                destination_port: 0,
+            ],
+            want: Ok("1:X0snYXpgwiv9TZtqg64sgzUn6Dk="),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        icmp_one_way {
+            args: func_args![
+                source_ip: "192.168.0.89",
+                destination_ip: "192.168.0.1",
+                protocol: 1,
+                source_port: 3,
+                destination_port: 1,
+            ],
+            want: Ok("1:LXCfhMUFbYXe+CEMrQuZCbiLY3o="),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        icmpv6 {
+            args: func_args![
+                source_ip: "fe80::200:86ff:fe05:80da",
+                destination_ip: "fe80::260:97ff:fe07:69ea",
+                protocol: 58,
+                source_port: 135,
+                destination_port: 0,
+            ],
+            want: Ok("1:dGHyGvjMfljg6Bppwm3bg0LO8TY="),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        without_ports {
+            args: func_args![source_ip: "1.2.3.4", destination_ip: "5.6.7.8", protocol: 47],
+            want: Ok("1:+mluqz76jObh6dJLwnhX08n5St4="),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        mixed_ip_versions {
+            args: func_args![source_ip: "1.2.3.4", destination_ip: "::1", protocol: 47],
+            want: Err("source_ip and destination_ip must be of the same IP version"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        single_port {
+            args: func_args![source_ip: "1.2.3.4", destination_ip: "5.6.7.8", protocol: 6, source_port: 80],
+            want: Err("source_port and destination_port must be given together"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        invalid_port {
+            args: func_args![
+                source_ip: "1.2.3.4",
+                destination_ip: "5.6.7.8",
+                protocol: 6,
+                source_port: 80,
+                destination_port: 65536,
+            ],
+            want: Err("destination_port must be between 0 and 65535, got 65536"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        invalid_protocol {
+            args: func_args![source_ip: "1.2.3.4", destination_ip: "5.6.7.8", protocol: -1],
+            want: Err("protocol must be between 0 and 255, got -1"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+    ];
+}
@@ -0,0 +1,75 @@
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Crc32;
+
+impl Function for Crc32 {
+    fn identifier(&self) -> &'static str {
+        "crc32"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "crc32",
+            source: r#"crc32("foobar")"#,
+            result: Ok("2666930069"),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(Box::new(Crc32Fn { value }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Crc32Fn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for Crc32Fn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?.try_bytes()?;
+
+        Ok(i64::from(crc32fast::hash(&value)).into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().infallible().integer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        crc32 => Crc32;
+
+        crc32 {
+            args: func_args![value: "foo"],
+            want: Ok(2356372769i64),
+            tdef: TypeDef::new().infallible().integer(),
+        }
+
+        empty {
+            args: func_args![value: ""],
+            want: Ok(0),
+            tdef: TypeDef::new().infallible().integer(),
+        }
+    ];
+}
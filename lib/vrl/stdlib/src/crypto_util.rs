@@ -0,0 +1,104 @@
+use aes::{Aes128, Aes192, Aes256};
+use aes_gcm::aead::{
+    generic_array::{typenum::Unsigned, ArrayLength, GenericArray},
+    Aead, NewAead,
+};
+use aes_gcm::{Aes128Gcm, Aes256Gcm};
+use block_modes::{
+    block_padding::Pkcs7,
+    cipher::{BlockCipher, BlockDecrypt, BlockEncrypt, NewBlockCipher},
+    BlockMode, Cbc,
+};
+use vrl::prelude::*;
+
+/// The symmetric encryption algorithms supported by the `encrypt` and
+/// `decrypt` functions.
+pub(crate) fn algorithms() -> Vec<Value> {
+    vec![
+        value!("AES-128-GCM"),
+        value!("AES-256-GCM"),
+        value!("AES-128-CBC-PKCS7"),
+        value!("AES-192-CBC-PKCS7"),
+        value!("AES-256-CBC-PKCS7"),
+    ]
+}
+
+pub(crate) fn encrypt(
+    algorithm: &[u8],
+    plaintext: &[u8],
+    key: &[u8],
+    iv: &[u8],
+) -> std::result::Result<Vec<u8>, String> {
+    let ciphertext = match algorithm {
+        b"AES-128-GCM" => gcm::<Aes128Gcm>(key, iv)?
+            .encrypt(nonce(iv), plaintext)
+            .ok(),
+        b"AES-256-GCM" => gcm::<Aes256Gcm>(key, iv)?
+            .encrypt(nonce(iv), plaintext)
+            .ok(),
+        b"AES-128-CBC-PKCS7" => Some(cbc::<Aes128>(key, iv)?.encrypt_vec(plaintext)),
+        b"AES-192-CBC-PKCS7" => Some(cbc::<Aes192>(key, iv)?.encrypt_vec(plaintext)),
+        b"AES-256-CBC-PKCS7" => Some(cbc::<Aes256>(key, iv)?.encrypt_vec(plaintext)),
+        _ => unreachable!("enum invariant"),
+    };
+
+    ciphertext.ok_or_else(|| "unable to encrypt value".to_owned())
+}
+
+pub(crate) fn decrypt(
+    algorithm: &[u8],
+    ciphertext: &[u8],
+    key: &[u8],
+    iv: &[u8],
+) -> std::result::Result<Vec<u8>, String> {
+    let plaintext = match algorithm {
+        b"AES-128-GCM" => gcm::<Aes128Gcm>(key, iv)?
+            .decrypt(nonce(iv), ciphertext)
+            .ok(),
+        b"AES-256-GCM" => gcm::<Aes256Gcm>(key, iv)?
+            .decrypt(nonce(iv), ciphertext)
+            .ok(),
+        b"AES-128-CBC-PKCS7" => cbc::<Aes128>(key, iv)?.decrypt_vec(ciphertext).ok(),
+        b"AES-192-CBC-PKCS7" => cbc::<Aes192>(key, iv)?.decrypt_vec(ciphertext).ok(),
+        b"AES-256-CBC-PKCS7" => cbc::<Aes256>(key, iv)?.decrypt_vec(ciphertext).ok(),
+        _ => unreachable!("enum invariant"),
+    };
+
+    // The reason decryption failed isn't reported, as it could reveal
+    // details about the key.
+    plaintext.ok_or_else(|| "unable to decrypt value".to_owned())
+}
+
+fn gcm<C: NewAead + Aead>(key: &[u8], iv: &[u8]) -> std::result::Result<C, String> {
+    check_length("key", key, C::KeySize::USIZE)?;
+    check_length("iv", iv, C::NonceSize::USIZE)?;
+
+    Ok(C::new(GenericArray::from_slice(key)))
+}
+
+fn nonce<N: ArrayLength<u8>>(iv: &[u8]) -> &GenericArray<u8, N> {
+    GenericArray::from_slice(iv)
+}
+
+fn cbc<C>(key: &[u8], iv: &[u8]) -> std::result::Result<Cbc<C, Pkcs7>, String>
+where
+    C: BlockCipher + NewBlockCipher + BlockEncrypt + BlockDecrypt,
+{
+    check_length("key", key, C::KeySize::USIZE)?;
+    check_length("iv", iv, C::BlockSize::USIZE)?;
+
+    Ok(Cbc::new_from_slices(key, iv).expect("lengths checked"))
+}
+
+fn check_length(name: &str, value: &[u8], expected: usize) -> std::result::Result<(), String> {
+    if value.len() == expected {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} must be {} bytes for this algorithm, got {}",
+            name,
+            expected,
+            value.len()
+        ))
+    }
+}
@@ -0,0 +1,131 @@
+use flate2::read::MultiGzDecoder;
+use std::io::Read;
+use vrl::prelude::*;
+
+/// The default maximum size of the decompressed value, 10 MiB, so that a small
+/// compressed value can't exhaust the memory of the process.
+const DEFAULT_MAX_SIZE: i64 = 10 * 1024 * 1024;
+
+#[derive(Clone, Copy, Debug)]
+pub struct DecodeGzip;
+
+impl Function for DecodeGzip {
+    fn identifier(&self) -> &'static str {
+        "decode_gzip"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "max_size",
+                kind: kind::INTEGER,
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let max_size = arguments
+            .optional("max_size")
+            .unwrap_or(expr!(DEFAULT_MAX_SIZE));
+
+        Ok(Box::new(DecodeGzipFn { value, max_size }))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "demo string",
+            source: r#"decode_gzip!(decode_base64!("H4sIAAAAAAAC/yvISU0sTlVISU3OT0lVyE0FAJsZ870QAAAA"))"#,
+            result: Ok("please decode me"),
+        }]
+    }
+}
+
+#[derive(Clone, Debug)]
+struct DecodeGzipFn {
+    value: Box<dyn Expression>,
+    max_size: Box<dyn Expression>,
+}
+
+impl Expression for DecodeGzipFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?.try_bytes()?;
+        let max_size = self.max_size.resolve(ctx)?.try_integer()?;
+        if max_size < 0 {
+            return Err("max_size must not be negative".into());
+        }
+        let max_size = max_size as u64;
+
+        // One byte more than the maximum is read to find out whether the
+        // decompressed value is larger than allowed.
+        let mut buffer = Vec::new();
+        match MultiGzDecoder::new(value.as_ref())
+            .take(max_size.saturating_add(1))
+            .read_to_end(&mut buffer)
+        {
+            Ok(_) if buffer.len() as u64 > max_size => Err(format!(
+                "decompressed value is larger than the maximum size of {} bytes",
+                max_size
+            )
+            .into()),
+            Ok(_) => Ok(buffer.into()),
+            Err(err) => Err(format!("unable to decode value with gzip decoder: {}", err).into()),
+        }
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().bytes().fallible()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ENCODED: &[u8] = b"\x1f\x8b\x08\x00\x00\x00\x00\x00\x00\xff+\xc8IM,NUH\xcdK\xceOIU\xc8M\x05\x00\x8e\x11\xe2\xf7\x10\x00\x00\x00";
+
+    test_function![
+        decode_gzip => DecodeGzip;
+
+        valid {
+            args: func_args![value: value!(ENCODED)],
+            want: Ok(value!("please encode me")),
+            tdef: TypeDef::new().bytes().fallible(),
+        }
+
+        max_size {
+            args: func_args![value: value!(ENCODED), max_size: 16],
+            want: Ok(value!("please encode me")),
+            tdef: TypeDef::new().bytes().fallible(),
+        }
+
+        too_large {
+            args: func_args![value: value!(ENCODED), max_size: 15],
+            want: Err("decompressed value is larger than the maximum size of 15 bytes"),
+            tdef: TypeDef::new().bytes().fallible(),
+        }
+
+        negative_max_size {
+            args: func_args![value: value!(ENCODED), max_size: -1],
+            want: Err("max_size must not be negative"),
+            tdef: TypeDef::new().bytes().fallible(),
+        }
+
+        invalid {
+            args: func_args![value: value!("please encode me")],
+            want: Err("unable to decode value with gzip decoder: invalid gzip header"),
+            tdef: TypeDef::new().bytes().fallible(),
+        }
+    ];
+}
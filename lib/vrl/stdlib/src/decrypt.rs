@@ -0,0 +1,140 @@
+use crate::crypto_util;
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Decrypt;
+
+impl Function for Decrypt {
+    fn identifier(&self) -> &'static str {
+        "decrypt"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "ciphertext",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "algorithm",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "key",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "iv",
+                kind: kind::BYTES,
+                required: true,
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let ciphertext = arguments.required("ciphertext");
+        let algorithm = arguments
+            .required_enum("algorithm", &crypto_util::algorithms())?
+            .try_bytes()
+            .expect("algorithm not bytes");
+        let key = arguments.required("key");
+        let iv = arguments.required("iv");
+
+        Ok(Box::new(DecryptFn {
+            ciphertext,
+            algorithm,
+            key,
+            iv,
+        }))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "AES-256-CBC-PKCS7",
+                source: r#"decrypt!(decode_base64!("J7OCxo4Ea3CMMbkQclCgtQ=="), "AES-256-CBC-PKCS7", key: "01234567890123456789012345678912", iv: "1234567890123456")"#,
+                result: Ok("data"),
+            },
+            Example {
+                title: "AES-256-GCM",
+                source: r#"decrypt!(decode_base64!("tAutH6N8KnTNP3fy5ZKNPboN3Fw="), "AES-256-GCM", key: "01234567890123456789012345678912", iv: "123456789012")"#,
+                result: Ok("data"),
+            },
+        ]
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DecryptFn {
+    ciphertext: Box<dyn Expression>,
+    algorithm: Bytes,
+    key: Box<dyn Expression>,
+    iv: Box<dyn Expression>,
+}
+
+impl Expression for DecryptFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let ciphertext = self.ciphertext.resolve(ctx)?.try_bytes()?;
+        let key = self.key.resolve(ctx)?.try_bytes()?;
+        let iv = self.iv.resolve(ctx)?.try_bytes()?;
+
+        Ok(crypto_util::decrypt(&self.algorithm, &ciphertext, &key, &iv)?.into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().bytes()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const CBC_CIPHERTEXT: &[u8] = b"'\xb3\x82\xc6\x8e\x04kp\x8c1\xb9\x10rP\xa0\xb5";
+    const GCM_CIPHERTEXT: &[u8] = b"\xb4\x0b\xad\x1f\xa3|*t\xcd?w\xf2\xe5\x92\x8d=\xba\r\xdc\\";
+
+    test_function![
+        decrypt => Decrypt;
+
+        aes_256_cbc_pkcs7 {
+            args: func_args![
+                ciphertext: value!(CBC_CIPHERTEXT),
+                algorithm: "AES-256-CBC-PKCS7",
+                key: "01234567890123456789012345678912",
+                iv: "1234567890123456",
+            ],
+            want: Ok(value!("data")),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        aes_256_gcm {
+            args: func_args![
+                ciphertext: value!(GCM_CIPHERTEXT),
+                algorithm: "AES-256-GCM",
+                key: "01234567890123456789012345678912",
+                iv: "123456789012",
+            ],
+            want: Ok(value!("data")),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        wrong_key {
+            args: func_args![
+                ciphertext: value!(GCM_CIPHERTEXT),
+                algorithm: "AES-256-GCM",
+                key: "98765432109876543210987654321098",
+                iv: "123456789012",
+            ],
+            want: Err("unable to decrypt value"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+    ];
+}
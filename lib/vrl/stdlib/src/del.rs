@@ -107,6 +107,13 @@ impl Expression for DelFn {
                 .unwrap_or(Value::Null));
         }
 
+        if self.query.is_metadata() {
+            return Ok(ctx
+                .target_mut()
+                .remove_metadata(path, false)?
+                .unwrap_or(Value::Null));
+        }
+
         if let Some(ident) = self.query.variable_ident() {
             return match ctx.state_mut().variable_mut(ident) {
                 Some(value) => {
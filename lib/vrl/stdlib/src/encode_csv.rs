@@ -0,0 +1,178 @@
+use chrono::SecondsFormat;
+use csv::{Terminator, WriterBuilder};
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct EncodeCsv;
+
+impl Function for EncodeCsv {
+    fn identifier(&self) -> &'static str {
+        "encode_csv"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::ARRAY,
+                required: true,
+            },
+            Parameter {
+                keyword: "delimiter",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "encode a single CSV formatted row",
+                source: r#"encode_csv!(["foo", 1, true, "foo \", bar"])"#,
+                result: Ok(r#"s'foo,1,true,"foo "", bar"'"#),
+            },
+            Example {
+                title: "custom delimiter",
+                source: r#"encode_csv!(["foo", "bar"], delimiter: "|")"#,
+                result: Ok("foo|bar"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let delimiter = arguments.optional("delimiter").unwrap_or(expr!(","));
+
+        Ok(Box::new(EncodeCsvFn { value, delimiter }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct EncodeCsvFn {
+    value: Box<dyn Expression>,
+    delimiter: Box<dyn Expression>,
+}
+
+impl Expression for EncodeCsvFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let fields = self.value.resolve(ctx)?.try_array()?;
+        let delimiter = self.delimiter.resolve(ctx)?.try_bytes()?;
+        if delimiter.len() != 1 {
+            return Err("delimiter must be a single character".into());
+        }
+        let delimiter = delimiter[0];
+
+        let record = fields
+            .iter()
+            .map(field)
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        // The writer encodes an empty record as `""`, to tell it apart from no
+        // record at all, which isn't needed for a single row.
+        if record.is_empty() {
+            return Ok("".into());
+        }
+
+        let mut writer = WriterBuilder::new()
+            .has_headers(false)
+            .delimiter(delimiter)
+            .terminator(Terminator::Any(b'\n'))
+            .from_writer(vec![]);
+
+        writer
+            .write_record(&record)
+            .map_err(|err| format!("unable to encode csv: {}", err))?;
+
+        let mut csv = writer
+            .into_inner()
+            .map_err(|err| format!("unable to encode csv: {}", err))?;
+
+        // Only a single row is encoded, so the terminator is left out.
+        csv.pop();
+
+        Ok(csv.into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().bytes()
+    }
+}
+
+/// Converts a value to the bytes of a CSV field, which can't be an array or
+/// an object.
+fn field(value: &Value) -> std::result::Result<Bytes, String> {
+    let field = match value {
+        Value::Bytes(bytes) => bytes.clone(),
+        Value::Integer(integer) => integer.to_string().into(),
+        Value::Float(float) => float.to_string().into(),
+        Value::Boolean(boolean) => boolean.to_string().into(),
+        Value::Timestamp(timestamp) => timestamp
+            .to_rfc3339_opts(SecondsFormat::AutoSi, true)
+            .into(),
+        Value::Regex(regex) => regex.to_string().into(),
+        Value::Null => Bytes::new(),
+        Value::Array(_) | Value::Object(_) => {
+            return Err(format!("unable to encode {} as a csv field", value.kind()))
+        }
+    };
+
+    Ok(field)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    test_function![
+        encode_csv => EncodeCsv;
+
+        fields {
+            args: func_args![value: value!(["foo", 1, 1.5, false, null])],
+            want: Ok("foo,1,1.5,false,"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        quoted {
+            args: func_args![value: value!(["foo, bar", "a \"quote\"", "line\nbreak"])],
+            want: Ok("\"foo, bar\",\"a \"\"quote\"\"\",\"line\nbreak\""),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        timestamp {
+            args: func_args![value: vec![Value::Timestamp(Utc.ymd(2021, 10, 1).and_hms(12, 30, 0))]],
+            want: Ok("2021-10-01T12:30:00Z"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        delimiter {
+            args: func_args![value: value!(["foo", "bar|baz"]), delimiter: "|"],
+            want: Ok("foo|\"bar|baz\""),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        empty {
+            args: func_args![value: value!([])],
+            want: Ok(""),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        invalid_delimiter {
+            args: func_args![value: value!(["foo"]), delimiter: ", "],
+            want: Err("delimiter must be a single character"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        nested {
+            args: func_args![value: value!(["foo", ["bar"]])],
+            want: Err(r#"unable to encode "array" as a csv field"#),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+    ];
+}
@@ -0,0 +1,117 @@
+use flate2::{write::GzEncoder, Compression};
+use std::io::Write;
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct EncodeGzip;
+
+impl Function for EncodeGzip {
+    fn identifier(&self) -> &'static str {
+        "encode_gzip"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "compression_level",
+                kind: kind::INTEGER,
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let compression_level = arguments.optional("compression_level");
+
+        Ok(Box::new(EncodeGzipFn {
+            value,
+            compression_level,
+        }))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "demo string",
+            source: r#"encode_base64(encode_gzip("please encode me"))"#,
+            result: Ok("H4sIAAAAAAAA/yvISU0sTlVIzUvOT0lVyE0FAI4R4vcQAAAA"),
+        }]
+    }
+}
+
+#[derive(Clone, Debug)]
+struct EncodeGzipFn {
+    value: Box<dyn Expression>,
+    compression_level: Option<Box<dyn Expression>>,
+}
+
+impl Expression for EncodeGzipFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?.try_bytes()?;
+
+        let compression = match &self.compression_level {
+            Some(level) => match level.resolve(ctx)?.try_integer()? {
+                level @ 0..=9 => Compression::new(level as u32),
+                level => {
+                    return Err(
+                        format!("compression level must be between 0 and 9, got {}", level).into(),
+                    )
+                }
+            },
+            None => Compression::default(),
+        };
+
+        let mut encoder = GzEncoder::new(Vec::new(), compression);
+        encoder
+            .write_all(&value)
+            .and_then(|_| encoder.finish())
+            .map(Value::from)
+            .map_err(|err| format!("unable to encode value with gzip encoder: {}", err).into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        // Only an invalid compression level makes the function fail.
+        TypeDef::new()
+            .bytes()
+            .with_fallibility(self.compression_level.is_some())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ENCODED: &[u8] = b"\x1f\x8b\x08\x00\x00\x00\x00\x00\x00\xff+\xc8IM,NUH\xcdK\xceOIU\xc8M\x05\x00\x8e\x11\xe2\xf7\x10\x00\x00\x00";
+
+    test_function![
+        encode_gzip => EncodeGzip;
+
+        with_defaults {
+            args: func_args![value: value!("please encode me")],
+            want: Ok(value!(ENCODED)),
+            tdef: TypeDef::new().bytes().infallible(),
+        }
+
+        with_compression_level {
+            args: func_args![value: value!("please encode me"), compression_level: 6],
+            want: Ok(value!(ENCODED)),
+            tdef: TypeDef::new().bytes().fallible(),
+        }
+
+        invalid_compression_level {
+            args: func_args![value: value!("please encode me"), compression_level: 10],
+            want: Err("compression level must be between 0 and 9, got 10"),
+            tdef: TypeDef::new().bytes().fallible(),
+        }
+    ];
+}
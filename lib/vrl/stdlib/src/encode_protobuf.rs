@@ -0,0 +1,211 @@
+use crate::protobuf_util::Schema;
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct EncodeProtobuf;
+
+impl Function for EncodeProtobuf {
+    fn identifier(&self) -> &'static str {
+        "encode_protobuf"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::OBJECT,
+                required: true,
+            },
+            Parameter {
+                keyword: "desc_file",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "message_type",
+                kind: kind::BYTES,
+                required: true,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "encode protobuf message",
+            source: r#"encode_base64(encode_protobuf!({"name": "Someone", "id": 1234, "email": "someone@example.com"}, "../stdlib/tests/data/protobuf/test_protobuf.desc", "test_protobuf.Person"))"#,
+            result: Ok("CgdTb21lb25lENIJGhNzb21lb25lQGV4YW1wbGUuY29t"),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+
+        let desc_file = arguments
+            .required_literal("desc_file")?
+            .to_value()
+            .try_bytes_utf8_lossy()
+            .expect("desc_file not bytes")
+            .into_owned();
+
+        let message_type = arguments
+            .required_literal("message_type")?
+            .to_value()
+            .try_bytes_utf8_lossy()
+            .expect("message_type not bytes")
+            .into_owned();
+
+        let schema = Schema::load(&desc_file, &message_type)
+            .map_err(|err| Box::new(err) as Box<dyn DiagnosticError>)?;
+
+        Ok(Box::new(EncodeProtobufFn { value, schema }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct EncodeProtobufFn {
+    value: Box<dyn Expression>,
+    schema: Schema,
+}
+
+impl Expression for EncodeProtobufFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let object = self.value.resolve(ctx)?.try_object()?;
+
+        let bytes = self
+            .schema
+            .encode(&object)
+            .map_err(|err| format!("unable to encode protobuf: {}", err))?;
+
+        Ok(bytes.into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DESC_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/data/protobuf/test_protobuf.desc"
+    );
+
+    const PERSON: &[u8] = b"\x0a\x07Someone\x10\xd2\x09\x1a\x13someone@example.com\x22\x0b\x0a\x07123-456\x10\x01*\x0c\x0a\x03key\x12\x05value2\x04\x01\x02\xac\x029\xcd\xcc\xcc\xcc\xcc\xcc\xfc?@\x01J\x02\x01\x02P\x09";
+
+    /// A `test_protobuf.Tree` message with the given number of nested children.
+    fn tree(depth: usize) -> Value {
+        (0..depth).fold(value!({}), |child, _| value!({ child: child }))
+    }
+
+    /// The encoded `test_protobuf.Tree` message of [`tree`].
+    fn tree_bytes(depth: usize) -> Bytes {
+        let bytes = (0..depth).fold(vec![], |child, _| {
+            let mut bytes = vec![0x0a];
+            let mut len = child.len();
+            while len >= 0x80 {
+                bytes.push(len as u8 | 0x80);
+                len >>= 7;
+            }
+            bytes.push(len as u8);
+            bytes.extend(child);
+            bytes
+        });
+
+        bytes.into()
+    }
+
+    test_function![
+        encode_protobuf => EncodeProtobuf;
+
+        message {
+            args: func_args![
+                value: value!({
+                    avatar: "\u{1}\u{2}",
+                    data: {key: "value"},
+                    email: "someone@example.com",
+                    height: 1.8,
+                    id: 1234,
+                    name: "Someone",
+                    offset: (-5),
+                    phones: [{number: "123-456", "type": "HOME"}],
+                    scores: [1, 2, 300],
+                    verified: true,
+                }),
+                desc_file: DESC_FILE,
+                message_type: "test_protobuf.Person",
+            ],
+            want: Ok(PERSON),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        enum_number {
+            args: func_args![
+                value: value!({number: "123", "type": 7}),
+                desc_file: DESC_FILE,
+                message_type: "test_protobuf.Person.PhoneNumber",
+            ],
+            want: Ok(&b"\x0a\x03123\x10\x07"[..]),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        null_field {
+            args: func_args![value: value!({id: 1, name: null}), desc_file: DESC_FILE, message_type: "test_protobuf.Person"],
+            want: Ok(&b"\x10\x01"[..]),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        negative_int32 {
+            args: func_args![value: value!({id: (-1)}), desc_file: DESC_FILE, message_type: "test_protobuf.Person"],
+            want: Ok(&b"\x10\xff\xff\xff\xff\xff\xff\xff\xff\xff\x01"[..]),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        unknown_field {
+            args: func_args![value: value!({age: 42}), desc_file: DESC_FILE, message_type: "test_protobuf.Person"],
+            want: Err("unable to encode protobuf: unknown field `age` for message type `test_protobuf.Person`"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        unknown_enum_value {
+            args: func_args![
+                value: value!({"type": "FAX"}),
+                desc_file: DESC_FILE,
+                message_type: "test_protobuf.Person.PhoneNumber",
+            ],
+            want: Err("unable to encode protobuf: field `type`: unknown enum value `FAX`"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        invalid_type {
+            args: func_args![value: value!({id: "1234"}), desc_file: DESC_FILE, message_type: "test_protobuf.Person"],
+            want: Err(r#"unable to encode protobuf: field `id`: expected "integer", got "string""#),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        invalid_desc_file {
+            args: func_args![value: value!({}), desc_file: "does-not-exist.desc", message_type: "test_protobuf.Person"],
+            want: Err("invalid descriptor file: does-not-exist.desc: No such file or directory (os error 2)"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        deepest_message {
+            args: func_args![value: tree(100), desc_file: DESC_FILE, message_type: "test_protobuf.Tree"],
+            want: Ok(tree_bytes(100)),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        too_deep_message {
+            args: func_args![value: tree(101), desc_file: DESC_FILE, message_type: "test_protobuf.Tree"],
+            want: Err("unable to encode protobuf: recursion limit reached"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+    ];
+}
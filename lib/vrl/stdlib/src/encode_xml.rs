@@ -0,0 +1,344 @@
+use chrono::SecondsFormat;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use vrl::prelude::*;
+
+struct EncodeXmlConfig<'a> {
+    /// The prefix of the keys that are encoded as attributes.
+    attr_prefix: Cow<'a, str>,
+    /// The key that is encoded as the text of its element.
+    text_key: Cow<'a, str>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct EncodeXml;
+
+impl Function for EncodeXml {
+    fn identifier(&self) -> &'static str {
+        "encode_xml"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::OBJECT,
+                required: true,
+            },
+            Parameter {
+                keyword: "root_name",
+                kind: kind::BYTES,
+                required: false,
+            },
+            Parameter {
+                keyword: "attr_prefix",
+                kind: kind::BYTES,
+                required: false,
+            },
+            Parameter {
+                keyword: "text_key",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "encode object",
+                source: r#"encode_xml!({"book": {"@category": "CHILDREN", "title": {"@lang": "en", "text": "Harry Potter"}, "year": 2005}})"#,
+                result: Ok(
+                    r#"s'<book category="CHILDREN"><title lang="en">Harry Potter</title><year>2005</year></book>'"#,
+                ),
+            },
+            Example {
+                title: "root name",
+                source: r#"encode_xml!({"item": ["a", "b"], "total": 2}, root_name: "list")"#,
+                result: Ok(r#"s'<list><item>a</item><item>b</item><total>2</total></list>'"#),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let root_name = arguments.optional("root_name");
+        let attr_prefix = arguments.optional("attr_prefix");
+        let text_key = arguments.optional("text_key");
+
+        Ok(Box::new(EncodeXmlFn {
+            value,
+            root_name,
+            attr_prefix,
+            text_key,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct EncodeXmlFn {
+    value: Box<dyn Expression>,
+    root_name: Option<Box<dyn Expression>>,
+    attr_prefix: Option<Box<dyn Expression>>,
+    text_key: Option<Box<dyn Expression>>,
+}
+
+impl Expression for EncodeXmlFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let object = self.value.resolve(ctx)?.try_object()?;
+
+        let root_name = match &self.root_name {
+            Some(expr) => Some(expr.resolve(ctx)?.try_bytes_utf8_lossy()?.into_owned()),
+            None => None,
+        };
+
+        let attr_prefix = match &self.attr_prefix {
+            Some(expr) => Cow::from(expr.resolve(ctx)?.try_bytes_utf8_lossy()?.into_owned()),
+            None => Cow::from("@"),
+        };
+
+        let text_key = match &self.text_key {
+            Some(expr) => Cow::from(expr.resolve(ctx)?.try_bytes_utf8_lossy()?.into_owned()),
+            None => Cow::from("text"),
+        };
+
+        let config = EncodeXmlConfig {
+            attr_prefix,
+            text_key,
+        };
+
+        let mut xml = String::new();
+        match root_name {
+            Some(name) => encode_element(&mut xml, &name, &Value::Object(object), &config)?,
+            None if object.len() == 1 => {
+                let (name, value) = object.iter().next().expect("one element");
+                encode_element(&mut xml, name, value, &config)?;
+            }
+            None => return Err(
+                "object must have a single key for the root element, or `root_name` must be set"
+                    .into(),
+            ),
+        }
+
+        Ok(xml.into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().bytes()
+    }
+}
+
+/// Encodes a value as the element `name`.
+///
+/// Arrays are encoded as one element per item, objects as the attributes,
+/// text and child elements of the element, and other values as its text.
+fn encode_element(
+    xml: &mut String,
+    name: &str,
+    value: &Value,
+    config: &EncodeXmlConfig<'_>,
+) -> std::result::Result<(), String> {
+    if !is_valid_name(name) {
+        return Err(format!("invalid element name `{}`", name));
+    }
+
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                encode_element(xml, name, item, config)?;
+            }
+        }
+        Value::Object(map) => encode_object(xml, name, map, config)?,
+        Value::Null => {
+            xml.push('<');
+            xml.push_str(name);
+            xml.push_str("/>");
+        }
+        value => {
+            xml.push('<');
+            xml.push_str(name);
+            xml.push('>');
+            xml.push_str(&escape(&text(value)?, false));
+            xml.push_str("</");
+            xml.push_str(name);
+            xml.push('>');
+        }
+    }
+
+    Ok(())
+}
+
+fn encode_object(
+    xml: &mut String,
+    name: &str,
+    map: &BTreeMap<String, Value>,
+    config: &EncodeXmlConfig<'_>,
+) -> std::result::Result<(), String> {
+    xml.push('<');
+    xml.push_str(name);
+
+    for (key, value) in map {
+        let attr = match key.strip_prefix(config.attr_prefix.as_ref()) {
+            Some(attr) if !config.attr_prefix.is_empty() => attr,
+            _ => continue,
+        };
+
+        if !is_valid_name(attr) {
+            return Err(format!("invalid attribute name `{}`", attr));
+        }
+
+        xml.push(' ');
+        xml.push_str(attr);
+        xml.push_str("=\"");
+        xml.push_str(&escape(&text(value)?, true));
+        xml.push('"');
+    }
+
+    let mut content = String::new();
+    for (key, value) in map {
+        if !config.attr_prefix.is_empty() && key.starts_with(config.attr_prefix.as_ref()) {
+            continue;
+        }
+
+        if key == config.text_key.as_ref() {
+            content.push_str(&escape(&text(value)?, false));
+        } else {
+            encode_element(&mut content, key, value, config)?;
+        }
+    }
+
+    if content.is_empty() {
+        xml.push_str("/>");
+    } else {
+        xml.push('>');
+        xml.push_str(&content);
+        xml.push_str("</");
+        xml.push_str(name);
+        xml.push('>');
+    }
+
+    Ok(())
+}
+
+/// Converts a value to the text of an element or an attribute, which can't be
+/// an array or an object.
+fn text(value: &Value) -> std::result::Result<Cow<'_, str>, String> {
+    let text = match value {
+        Value::Bytes(bytes) => String::from_utf8_lossy(bytes),
+        Value::Integer(integer) => integer.to_string().into(),
+        Value::Float(float) => float.to_string().into(),
+        Value::Boolean(boolean) => boolean.to_string().into(),
+        Value::Timestamp(timestamp) => timestamp
+            .to_rfc3339_opts(SecondsFormat::AutoSi, true)
+            .into(),
+        Value::Regex(regex) => regex.to_string().into(),
+        Value::Null => "".into(),
+        Value::Array(_) | Value::Object(_) => {
+            return Err(format!("unable to encode {} as xml text", value.kind()))
+        }
+    };
+
+    Ok(text)
+}
+
+fn escape(text: &str, attribute: bool) -> Cow<'_, str> {
+    if !text.contains(&['&', '<', '>', '"'][..]) {
+        return text.into();
+    }
+
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' if attribute => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.into()
+}
+
+/// Checks that the name of an element or attribute is a valid XML name,
+/// allowing namespace prefixes.
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    chars
+        .next()
+        .map_or(false, |c| c.is_alphabetic() || c == '_' || c == ':')
+        && chars.all(|c| c.is_alphanumeric() || matches!(c, '_' | ':' | '-' | '.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        encode_xml => EncodeXml;
+
+        single_root {
+            args: func_args![value: value!({"root": {"a": "foo", "b": 1}})],
+            want: Ok("<root><a>foo</a><b>1</b></root>"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        root_name {
+            args: func_args![value: value!({"a": "foo", "b": true}), root_name: "root"],
+            want: Ok("<root><a>foo</a><b>true</b></root>"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        attributes_and_text {
+            args: func_args![value: value!({"a": {"@id": 1, "@class": "x\"y", "text": "foo"}})],
+            want: Ok(r#"<a class="x&quot;y" id="1">foo</a>"#),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        custom_keys {
+            args: func_args![
+                value: value!({"a": {"_id": 1, "value": "foo"}}),
+                attr_prefix: "_",
+                text_key: "value",
+            ],
+            want: Ok(r#"<a id="1">foo</a>"#),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        arrays {
+            args: func_args![value: value!({"list": {"item": [1, {"@id": 2}, null]}})],
+            want: Ok(r#"<list><item>1</item><item id="2"/><item/></list>"#),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        escaped_text {
+            args: func_args![value: value!({"a": "<b> & \"c\""})],
+            want: Ok(r#"<a>&lt;b&gt; &amp; "c"</a>"#),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        multiple_roots {
+            args: func_args![value: value!({"a": 1, "b": 2})],
+            want: Err("object must have a single key for the root element, or `root_name` must be set"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        invalid_name {
+            args: func_args![value: value!({"root": {"1st": "foo"}})],
+            want: Err("invalid element name `1st`"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        nested_text {
+            args: func_args![value: value!({"a": {"text": ["foo"]}})],
+            want: Err(r#"unable to encode "array" as xml text"#),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+    ];
+}
@@ -0,0 +1,108 @@
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct EncodeYaml;
+
+impl Function for EncodeYaml {
+    fn identifier(&self) -> &'static str {
+        "encode_yaml"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::ANY,
+            required: true,
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(Box::new(EncodeYamlFn { value }))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "encode to yaml",
+            source: r#"encode_yaml({"field": "value", "list": [1, true]})"#,
+            result: Ok(r#"s'field: value
+list:
+  - 1
+  - true'"#),
+        }]
+    }
+}
+
+#[derive(Clone, Debug)]
+struct EncodeYamlFn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for EncodeYamlFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+
+        // With `vrl::Value` it should not be possible to get `Err`.
+        let yaml = match serde_yaml::to_string(&value) {
+            Ok(yaml) => yaml,
+            Err(error) => unreachable!("unable encode to yaml: {}", error),
+        };
+
+        // Leave out the document start marker, and the trailing newline, as
+        // with `encode_json`.
+        let yaml = yaml.strip_prefix("---").unwrap_or(&yaml);
+        let yaml = yaml.strip_prefix(&[' ', '\n'][..]).unwrap_or(yaml);
+
+        Ok(yaml.trim_end_matches('\n').into())
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        self.value.type_def(state).infallible().bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    test_function![
+        encode_yaml => EncodeYaml;
+
+        bytes {
+            args: func_args![value: "hello"],
+            want: Ok("hello"),
+            tdef: TypeDef::new().infallible().bytes(),
+        }
+
+        integer {
+            args: func_args![value: 42],
+            want: Ok("42"),
+            tdef: TypeDef::new().infallible().bytes(),
+        }
+
+        null {
+            args: func_args![value: value!(null)],
+            want: Ok("~"),
+            tdef: TypeDef::new().infallible().bytes(),
+        }
+
+        timestamp {
+            args: func_args![value: Utc.ymd(2021, 10, 1).and_hms(12, 30, 0)],
+            want: Ok("\"2021-10-01T12:30:00Z\""),
+            tdef: TypeDef::new().infallible().bytes(),
+        }
+
+        object {
+            args: func_args![value: value!({field: "value", nested: {list: [1, "two"]}})],
+            want: Ok("field: value\nnested:\n  list:\n    - 1\n    - two"),
+            tdef: TypeDef::new().infallible().bytes(),
+        }
+    ];
+}
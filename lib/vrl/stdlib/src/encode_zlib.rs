@@ -0,0 +1,117 @@
+use flate2::{write::ZlibEncoder, Compression};
+use std::io::Write;
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct EncodeZlib;
+
+impl Function for EncodeZlib {
+    fn identifier(&self) -> &'static str {
+        "encode_zlib"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "compression_level",
+                kind: kind::INTEGER,
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let compression_level = arguments.optional("compression_level");
+
+        Ok(Box::new(EncodeZlibFn {
+            value,
+            compression_level,
+        }))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "demo string",
+            source: r#"encode_base64(encode_zlib("please encode me"))"#,
+            result: Ok("eJwryElNLE5VSM1Lzk9JVchNBQA0RQX7"),
+        }]
+    }
+}
+
+#[derive(Clone, Debug)]
+struct EncodeZlibFn {
+    value: Box<dyn Expression>,
+    compression_level: Option<Box<dyn Expression>>,
+}
+
+impl Expression for EncodeZlibFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?.try_bytes()?;
+
+        let compression = match &self.compression_level {
+            Some(level) => match level.resolve(ctx)?.try_integer()? {
+                level @ 0..=9 => Compression::new(level as u32),
+                level => {
+                    return Err(
+                        format!("compression level must be between 0 and 9, got {}", level).into(),
+                    )
+                }
+            },
+            None => Compression::default(),
+        };
+
+        let mut encoder = ZlibEncoder::new(Vec::new(), compression);
+        encoder
+            .write_all(&value)
+            .and_then(|_| encoder.finish())
+            .map(Value::from)
+            .map_err(|err| format!("unable to encode value with zlib encoder: {}", err).into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        // Only an invalid compression level makes the function fail.
+        TypeDef::new()
+            .bytes()
+            .with_fallibility(self.compression_level.is_some())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ENCODED: &[u8] = b"x\x9c+\xc8IM,NUH\xcdK\xceOIU\xc8M\x05\x004E\x05\xfb";
+
+    test_function![
+        encode_zlib => EncodeZlib;
+
+        with_defaults {
+            args: func_args![value: value!("please encode me")],
+            want: Ok(value!(ENCODED)),
+            tdef: TypeDef::new().bytes().infallible(),
+        }
+
+        with_compression_level {
+            args: func_args![value: value!("please encode me"), compression_level: 6],
+            want: Ok(value!(ENCODED)),
+            tdef: TypeDef::new().bytes().fallible(),
+        }
+
+        invalid_compression_level {
+            args: func_args![value: value!("please encode me"), compression_level: 10],
+            want: Err("compression level must be between 0 and 9, got 10"),
+            tdef: TypeDef::new().bytes().fallible(),
+        }
+    ];
+}
@@ -0,0 +1,151 @@
+use crate::crypto_util;
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Encrypt;
+
+impl Function for Encrypt {
+    fn identifier(&self) -> &'static str {
+        "encrypt"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "plaintext",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "algorithm",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "key",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "iv",
+                kind: kind::BYTES,
+                required: true,
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let plaintext = arguments.required("plaintext");
+        let algorithm = arguments
+            .required_enum("algorithm", &crypto_util::algorithms())?
+            .try_bytes()
+            .expect("algorithm not bytes");
+        let key = arguments.required("key");
+        let iv = arguments.required("iv");
+
+        Ok(Box::new(EncryptFn {
+            plaintext,
+            algorithm,
+            key,
+            iv,
+        }))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "AES-256-GCM",
+                source: r#"iv = random_bytes!(12); decrypt!(encrypt!("data", "AES-256-GCM", key: "01234567890123456789012345678912", iv: iv), "AES-256-GCM", key: "01234567890123456789012345678912", iv: iv)"#,
+                result: Ok("data"),
+            },
+            Example {
+                title: "AES-256-CBC-PKCS7",
+                source: r#"iv = random_bytes!(16); decrypt!(encrypt!("data", "AES-256-CBC-PKCS7", key: "01234567890123456789012345678912", iv: iv), "AES-256-CBC-PKCS7", key: "01234567890123456789012345678912", iv: iv)"#,
+                result: Ok("data"),
+            },
+        ]
+    }
+}
+
+#[derive(Debug, Clone)]
+struct EncryptFn {
+    plaintext: Box<dyn Expression>,
+    algorithm: Bytes,
+    key: Box<dyn Expression>,
+    iv: Box<dyn Expression>,
+}
+
+impl Expression for EncryptFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let plaintext = self.plaintext.resolve(ctx)?.try_bytes()?;
+        let key = self.key.resolve(ctx)?.try_bytes()?;
+        let iv = self.iv.resolve(ctx)?.try_bytes()?;
+
+        Ok(crypto_util::encrypt(&self.algorithm, &plaintext, &key, &iv)?.into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().bytes()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const CBC_CIPHERTEXT: &[u8] = b"'\xb3\x82\xc6\x8e\x04kp\x8c1\xb9\x10rP\xa0\xb5";
+    const GCM_CIPHERTEXT: &[u8] = b"\xb4\x0b\xad\x1f\xa3|*t\xcd?w\xf2\xe5\x92\x8d=\xba\r\xdc\\";
+
+    test_function![
+        encrypt => Encrypt;
+
+        aes_256_cbc_pkcs7 {
+            args: func_args![
+                plaintext: "data",
+                algorithm: "AES-256-CBC-PKCS7",
+                key: "01234567890123456789012345678912",
+                iv: "1234567890123456",
+            ],
+            want: Ok(value!(CBC_CIPHERTEXT)),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        aes_256_gcm {
+            args: func_args![
+                plaintext: "data",
+                algorithm: "AES-256-GCM",
+                key: "01234567890123456789012345678912",
+                iv: "123456789012",
+            ],
+            want: Ok(value!(GCM_CIPHERTEXT)),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        invalid_key_length {
+            args: func_args![
+                plaintext: "data",
+                algorithm: "AES-128-CBC-PKCS7",
+                key: "01234567890123456789012345678912",
+                iv: "1234567890123456",
+            ],
+            want: Err("key must be 16 bytes for this algorithm, got 32"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        invalid_iv_length {
+            args: func_args![
+                plaintext: "data",
+                algorithm: "AES-256-GCM",
+                key: "01234567890123456789012345678912",
+                iv: "1234567890123456",
+            ],
+            want: Err("iv must be 12 bytes for this algorithm, got 16"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+    ];
+}
@@ -56,6 +56,11 @@ impl Expression for ExistsFn {
             return Ok(ctx.target_mut().get(path).ok().flatten().is_some().into());
         }
 
+        if self.query.is_metadata() {
+            let value = ctx.target().get_metadata(path)?;
+            return Ok(value.is_some().into());
+        }
+
         if let Some(ident) = self.query.variable_ident() {
             return match ctx.state().variable(ident) {
                 Some(value) => Ok(value.get_by_path(path).is_some().into()),
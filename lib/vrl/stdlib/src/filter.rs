@@ -0,0 +1,167 @@
+use crate::util;
+use std::collections::BTreeMap;
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Filter;
+
+impl Function for Filter {
+    fn identifier(&self) -> &'static str {
+        "filter"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::OBJECT | kind::ARRAY,
+            required: true,
+        }]
+    }
+
+    fn closure(&self) -> Option<closure::Definition> {
+        Some(closure::Definition {
+            input: "value",
+            variables: &[closure::Variable::Key, closure::Variable::Value],
+            output: closure::Output::Kind(kind::BOOLEAN),
+        })
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "filter object",
+                source: r#"filter({ "a": 1, "b": 2, "c": 3 }) -> |key, _value| { key != "b" }"#,
+                result: Ok(r#"{ "a": 1, "c": 3 }"#),
+            },
+            Example {
+                title: "filter array",
+                source: r#"filter([1, 2, 3, 4]) -> |_index, value| { value > 2 }"#,
+                result: Ok("[3, 4]"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let closure = arguments.required_closure();
+
+        Ok(Box::new(FilterFn { value, closure }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct FilterFn {
+    value: Box<dyn Expression>,
+    closure: FunctionClosure,
+}
+
+impl Expression for FilterFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        match self.value.resolve(ctx)? {
+            Value::Object(object) => {
+                let mut filtered = BTreeMap::new();
+                for (key, value) in object {
+                    let keep = self
+                        .closure
+                        .run(ctx, vec![key.clone().into(), value.clone()])?
+                        .try_boolean()?;
+
+                    if keep {
+                        filtered.insert(key, value);
+                    }
+                }
+
+                Ok(filtered.into())
+            }
+            Value::Array(array) => {
+                let mut filtered = Vec::new();
+                for (index, value) in array.into_iter().enumerate() {
+                    let keep = self
+                        .closure
+                        .run(ctx, vec![(index as i64).into(), value.clone()])?
+                        .try_boolean()?;
+
+                    if keep {
+                        filtered.push(value);
+                    }
+                }
+
+                Ok(filtered.into())
+            }
+            value => Err(value::Error::Expected {
+                got: value.kind(),
+                expected: Kind::Array | Kind::Object,
+            }
+            .into()),
+        }
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        let value = self.value.type_def(state);
+        let element = value.element_type_def().kind();
+
+        util::container_type_def(&value, element)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn closure(source: &str, output: Kind) -> FunctionClosure {
+        FunctionClosure::from_source(
+            &[
+                ("key", TypeDef::new().bytes()),
+                ("value", TypeDef::new().integer()),
+            ],
+            source,
+            output,
+        )
+    }
+
+    test_function![
+        filter => Filter;
+
+        object {
+            args: ArgumentList::from(func_args![value: value!({ "a": 1, "b": 2, "c": 3 })])
+                .with_closure(closure(r#"key != "b""#, Kind::Boolean)),
+            want: Ok(value!({ "a": 1, "c": 3 })),
+            tdef: TypeDef::new().object::<(), Kind>(map! { (): Kind::Integer }),
+        }
+
+        array {
+            args: ArgumentList::from(func_args![value: value!([1, 2, 3])])
+                .with_closure(closure("value != 2", Kind::Boolean)),
+            want: Ok(value!([1, 3])),
+            tdef: TypeDef::new().array_mapped::<(), Kind>(map! { (): Kind::Integer }),
+        }
+
+        partially_matching_kind {
+            args: ArgumentList::from(func_args![value: value!([1, 2, 3])])
+                .with_closure(closure(r#"if value == 1 { true } else { "no" }"#, Kind::Boolean)),
+            want: Err(r#"expected "boolean", got "string""#),
+            tdef: TypeDef::new().array_mapped::<(), Kind>(map! { (): Kind::Integer }),
+        }
+
+        non_boolean {
+            args: ArgumentList::from(func_args![value: value!({ "a": 1 })])
+                .with_closure(closure("value", Kind::all())),
+            want: Err(r#"expected "boolean", got "integer""#),
+            tdef: TypeDef::new().object::<(), Kind>(map! { (): Kind::Integer }),
+        }
+
+        non_container {
+            args: ArgumentList::from(func_args![value: "foo"])
+                .with_closure(closure("true", Kind::Boolean)),
+            want: Err(r#"expected "object" or "array", got "string""#),
+            tdef: TypeDef::new()
+                .array_mapped::<(), Kind>(map! { (): Kind::all() })
+                .merge(TypeDef::new().object::<(), Kind>(map! { (): Kind::all() })),
+        }
+    ];
+}
@@ -0,0 +1,127 @@
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct ForEach;
+
+impl Function for ForEach {
+    fn identifier(&self) -> &'static str {
+        "for_each"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::OBJECT | kind::ARRAY,
+            required: true,
+        }]
+    }
+
+    fn closure(&self) -> Option<closure::Definition> {
+        Some(closure::Definition {
+            input: "value",
+            variables: &[closure::Variable::Key, closure::Variable::Value],
+            output: closure::Output::Kind(kind::ANY),
+        })
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "sum values",
+            source: indoc! {r#"
+                sum = 0
+                for_each({ "a": 1, "b": 2 }) -> |_key, value| { sum = sum + value }
+                sum
+            "#},
+            result: Ok("3"),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let closure = arguments.required_closure();
+
+        Ok(Box::new(ForEachFn { value, closure }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ForEachFn {
+    value: Box<dyn Expression>,
+    closure: FunctionClosure,
+}
+
+impl Expression for ForEachFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        match self.value.resolve(ctx)? {
+            Value::Object(object) => {
+                for (key, value) in object {
+                    self.closure.run(ctx, vec![key.into(), value])?;
+                }
+            }
+            Value::Array(array) => {
+                for (index, value) in array.into_iter().enumerate() {
+                    self.closure.run(ctx, vec![(index as i64).into(), value])?;
+                }
+            }
+            value => {
+                return Err(value::Error::Expected {
+                    got: value.kind(),
+                    expected: Kind::Array | Kind::Object,
+                }
+                .into())
+            }
+        }
+
+        Ok(Value::Null)
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().null()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn closure(source: &str) -> FunctionClosure {
+        FunctionClosure::from_source(
+            &[
+                ("key", TypeDef::new().bytes()),
+                ("value", TypeDef::new().integer()),
+            ],
+            source,
+            Kind::all(),
+        )
+    }
+
+    test_function![
+        for_each => ForEach;
+
+        object {
+            args: ArgumentList::from(func_args![value: value!({ "a": 1, "b": 2 })])
+                .with_closure(closure("value")),
+            want: Ok(()),
+            tdef: TypeDef::new().null(),
+        }
+
+        array {
+            args: ArgumentList::from(func_args![value: value!([1, 2])])
+                .with_closure(closure("key")),
+            want: Ok(()),
+            tdef: TypeDef::new().null(),
+        }
+
+        non_container {
+            args: ArgumentList::from(func_args![value: true])
+                .with_closure(closure("value")),
+            want: Err(r#"expected "object" or "array", got "boolean""#),
+            tdef: TypeDef::new().null(),
+        }
+    ];
+}
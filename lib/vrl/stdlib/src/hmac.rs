@@ -0,0 +1,153 @@
+use ::sha1::Sha1;
+use hmac_lib::{Mac, NewMac};
+use sha_2::{Sha224, Sha256, Sha384, Sha512};
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Hmac;
+
+impl Function for Hmac {
+    fn identifier(&self) -> &'static str {
+        "hmac"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "key",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "algorithm",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "default algorithm",
+                source: r#"hmac("The quick brown fox jumps over the lazy dog", "key")"#,
+                result: Ok("f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"),
+            },
+            Example {
+                title: "custom algorithm",
+                source: r#"hmac("The quick brown fox jumps over the lazy dog", "key", "SHA1")"#,
+                result: Ok("de7c9b85b8b78aa6bc8a7a36f70a90701c9db4d9"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let algorithms = vec![
+            value!("SHA1"),
+            value!("SHA-224"),
+            value!("SHA-256"),
+            value!("SHA-384"),
+            value!("SHA-512"),
+        ];
+
+        let value = arguments.required("value");
+        let key = arguments.required("key");
+        let algorithm = arguments
+            .optional_enum("algorithm", &algorithms)?
+            .unwrap_or_else(|| value!("SHA-256"))
+            .try_bytes()
+            .expect("algorithm not bytes");
+
+        Ok(Box::new(HmacFn {
+            value,
+            key,
+            algorithm,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct HmacFn {
+    value: Box<dyn Expression>,
+    key: Box<dyn Expression>,
+    algorithm: Bytes,
+}
+
+impl Expression for HmacFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?.try_bytes()?;
+        let key = self.key.resolve(ctx)?.try_bytes()?;
+
+        let hash = match self.algorithm.as_ref() {
+            b"SHA1" => encode::<hmac_lib::Hmac<Sha1>>(&value, &key),
+            b"SHA-224" => encode::<hmac_lib::Hmac<Sha224>>(&value, &key),
+            b"SHA-256" => encode::<hmac_lib::Hmac<Sha256>>(&value, &key),
+            b"SHA-384" => encode::<hmac_lib::Hmac<Sha384>>(&value, &key),
+            b"SHA-512" => encode::<hmac_lib::Hmac<Sha512>>(&value, &key),
+            _ => unreachable!("enum invariant"),
+        };
+
+        Ok(hash.into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().infallible().bytes()
+    }
+}
+
+#[inline]
+fn encode<T: Mac + NewMac>(value: &[u8], key: &[u8]) -> String {
+    let mut mac = T::new_from_slice(key).expect("HMAC takes keys of any length");
+    mac.update(value);
+
+    hex::encode(mac.finalize().into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        hmac => Hmac;
+
+        hmac {
+            args: func_args![value: "", key: ""],
+            want: Ok("b613679a0814d9ec772f95d778c35fc5ff1697c493715653c6c712144292c5ad"),
+            tdef: TypeDef::new().infallible().bytes(),
+        }
+
+        sha1 {
+            args: func_args![value: "", key: "", algorithm: "SHA1"],
+            want: Ok("fbdb1d1b18aa6c08324b7d64b71fb76370690e1d"),
+            tdef: TypeDef::new().infallible().bytes(),
+        }
+
+        sha224 {
+            args: func_args![value: "foo", key: "bar", algorithm: "SHA-224"],
+            want: Ok("d7f508375f4b5b1c236d2df1b850de2474a913644876705e62bd78cc"),
+            tdef: TypeDef::new().infallible().bytes(),
+        }
+
+        sha384 {
+            args: func_args![value: "foo", key: "bar", algorithm: "SHA-384"],
+            want: Ok("1d9070d07cb7746e0664cccc6cec1fa996dc7f46368982acfa2095ee8d73fe25b5b6e32279900cdb0fd372a3654e41c5"),
+            tdef: TypeDef::new().infallible().bytes(),
+        }
+
+        sha512 {
+            args: func_args![value: "foo", key: "bar", algorithm: "SHA-512"],
+            want: Ok("24257d7210582a65c731ec55159c8184cc24c02489453e58587f71f44c23a2d61b4b72154a89d17b2d49448a8452ea066f4fc56a2bcead45c088572ffccdb3d8"),
+            tdef: TypeDef::new().infallible().bytes(),
+        }
+    ];
+}
@@ -0,0 +1,106 @@
+use std::net::IpAddr;
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct IpIsLoopback;
+
+impl Function for IpIsLoopback {
+    fn identifier(&self) -> &'static str {
+        "ip_is_loopback"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "loopback",
+                source: r#"ip_is_loopback!("127.0.0.1")"#,
+                result: Ok("true"),
+            },
+            Example {
+                title: "not loopback",
+                source: r#"ip_is_loopback!("192.168.0.1")"#,
+                result: Ok("false"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(Box::new(IpIsLoopbackFn { value }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct IpIsLoopbackFn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for IpIsLoopbackFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let ip: IpAddr = self
+            .value
+            .resolve(ctx)?
+            .try_bytes_utf8_lossy()?
+            .parse()
+            .map_err(|err| format!("unable to parse IP address: {}", err))?;
+
+        Ok(ip.is_loopback().into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().boolean()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        ip_is_loopback => IpIsLoopback;
+
+        ipv4_yes {
+            args: func_args![value: "127.0.0.1"],
+            want: Ok(value!(true)),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        ipv4_no {
+            args: func_args![value: "192.168.0.1"],
+            want: Ok(value!(false)),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        ipv6_yes {
+            args: func_args![value: "::1"],
+            want: Ok(value!(true)),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        ipv6_no {
+            args: func_args![value: "2001:db8::1"],
+            want: Ok(value!(false)),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        invalid {
+            args: func_args![value: "i am not an ipaddress"],
+            want: Err("unable to parse IP address: invalid IP address syntax"),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+    ];
+}
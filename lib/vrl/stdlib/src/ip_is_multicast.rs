@@ -0,0 +1,106 @@
+use std::net::IpAddr;
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct IpIsMulticast;
+
+impl Function for IpIsMulticast {
+    fn identifier(&self) -> &'static str {
+        "ip_is_multicast"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "multicast",
+                source: r#"ip_is_multicast!("224.0.0.251")"#,
+                result: Ok("true"),
+            },
+            Example {
+                title: "not multicast",
+                source: r#"ip_is_multicast!("192.168.0.1")"#,
+                result: Ok("false"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(Box::new(IpIsMulticastFn { value }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct IpIsMulticastFn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for IpIsMulticastFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let ip: IpAddr = self
+            .value
+            .resolve(ctx)?
+            .try_bytes_utf8_lossy()?
+            .parse()
+            .map_err(|err| format!("unable to parse IP address: {}", err))?;
+
+        Ok(ip.is_multicast().into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().boolean()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        ip_is_multicast => IpIsMulticast;
+
+        ipv4_yes {
+            args: func_args![value: "224.0.0.251"],
+            want: Ok(value!(true)),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        ipv4_no {
+            args: func_args![value: "192.168.0.1"],
+            want: Ok(value!(false)),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        ipv6_yes {
+            args: func_args![value: "ff02::1"],
+            want: Ok(value!(true)),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        ipv6_no {
+            args: func_args![value: "fe80::1"],
+            want: Ok(value!(false)),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        invalid {
+            args: func_args![value: "i am not an ipaddress"],
+            want: Err("unable to parse IP address: invalid IP address syntax"),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+    ];
+}
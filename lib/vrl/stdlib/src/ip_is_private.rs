@@ -0,0 +1,112 @@
+use std::net::IpAddr;
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct IpIsPrivate;
+
+impl Function for IpIsPrivate {
+    fn identifier(&self) -> &'static str {
+        "ip_is_private"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "private",
+                source: r#"ip_is_private!("192.168.10.32")"#,
+                result: Ok("true"),
+            },
+            Example {
+                title: "not private",
+                source: r#"ip_is_private!("8.8.8.8")"#,
+                result: Ok("false"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(Box::new(IpIsPrivateFn { value }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct IpIsPrivateFn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for IpIsPrivateFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let ip: IpAddr = self
+            .value
+            .resolve(ctx)?
+            .try_bytes_utf8_lossy()?
+            .parse()
+            .map_err(|err| format!("unable to parse IP address: {}", err))?;
+
+        let is_private = match ip {
+            IpAddr::V4(addr) => addr.is_private(),
+            // Unique local addresses, `fc00::/7`, are the IPv6 private addresses.
+            IpAddr::V6(addr) => addr.segments()[0] & 0xfe00 == 0xfc00,
+        };
+
+        Ok(is_private.into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().boolean()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        ip_is_private => IpIsPrivate;
+
+        ipv4_yes {
+            args: func_args![value: "192.168.10.32"],
+            want: Ok(value!(true)),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        ipv4_no {
+            args: func_args![value: "8.8.8.8"],
+            want: Ok(value!(false)),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        ipv6_yes {
+            args: func_args![value: "fd12:3456:789a:1::1"],
+            want: Ok(value!(true)),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        ipv6_no {
+            args: func_args![value: "2001:4860:4860::8888"],
+            want: Ok(value!(false)),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+
+        invalid {
+            args: func_args![value: "i am not an ipaddress"],
+            want: Err("unable to parse IP address: invalid IP address syntax"),
+            tdef: TypeDef::new().fallible().boolean(),
+        }
+    ];
+}
@@ -0,0 +1,97 @@
+use std::net::IpAddr;
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct IpVersion;
+
+impl Function for IpVersion {
+    fn identifier(&self) -> &'static str {
+        "ip_version"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "IPv4",
+                source: r#"ip_version!("192.168.0.1")"#,
+                result: Ok("4"),
+            },
+            Example {
+                title: "IPv6",
+                source: r#"ip_version!("2001:db8::1")"#,
+                result: Ok("6"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(Box::new(IpVersionFn { value }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct IpVersionFn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for IpVersionFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let ip: IpAddr = self
+            .value
+            .resolve(ctx)?
+            .try_bytes_utf8_lossy()?
+            .parse()
+            .map_err(|err| format!("unable to parse IP address: {}", err))?;
+
+        match ip {
+            IpAddr::V4(_) => Ok(4.into()),
+            IpAddr::V6(_) => Ok(6.into()),
+        }
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().integer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        ip_version => IpVersion;
+
+        ipv4 {
+            args: func_args![value: "192.168.0.1"],
+            want: Ok(value!(4)),
+            tdef: TypeDef::new().fallible().integer(),
+        }
+
+        ipv6 {
+            args: func_args![value: "::ffff:192.168.0.1"],
+            want: Ok(value!(6)),
+            tdef: TypeDef::new().fallible().integer(),
+        }
+
+        invalid {
+            args: func_args![value: "i am not an ipaddress"],
+            want: Err("unable to parse IP address: invalid IP address syntax"),
+            tdef: TypeDef::new().fallible().integer(),
+        }
+    ];
+}
@@ -0,0 +1,105 @@
+use std::net::Ipv6Addr;
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct IsIpv6;
+
+impl Function for IsIpv6 {
+    fn identifier(&self) -> &'static str {
+        "is_ipv6"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::ANY,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "IPv6 address",
+                source: r#"is_ipv6("2001:db8::1")"#,
+                result: Ok("true"),
+            },
+            Example {
+                title: "other address",
+                source: r#"is_ipv6("192.168.0.1")"#,
+                result: Ok("false"),
+            },
+            Example {
+                title: "integer",
+                source: r#"is_ipv6(1)"#,
+                result: Ok("false"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(Box::new(IsIpv6Fn { value }))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct IsIpv6Fn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for IsIpv6Fn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let is_ipv6 = match self.value.resolve(ctx)? {
+            Value::Bytes(bytes) => {
+                std::str::from_utf8(&bytes).map_or(false, |value| value.parse::<Ipv6Addr>().is_ok())
+            }
+            _ => false,
+        };
+
+        Ok(is_ipv6.into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().infallible().boolean()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        is_ipv6 => IsIpv6;
+
+        ipv6 {
+            args: func_args![value: "2001:db8::1"],
+            want: Ok(value!(true)),
+            tdef: TypeDef::new().infallible().boolean(),
+        }
+
+        other_address {
+            args: func_args![value: "192.168.0.1"],
+            want: Ok(value!(false)),
+            tdef: TypeDef::new().infallible().boolean(),
+        }
+
+        invalid {
+            args: func_args![value: "not an address"],
+            want: Ok(value!(false)),
+            tdef: TypeDef::new().infallible().boolean(),
+        }
+
+        integer {
+            args: func_args![value: 1],
+            want: Ok(value!(false)),
+            tdef: TypeDef::new().infallible().boolean(),
+        }
+    ];
+}
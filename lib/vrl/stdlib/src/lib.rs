@@ -12,20 +12,38 @@ mod assert_eq;
 mod boolean;
 #[cfg(feature = "ceil")]
 mod ceil;
+#[cfg(feature = "community_id")]
+mod community_id;
 #[cfg(feature = "compact")]
 mod compact;
 #[cfg(feature = "contains")]
 mod contains;
+#[cfg(feature = "crc32")]
+mod crc32;
+#[cfg(any(feature = "decrypt", feature = "encrypt"))]
+mod crypto_util;
 #[cfg(feature = "decode_base64")]
 mod decode_base64;
+#[cfg(feature = "decode_gzip")]
+mod decode_gzip;
 #[cfg(feature = "decode_percent")]
 mod decode_percent;
+#[cfg(feature = "decode_zlib")]
+mod decode_zlib;
+#[cfg(feature = "decrypt")]
+mod decrypt;
 #[cfg(feature = "del")]
 mod del;
 #[cfg(feature = "downcase")]
 mod downcase;
+#[cfg(feature = "encode_base16")]
+mod encode_base16;
 #[cfg(feature = "encode_base64")]
 mod encode_base64;
+#[cfg(feature = "encode_csv")]
+mod encode_csv;
+#[cfg(feature = "encode_gzip")]
+mod encode_gzip;
 #[cfg(feature = "encode_json")]
 mod encode_json;
 #[cfg(feature = "encode_key_value")]
@@ -34,10 +52,22 @@ mod encode_key_value;
 mod encode_logfmt;
 #[cfg(feature = "encode_percent")]
 mod encode_percent;
+#[cfg(feature = "encode_protobuf")]
+mod encode_protobuf;
+#[cfg(feature = "encode_xml")]
+mod encode_xml;
+#[cfg(feature = "encode_yaml")]
+mod encode_yaml;
+#[cfg(feature = "encode_zlib")]
+mod encode_zlib;
+#[cfg(feature = "encrypt")]
+mod encrypt;
 #[cfg(feature = "ends_with")]
 mod ends_with;
 #[cfg(feature = "exists")]
 mod exists;
+#[cfg(feature = "filter")]
+mod filter;
 #[cfg(feature = "find")]
 mod find;
 #[cfg(feature = "flatten")]
@@ -46,6 +76,8 @@ mod flatten;
 mod float;
 #[cfg(feature = "floor")]
 mod floor;
+#[cfg(feature = "for_each")]
+mod for_each;
 #[cfg(feature = "format_int")]
 mod format_int;
 #[cfg(feature = "format_number")]
@@ -58,6 +90,8 @@ mod get;
 mod get_env_var;
 #[cfg(feature = "get_hostname")]
 mod get_hostname;
+#[cfg(feature = "hmac")]
+mod hmac;
 #[cfg(feature = "includes")]
 mod includes;
 #[cfg(feature = "integer")]
@@ -66,12 +100,20 @@ mod integer;
 mod ip_aton;
 #[cfg(feature = "ip_cidr_contains")]
 mod ip_cidr_contains;
+#[cfg(feature = "ip_is_loopback")]
+mod ip_is_loopback;
+#[cfg(feature = "ip_is_multicast")]
+mod ip_is_multicast;
+#[cfg(feature = "ip_is_private")]
+mod ip_is_private;
 #[cfg(feature = "ip_ntoa")]
 mod ip_ntoa;
 #[cfg(feature = "ip_subnet")]
 mod ip_subnet;
 #[cfg(feature = "ip_to_ipv6")]
 mod ip_to_ipv6;
+#[cfg(feature = "ip_version")]
+mod ip_version;
 #[cfg(feature = "ipv6_to_ipv4")]
 mod ipv6_to_ipv4;
 #[cfg(feature = "is_array")]
@@ -82,6 +124,10 @@ mod is_boolean;
 mod is_float;
 #[cfg(feature = "is_integer")]
 mod is_integer;
+#[cfg(feature = "is_ipv4")]
+mod is_ipv4;
+#[cfg(feature = "is_ipv6")]
+mod is_ipv6;
 #[cfg(feature = "is_null")]
 mod is_null;
 #[cfg(feature = "is_nullish")]
@@ -106,6 +152,10 @@ mod log;
     feature = "parse_nginx_log"
 ))]
 mod log_util;
+#[cfg(feature = "map_keys")]
+mod map_keys;
+#[cfg(feature = "map_values")]
+mod map_values;
 #[cfg(feature = "match")]
 mod r#match;
 #[cfg(feature = "match_any")]
@@ -132,6 +182,8 @@ mod parse_aws_alb_log;
 mod parse_aws_cloudwatch_log_subscription_message;
 #[cfg(feature = "parse_aws_vpc_flow_log")]
 mod parse_aws_vpc_flow_log;
+#[cfg(feature = "parse_cef")]
+mod parse_cef;
 #[cfg(feature = "parse_common_log")]
 mod parse_common_log;
 #[cfg(feature = "parse_csv")]
@@ -150,12 +202,18 @@ mod parse_json;
 mod parse_key_value;
 #[cfg(feature = "parse_klog")]
 mod parse_klog;
+#[cfg(feature = "parse_leef")]
+mod parse_leef;
 #[cfg(feature = "parse_linux_authorization")]
 mod parse_linux_authorization;
 #[cfg(feature = "parse_logfmt")]
 mod parse_logfmt;
 #[cfg(feature = "parse_nginx_log")]
 mod parse_nginx_log;
+#[cfg(feature = "parse_port_name")]
+mod parse_port_name;
+#[cfg(feature = "parse_protobuf")]
+mod parse_protobuf;
 #[cfg(feature = "parse_query_string")]
 mod parse_query_string;
 #[cfg(feature = "parse_regex")]
@@ -176,10 +234,18 @@ mod parse_url;
 mod parse_user_agent;
 #[cfg(feature = "parse_xml")]
 mod parse_xml;
+#[cfg(feature = "parse_yaml")]
+mod parse_yaml;
+#[cfg(any(feature = "encode_protobuf", feature = "parse_protobuf"))]
+mod protobuf_util;
 #[cfg(feature = "push")]
 mod push;
+#[cfg(feature = "random_bytes")]
+mod random_bytes;
 #[cfg(feature = "redact")]
 mod redact;
+#[cfg(feature = "reduce")]
+mod reduce;
 #[cfg(feature = "remove")]
 mod remove;
 #[cfg(feature = "replace")]
@@ -244,6 +310,8 @@ mod unnest;
 mod upcase;
 #[cfg(feature = "uuid_v4")]
 mod uuid_v4;
+#[cfg(feature = "xxhash")]
+mod xxhash;
 
 // -----------------------------------------------------------------------------
 
@@ -263,20 +331,36 @@ pub use assert_eq::AssertEq;
 pub use boolean::Boolean;
 #[cfg(feature = "ceil")]
 pub use ceil::Ceil;
+#[cfg(feature = "community_id")]
+pub use community_id::CommunityId;
 #[cfg(feature = "compact")]
 pub use compact::Compact;
 #[cfg(feature = "contains")]
 pub use contains::Contains;
+#[cfg(feature = "crc32")]
+pub use crc32::Crc32;
 #[cfg(feature = "decode_base64")]
 pub use decode_base64::DecodeBase64;
+#[cfg(feature = "decode_gzip")]
+pub use decode_gzip::DecodeGzip;
 #[cfg(feature = "decode_percent")]
 pub use decode_percent::DecodePercent;
+#[cfg(feature = "decode_zlib")]
+pub use decode_zlib::DecodeZlib;
+#[cfg(feature = "decrypt")]
+pub use decrypt::Decrypt;
 #[cfg(feature = "del")]
 pub use del::Del;
 #[cfg(feature = "downcase")]
 pub use downcase::Downcase;
+#[cfg(feature = "encode_base16")]
+pub use encode_base16::EncodeBase16;
 #[cfg(feature = "encode_base64")]
 pub use encode_base64::EncodeBase64;
+#[cfg(feature = "encode_csv")]
+pub use encode_csv::EncodeCsv;
+#[cfg(feature = "encode_gzip")]
+pub use encode_gzip::EncodeGzip;
 #[cfg(feature = "encode_json")]
 pub use encode_json::EncodeJson;
 #[cfg(feature = "encode_key_value")]
@@ -285,10 +369,22 @@ pub use encode_key_value::EncodeKeyValue;
 pub use encode_logfmt::EncodeLogfmt;
 #[cfg(feature = "encode_percent")]
 pub use encode_percent::EncodePercent;
+#[cfg(feature = "encode_protobuf")]
+pub use encode_protobuf::EncodeProtobuf;
+#[cfg(feature = "encode_xml")]
+pub use encode_xml::EncodeXml;
+#[cfg(feature = "encode_yaml")]
+pub use encode_yaml::EncodeYaml;
+#[cfg(feature = "encode_zlib")]
+pub use encode_zlib::EncodeZlib;
+#[cfg(feature = "encrypt")]
+pub use encrypt::Encrypt;
 #[cfg(feature = "ends_with")]
 pub use ends_with::EndsWith;
 #[cfg(feature = "exists")]
 pub use exists::Exists;
+#[cfg(feature = "filter")]
+pub use filter::Filter;
 #[cfg(feature = "find")]
 pub use find::Find;
 #[cfg(feature = "flatten")]
@@ -297,6 +393,8 @@ pub use flatten::Flatten;
 pub use float::Float;
 #[cfg(feature = "floor")]
 pub use floor::Floor;
+#[cfg(feature = "for_each")]
+pub use for_each::ForEach;
 #[cfg(feature = "format_int")]
 pub use format_int::FormatInt;
 #[cfg(feature = "format_number")]
@@ -309,6 +407,8 @@ pub use get::Get;
 pub use get_env_var::GetEnvVar;
 #[cfg(feature = "get_hostname")]
 pub use get_hostname::GetHostname;
+#[cfg(feature = "hmac")]
+pub use hmac::Hmac;
 #[cfg(feature = "includes")]
 pub use includes::Includes;
 #[cfg(feature = "integer")]
@@ -317,12 +417,20 @@ pub use integer::Integer;
 pub use ip_aton::IpAton;
 #[cfg(feature = "ip_cidr_contains")]
 pub use ip_cidr_contains::IpCidrContains;
+#[cfg(feature = "ip_is_loopback")]
+pub use ip_is_loopback::IpIsLoopback;
+#[cfg(feature = "ip_is_multicast")]
+pub use ip_is_multicast::IpIsMulticast;
+#[cfg(feature = "ip_is_private")]
+pub use ip_is_private::IpIsPrivate;
 #[cfg(feature = "ip_ntoa")]
 pub use ip_ntoa::IpNtoa;
 #[cfg(feature = "ip_subnet")]
 pub use ip_subnet::IpSubnet;
 #[cfg(feature = "ip_to_ipv6")]
 pub use ip_to_ipv6::IpToIpv6;
+#[cfg(feature = "ip_version")]
+pub use ip_version::IpVersion;
 #[cfg(feature = "ipv6_to_ipv4")]
 pub use ipv6_to_ipv4::Ipv6ToIpV4;
 #[cfg(feature = "is_array")]
@@ -333,6 +441,10 @@ pub use is_boolean::IsBoolean;
 pub use is_float::IsFloat;
 #[cfg(feature = "is_integer")]
 pub use is_integer::IsInteger;
+#[cfg(feature = "is_ipv4")]
+pub use is_ipv4::IsIpv4;
+#[cfg(feature = "is_ipv6")]
+pub use is_ipv6::IsIpv6;
 #[cfg(feature = "is_null")]
 pub use is_null::IsNull;
 #[cfg(feature = "is_nullish")]
@@ -351,6 +463,10 @@ pub use join::Join;
 pub use length::Length;
 #[cfg(feature = "log")]
 pub use log::Log;
+#[cfg(feature = "map_keys")]
+pub use map_keys::MapKeys;
+#[cfg(feature = "map_values")]
+pub use map_values::MapValues;
 #[cfg(feature = "match_any")]
 pub use match_any::MatchAny;
 #[cfg(feature = "match_array")]
@@ -373,6 +489,8 @@ pub use parse_aws_alb_log::ParseAwsAlbLog;
 pub use parse_aws_cloudwatch_log_subscription_message::ParseAwsCloudWatchLogSubscriptionMessage;
 #[cfg(feature = "parse_aws_vpc_flow_log")]
 pub use parse_aws_vpc_flow_log::ParseAwsVpcFlowLog;
+#[cfg(feature = "parse_cef")]
+pub use parse_cef::ParseCef;
 #[cfg(feature = "parse_common_log")]
 pub use parse_common_log::ParseCommonLog;
 #[cfg(feature = "parse_csv")]
@@ -391,12 +509,18 @@ pub use parse_json::ParseJson;
 pub use parse_key_value::ParseKeyValue;
 #[cfg(feature = "parse_klog")]
 pub use parse_klog::ParseKlog;
+#[cfg(feature = "parse_leef")]
+pub use parse_leef::ParseLeef;
 #[cfg(feature = "parse_linux_authorization")]
 pub use parse_linux_authorization::ParseLinuxAuthorization;
 #[cfg(feature = "parse_logfmt")]
 pub use parse_logfmt::ParseLogFmt;
 #[cfg(feature = "parse_nginx_log")]
 pub use parse_nginx_log::ParseNginxLog;
+#[cfg(feature = "parse_port_name")]
+pub use parse_port_name::ParsePortName;
+#[cfg(feature = "parse_protobuf")]
+pub use parse_protobuf::ParseProtobuf;
 #[cfg(feature = "parse_query_string")]
 pub use parse_query_string::ParseQueryString;
 #[cfg(feature = "parse_regex")]
@@ -417,12 +541,18 @@ pub use parse_url::ParseUrl;
 pub use parse_user_agent::ParseUserAgent;
 #[cfg(feature = "parse_xml")]
 pub use parse_xml::ParseXml;
+#[cfg(feature = "parse_yaml")]
+pub use parse_yaml::ParseYaml;
 #[cfg(feature = "push")]
 pub use push::Push;
 #[cfg(feature = "match")]
 pub use r#match::Match;
+#[cfg(feature = "random_bytes")]
+pub use random_bytes::RandomBytes;
 #[cfg(feature = "redact")]
 pub use redact::Redact;
+#[cfg(feature = "reduce")]
+pub use reduce::Reduce;
 #[cfg(feature = "remove")]
 pub use remove::Remove;
 #[cfg(feature = "replace")]
@@ -485,6 +615,8 @@ pub use unnest::Unnest;
 pub use upcase::Upcase;
 #[cfg(feature = "uuid_v4")]
 pub use uuid_v4::UuidV4;
+#[cfg(feature = "xxhash")]
+pub use xxhash::Xxhash;
 
 pub fn all() -> Vec<Box<dyn vrl::Function>> {
     vec![
@@ -500,20 +632,36 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(Boolean),
         #[cfg(feature = "ceil")]
         Box::new(Ceil),
+        #[cfg(feature = "community_id")]
+        Box::new(CommunityId),
         #[cfg(feature = "compact")]
         Box::new(Compact),
         #[cfg(feature = "contains")]
         Box::new(Contains),
+        #[cfg(feature = "crc32")]
+        Box::new(Crc32),
         #[cfg(feature = "decode_base64")]
         Box::new(DecodeBase64),
+        #[cfg(feature = "decode_gzip")]
+        Box::new(DecodeGzip),
         #[cfg(feature = "decode_percent")]
         Box::new(DecodePercent),
+        #[cfg(feature = "decode_zlib")]
+        Box::new(DecodeZlib),
+        #[cfg(feature = "decrypt")]
+        Box::new(Decrypt),
         #[cfg(feature = "del")]
         Box::new(Del),
         #[cfg(feature = "downcase")]
         Box::new(Downcase),
+        #[cfg(feature = "encode_base16")]
+        Box::new(EncodeBase16),
         #[cfg(feature = "encode_base64")]
         Box::new(EncodeBase64),
+        #[cfg(feature = "encode_csv")]
+        Box::new(EncodeCsv),
+        #[cfg(feature = "encode_gzip")]
+        Box::new(EncodeGzip),
         #[cfg(feature = "encode_json")]
         Box::new(EncodeJson),
         #[cfg(feature = "encode_key_value")]
@@ -522,10 +670,22 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(EncodeLogfmt),
         #[cfg(feature = "encode_percent")]
         Box::new(EncodePercent),
+        #[cfg(feature = "encode_protobuf")]
+        Box::new(EncodeProtobuf),
+        #[cfg(feature = "encode_xml")]
+        Box::new(EncodeXml),
+        #[cfg(feature = "encode_yaml")]
+        Box::new(EncodeYaml),
+        #[cfg(feature = "encode_zlib")]
+        Box::new(EncodeZlib),
+        #[cfg(feature = "encrypt")]
+        Box::new(Encrypt),
         #[cfg(feature = "ends_with")]
         Box::new(EndsWith),
         #[cfg(feature = "exists")]
         Box::new(Exists),
+        #[cfg(feature = "filter")]
+        Box::new(Filter),
         #[cfg(feature = "find")]
         Box::new(Find),
         #[cfg(feature = "flatten")]
@@ -534,6 +694,8 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(Float),
         #[cfg(feature = "floor")]
         Box::new(Floor),
+        #[cfg(feature = "for_each")]
+        Box::new(ForEach),
         #[cfg(feature = "format_int")]
         Box::new(FormatInt),
         #[cfg(feature = "format_number")]
@@ -546,6 +708,8 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(GetEnvVar),
         #[cfg(feature = "get_hostname")]
         Box::new(GetHostname),
+        #[cfg(feature = "hmac")]
+        Box::new(Hmac),
         #[cfg(feature = "includes")]
         Box::new(Includes),
         #[cfg(feature = "integer")]
@@ -554,12 +718,20 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(IpAton),
         #[cfg(feature = "ip_cidr_contains")]
         Box::new(IpCidrContains),
+        #[cfg(feature = "ip_is_loopback")]
+        Box::new(IpIsLoopback),
+        #[cfg(feature = "ip_is_multicast")]
+        Box::new(IpIsMulticast),
+        #[cfg(feature = "ip_is_private")]
+        Box::new(IpIsPrivate),
         #[cfg(feature = "ip_ntoa")]
         Box::new(IpNtoa),
         #[cfg(feature = "ip_subnet")]
         Box::new(IpSubnet),
         #[cfg(feature = "ip_to_ipv6")]
         Box::new(IpToIpv6),
+        #[cfg(feature = "ip_version")]
+        Box::new(IpVersion),
         #[cfg(feature = "ipv6_to_ipv4")]
         Box::new(Ipv6ToIpV4),
         #[cfg(feature = "is_array")]
@@ -570,6 +742,10 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(IsFloat),
         #[cfg(feature = "is_integer")]
         Box::new(IsInteger),
+        #[cfg(feature = "is_ipv4")]
+        Box::new(IsIpv4),
+        #[cfg(feature = "is_ipv6")]
+        Box::new(IsIpv6),
         #[cfg(feature = "is_null")]
         Box::new(IsNull),
         #[cfg(feature = "is_nullish")]
@@ -588,6 +764,10 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(Length),
         #[cfg(feature = "log")]
         Box::new(Log),
+        #[cfg(feature = "map_keys")]
+        Box::new(MapKeys),
+        #[cfg(feature = "map_values")]
+        Box::new(MapValues),
         #[cfg(feature = "match")]
         Box::new(Match),
         #[cfg(feature = "match_any")]
@@ -616,6 +796,8 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(ParseAwsCloudWatchLogSubscriptionMessage),
         #[cfg(feature = "parse_aws_vpc_flow_log")]
         Box::new(ParseAwsVpcFlowLog),
+        #[cfg(feature = "parse_cef")]
+        Box::new(ParseCef),
         #[cfg(feature = "parse_common_log")]
         Box::new(ParseCommonLog),
         #[cfg(feature = "parse_csv")]
@@ -634,12 +816,18 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(ParseKeyValue),
         #[cfg(feature = "parse_klog")]
         Box::new(ParseKlog),
+        #[cfg(feature = "parse_leef")]
+        Box::new(ParseLeef),
         #[cfg(feature = "parse_linux_authorization")]
         Box::new(ParseLinuxAuthorization),
         #[cfg(feature = "parse_logfmt")]
         Box::new(ParseLogFmt),
         #[cfg(feature = "parse_nginx_log")]
         Box::new(ParseNginxLog),
+        #[cfg(feature = "parse_port_name")]
+        Box::new(ParsePortName),
+        #[cfg(feature = "parse_protobuf")]
+        Box::new(ParseProtobuf),
         #[cfg(feature = "parse_query_string")]
         Box::new(ParseQueryString),
         #[cfg(feature = "parse_regex")]
@@ -660,10 +848,16 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(ParseUserAgent),
         #[cfg(feature = "parse_xml")]
         Box::new(ParseXml),
+        #[cfg(feature = "parse_yaml")]
+        Box::new(ParseYaml),
         #[cfg(feature = "push")]
         Box::new(Push),
+        #[cfg(feature = "random_bytes")]
+        Box::new(RandomBytes),
         #[cfg(feature = "redact")]
         Box::new(Redact),
+        #[cfg(feature = "reduce")]
+        Box::new(Reduce),
         #[cfg(feature = "remove")]
         Box::new(Remove),
         #[cfg(feature = "replace")]
@@ -728,5 +922,7 @@ pub fn all() -> Vec<Box<dyn vrl::Function>> {
         Box::new(Upcase),
         #[cfg(feature = "uuid_v4")]
         Box::new(UuidV4),
+        #[cfg(feature = "xxhash")]
+        Box::new(Xxhash),
     ]
 }
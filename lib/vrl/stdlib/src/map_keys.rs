@@ -0,0 +1,114 @@
+use std::collections::BTreeMap;
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct MapKeys;
+
+impl Function for MapKeys {
+    fn identifier(&self) -> &'static str {
+        "map_keys"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::OBJECT,
+            required: true,
+        }]
+    }
+
+    fn closure(&self) -> Option<closure::Definition> {
+        Some(closure::Definition {
+            input: "value",
+            variables: &[closure::Variable::Key],
+            output: closure::Output::Kind(kind::BYTES),
+        })
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "upcase keys",
+            source: r#"map_keys({ "a": 1, "b": 2 }) -> |key| { upcase(key) }"#,
+            result: Ok(r#"{ "A": 1, "B": 2 }"#),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let closure = arguments.required_closure();
+
+        Ok(Box::new(MapKeysFn { value, closure }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct MapKeysFn {
+    value: Box<dyn Expression>,
+    closure: FunctionClosure,
+}
+
+impl Expression for MapKeysFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let object = self.value.resolve(ctx)?.try_object()?;
+
+        // Keys mapped to the same key overwrite each other, the last one in
+        // key order wins.
+        let mut mapped = BTreeMap::new();
+        for (key, value) in object {
+            let key = self
+                .closure
+                .run(ctx, vec![key.into()])?
+                .try_bytes_utf8_lossy()?
+                .into_owned();
+
+            mapped.insert(key, value);
+        }
+
+        Ok(mapped.into())
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        let element = self.value.type_def(state).element_type_def().kind();
+
+        TypeDef::new().object::<(), Kind>(map! { (): element })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn closure(source: &str) -> FunctionClosure {
+        FunctionClosure::from_source(&[("key", TypeDef::new().bytes())], source, Kind::Bytes)
+    }
+
+    test_function![
+        map_keys => MapKeys;
+
+        object {
+            args: ArgumentList::from(func_args![value: value!({ "a": 1, "b": "2" })])
+                .with_closure(closure(r#""prefix_" + key"#)),
+            want: Ok(value!({ "prefix_a": 1, "prefix_b": "2" })),
+            tdef: TypeDef::new().object::<(), Kind>(map! { (): Kind::Integer | Kind::Bytes }),
+        }
+
+        partially_matching_kind {
+            args: ArgumentList::from(func_args![value: value!({ "a": 1, "b": 2 })])
+                .with_closure(closure(r#"if key == "a" { key } else { 1 }"#)),
+            want: Err(r#"expected "string", got "integer""#),
+            tdef: TypeDef::new().object::<(), Kind>(map! { (): Kind::Integer }),
+        }
+
+        non_object {
+            args: ArgumentList::from(func_args![value: value!([1, 2])])
+                .with_closure(closure("key")),
+            want: Err(r#"expected "object", got "array""#),
+            tdef: TypeDef::new().object::<(), Kind>(map! { (): Kind::Integer }),
+        }
+    ];
+}
@@ -0,0 +1,126 @@
+use crate::util;
+use std::collections::BTreeMap;
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct MapValues;
+
+impl Function for MapValues {
+    fn identifier(&self) -> &'static str {
+        "map_values"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::OBJECT | kind::ARRAY,
+            required: true,
+        }]
+    }
+
+    fn closure(&self) -> Option<closure::Definition> {
+        Some(closure::Definition {
+            input: "value",
+            variables: &[closure::Variable::Value],
+            output: closure::Output::Kind(kind::ANY),
+        })
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "map object values",
+                source: r#"map_values({ "a": 1, "b": 2 }) -> |value| { value * 10 }"#,
+                result: Ok(r#"{ "a": 10, "b": 20 }"#),
+            },
+            Example {
+                title: "map array values",
+                source: r#"map_values(["foo", "bar"]) -> |value| { upcase(value) }"#,
+                result: Ok(r#"["FOO", "BAR"]"#),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let closure = arguments.required_closure();
+
+        Ok(Box::new(MapValuesFn { value, closure }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct MapValuesFn {
+    value: Box<dyn Expression>,
+    closure: FunctionClosure,
+}
+
+impl Expression for MapValuesFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        match self.value.resolve(ctx)? {
+            Value::Object(object) => object
+                .into_iter()
+                .map(|(key, value)| Ok((key, self.closure.run(ctx, vec![value])?)))
+                .collect::<Result<BTreeMap<_, _>>>()
+                .map(Into::into),
+            Value::Array(array) => array
+                .into_iter()
+                .map(|value| self.closure.run(ctx, vec![value]))
+                .collect::<Result<Vec<_>>>()
+                .map(Into::into),
+            value => Err(value::Error::Expected {
+                got: value.kind(),
+                expected: Kind::Array | Kind::Object,
+            }
+            .into()),
+        }
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        let value = self.value.type_def(state);
+        let element = self.closure.type_def().kind();
+
+        util::container_type_def(&value, element)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn closure(source: &str) -> FunctionClosure {
+        FunctionClosure::from_source(&[("value", TypeDef::new().integer())], source, Kind::all())
+    }
+
+    test_function![
+        map_values => MapValues;
+
+        object {
+            args: ArgumentList::from(func_args![value: value!({ "a": 1, "b": 2 })])
+                .with_closure(closure("value * 10")),
+            want: Ok(value!({ "a": 10, "b": 20 })),
+            tdef: TypeDef::new().object::<(), Kind>(map! { (): Kind::Integer }),
+        }
+
+        array {
+            args: ArgumentList::from(func_args![value: value!([1, 2])])
+                .with_closure(closure("value * 10")),
+            want: Ok(value!([10, 20])),
+            tdef: TypeDef::new().array_mapped::<(), Kind>(map! { (): Kind::Integer }),
+        }
+
+        non_container {
+            args: ArgumentList::from(func_args![value: 1])
+                .with_closure(closure("value * 10")),
+            want: Err(r#"expected "object" or "array", got "integer""#),
+            tdef: TypeDef::new()
+                .array_mapped::<(), Kind>(map! { (): Kind::Integer })
+                .merge(TypeDef::new().object::<(), Kind>(map! { (): Kind::Integer })),
+        }
+    ];
+}
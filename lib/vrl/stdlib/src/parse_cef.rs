@@ -0,0 +1,403 @@
+use std::collections::BTreeMap;
+use vrl::prelude::*;
+
+/// The names of the fields of the CEF header, in order.
+const HEADER_FIELDS: [&str; 7] = [
+    "cefVersion",
+    "deviceVendor",
+    "deviceProduct",
+    "deviceVersion",
+    "deviceEventClassId",
+    "name",
+    "severity",
+];
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseCef;
+
+impl Function for ParseCef {
+    fn identifier(&self) -> &'static str {
+        "parse_cef"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "translate_custom_fields",
+                kind: kind::BOOLEAN,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "parse CEF",
+                source: r#"parse_cef!(s'CEF:0|Security|threatmanager|1.0|100|worm successfully stopped|10|src=10.0.0.1 dst=2.1.2.2 spt=1232 msg=Detected a threat. No action needed.')"#,
+                result: Ok(indoc! {r#"{
+                    "cefVersion": "0",
+                    "deviceEventClassId": "100",
+                    "deviceProduct": "threatmanager",
+                    "deviceVendor": "Security",
+                    "deviceVersion": "1.0",
+                    "dst": "2.1.2.2",
+                    "msg": "Detected a threat. No action needed.",
+                    "name": "worm successfully stopped",
+                    "severity": "10",
+                    "spt": "1232",
+                    "src": "10.0.0.1"
+                }"#}),
+            },
+            Example {
+                title: "translate custom fields",
+                source: r#"parse_cef!(s'CEF:0|Dev|firewall|2.1|4000|Blocked|5|cs1Label=policy cs1=default deny', translate_custom_fields: true)"#,
+                result: Ok(indoc! {r#"{
+                    "cefVersion": "0",
+                    "deviceEventClassId": "4000",
+                    "deviceProduct": "firewall",
+                    "deviceVendor": "Dev",
+                    "deviceVersion": "2.1",
+                    "name": "Blocked",
+                    "policy": "default deny",
+                    "severity": "5"
+                }"#}),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let translate_custom_fields = arguments.optional("translate_custom_fields");
+
+        Ok(Box::new(ParseCefFn {
+            value,
+            translate_custom_fields,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParseCefFn {
+    value: Box<dyn Expression>,
+    translate_custom_fields: Option<Box<dyn Expression>>,
+}
+
+impl Expression for ParseCefFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let bytes = self.value.resolve(ctx)?.try_bytes()?;
+        let message = String::from_utf8_lossy(&bytes);
+
+        let translate_custom_fields = match &self.translate_custom_fields {
+            Some(expr) => expr.resolve(ctx)?.try_boolean()?,
+            None => false,
+        };
+
+        let mut log = parse(&message).map_err(|err| format!("unable to parse CEF: {}", err))?;
+
+        if translate_custom_fields {
+            translate(&mut log);
+        }
+
+        Ok(log
+            .into_iter()
+            .map(|(key, value)| (key, value.into()))
+            .collect::<BTreeMap<_, Value>>()
+            .into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        type_def()
+    }
+}
+
+fn type_def() -> TypeDef {
+    TypeDef::new().fallible().object::<(), Kind>(map! {
+        (): Kind::Bytes
+    })
+}
+
+/// Parses a CEF message, optionally preceded by a syslog header, into the
+/// fields of its header and its extension.
+fn parse(message: &str) -> std::result::Result<BTreeMap<String, String>, String> {
+    let start = message.find("CEF:").ok_or("missing `CEF:` prefix")?;
+    let mut rest = &message[start + 4..];
+
+    let mut log = BTreeMap::new();
+    for (index, &field) in HEADER_FIELDS.iter().enumerate() {
+        let (value, remainder) = header_field(rest);
+
+        match (remainder, HEADER_FIELDS.get(index + 1)) {
+            (Some(remainder), _) => rest = remainder,
+            // The extension is optional, and so is the `|` preceding it.
+            (None, None) if !value.is_empty() => rest = "",
+            (None, next) => {
+                let missing = next.unwrap_or(&field);
+                return Err(format!("missing `{}` header field", missing));
+            }
+        }
+
+        log.insert(field.to_owned(), value);
+    }
+
+    log.extend(extension(rest));
+
+    Ok(log)
+}
+
+/// Splits off the next header field, ended by an unescaped `|`, returning it
+/// unescaped along with the rest of the message, if the `|` was found.
+fn header_field(input: &str) -> (String, Option<&str>) {
+    let mut value = String::new();
+    let mut chars = input.char_indices();
+
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some((_, c @ '|')) | Some((_, c @ '\\')) => value.push(c),
+                Some((_, c)) => {
+                    value.push('\\');
+                    value.push(c);
+                }
+                None => value.push('\\'),
+            },
+            '|' => return (value, Some(&input[index + 1..])),
+            c => value.push(c),
+        }
+    }
+
+    (value, None)
+}
+
+/// Parses the space separated `key=value` pairs of the extension.
+///
+/// Values can contain spaces, so a value ends where the next key starts: at
+/// the last space before the next unescaped `=`.
+fn extension(input: &str) -> Vec<(String, String)> {
+    let mut separators = vec![];
+    let mut escaped = false;
+    for (index, c) in input.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '=' => separators.push(index),
+            _ => {}
+        }
+    }
+
+    // Find where each key starts, ignoring the `=` that can't be preceded by
+    // a key, as they are part of the previous value.
+    let mut pairs: Vec<(usize, usize)> = vec![];
+    for separator in separators {
+        let from = pairs.last().map_or(0, |&(_, previous)| previous + 1);
+        let key_start = match input[from..separator].rfind(' ') {
+            Some(space) => from + space + 1,
+            None if pairs.is_empty() => from,
+            None => continue,
+        };
+
+        if key_start < separator {
+            pairs.push((key_start, separator));
+        }
+    }
+
+    pairs
+        .iter()
+        .enumerate()
+        .map(|(index, &(key_start, separator))| {
+            let end = pairs.get(index + 1).map_or(input.len(), |&(next, _)| next);
+            let key = input[key_start..separator].trim_start();
+            let value = input[separator + 1..end].trim_end();
+
+            (key.to_owned(), unescape_value(value))
+        })
+        .collect()
+}
+
+fn unescape_value(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(c @ '=') | Some(c @ '\\') => unescaped.push(c),
+            Some(c) => {
+                unescaped.push('\\');
+                unescaped.push(c);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
+
+/// Renames the custom fields, such as `cs1` or `cn1`, to the value of their
+/// label field, such as `cs1Label`, removing the label.
+fn translate(log: &mut BTreeMap<String, String>) {
+    let labelled = log
+        .keys()
+        .filter_map(|key| {
+            let field = key.strip_suffix("Label")?;
+            log.contains_key(field).then(|| field.to_owned())
+        })
+        .collect::<Vec<_>>();
+
+    for field in labelled {
+        let label = log
+            .remove(&format!("{}Label", field))
+            .expect("label exists");
+        let value = log.remove(&field).expect("field exists");
+
+        log.insert(label, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::btreemap;
+
+    test_function![
+        parse_cef => ParseCef;
+
+        header_only {
+            args: func_args![value: "CEF:0|Security|threatmanager|1.0|100|worm stopped|10|"],
+            want: Ok(btreemap! {
+                "cefVersion" => "0",
+                "deviceVendor" => "Security",
+                "deviceProduct" => "threatmanager",
+                "deviceVersion" => "1.0",
+                "deviceEventClassId" => "100",
+                "name" => "worm stopped",
+                "severity" => "10",
+            }),
+            tdef: type_def(),
+        }
+
+        syslog_prefix {
+            args: func_args![value: "Sep 29 08:26:10 host CEF:1|Vendor|Product|1|42|Name|Low|act=blocked"],
+            want: Ok(btreemap! {
+                "cefVersion" => "1",
+                "deviceVendor" => "Vendor",
+                "deviceProduct" => "Product",
+                "deviceVersion" => "1",
+                "deviceEventClassId" => "42",
+                "name" => "Name",
+                "severity" => "Low",
+                "act" => "blocked",
+            }),
+            tdef: type_def(),
+        }
+
+        escaped_header {
+            args: func_args![value: r#"CEF:0|security\|corp|threat\\manager|1.0|100|detected a \| in message|10|"#],
+            want: Ok(btreemap! {
+                "cefVersion" => "0",
+                "deviceVendor" => "security|corp",
+                "deviceProduct" => r#"threat\manager"#,
+                "deviceVersion" => "1.0",
+                "deviceEventClassId" => "100",
+                "name" => "detected a | in message",
+                "severity" => "10",
+            }),
+            tdef: type_def(),
+        }
+
+        escaped_extension {
+            args: func_args![value: r#"CEF:0|a|b|c|d|e|1|msg=a\=b c\\d\nline fname=C:\\Windows\\ x=|y"#],
+            want: Ok(btreemap! {
+                "cefVersion" => "0",
+                "deviceVendor" => "a",
+                "deviceProduct" => "b",
+                "deviceVersion" => "c",
+                "deviceEventClassId" => "d",
+                "name" => "e",
+                "severity" => "1",
+                "msg" => "a=b c\\d\nline",
+                "fname" => r#"C:\Windows\"#,
+                "x" => "|y",
+            }),
+            tdef: type_def(),
+        }
+
+        unescaped_equals_in_value {
+            args: func_args![value: "CEF:0|a|b|c|d|e|1|request=https://example.com/?a=b&c=d src=10.0.0.1"],
+            want: Ok(btreemap! {
+                "cefVersion" => "0",
+                "deviceVendor" => "a",
+                "deviceProduct" => "b",
+                "deviceVersion" => "c",
+                "deviceEventClassId" => "d",
+                "name" => "e",
+                "severity" => "1",
+                "request" => "https://example.com/?a=b&c=d",
+                "src" => "10.0.0.1",
+            }),
+            tdef: type_def(),
+        }
+
+        translate_custom_fields {
+            args: func_args![
+                value: "CEF:0|a|b|c|d|e|1|cs1=default deny cs1Label=policy cn1Label=count cs2=x",
+                translate_custom_fields: true,
+            ],
+            want: Ok(btreemap! {
+                "cefVersion" => "0",
+                "deviceVendor" => "a",
+                "deviceProduct" => "b",
+                "deviceVersion" => "c",
+                "deviceEventClassId" => "d",
+                "name" => "e",
+                "severity" => "1",
+                "policy" => "default deny",
+                "cn1Label" => "count",
+                "cs2" => "x",
+            }),
+            tdef: type_def(),
+        }
+
+        no_extension {
+            args: func_args![value: "CEF:0|a|b|c|d|e|1"],
+            want: Ok(btreemap! {
+                "cefVersion" => "0",
+                "deviceVendor" => "a",
+                "deviceProduct" => "b",
+                "deviceVersion" => "c",
+                "deviceEventClassId" => "d",
+                "name" => "e",
+                "severity" => "1",
+            }),
+            tdef: type_def(),
+        }
+
+        missing_prefix {
+            args: func_args![value: "0|a|b|c|d|e|1|"],
+            want: Err("unable to parse CEF: missing `CEF:` prefix"),
+            tdef: type_def(),
+        }
+
+        missing_header_field {
+            args: func_args![value: "CEF:0|a|b|c|d|e"],
+            want: Err("unable to parse CEF: missing `severity` header field"),
+            tdef: type_def(),
+        }
+    ];
+}
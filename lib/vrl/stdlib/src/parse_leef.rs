@@ -0,0 +1,291 @@
+use std::collections::BTreeMap;
+use vrl::prelude::*;
+
+/// The names of the fields of the LEEF header, in order.
+const HEADER_FIELDS: [&str; 5] = [
+    "leefVersion",
+    "vendor",
+    "productName",
+    "productVersion",
+    "eventId",
+];
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseLeef;
+
+impl Function for ParseLeef {
+    fn identifier(&self) -> &'static str {
+        "parse_leef"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "LEEF 1.0",
+                source: r#"parse_leef!("LEEF:1.0|Microsoft|MSExchange|4.0 SP1|15345|src=192.0.2.0\tdst=172.50.123.1\tsev=5\tcat=anomaly")"#,
+                result: Ok(indoc! {r#"{
+                    "cat": "anomaly",
+                    "dst": "172.50.123.1",
+                    "eventId": "15345",
+                    "leefVersion": "1.0",
+                    "productName": "MSExchange",
+                    "productVersion": "4.0 SP1",
+                    "sev": "5",
+                    "src": "192.0.2.0",
+                    "vendor": "Microsoft"
+                }"#}),
+            },
+            Example {
+                title: "LEEF 2.0 with a custom delimiter",
+                source: r#"parse_leef!("LEEF:2.0|Lancope|StealthWatch|1.0|41|^|src=10.0.1.8^dst=10.0.0.5^proto=6")"#,
+                result: Ok(indoc! {r#"{
+                    "dst": "10.0.0.5",
+                    "eventId": "41",
+                    "leefVersion": "2.0",
+                    "productName": "StealthWatch",
+                    "productVersion": "1.0",
+                    "proto": "6",
+                    "src": "10.0.1.8",
+                    "vendor": "Lancope"
+                }"#}),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(Box::new(ParseLeefFn { value }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParseLeefFn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for ParseLeefFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let bytes = self.value.resolve(ctx)?.try_bytes()?;
+        let message = String::from_utf8_lossy(&bytes);
+
+        let log = parse(&message).map_err(|err| format!("unable to parse LEEF: {}", err))?;
+
+        Ok(log
+            .into_iter()
+            .map(|(key, value)| (key, value.into()))
+            .collect::<BTreeMap<_, Value>>()
+            .into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        type_def()
+    }
+}
+
+fn type_def() -> TypeDef {
+    TypeDef::new().fallible().object::<(), Kind>(map! {
+        (): Kind::Bytes
+    })
+}
+
+/// Parses a LEEF message, optionally preceded by a syslog header, into the
+/// fields of its header and its event attributes.
+fn parse(message: &str) -> std::result::Result<BTreeMap<String, String>, String> {
+    let start = message.find("LEEF:").ok_or("missing `LEEF:` prefix")?;
+    let mut rest = &message[start + 5..];
+
+    let mut log = BTreeMap::new();
+    for (index, &field) in HEADER_FIELDS.iter().enumerate() {
+        let value = match (rest.split_once('|'), HEADER_FIELDS.get(index + 1)) {
+            (Some((value, remainder)), _) => {
+                rest = remainder;
+                value
+            }
+            // The attributes are optional, and so is the `|` preceding them.
+            (None, None) if !rest.is_empty() => std::mem::take(&mut rest),
+            (None, next) => {
+                let missing = next.unwrap_or(&field);
+                return Err(format!("missing `{}` header field", missing));
+            }
+        };
+
+        log.insert(field.to_owned(), value.to_owned());
+    }
+
+    // LEEF 2.0 adds a header field for the delimiter of the attributes, which
+    // can be left out to use the default tab.
+    let mut delimiter = '\t';
+    if !log["leefVersion"].starts_with('1') {
+        if let Some((field, remainder)) = rest.split_once('|') {
+            if !field.contains('=') {
+                delimiter = parse_delimiter(field)?;
+                rest = remainder;
+            }
+        }
+    }
+
+    log.extend(attributes(
+        rest.trim_end_matches(&['\r', '\n'][..]),
+        delimiter,
+    ));
+
+    Ok(log)
+}
+
+/// Parses the delimiter header field, either a single character or the
+/// hexadecimal code of one, prefixed by `x` or `0x`.
+fn parse_delimiter(field: &str) -> std::result::Result<char, String> {
+    let mut chars = field.chars();
+    match (chars.next(), chars.next()) {
+        (None, _) => return Ok('\t'),
+        (Some(c), None) => return Ok(c),
+        _ => {}
+    }
+
+    let hex = field
+        .strip_prefix("0x")
+        .or_else(|| field.strip_prefix('x'))
+        .or_else(|| field.strip_prefix("0X"))
+        .or_else(|| field.strip_prefix('X'));
+
+    hex.and_then(|hex| u32::from_str_radix(hex, 16).ok())
+        .and_then(std::char::from_u32)
+        .ok_or_else(|| format!("invalid delimiter `{}`", field))
+}
+
+/// Parses the `key=value` attributes separated by the delimiter.
+///
+/// Values can contain `=`, and a part without one continues the previous
+/// value, as some sources don't escape the delimiter in values.
+fn attributes(input: &str, delimiter: char) -> Vec<(String, String)> {
+    let mut attributes: Vec<(String, String)> = vec![];
+
+    for part in input.split(delimiter).filter(|part| !part.is_empty()) {
+        match (part.split_once('='), attributes.last_mut()) {
+            (Some((key, value)), _) => attributes.push((key.trim().to_owned(), value.to_owned())),
+            (None, Some((_, value))) => {
+                value.push(delimiter);
+                value.push_str(part);
+            }
+            (None, None) => {}
+        }
+    }
+
+    attributes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::btreemap;
+
+    test_function![
+        parse_leef => ParseLeef;
+
+        leef_1 {
+            args: func_args![value: "LEEF:1.0|Vendor|Product|1.2|login|usrName=joe\tsrc=10.0.0.1\n"],
+            want: Ok(btreemap! {
+                "leefVersion" => "1.0",
+                "vendor" => "Vendor",
+                "productName" => "Product",
+                "productVersion" => "1.2",
+                "eventId" => "login",
+                "usrName" => "joe",
+                "src" => "10.0.0.1",
+            }),
+            tdef: type_def(),
+        }
+
+        syslog_prefix {
+            args: func_args![value: "<13>Jan 18 11:07:53 host LEEF:1.0|Vendor|Product|1.2|login|"],
+            want: Ok(btreemap! {
+                "leefVersion" => "1.0",
+                "vendor" => "Vendor",
+                "productName" => "Product",
+                "productVersion" => "1.2",
+                "eventId" => "login",
+            }),
+            tdef: type_def(),
+        }
+
+        no_attributes {
+            args: func_args![value: "LEEF:1.0|Vendor|Product|1.2|login"],
+            want: Ok(btreemap! {
+                "leefVersion" => "1.0",
+                "vendor" => "Vendor",
+                "productName" => "Product",
+                "productVersion" => "1.2",
+                "eventId" => "login",
+            }),
+            tdef: type_def(),
+        }
+
+        leef_2_hex_delimiter {
+            args: func_args![value: "LEEF:2.0|Vendor|Product|1.2|login|x7C|usrName=joe|url=/?a=b"],
+            want: Ok(btreemap! {
+                "leefVersion" => "2.0",
+                "vendor" => "Vendor",
+                "productName" => "Product",
+                "productVersion" => "1.2",
+                "eventId" => "login",
+                "usrName" => "joe",
+                "url" => "/?a=b",
+            }),
+            tdef: type_def(),
+        }
+
+        leef_2_default_delimiter {
+            args: func_args![value: "LEEF:2.0|Vendor|Product|1.2|login|usrName=joe\tsrc=10.0.0.1"],
+            want: Ok(btreemap! {
+                "leefVersion" => "2.0",
+                "vendor" => "Vendor",
+                "productName" => "Product",
+                "productVersion" => "1.2",
+                "eventId" => "login",
+                "usrName" => "joe",
+                "src" => "10.0.0.1",
+            }),
+            tdef: type_def(),
+        }
+
+        unescaped_delimiter_in_value {
+            args: func_args![value: "LEEF:2.0|Vendor|Product|1.2|login| |usrName=joe smith src=10.0.0.1"],
+            want: Ok(btreemap! {
+                "leefVersion" => "2.0",
+                "vendor" => "Vendor",
+                "productName" => "Product",
+                "productVersion" => "1.2",
+                "eventId" => "login",
+                "usrName" => "joe smith",
+                "src" => "10.0.0.1",
+            }),
+            tdef: type_def(),
+        }
+
+        invalid_delimiter {
+            args: func_args![value: "LEEF:2.0|Vendor|Product|1.2|login|tab|usrName=joe"],
+            want: Err("unable to parse LEEF: invalid delimiter `tab`"),
+            tdef: type_def(),
+        }
+
+        missing_header_field {
+            args: func_args![value: "LEEF:1.0|Vendor|Product"],
+            want: Err("unable to parse LEEF: missing `productVersion` header field"),
+            tdef: type_def(),
+        }
+    ];
+}
@@ -0,0 +1,211 @@
+use vrl::prelude::*;
+
+/// A curated subset of the IANA service name and port number registry, with
+/// the TCP and UDP ports each service is registered for. Only the names the
+/// registry assigns are included, not aliases such as `dns` for `domain` or
+/// `rdp` for `ms-wbt-server`.
+const SERVICES: &[(&str, Option<u16>, Option<u16>)] = &[
+    ("amqp", Some(5672), Some(5672)),
+    ("amqps", Some(5671), Some(5671)),
+    ("bgp", Some(179), Some(179)),
+    ("dhcpv6-client", Some(546), Some(546)),
+    ("dhcpv6-server", Some(547), Some(547)),
+    ("domain", Some(53), Some(53)),
+    ("ftp", Some(21), Some(21)),
+    ("ftp-data", Some(20), Some(20)),
+    ("ftps", Some(990), Some(990)),
+    ("http", Some(80), Some(80)),
+    ("http-alt", Some(8080), Some(8080)),
+    ("https", Some(443), Some(443)),
+    ("imap", Some(143), Some(143)),
+    ("imaps", Some(993), Some(993)),
+    ("ipp", Some(631), Some(631)),
+    ("isakmp", Some(500), Some(500)),
+    ("kerberos", Some(88), Some(88)),
+    ("ldap", Some(389), Some(389)),
+    ("ldaps", Some(636), Some(636)),
+    ("memcache", Some(11211), Some(11211)),
+    ("microsoft-ds", Some(445), Some(445)),
+    ("mongodb", Some(27017), Some(27017)),
+    ("ms-sql-s", Some(1433), Some(1433)),
+    ("ms-wbt-server", Some(3389), Some(3389)),
+    ("mysql", Some(3306), Some(3306)),
+    ("netbios-dgm", Some(138), Some(138)),
+    ("netbios-ns", Some(137), Some(137)),
+    ("netbios-ssn", Some(139), Some(139)),
+    ("nfs", Some(2049), Some(2049)),
+    ("ntp", Some(123), Some(123)),
+    ("pop3", Some(110), Some(110)),
+    ("pop3s", Some(995), Some(995)),
+    ("postgresql", Some(5432), Some(5432)),
+    ("radius", Some(1812), Some(1812)),
+    ("radius-acct", Some(1813), Some(1813)),
+    ("redis", Some(6379), None),
+    ("rsync", Some(873), Some(873)),
+    ("sip", Some(5060), Some(5060)),
+    ("sips", Some(5061), Some(5061)),
+    ("smtp", Some(25), Some(25)),
+    ("snmp", Some(161), Some(161)),
+    ("snmptrap", Some(162), Some(162)),
+    ("ssh", Some(22), Some(22)),
+    ("submission", Some(587), Some(587)),
+    ("submissions", Some(465), None),
+    ("syslog", None, Some(514)),
+    ("syslog-tls", Some(6514), Some(6514)),
+    ("telnet", Some(23), Some(23)),
+    ("tftp", Some(69), Some(69)),
+];
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParsePortName;
+
+impl Function for ParsePortName {
+    fn identifier(&self) -> &'static str {
+        "parse_port_name"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "protocol",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "service name",
+                source: r#"parse_port_name!("https")"#,
+                result: Ok("443"),
+            },
+            Example {
+                title: "UDP service name",
+                source: r#"parse_port_name!("ntp", protocol: "udp")"#,
+                result: Ok("123"),
+            },
+            Example {
+                title: "port number",
+                source: r#"parse_port_name!("8443")"#,
+                result: Ok("8443"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let protocol = arguments
+            .optional_enum("protocol", &protocols())?
+            .unwrap_or_else(|| value!("tcp"))
+            .try_bytes()
+            .expect("protocol not bytes");
+
+        Ok(Box::new(ParsePortNameFn { value, protocol }))
+    }
+}
+
+fn protocols() -> Vec<Value> {
+    vec![value!("tcp"), value!("udp")]
+}
+
+#[derive(Debug, Clone)]
+struct ParsePortNameFn {
+    value: Box<dyn Expression>,
+    protocol: Bytes,
+}
+
+impl Expression for ParsePortNameFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let bytes = self.value.resolve(ctx)?.try_bytes()?;
+        let name = String::from_utf8_lossy(&bytes);
+        let name = name.trim();
+
+        if let Ok(port) = name.parse::<u16>() {
+            return Ok(port.into());
+        }
+
+        let udp = self.protocol.as_ref() == b"udp";
+        SERVICES
+            .iter()
+            .find(|(service, _, _)| service.eq_ignore_ascii_case(name))
+            .and_then(|&(_, tcp_port, udp_port)| if udp { udp_port } else { tcp_port })
+            .map(Into::into)
+            .ok_or_else(|| {
+                let protocol = String::from_utf8_lossy(&self.protocol);
+                format!("unknown {} port name: {}", protocol, name).into()
+            })
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().integer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        parse_port_name => ParsePortName;
+
+        tcp {
+            args: func_args![value: "https"],
+            want: Ok(value!(443)),
+            tdef: TypeDef::new().fallible().integer(),
+        }
+
+        udp {
+            args: func_args![value: "syslog", protocol: "udp"],
+            want: Ok(value!(514)),
+            tdef: TypeDef::new().fallible().integer(),
+        }
+
+        case_insensitive {
+            args: func_args![value: "SSH"],
+            want: Ok(value!(22)),
+            tdef: TypeDef::new().fallible().integer(),
+        }
+
+        number {
+            args: func_args![value: "8080"],
+            want: Ok(value!(8080)),
+            tdef: TypeDef::new().fallible().integer(),
+        }
+
+        udp_and_tcp {
+            args: func_args![value: "http", protocol: "udp"],
+            want: Ok(value!(80)),
+            tdef: TypeDef::new().fallible().integer(),
+        }
+
+        wrong_protocol {
+            args: func_args![value: "syslog"],
+            want: Err("unknown tcp port name: syslog"),
+            tdef: TypeDef::new().fallible().integer(),
+        }
+
+        alias {
+            args: func_args![value: "dns"],
+            want: Err("unknown tcp port name: dns"),
+            tdef: TypeDef::new().fallible().integer(),
+        }
+
+        unknown {
+            args: func_args![value: "gopher-ish"],
+            want: Err("unknown tcp port name: gopher-ish"),
+            tdef: TypeDef::new().fallible().integer(),
+        }
+    ];
+}
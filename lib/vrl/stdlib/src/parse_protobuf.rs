@@ -0,0 +1,209 @@
+use crate::protobuf_util::Schema;
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseProtobuf;
+
+impl Function for ParseProtobuf {
+    fn identifier(&self) -> &'static str {
+        "parse_protobuf"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "desc_file",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "message_type",
+                kind: kind::BYTES,
+                required: true,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "parse protobuf message",
+            source: r#"parse_protobuf!(decode_base64!("CgdTb21lb25lENIJGhNzb21lb25lQGV4YW1wbGUuY29t"), "../stdlib/tests/data/protobuf/test_protobuf.desc", "test_protobuf.Person")"#,
+            result: Ok(r#"{ "email": "someone@example.com", "id": 1234, "name": "Someone" }"#),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+
+        let desc_file = arguments
+            .required_literal("desc_file")?
+            .to_value()
+            .try_bytes_utf8_lossy()
+            .expect("desc_file not bytes")
+            .into_owned();
+
+        let message_type = arguments
+            .required_literal("message_type")?
+            .to_value()
+            .try_bytes_utf8_lossy()
+            .expect("message_type not bytes")
+            .into_owned();
+
+        let schema = Schema::load(&desc_file, &message_type)
+            .map_err(|err| Box::new(err) as Box<dyn DiagnosticError>)?;
+
+        Ok(Box::new(ParseProtobufFn { value, schema }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParseProtobufFn {
+    value: Box<dyn Expression>,
+    schema: Schema,
+}
+
+impl Expression for ParseProtobufFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let bytes = self.value.resolve(ctx)?.try_bytes()?;
+
+        Ok(self
+            .schema
+            .decode(&bytes)
+            .map_err(|err| format!("unable to parse protobuf: {}", err))?)
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        type_def()
+    }
+}
+
+fn type_def() -> TypeDef {
+    TypeDef::new().fallible().object::<(), Kind>(map! {
+        (): Kind::all()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DESC_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/data/protobuf/test_protobuf.desc"
+    );
+
+    const PERSON: &[u8] = b"\x0a\x07Someone\x10\xd2\x09\x1a\x13someone@example.com\x22\x0b\x0a\x07123-456\x10\x01*\x0c\x0a\x03key\x12\x05value2\x04\x01\x02\xac\x029\xcd\xcc\xcc\xcc\xcc\xcc\xfc?@\x01J\x02\x01\x02P\x09";
+
+    /// A `test_protobuf.Tree` message with the given number of nested children.
+    fn tree(depth: usize) -> Value {
+        (0..depth).fold(value!({}), |child, _| value!({ child: child }))
+    }
+
+    /// The encoded `test_protobuf.Tree` message of [`tree`].
+    fn tree_bytes(depth: usize) -> Bytes {
+        let bytes = (0..depth).fold(vec![], |child, _| {
+            let mut bytes = vec![0x0a];
+            let mut len = child.len();
+            while len >= 0x80 {
+                bytes.push(len as u8 | 0x80);
+                len >>= 7;
+            }
+            bytes.push(len as u8);
+            bytes.extend(child);
+            bytes
+        });
+
+        bytes.into()
+    }
+
+    test_function![
+        parse_protobuf => ParseProtobuf;
+
+        message {
+            args: func_args![value: PERSON, desc_file: DESC_FILE, message_type: "test_protobuf.Person"],
+            want: Ok(value!({
+                avatar: "\u{1}\u{2}",
+                data: {key: "value"},
+                email: "someone@example.com",
+                height: 1.8,
+                id: 1234,
+                name: "Someone",
+                offset: (-5),
+                phones: [{number: "123-456", "type": "HOME"}],
+                scores: [1, 2, 300],
+                verified: true,
+            })),
+            tdef: type_def(),
+        }
+
+        unpacked_repeated_field {
+            args: func_args![
+                value: &b"\x30\x01\x30\x02"[..],
+                desc_file: DESC_FILE,
+                message_type: "test_protobuf.Person",
+            ],
+            want: Ok(value!({scores: [1, 2]})),
+            tdef: type_def(),
+        }
+
+        unknown_field {
+            args: func_args![
+                value: &b"\x10\x01\x98\x06\x01"[..],
+                desc_file: DESC_FILE,
+                message_type: "test_protobuf.Person",
+            ],
+            want: Ok(value!({id: 1})),
+            tdef: type_def(),
+        }
+
+        nested_message_type {
+            args: func_args![
+                value: &b"\x0a\x03123\x10\x07"[..],
+                desc_file: DESC_FILE,
+                message_type: "test_protobuf.Person.PhoneNumber",
+            ],
+            want: Ok(value!({number: "123", "type": 7})),
+            tdef: type_def(),
+        }
+
+        truncated_message {
+            args: func_args![value: &b"\x0a\x07Some"[..], desc_file: DESC_FILE, message_type: "test_protobuf.Person"],
+            want: Err("unable to parse protobuf: unexpected end of message"),
+            tdef: type_def(),
+        }
+
+        invalid_wire_type {
+            args: func_args![value: &b"\x15\x01\x02\x03\x04"[..], desc_file: DESC_FILE, message_type: "test_protobuf.Person"],
+            want: Err("unable to parse protobuf: field `id`: invalid wire type 5"),
+            tdef: type_def(),
+        }
+
+        unknown_message_type {
+            args: func_args![value: "", desc_file: DESC_FILE, message_type: "Person"],
+            want: Err("unknown message type `Person`"),
+            tdef: type_def(),
+        }
+
+        deepest_message {
+            args: func_args![value: tree_bytes(100), desc_file: DESC_FILE, message_type: "test_protobuf.Tree"],
+            want: Ok(tree(100)),
+            tdef: type_def(),
+        }
+
+        too_deep_message {
+            args: func_args![value: tree_bytes(101), desc_file: DESC_FILE, message_type: "test_protobuf.Tree"],
+            want: Err("unable to parse protobuf: recursion limit reached"),
+            tdef: type_def(),
+        }
+    ];
+}
@@ -22,6 +22,9 @@ struct ParseXmlConfig<'a> {
     parse_null: bool,
     /// Parse numeric values as integers/floats. Default: true.
     parse_number: bool,
+    /// Key the `Data` elements of Windows events by their `Name` attribute,
+    /// e.g. `<Data Name="user">bob</Data>` -> `{ "user": "bob" }`. Default: false.
+    windows_event: bool,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -33,17 +36,30 @@ impl Function for ParseXml {
     }
 
     fn examples(&self) -> &'static [Example] {
-        &[Example {
-            title: "parse XML",
-            source: indoc! {r#"
+        &[
+            Example {
+                title: "parse XML",
+                source: indoc! {r#"
 				value = s'<book category="CHILDREN"><title lang="en">Harry Potter</title><author>J K. Rowling</author><year>2005</year></book>';
 
 				parse_xml!(value, text_key: "value", parse_number: false)
             "#},
-            result: Ok(
-                r#"{ "book": { "@category": "CHILDREN", "author": "J K. Rowling", "title": { "@lang": "en", "value": "Harry Potter" }, "year": "2005" } }"#,
-            ),
-        }]
+                result: Ok(
+                    r#"{ "book": { "@category": "CHILDREN", "author": "J K. Rowling", "title": { "@lang": "en", "value": "Harry Potter" }, "year": "2005" } }"#,
+                ),
+            },
+            Example {
+                title: "parse Windows event XML",
+                source: indoc! {r#"
+				value = s'<Event xmlns="http://schemas.microsoft.com/win/2004/08/events/event"><System><Provider Name="Microsoft-Windows-Security-Auditing"/><EventID>4624</EventID><Channel>Security</Channel></System><EventData><Data Name="TargetUserName">bob</Data><Data Name="LogonType">3</Data></EventData></Event>';
+
+				parse_xml!(value, windows_event: true)
+            "#},
+                result: Ok(
+                    r#"{ "Event": { "EventData": { "LogonType": 3, "TargetUserName": "bob" }, "System": { "Channel": "Security", "EventID": 4624, "Provider": { "@Name": "Microsoft-Windows-Security-Auditing" } } } }"#,
+                ),
+            },
+        ]
     }
 
     fn compile(
@@ -62,6 +78,7 @@ impl Function for ParseXml {
         let parse_bool = arguments.optional("parse_bool");
         let parse_null = arguments.optional("parse_null");
         let parse_number = arguments.optional("parse_number");
+        let windows_event = arguments.optional("windows_event");
 
         Ok(Box::new(ParseXmlFn {
             value,
@@ -73,6 +90,7 @@ impl Function for ParseXml {
             parse_bool,
             parse_null,
             parse_number,
+            windows_event,
         }))
     }
 
@@ -123,6 +141,11 @@ impl Function for ParseXml {
                 kind: kind::BOOLEAN,
                 required: false,
             },
+            Parameter {
+                keyword: "windows_event",
+                kind: kind::BOOLEAN,
+                required: false,
+            },
         ]
     }
 }
@@ -139,6 +162,7 @@ struct ParseXmlFn {
     parse_bool: Option<Box<dyn Expression>>,
     parse_null: Option<Box<dyn Expression>>,
     parse_number: Option<Box<dyn Expression>>,
+    windows_event: Option<Box<dyn Expression>>,
 }
 
 impl Expression for ParseXmlFn {
@@ -186,6 +210,11 @@ impl Expression for ParseXmlFn {
             None => true,
         };
 
+        let windows_event = match &self.windows_event {
+            Some(expr) => expr.resolve(ctx)?.try_boolean()?,
+            None => false,
+        };
+
         let config = ParseXmlConfig {
             include_attr,
             attr_prefix,
@@ -194,6 +223,7 @@ impl Expression for ParseXmlFn {
             parse_bool,
             parse_null,
             parse_number,
+            windows_event,
         };
 
         // Trim whitespace around XML elements, if applicable.
@@ -238,26 +268,7 @@ fn process_node<'a>(node: Node, config: &ParseXmlConfig<'a>) -> Value {
             // Transform the node into a VRL `Value`.
             let value = process_node(n, config);
 
-            // If the key already exists, add it. Otherwise, insert.
-            match map.entry(name) {
-                Entry::Occupied(mut entry) => {
-                    let v = entry.get_mut();
-
-                    // Push a value onto the existing array, or wrap in a `Value::Array`.
-                    match v {
-                        Value::Array(v) => v.push(value),
-                        v => {
-                            let prev = std::mem::replace(v, Value::Array(Vec::with_capacity(2)));
-                            if let Value::Array(v) = v {
-                                v.extend_from_slice(&[prev, value]);
-                            }
-                        }
-                    };
-                }
-                Entry::Vacant(entry) => {
-                    entry.insert(value);
-                }
-            }
+            insert_value(&mut map, name, value);
         }
 
         map
@@ -266,6 +277,8 @@ fn process_node<'a>(node: Node, config: &ParseXmlConfig<'a>) -> Value {
     match node.node_type() {
         NodeType::Root => Value::Object(recurse(node)),
 
+        NodeType::Element if is_event_data(node, config) => process_event_data(node, config),
+
         NodeType::Element => {
             let mut map = BTreeMap::new();
 
@@ -300,11 +313,14 @@ fn process_node<'a>(node: Node, config: &ParseXmlConfig<'a>) -> Value {
 
                         // If the node is an element, treat it as an object.
                         if node.is_element() {
+                            let value = if is_event_data(node, config) {
+                                process_event_data(node, config)
+                            } else {
+                                Value::Object(recurse(node))
+                            };
+
                             let mut map = BTreeMap::new();
-                            map.insert(
-                                node.tag_name().name().to_string(),
-                                Value::Object(recurse(node)),
-                            );
+                            map.insert(node.tag_name().name().to_string(), value);
 
                             Value::Object(map)
                         } else {
@@ -322,6 +338,55 @@ fn process_node<'a>(node: Node, config: &ParseXmlConfig<'a>) -> Value {
     }
 }
 
+/// Insert a value into an object. If the key already exists, the values are
+/// collected into an array.
+fn insert_value(map: &mut BTreeMap<String, Value>, name: String, value: Value) {
+    match map.entry(name) {
+        Entry::Occupied(mut entry) => {
+            let v = entry.get_mut();
+
+            // Push a value onto the existing array, or wrap in a `Value::Array`.
+            match v {
+                Value::Array(v) => v.push(value),
+                v => {
+                    let prev = std::mem::replace(v, Value::Array(Vec::with_capacity(2)));
+                    if let Value::Array(v) = v {
+                        v.extend_from_slice(&[prev, value]);
+                    }
+                }
+            };
+        }
+        Entry::Vacant(entry) => {
+            entry.insert(value);
+        }
+    }
+}
+
+fn is_event_data<'a>(node: Node, config: &ParseXmlConfig<'a>) -> bool {
+    config.windows_event && node.tag_name().name() == "EventData"
+}
+
+/// Process the `EventData` element of a Windows event, keying the values of
+/// its `Data` elements by their `Name` attribute. Other elements, such as
+/// unnamed `Data` elements, are processed as usual.
+fn process_event_data<'a>(node: Node, config: &ParseXmlConfig<'a>) -> Value {
+    let mut map = BTreeMap::new();
+
+    for n in node.children().filter(|n| n.is_element()) {
+        let name = n.tag_name().name();
+
+        match n.attribute("Name") {
+            Some(key) if name == "Data" => {
+                let value = process_text(n.text().unwrap_or(""), config);
+                insert_value(&mut map, key.to_owned(), value);
+            }
+            _ => insert_value(&mut map, name.to_owned(), process_node(n, config)),
+        }
+    }
+
+    Value::Object(map)
+}
+
 /// Process a text node, and return the correct `Value` type based on config.
 fn process_text<'a>(text: &'a str, config: &ParseXmlConfig<'a>) -> Value {
     match text {
@@ -610,5 +675,85 @@ mod tests {
             )),
             tdef: type_def(),
         }
+
+        windows_event {
+            args: func_args![ value: indoc!{r#"
+                <Event xmlns="http://schemas.microsoft.com/win/2004/08/events/event">
+                  <System>
+                    <Provider Name="Microsoft-Windows-Security-Auditing" />
+                    <EventID>4625</EventID>
+                    <TimeCreated SystemTime="2021-10-18T09:35:12.3848176Z" />
+                    <Channel>Security</Channel>
+                  </System>
+                  <EventData>
+                    <Data Name="TargetUserName">bob</Data>
+                    <Data Name="Status">0xc000006d</Data>
+                    <Data Name="LogonType">3</Data>
+                    <Data Name="IpAddress">-</Data>
+                    <Data Name="ProcessName"></Data>
+                  </EventData>
+                </Event>
+            "#}, windows_event: true ],
+            want: Ok(value!(
+                {
+                  "Event": {
+                    "System": {
+                      "Provider": { "@Name": "Microsoft-Windows-Security-Auditing" },
+                      "EventID": 4625,
+                      "TimeCreated": { "@SystemTime": "2021-10-18T09:35:12.3848176Z" },
+                      "Channel": "Security"
+                    },
+                    "EventData": {
+                      "TargetUserName": "bob",
+                      "Status": "0xc000006d",
+                      "LogonType": 3,
+                      "IpAddress": "-",
+                      "ProcessName": null
+                    }
+                  }
+                }
+            )),
+            tdef: type_def(),
+        }
+
+        windows_event_unnamed_data {
+            args: func_args![ value: indoc!{r#"
+                <Event>
+                  <EventData>
+                    <Data>first</Data>
+                    <Data>second</Data>
+                    <Data Name="Name">named</Data>
+                    <Binary>00AA</Binary>
+                  </EventData>
+                </Event>
+            "#}, windows_event: true ],
+            want: Ok(value!(
+                {
+                  "Event": {
+                    "EventData": {
+                      "Data": ["first", "second"],
+                      "Name": "named",
+                      "Binary": "00AA"
+                    }
+                  }
+                }
+            )),
+            tdef: type_def(),
+        }
+
+        windows_event_disabled {
+            args: func_args![ value: r#"<EventData><Data Name="a">1</Data><Data Name="b">2</Data></EventData>"# ],
+            want: Ok(value!(
+                {
+                  "EventData": {
+                    "Data": [
+                      { "@Name": "a", "text": 1 },
+                      { "@Name": "b", "text": 2 }
+                    ]
+                  }
+                }
+            )),
+            tdef: type_def(),
+        }
     ];
 }
@@ -0,0 +1,133 @@
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseYaml;
+
+impl Function for ParseYaml {
+    fn identifier(&self) -> &'static str {
+        "parse_yaml"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "object",
+                source: r#"parse_yaml!("field: value\nlist:\n  - 1\n  - true")"#,
+                result: Ok(r#"{ "field": "value", "list": [1, true] }"#),
+            },
+            Example {
+                title: "flow style",
+                source: r#"parse_yaml!("{field: value, list: [1, 2]}")"#,
+                result: Ok(r#"{ "field": "value", "list": [1, 2] }"#),
+            },
+            Example {
+                title: "scalar",
+                source: r#"parse_yaml!("42.5")"#,
+                result: Ok("42.5"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(Box::new(ParseYamlFn { value }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParseYamlFn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for ParseYamlFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let bytes = self.value.resolve(ctx)?.try_bytes()?;
+        let value = serde_yaml::from_slice::<'_, Value>(&bytes)
+            .map_err(|e| format!("unable to parse yaml: {}", e))?;
+
+        Ok(value)
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        type_def()
+    }
+}
+
+fn inner_kind() -> Kind {
+    Kind::Null
+        | Kind::Bytes
+        | Kind::Integer
+        | Kind::Float
+        | Kind::Boolean
+        | Kind::Array
+        | Kind::Object
+}
+
+fn type_def() -> TypeDef {
+    TypeDef::new()
+        .fallible()
+        .bytes()
+        .add_boolean()
+        .add_integer()
+        .add_float()
+        .add_null()
+        .add_array_mapped::<(), Kind>(map! { (): inner_kind() })
+        .add_object::<(), Kind>(map! { (): inner_kind() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        parse_yaml => ParseYaml;
+
+        parses {
+            args: func_args![value: "field: value"],
+            want: Ok(value!({field: "value"})),
+            tdef: type_def(),
+        }
+
+        complex_yaml {
+            args: func_args![value: indoc! {"
+                object:
+                  string: value
+                  number: 42
+                  float: 1.5
+                  array:
+                    - hello
+                    - world
+                  boolean: false
+                  empty: ~
+            "}],
+            want: Ok(value!({object: {string: "value", number: 42, float: 1.5, array: ["hello", "world"], boolean: false, empty: null}})),
+            tdef: type_def(),
+        }
+
+        multiline_string {
+            args: func_args![value: "message: |\n  first line\n  second line\n"],
+            want: Ok(value!({message: "first line\nsecond line\n"})),
+            tdef: type_def(),
+        }
+
+        invalid_yaml_errors {
+            args: func_args![value: r#""unterminated"#],
+            want: Err("unable to parse yaml: while scanning a quoted scalar, found unexpected end of stream at line 1 column 1"),
+            tdef: type_def(),
+        }
+    ];
+}
@@ -0,0 +1,603 @@
+use prost::Message;
+use prost_types::{
+    field_descriptor_proto::{Label, Type},
+    DescriptorProto, EnumDescriptorProto, FieldDescriptorProto, FileDescriptorSet,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::Arc;
+use vrl::{
+    diagnostic::{Label as DiagnosticLabel, Span},
+    prelude::*,
+};
+
+const VARINT: u64 = 0;
+const FIXED64: u64 = 1;
+const LENGTH_DELIMITED: u64 = 2;
+const FIXED32: u64 = 5;
+
+/// How deep messages can be nested, the same limit `prost` decodes messages
+/// with, so that malicious input can't overflow the stack.
+const RECURSION_LIMIT: usize = 100;
+
+#[derive(Debug)]
+pub enum Error {
+    InvalidDescriptorFile(String),
+    UnknownMessageType(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidDescriptorFile(err) => write!(f, "invalid descriptor file: {}", err),
+            Error::UnknownMessageType(name) => write!(f, "unknown message type `{}`", name),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl DiagnosticError for Error {
+    fn code(&self) -> usize {
+        403
+    }
+
+    fn labels(&self) -> Vec<DiagnosticLabel> {
+        match self {
+            Error::InvalidDescriptorFile(_) => vec![DiagnosticLabel::primary(
+                format!("protobuf descriptor error: {}", self),
+                Span::default(),
+            )],
+            Error::UnknownMessageType(_) => vec![
+                DiagnosticLabel::primary(
+                    format!("protobuf descriptor error: {}", self),
+                    Span::default(),
+                ),
+                DiagnosticLabel::context(
+                    "the message type must include its package, such as `package.Message`",
+                    Span::default(),
+                ),
+            ],
+        }
+    }
+}
+
+/// The message and enum types of a descriptor file, by their fully qualified
+/// name, such as `.package.Message.Nested`.
+#[derive(Debug, Default)]
+struct Descriptors {
+    messages: HashMap<String, DescriptorProto>,
+    enums: HashMap<String, EnumDescriptorProto>,
+}
+
+impl Descriptors {
+    fn add_message(&mut self, scope: &str, message: &DescriptorProto) {
+        let name = format!("{}.{}", scope, message.name());
+
+        for nested in &message.nested_type {
+            self.add_message(&name, nested);
+        }
+
+        for enum_type in &message.enum_type {
+            self.add_enum(&name, enum_type);
+        }
+
+        self.messages.insert(name, message.clone());
+    }
+
+    fn add_enum(&mut self, scope: &str, enum_type: &EnumDescriptorProto) {
+        let name = format!("{}.{}", scope, enum_type.name());
+        self.enums.insert(name, enum_type.clone());
+    }
+
+    fn message(&self, name: &str) -> std::result::Result<&DescriptorProto, String> {
+        self.messages
+            .get(name)
+            .ok_or_else(|| format!("unknown message type `{}`", name.trim_start_matches('.')))
+    }
+}
+
+/// A protobuf message type, used to convert between VRL values and the
+/// protobuf wire format.
+#[derive(Debug, Clone)]
+pub(crate) struct Schema {
+    descriptors: Arc<Descriptors>,
+    message_type: String,
+}
+
+impl Schema {
+    /// Loads the `message_type` from the file descriptor set in `desc_file`,
+    /// as written by `protoc --descriptor_set_out`.
+    pub(crate) fn load(desc_file: &str, message_type: &str) -> std::result::Result<Self, Error> {
+        let bytes = std::fs::read(desc_file)
+            .map_err(|err| Error::InvalidDescriptorFile(format!("{}: {}", desc_file, err)))?;
+        let set = FileDescriptorSet::decode(&bytes[..])
+            .map_err(|err| Error::InvalidDescriptorFile(format!("{}: {}", desc_file, err)))?;
+
+        let mut descriptors = Descriptors::default();
+        for file in &set.file {
+            let scope = match file.package() {
+                "" => String::new(),
+                package => format!(".{}", package),
+            };
+
+            for message in &file.message_type {
+                descriptors.add_message(&scope, message);
+            }
+
+            for enum_type in &file.enum_type {
+                descriptors.add_enum(&scope, enum_type);
+            }
+        }
+
+        let message_type = format!(".{}", message_type.trim_start_matches('.'));
+        if !descriptors.messages.contains_key(&message_type) {
+            return Err(Error::UnknownMessageType(
+                message_type.trim_start_matches('.').to_owned(),
+            ));
+        }
+
+        Ok(Self {
+            descriptors: Arc::new(descriptors),
+            message_type,
+        })
+    }
+
+    pub(crate) fn decode(&self, bytes: &[u8]) -> std::result::Result<Value, String> {
+        decode_message(&self.descriptors, &self.message_type, bytes, 0).map(Value::from)
+    }
+
+    pub(crate) fn encode(
+        &self,
+        object: &BTreeMap<String, Value>,
+    ) -> std::result::Result<Vec<u8>, String> {
+        let mut buf = vec![];
+        encode_message(&self.descriptors, &self.message_type, object, &mut buf, 0)?;
+
+        Ok(buf)
+    }
+}
+
+fn is_map_entry(descriptors: &Descriptors, field: &FieldDescriptorProto) -> bool {
+    field.r#type() == Type::Message
+        && field.label() == Label::Repeated
+        && descriptors
+            .messages
+            .get(field.type_name())
+            .and_then(|message| message.options.as_ref())
+            .map_or(false, |options| options.map_entry())
+}
+
+/// Returns the wire type of a single value of the field type.
+fn wire_type(field_type: Type) -> u64 {
+    match field_type {
+        Type::Double | Type::Fixed64 | Type::Sfixed64 => FIXED64,
+        Type::Float | Type::Fixed32 | Type::Sfixed32 => FIXED32,
+        Type::String | Type::Bytes | Type::Message | Type::Group => LENGTH_DELIMITED,
+        _ => VARINT,
+    }
+}
+
+/// Checks whether repeated values of the field type can be packed, which is
+/// the case for all scalar numeric types.
+fn is_packable(field_type: Type) -> bool {
+    !matches!(
+        field_type,
+        Type::String | Type::Bytes | Type::Message | Type::Group
+    )
+}
+
+fn decode_message(
+    descriptors: &Descriptors,
+    message_type: &str,
+    mut buf: &[u8],
+    depth: usize,
+) -> std::result::Result<BTreeMap<String, Value>, String> {
+    if depth > RECURSION_LIMIT {
+        return Err("recursion limit reached".to_owned());
+    }
+
+    let message = descriptors.message(message_type)?;
+    let mut object = BTreeMap::new();
+
+    while !buf.is_empty() {
+        let key = decode_varint(&mut buf)?;
+        let (number, wire_type) = (key >> 3, key & 0x7);
+
+        let field = match message
+            .field
+            .iter()
+            .find(|field| field.number() as u64 == number)
+        {
+            Some(field) => field,
+            // Unknown fields, such as ones added in a newer version of the
+            // message type, are skipped.
+            None => {
+                skip_field(&mut buf, wire_type)?;
+                continue;
+            }
+        };
+
+        let mut values = vec![];
+        if wire_type == LENGTH_DELIMITED && is_packable(field.r#type()) {
+            let len = decode_varint(&mut buf)? as usize;
+            let mut packed = take(&mut buf, len)?;
+            while !packed.is_empty() {
+                let wire_type = self::wire_type(field.r#type());
+                values.push(decode_value(
+                    descriptors,
+                    field,
+                    wire_type,
+                    &mut packed,
+                    depth,
+                )?);
+            }
+        } else {
+            values.push(decode_value(
+                descriptors,
+                field,
+                wire_type,
+                &mut buf,
+                depth,
+            )?);
+        }
+
+        if is_map_entry(descriptors, field) {
+            let map = object
+                .entry(field.name().to_owned())
+                .or_insert_with(|| Value::from(BTreeMap::new()));
+
+            for entry in values {
+                let mut entry = entry.try_object().map_err(|err| err.to_string())?;
+                let key = match entry.remove("key") {
+                    Some(Value::Bytes(bytes)) => String::from_utf8_lossy(&bytes).into_owned(),
+                    Some(Value::Integer(integer)) => integer.to_string(),
+                    Some(Value::Boolean(boolean)) => boolean.to_string(),
+                    _ => String::new(),
+                };
+                let value = entry.remove("value").unwrap_or(Value::Null);
+
+                if let Value::Object(map) = map {
+                    map.insert(key, value);
+                }
+            }
+        } else if field.label() == Label::Repeated {
+            let array = object
+                .entry(field.name().to_owned())
+                .or_insert_with(|| Value::from(Vec::<Value>::new()));
+
+            if let Value::Array(array) = array {
+                array.extend(values);
+            }
+        } else if let Some(value) = values.pop() {
+            // The last value of a field that isn't repeated wins.
+            object.insert(field.name().to_owned(), value);
+        }
+    }
+
+    Ok(object)
+}
+
+fn decode_value(
+    descriptors: &Descriptors,
+    field: &FieldDescriptorProto,
+    wire_type: u64,
+    buf: &mut &[u8],
+    depth: usize,
+) -> std::result::Result<Value, String> {
+    if field.r#type() == Type::Group {
+        return Err(format!("field `{}`: groups aren't supported", field.name()));
+    }
+
+    if wire_type != self::wire_type(field.r#type()) {
+        return Err(format!(
+            "field `{}`: invalid wire type {}",
+            field.name(),
+            wire_type
+        ));
+    }
+
+    let value = match field.r#type() {
+        Type::Double => f64::from_bits(decode_fixed64(buf)?).into(),
+        Type::Float => f64::from(f32::from_bits(decode_fixed32(buf)?)).into(),
+        Type::Fixed64 | Type::Sfixed64 => (decode_fixed64(buf)? as i64).into(),
+        Type::Fixed32 => i64::from(decode_fixed32(buf)?).into(),
+        Type::Sfixed32 => (decode_fixed32(buf)? as i32).into(),
+        Type::Int64 | Type::Uint64 => (decode_varint(buf)? as i64).into(),
+        Type::Int32 => (decode_varint(buf)? as i32).into(),
+        Type::Uint32 => i64::from(decode_varint(buf)? as u32).into(),
+        Type::Sint32 | Type::Sint64 => {
+            let value = decode_varint(buf)?;
+            ((value >> 1) as i64 ^ -((value & 1) as i64)).into()
+        }
+        Type::Bool => (decode_varint(buf)? != 0).into(),
+        Type::Enum => {
+            let number = decode_varint(buf)? as i32;
+            descriptors
+                .enums
+                .get(field.type_name())
+                .and_then(|enum_type| {
+                    enum_type
+                        .value
+                        .iter()
+                        .find(|value| value.number() == number)
+                })
+                .map_or_else(|| number.into(), |value| value.name().into())
+        }
+        Type::String | Type::Bytes => {
+            let len = decode_varint(buf)? as usize;
+            Bytes::copy_from_slice(take(buf, len)?).into()
+        }
+        Type::Message => {
+            let len = decode_varint(buf)? as usize;
+            decode_message(descriptors, field.type_name(), take(buf, len)?, depth + 1)?.into()
+        }
+        Type::Group => unreachable!("checked above"),
+    };
+
+    Ok(value)
+}
+
+fn skip_field(buf: &mut &[u8], wire_type: u64) -> std::result::Result<(), String> {
+    match wire_type {
+        VARINT => {
+            decode_varint(buf)?;
+        }
+        FIXED64 => {
+            take(buf, 8)?;
+        }
+        LENGTH_DELIMITED => {
+            let len = decode_varint(buf)? as usize;
+            take(buf, len)?;
+        }
+        FIXED32 => {
+            take(buf, 4)?;
+        }
+        wire_type => return Err(format!("unsupported wire type {}", wire_type)),
+    }
+
+    Ok(())
+}
+
+fn encode_message(
+    descriptors: &Descriptors,
+    message_type: &str,
+    object: &BTreeMap<String, Value>,
+    buf: &mut Vec<u8>,
+    depth: usize,
+) -> std::result::Result<(), String> {
+    if depth > RECURSION_LIMIT {
+        return Err("recursion limit reached".to_owned());
+    }
+
+    let message = descriptors.message(message_type)?;
+
+    if let Some(key) = object.keys().find(|key| {
+        !message
+            .field
+            .iter()
+            .any(|field| field.name() == key.as_str())
+    }) {
+        return Err(format!(
+            "unknown field `{}` for message type `{}`",
+            key,
+            message_type.trim_start_matches('.')
+        ));
+    }
+
+    let mut fields = message.field.iter().collect::<Vec<_>>();
+    fields.sort_by_key(|field| field.number());
+
+    for field in fields {
+        let value = match object.get(field.name()) {
+            Some(Value::Null) | None => continue,
+            Some(value) => value,
+        };
+
+        let number = field.number() as u64;
+        if is_map_entry(descriptors, field) {
+            let map = match value {
+                Value::Object(map) => map,
+                value => return Err(type_error(field, "object", value)),
+            };
+
+            let entry_type = descriptors.message(field.type_name())?;
+            let key_field = entry_type.field.iter().find(|field| field.number() == 1);
+            for (key, value) in map {
+                let key = match key_field.map(|field| field.r#type()) {
+                    Some(Type::String) | None => Value::from(key.as_str()),
+                    Some(Type::Bool) => Value::from(key == "true"),
+                    Some(_) => key.parse::<i64>().map(Value::from).map_err(|_| {
+                        format!("field `{}`: invalid map key `{}`", field.name(), key)
+                    })?,
+                };
+
+                let mut entry = BTreeMap::new();
+                entry.insert("key".to_owned(), key);
+                entry.insert("value".to_owned(), value.clone());
+
+                let mut nested = vec![];
+                encode_message(
+                    descriptors,
+                    field.type_name(),
+                    &entry,
+                    &mut nested,
+                    depth + 1,
+                )?;
+                encode_varint(number << 3 | LENGTH_DELIMITED, buf);
+                encode_varint(nested.len() as u64, buf);
+                buf.extend(nested);
+            }
+        } else if field.label() == Label::Repeated {
+            let values = match value {
+                Value::Array(values) => values,
+                value => return Err(type_error(field, "array", value)),
+            };
+
+            if is_packable(field.r#type()) {
+                let mut packed = vec![];
+                for value in values {
+                    encode_value(descriptors, field, value, &mut packed, depth)?;
+                }
+
+                encode_varint(number << 3 | LENGTH_DELIMITED, buf);
+                encode_varint(packed.len() as u64, buf);
+                buf.extend(packed);
+            } else {
+                for value in values {
+                    encode_varint(number << 3 | LENGTH_DELIMITED, buf);
+                    encode_value(descriptors, field, value, buf, depth)?;
+                }
+            }
+        } else {
+            encode_varint(number << 3 | wire_type(field.r#type()), buf);
+            encode_value(descriptors, field, value, buf, depth)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Encodes a single value of the field, without its key.
+fn encode_value(
+    descriptors: &Descriptors,
+    field: &FieldDescriptorProto,
+    value: &Value,
+    buf: &mut Vec<u8>,
+    depth: usize,
+) -> std::result::Result<(), String> {
+    let integer = || match value {
+        Value::Integer(integer) => Ok(*integer),
+        value => Err(type_error(field, "integer", value)),
+    };
+
+    let float = || match value {
+        Value::Float(float) => Ok(float.into_inner()),
+        Value::Integer(integer) => Ok(*integer as f64),
+        value => Err(type_error(field, "float", value)),
+    };
+
+    match field.r#type() {
+        Type::Double => buf.extend(&float()?.to_bits().to_le_bytes()),
+        Type::Float => buf.extend(&(float()? as f32).to_bits().to_le_bytes()),
+        Type::Fixed64 | Type::Sfixed64 => buf.extend(&integer()?.to_le_bytes()),
+        Type::Fixed32 | Type::Sfixed32 => buf.extend(&(integer()? as u32).to_le_bytes()),
+        Type::Int64 | Type::Uint64 | Type::Int32 => encode_varint(integer()? as u64, buf),
+        Type::Uint32 => encode_varint(u64::from(integer()? as u32), buf),
+        Type::Sint32 | Type::Sint64 => {
+            let integer = integer()?;
+            encode_varint(((integer << 1) ^ (integer >> 63)) as u64, buf)
+        }
+        Type::Bool => match value {
+            Value::Boolean(boolean) => encode_varint(u64::from(*boolean), buf),
+            value => return Err(type_error(field, "boolean", value)),
+        },
+        Type::Enum => {
+            let number = match value {
+                Value::Integer(integer) => *integer,
+                Value::Bytes(name) => descriptors
+                    .enums
+                    .get(field.type_name())
+                    .and_then(|enum_type| {
+                        enum_type
+                            .value
+                            .iter()
+                            .find(|value| value.name().as_bytes() == name.as_ref())
+                    })
+                    .map(|value| i64::from(value.number()))
+                    .ok_or_else(|| {
+                        format!(
+                            "field `{}`: unknown enum value `{}`",
+                            field.name(),
+                            String::from_utf8_lossy(name)
+                        )
+                    })?,
+                value => return Err(type_error(field, "string", value)),
+            };
+
+            encode_varint(number as u64, buf)
+        }
+        Type::String | Type::Bytes => match value {
+            Value::Bytes(bytes) => {
+                encode_varint(bytes.len() as u64, buf);
+                buf.extend(bytes.as_ref());
+            }
+            value => return Err(type_error(field, "string", value)),
+        },
+        Type::Message => match value {
+            Value::Object(object) => {
+                let mut nested = vec![];
+                encode_message(
+                    descriptors,
+                    field.type_name(),
+                    object,
+                    &mut nested,
+                    depth + 1,
+                )?;
+                encode_varint(nested.len() as u64, buf);
+                buf.extend(nested);
+            }
+            value => return Err(type_error(field, "object", value)),
+        },
+        Type::Group => return Err(format!("field `{}`: groups aren't supported", field.name())),
+    }
+
+    Ok(())
+}
+
+fn type_error(field: &FieldDescriptorProto, expected: &str, value: &Value) -> String {
+    format!(
+        r#"field `{}`: expected "{}", got {}"#,
+        field.name(),
+        expected,
+        value.kind()
+    )
+}
+
+fn decode_varint(buf: &mut &[u8]) -> std::result::Result<u64, String> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = take(buf, 1)?[0];
+        value |= u64::from(byte & 0x7f) << shift;
+
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+
+    Err("invalid varint".to_owned())
+}
+
+fn decode_fixed64(buf: &mut &[u8]) -> std::result::Result<u64, String> {
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(take(buf, 8)?);
+
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn decode_fixed32(buf: &mut &[u8]) -> std::result::Result<u32, String> {
+    let mut bytes = [0; 4];
+    bytes.copy_from_slice(take(buf, 4)?);
+
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn take<'a>(buf: &mut &'a [u8], len: usize) -> std::result::Result<&'a [u8], String> {
+    if buf.len() < len {
+        return Err("unexpected end of message".to_owned());
+    }
+
+    let (taken, rest) = buf.split_at(len);
+    *buf = rest;
+
+    Ok(taken)
+}
+
+fn encode_varint(mut value: u64, buf: &mut Vec<u8>) {
+    while value >= 0x80 {
+        buf.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+
+    buf.push(value as u8);
+}
@@ -0,0 +1,112 @@
+use rand::{thread_rng, RngCore};
+use vrl::prelude::*;
+
+/// The largest number of bytes that can be generated at once.
+const MAX_LENGTH: i64 = 64 * 1024;
+
+#[derive(Clone, Copy, Debug)]
+pub struct RandomBytes;
+
+impl Function for RandomBytes {
+    fn identifier(&self) -> &'static str {
+        "random_bytes"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "length",
+            kind: kind::INTEGER,
+            required: true,
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let length = arguments.required("length");
+
+        Ok(Box::new(RandomBytesFn { length }))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "generate random bytes",
+            source: r#"length(random_bytes!(16))"#,
+            result: Ok("16"),
+        }]
+    }
+}
+
+#[derive(Debug, Clone)]
+struct RandomBytesFn {
+    length: Box<dyn Expression>,
+}
+
+impl Expression for RandomBytesFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let length = self.length.resolve(ctx)?.try_integer()?;
+        if !(0..=MAX_LENGTH).contains(&length) {
+            return Err(format!(
+                "length must be between 0 and {}, got {}",
+                MAX_LENGTH, length
+            )
+            .into());
+        }
+
+        let mut bytes = vec![0; length as usize];
+        thread_rng().fill_bytes(&mut bytes);
+
+        Ok(Bytes::from(bytes).into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().fallible().bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::TimeZone;
+
+    test_function![
+        random_bytes => RandomBytes;
+
+        empty {
+            args: func_args![length: 0],
+            want: Ok(value!("")),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        negative_length {
+            args: func_args![length: -1],
+            want: Err("length must be between 0 and 65536, got -1"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+
+        too_long {
+            args: func_args![length: 65537],
+            want: Err("length must be between 0 and 65536, got 65537"),
+            tdef: TypeDef::new().fallible().bytes(),
+        }
+    ];
+
+    #[test]
+    fn random_bytes() {
+        let mut state = vrl::state::Runtime::default();
+        let mut object: Value = map![].into();
+        let tz = TimeZone::default();
+        let mut ctx = Context::new(&mut object, &mut state, &tz);
+        let function = RandomBytesFn { length: expr!(16) };
+
+        let first = function.resolve(&mut ctx).unwrap().try_bytes().unwrap();
+        let second = function.resolve(&mut ctx).unwrap().try_bytes().unwrap();
+
+        assert_eq!(first.len(), 16);
+        assert_eq!(second.len(), 16);
+        assert_ne!(first, second);
+    }
+}
@@ -0,0 +1,167 @@
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Reduce;
+
+impl Function for Reduce {
+    fn identifier(&self) -> &'static str {
+        "reduce"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::OBJECT | kind::ARRAY,
+                required: true,
+            },
+            Parameter {
+                keyword: "initial",
+                kind: kind::ANY,
+                required: true,
+            },
+        ]
+    }
+
+    fn closure(&self) -> Option<closure::Definition> {
+        Some(closure::Definition {
+            input: "value",
+            variables: &[
+                closure::Variable::Accumulator("initial"),
+                closure::Variable::Key,
+                closure::Variable::Value,
+            ],
+            output: closure::Output::Parameter("initial"),
+        })
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "sum array",
+                source: r#"reduce([1, 2, 3], 0) -> |sum, _index, value| { sum + value }"#,
+                result: Ok("6"),
+            },
+            Example {
+                title: "join object",
+                source: r#"reduce({ "a": "1", "b": "2" }, "") -> |joined, key, value| { joined + key + value }"#,
+                result: Ok(r#""a1b2""#),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let initial = arguments.required("initial");
+        let closure = arguments.required_closure();
+
+        Ok(Box::new(ReduceFn {
+            value,
+            initial,
+            closure,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ReduceFn {
+    value: Box<dyn Expression>,
+    initial: Box<dyn Expression>,
+    closure: FunctionClosure,
+}
+
+impl Expression for ReduceFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let mut accumulator = self.initial.resolve(ctx)?;
+
+        match value {
+            Value::Object(object) => {
+                for (key, value) in object {
+                    accumulator = self
+                        .closure
+                        .run(ctx, vec![accumulator, key.into(), value])?;
+                }
+            }
+            Value::Array(array) => {
+                for (index, value) in array.into_iter().enumerate() {
+                    let index = (index as i64).into();
+                    accumulator = self.closure.run(ctx, vec![accumulator, index, value])?;
+                }
+            }
+            value => {
+                return Err(value::Error::Expected {
+                    got: value.kind(),
+                    expected: Kind::Array | Kind::Object,
+                }
+                .into())
+            }
+        }
+
+        Ok(accumulator)
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        let initial = self.initial.type_def(state).kind();
+
+        TypeDef::from(initial | self.closure.type_def().kind())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn closure(accumulator: TypeDef, source: &str, output: Kind) -> FunctionClosure {
+        FunctionClosure::from_source(
+            &[
+                ("accumulator", accumulator),
+                ("key", TypeDef::new().scalar(Kind::Bytes | Kind::Integer)),
+                ("value", TypeDef::new().bytes()),
+            ],
+            source,
+            output,
+        )
+    }
+
+    test_function![
+        reduce => Reduce;
+
+        object {
+            args: ArgumentList::from(func_args![value: value!({ "a": "1", "b": "2" }), initial: ""])
+                .with_closure(closure(TypeDef::new().bytes(), "accumulator + value", Kind::Bytes)),
+            want: Ok("12"),
+            tdef: TypeDef::new().bytes(),
+        }
+
+        array {
+            args: ArgumentList::from(func_args![value: value!(["a", "b"]), initial: 0])
+                .with_closure(closure(TypeDef::new().integer(), "accumulator + 1", Kind::Integer)),
+            want: Ok(2),
+            tdef: TypeDef::new().integer(),
+        }
+
+        partially_matching_kind {
+            args: ArgumentList::from(func_args![value: value!(["a", "b"]), initial: 0])
+                .with_closure(closure(
+                    TypeDef::new().integer(),
+                    r#"if accumulator == 0 { 1 } else { value }"#,
+                    Kind::Integer,
+                )),
+            want: Err(r#"expected "integer", got "string""#),
+            tdef: TypeDef::new().scalar(Kind::Integer | Kind::Bytes),
+        }
+
+        non_container {
+            args: ArgumentList::from(func_args![value: 1, initial: 0])
+                .with_closure(closure(TypeDef::new().integer(), "accumulator", Kind::Integer)),
+            want: Err(r#"expected "object" or "array", got "integer""#),
+            tdef: TypeDef::new().integer(),
+        }
+    ];
+}
@@ -80,6 +80,13 @@ impl Expression for UnnestFn {
         let value: Value;
         let target: Box<&dyn Target> = match self.path.target() {
             expression::Target::External => Box::new(ctx.target()) as Box<_>,
+            expression::Target::Metadata => {
+                value = ctx
+                    .target()
+                    .get_metadata(&LookupBuf::root())?
+                    .unwrap_or(Value::Null);
+                Box::new(&value as &dyn Target) as Box<&dyn Target>
+            }
             expression::Target::Internal(v) => {
                 let v = ctx.state().variable(v.ident()).unwrap_or(&Value::Null);
                 Box::new(v as &dyn Target) as Box<_>
@@ -125,6 +132,7 @@ impl Expression for UnnestFn {
                 Some(root_type_def) => invert_array_at_path(root_type_def, self.path.path()),
                 None => self.path.type_def(state).restrict_array().add_null(),
             },
+            Target::Metadata => self.path.type_def(state).restrict_array().add_null(),
             Target::Internal(v) => invert_array_at_path(&v.type_def(state), self.path.path()),
             Target::FunctionCall(f) => invert_array_at_path(&f.type_def(state), self.path.path()),
             Target::Container(c) => invert_array_at_path(&c.type_def(state), self.path.path()),
@@ -122,3 +122,25 @@ impl std::str::FromStr for Base64Charset {
         }
     }
 }
+
+/// Returns the type definition of the array or object a function iterating
+/// over the given array or object returns, holding values of the given kind.
+#[cfg(any(feature = "filter", feature = "map_values"))]
+pub(crate) fn container_type_def(
+    container: &vrl::prelude::TypeDef,
+    element: vrl::prelude::Kind,
+) -> vrl::prelude::TypeDef {
+    use vrl::prelude::*;
+
+    let array = TypeDef::new().array_mapped::<(), Kind>(map! { (): element });
+    let object = TypeDef::new().object::<(), Kind>(map! { (): element });
+
+    match (
+        container.has_kind(Kind::Array),
+        container.has_kind(Kind::Object),
+    ) {
+        (true, false) => array,
+        (false, true) => object,
+        _ => array.merge(object),
+    }
+}
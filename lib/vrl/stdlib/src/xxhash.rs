@@ -0,0 +1,119 @@
+use std::hash::Hasher;
+use twox_hash::{XxHash32, XxHash64};
+use vrl::prelude::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Xxhash;
+
+impl Function for Xxhash {
+    fn identifier(&self) -> &'static str {
+        "xxhash"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "variant",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "default variant",
+                source: r#"xxhash("foobar")"#,
+                result: Ok("-6725556575634347271"),
+            },
+            Example {
+                title: "custom variant",
+                source: r#"xxhash("foobar", "XXH32")"#,
+                result: Ok("3986901679"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let variants = vec![value!("XXH32"), value!("XXH64")];
+
+        let value = arguments.required("value");
+        let variant = arguments
+            .optional_enum("variant", &variants)?
+            .unwrap_or_else(|| value!("XXH64"))
+            .try_bytes()
+            .expect("variant not bytes");
+
+        Ok(Box::new(XxhashFn { value, variant }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct XxhashFn {
+    value: Box<dyn Expression>,
+    variant: Bytes,
+}
+
+impl Expression for XxhashFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?.try_bytes()?;
+
+        let hash = match self.variant.as_ref() {
+            b"XXH32" => hash::<XxHash32>(&value),
+            b"XXH64" => hash::<XxHash64>(&value),
+            _ => unreachable!("enum invariant"),
+        };
+
+        // 64-bit hashes don't fit in an integer, they wrap around instead.
+        Ok((hash as i64).into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().infallible().integer()
+    }
+}
+
+#[inline]
+fn hash<T: Hasher + Default>(value: &[u8]) -> u64 {
+    let mut hasher = T::default();
+    hasher.write(value);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        xxhash => Xxhash;
+
+        xxhash {
+            args: func_args![value: "foo"],
+            want: Ok(3728699739546630719i64),
+            tdef: TypeDef::new().infallible().integer(),
+        }
+
+        xxh32 {
+            args: func_args![value: "foo", variant: "XXH32"],
+            want: Ok(3792637401i64),
+            tdef: TypeDef::new().infallible().integer(),
+        }
+
+        xxh64_empty {
+            args: func_args![value: "", variant: "XXH64"],
+            want: Ok(-1205034819632174695i64),
+            tdef: TypeDef::new().infallible().integer(),
+        }
+    ];
+}
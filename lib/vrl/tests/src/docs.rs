@@ -183,6 +183,7 @@ impl Test {
             error: None,
             source,
             object,
+            metadata: Value::Object(BTreeMap::default()),
             result,
             result_approx: false,
             skip,
@@ -3,9 +3,10 @@ use chrono::{DateTime, SecondsFormat, Utc};
 use chrono_tz::Tz;
 use glob::glob;
 use shared::TimeZone;
+use std::path::PathBuf;
 use std::str::FromStr;
 use structopt::StructOpt;
-use vrl::{diagnostic::Formatter, state, Runtime, Terminate, Value};
+use vrl::{diagnostic::Formatter, state, Runtime, TargetValue, Terminate, Value, VrlRuntime};
 
 use vrl_tests::{docs, Test};
 
@@ -34,6 +35,11 @@ pub struct Cmd {
 
     #[structopt(short = "tz", long)]
     timezone: Option<String>,
+
+    /// How the programs are run, either by resolving their expressions
+    /// (`ast`) or by running their bytecode (`vm`).
+    #[structopt(short, long, default_value = "ast")]
+    runtime: VrlRuntime,
 }
 
 impl Cmd {
@@ -99,7 +105,7 @@ fn main() {
         .filter(|test| should_run(&format!("{}/{}", test.category, test.name), &cmd.pattern))
         .collect::<Vec<_>>();
 
-    for mut test in tests {
+    for test in tests {
         if category != test.category {
             category = test.category.clone();
             println!("{}", Colour::Fixed(3).bold().paint(category.to_string()));
@@ -125,14 +131,26 @@ fn main() {
 
         let state = state::Runtime::default();
         let mut runtime = Runtime::new(state);
-        let program = vrl::compile(&test.source, &stdlib::all(), None);
+        let mut compiler_state = state::Compiler::new();
+        compiler_state.set_module_paths(vec![PathBuf::from("modules")]);
+        let program = vrl::compile_with_state(&test.source, &stdlib::all(), &mut compiler_state);
 
         let want = test.result.clone();
         let timezone = cmd.timezone();
+        let mut target = TargetValue {
+            value: test.object.clone(),
+            metadata: test.metadata.clone(),
+        };
 
         match program {
             Ok(program) => {
-                let result = runtime.resolve(&mut test.object, &program, &timezone);
+                let result = match cmd.runtime {
+                    VrlRuntime::Ast => runtime.resolve(&mut target, &program, &timezone),
+                    VrlRuntime::Vm => match program.compile_to_vm() {
+                        Ok(vm) => runtime.run_vm(&mut target, &vm, &timezone),
+                        Err(err) => Err(Terminate::Error(err.into())),
+                    },
+                };
 
                 match result {
                     Ok(got) => {
@@ -212,7 +230,7 @@ fn main() {
                                         }
                                     };
 
-                                let got = vrl_value_to_json_value(test.object.clone());
+                                let got = vrl_value_to_json_value(target.value.clone());
                                 if got == want {
                                     println!("{} (abort)", Colour::Green.bold().paint("OK"));
                                 } else {
@@ -10,6 +10,7 @@ pub struct Test {
     pub error: Option<String>,
     pub source: String,
     pub object: Value,
+    pub metadata: Value,
     pub result: String,
     pub result_approx: bool,
     pub skip: bool,
@@ -18,6 +19,7 @@ pub struct Test {
 enum CaptureMode {
     Result,
     Object,
+    Metadata,
     None,
     Done,
 }
@@ -30,6 +32,7 @@ impl Test {
 
         let mut source = String::new();
         let mut object = String::new();
+        let mut metadata = String::new();
         let mut result = String::new();
         let mut result_approx = false;
         let mut skip = false;
@@ -47,6 +50,12 @@ impl Test {
                 if line.starts_with("object:") {
                     capture_mode = CaptureMode::Object;
                     line = line.strip_prefix("object:").expect("object").trim_start();
+                } else if line.starts_with("metadata:") {
+                    capture_mode = CaptureMode::Metadata;
+                    line = line
+                        .strip_prefix("metadata:")
+                        .expect("metadata")
+                        .trim_start();
                 } else if line.starts_with("result: ~") {
                     capture_mode = CaptureMode::Result;
                     result_approx = true;
@@ -65,6 +74,9 @@ impl Test {
                     CaptureMode::Object => {
                         object.push_str(line);
                     }
+                    CaptureMode::Metadata => {
+                        metadata.push_str(line);
+                    }
                 }
             } else {
                 capture_mode = CaptureMode::Done;
@@ -86,6 +98,17 @@ impl Test {
                 }
             }
         };
+        let metadata = if metadata.is_empty() {
+            Value::Object(BTreeMap::default())
+        } else {
+            match serde_json::from_str::<'_, Value>(&metadata) {
+                Ok(value) => value,
+                Err(err) => {
+                    error = Some(format!("unable to parse metadata as JSON: {}", err));
+                    Value::Null
+                }
+            }
+        };
 
         result = result.trim_end().to_owned();
 
@@ -95,6 +118,7 @@ impl Test {
             error,
             source,
             object,
+            metadata,
             result,
             result_approx,
             skip,
@@ -103,6 +127,7 @@ impl Test {
 
     pub fn from_example(func: &'static str, example: &Example) -> Self {
         let object = Value::Object(BTreeMap::default());
+        let metadata = Value::Object(BTreeMap::default());
         let result = match example.result {
             Ok(string) => string.to_owned(),
             Err(err) => err.to_string(),
@@ -114,6 +139,7 @@ impl Test {
             error: None,
             source: example.source.to_owned(),
             object,
+            metadata,
             result,
             result_approx: false,
             skip: false,
@@ -5,11 +5,13 @@ set -euo pipefail
 #
 # SUMMARY
 #
-#   Run the Vector Remap Language test suite
+#   Run the Vector Remap Language test suite, with both the runtime resolving
+#   the expressions of the programs and the one running their bytecode.
 
 (
   cd "$(dirname "${BASH_SOURCE[0]}")/../lib/vrl/tests"
 
-  cargo run
+  cargo run -- --runtime ast
+  cargo run -- --runtime vm
 )
 
@@ -212,6 +212,17 @@ impl ConfigBuilder {
 
         self.global.proxy = self.global.proxy.merge(&with.global.proxy);
 
+        match (
+            self.global.enrichment_tables_reload_interval_secs,
+            with.global.enrichment_tables_reload_interval_secs,
+        ) {
+            (Some(interval), Some(other)) if interval != other => errors.push(
+                "conflicting values for 'enrichment_tables_reload_interval_secs' found".to_owned(),
+            ),
+            (None, interval) => self.global.enrichment_tables_reload_interval_secs = interval,
+            _ => {}
+        }
+
         if self.global.data_dir.is_none() || self.global.data_dir == default_data_dir() {
             self.global.data_dir = with.global.data_dir;
         } else if with.global.data_dir != default_data_dir()
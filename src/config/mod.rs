@@ -421,8 +421,10 @@ impl EnrichmentTableOuter {
 #[async_trait]
 #[typetag::serde(tag = "type")]
 pub trait EnrichmentTableConfig: core::fmt::Debug + Send + Sync + dyn_clone::DynClone {
+    /// Builds the table, which is registered under the given key.
     async fn build(
         &self,
+        key: &ComponentKey,
         globals: &GlobalOptions,
     ) -> crate::Result<Box<dyn enrichment::Table + Send + Sync>>;
 }
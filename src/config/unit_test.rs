@@ -466,7 +466,9 @@ async fn build_unit_test(
 
     errors.extend(tables_errors);
 
+    // Transforms built for tests don't keep data in the data directory.
     let context = TransformContext {
+        key: None,
         globals: config.global.clone(),
         enrichment_tables: enrichment_tables.clone(),
     };
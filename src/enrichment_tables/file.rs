@@ -1,11 +1,12 @@
 use crate::config::{EnrichmentTableConfig, EnrichmentTableDescription};
 use bytes::Bytes;
-use enrichment::{Case, Condition, IndexHandle, Table};
+use enrichment::{parse_range, Case, Cidr, Condition, IndexHandle, RangeKind, Table};
 use serde::{Deserialize, Serialize};
 use shared::{conversion::Conversion, datetime::TimeZone};
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::hash::Hasher;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 use tracing::trace;
 use vrl::Value;
 
@@ -51,6 +52,9 @@ struct FileConfig {
     file: FileC,
     #[serde(default)]
     schema: HashMap<String, SchemaType>,
+    /// Whether the table is reloaded when the file is modified.
+    #[serde(default)]
+    auto_reload: bool,
 }
 
 const fn default_delimiter() -> char {
@@ -94,20 +98,18 @@ impl FileConfig {
             Some(SchemaType::String) | None => value.into(),
         })
     }
-}
 
-#[async_trait::async_trait]
-#[typetag::serde(name = "file")]
-impl EnrichmentTableConfig for FileConfig {
-    async fn build(
-        &self,
-        globals: &crate::config::GlobalOptions,
-    ) -> crate::Result<Box<dyn Table + Send + Sync>> {
+    /// Reads the file and parses it into a table.
+    fn load_file(&self, timezone: TimeZone) -> crate::Result<File> {
         let Encoding::Csv {
             include_headers,
             delimiter,
         } = self.file.encoding;
 
+        // The modification time is read first so that any changes made
+        // whilst the file is being read cause it to be reloaded again.
+        let modified = modified_time(&self.file.path);
+
         let mut reader = csv::ReaderBuilder::new()
             .has_headers(include_headers)
             .delimiter(delimiter as u8)
@@ -134,7 +136,7 @@ impl EnrichmentTableConfig for FileConfig {
                 Ok(row?
                     .iter()
                     .enumerate()
-                    .map(|(idx, col)| self.parse_column(globals.timezone, &headers[idx], idx, col))
+                    .map(|(idx, col)| self.parse_column(timezone, &headers[idx], idx, col))
                     .collect::<Result<Vec<_>, String>>()?)
             })
             .collect::<crate::Result<Vec<_>>>()?;
@@ -145,7 +147,35 @@ impl EnrichmentTableConfig for FileConfig {
             headers
         );
 
-        Ok(Box::new(File::new(data, headers)))
+        let mut file = File::new(data, headers);
+        if self.auto_reload {
+            file.source = Some(FileSource {
+                config: self.clone(),
+                timezone,
+                modified,
+            });
+        }
+
+        Ok(file)
+    }
+}
+
+/// Returns the time the file at the given path was last modified, if it can be determined.
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "file")]
+impl EnrichmentTableConfig for FileConfig {
+    async fn build(
+        &self,
+        _key: &crate::config::ComponentKey,
+        globals: &crate::config::GlobalOptions,
+    ) -> crate::Result<Box<dyn Table + Send + Sync>> {
+        Ok(Box::new(self.load_file(globals.timezone)?))
     }
 }
 
@@ -155,6 +185,15 @@ inventory::submit! {
 
 impl_generate_config_from_default!(FileConfig);
 
+/// Where a table was loaded from, kept for tables which are reloaded when
+/// their file changes.
+#[derive(Clone)]
+struct FileSource {
+    config: FileConfig,
+    timezone: TimeZone,
+    modified: Option<SystemTime>,
+}
+
 #[derive(Clone)]
 pub struct File {
     data: Vec<Vec<Value>>,
@@ -164,6 +203,42 @@ pub struct File {
         Vec<usize>,
         HashMap<u64, Vec<usize>, hash_hasher::HashBuildHasher>,
     )>,
+    range_indexes: Vec<RangeIndex>,
+    source: Option<FileSource>,
+}
+
+/// An index of the ranges held by a column, searched by conditions of the given kind.
+#[derive(Clone)]
+struct RangeIndex {
+    case: Case,
+    column: usize,
+    kind: RangeKind,
+    ranges: Ranges,
+}
+
+impl RangeIndex {
+    /// Can the index be used to search the column with conditions of the given kind? Only
+    /// prefixes are affected by the case of the search.
+    fn covers(&self, case: Case, column: usize, kind: RangeKind) -> bool {
+        self.column == column
+            && self.kind == kind
+            && (self.case == case || kind != RangeKind::Prefix)
+    }
+}
+
+#[derive(Clone)]
+enum Ranges {
+    /// The numeric ranges with the rows holding them, ordered by their lower bound. Alongside each
+    /// range is the highest upper bound of it and all the ranges before it, which tells when a
+    /// search can stop looking at earlier ranges.
+    Numbers(Vec<((f64, f64), f64, usize)>),
+    /// The rows holding each network by the prefix length and address of the network.
+    Networks(BTreeMap<(bool, u8), HashMap<u128, Vec<usize>>>),
+    /// The rows holding each prefix, and the lengths of the prefixes held.
+    Prefixes {
+        lengths: BTreeSet<usize>,
+        rows: HashMap<Vec<u8>, Vec<usize>>,
+    },
 }
 
 impl File {
@@ -172,6 +247,8 @@ impl File {
             data,
             headers,
             indexes: Vec::new(),
+            range_indexes: Vec::new(),
+            source: None,
         }
     }
 
@@ -181,28 +258,12 @@ impl File {
 
     /// Does the given row match all the conditions specified?
     fn row_equals(&self, case: Case, condition: &[Condition], row: &[Value]) -> bool {
-        condition.iter().all(|condition| match condition {
-            Condition::Equals { field, value } => match self.column_index(field) {
-                None => false,
-                Some(idx) => match (case, &row[idx], value) {
-                    (Case::Insensitive, Value::Bytes(bytes1), Value::Bytes(bytes2)) => {
-                        match (std::str::from_utf8(bytes1), std::str::from_utf8(bytes2)) {
-                            (Ok(s1), Ok(s2)) => s1.to_lowercase() == s2.to_lowercase(),
-                            (Err(_), Err(_)) => bytes1 == bytes2,
-                            _ => false,
-                        }
-                    }
-                    (_, value1, value2) => value1 == value2,
-                },
-            },
-            Condition::BetweenDates { field, from, to } => match self.column_index(field) {
+        condition
+            .iter()
+            .all(|condition| match self.column_index(condition.field()) {
                 None => false,
-                Some(idx) => match row[idx] {
-                    Value::Timestamp(date) => from <= &date && &date <= to,
-                    _ => false,
-                },
-            },
-        })
+                Some(idx) => condition.matches(case, &row[idx]),
+            })
     }
 
     fn add_columns(&self, select: Option<&[String]>, row: &[Value]) -> BTreeMap<String, Value> {
@@ -286,6 +347,127 @@ impl File {
         Ok(index)
     }
 
+    /// Indexes the ranges held by the given column.
+    fn index_ranges(&self, column: usize, case: Case, kind: RangeKind) -> Ranges {
+        let values = self
+            .data
+            .iter()
+            .enumerate()
+            .map(|(idx, row)| (idx, &row[column]));
+
+        match kind {
+            RangeKind::Range => {
+                // Ranges which can't contain any number, including those with NaN bounds, are
+                // left out so the rest can be ordered.
+                let mut ranges = values
+                    .filter_map(|(idx, value)| Some((parse_range(value)?, idx)))
+                    .filter(|((low, high), _)| low <= high)
+                    .collect::<Vec<_>>();
+                ranges.sort_by(|((low1, _), _), ((low2, _), _)| {
+                    low1.partial_cmp(low2).expect("bounds should not be NaN")
+                });
+
+                let mut max_high = f64::NEG_INFINITY;
+                Ranges::Numbers(
+                    ranges
+                        .into_iter()
+                        .map(|((low, high), idx)| {
+                            max_high = max_high.max(high);
+                            ((low, high), max_high, idx)
+                        })
+                        .collect(),
+                )
+            }
+            RangeKind::Cidr => {
+                let mut networks = BTreeMap::<_, HashMap<_, Vec<_>>>::new();
+                for (idx, cidr) in
+                    values.filter_map(|(idx, value)| Some((idx, Cidr::parse(value)?)))
+                {
+                    networks
+                        .entry((cidr.is_ipv6(), cidr.prefix_len()))
+                        .or_default()
+                        .entry(cidr.network())
+                        .or_default()
+                        .push(idx);
+                }
+                Ranges::Networks(networks)
+            }
+            RangeKind::Prefix => {
+                let mut lengths = BTreeSet::new();
+                let mut rows = HashMap::<_, Vec<_>>::new();
+                for (idx, value) in values {
+                    if let Value::Bytes(prefix) = value {
+                        let prefix = match case {
+                            Case::Sensitive => prefix.to_vec(),
+                            Case::Insensitive => {
+                                String::from_utf8_lossy(prefix).to_lowercase().into_bytes()
+                            }
+                        };
+                        lengths.insert(prefix.len());
+                        rows.entry(prefix).or_default().push(idx);
+                    }
+                }
+                Ranges::Prefixes { lengths, rows }
+            }
+        }
+    }
+
+    /// Uses a range index of one of the conditions, if there is one, to find the rows which may
+    /// match the conditions. The rows are returned in the order they appear in the file.
+    fn range_indexed(&self, case: Case, condition: &[Condition]) -> Option<Vec<usize>> {
+        let (condition, index) = condition.iter().find_map(|condition| {
+            let kind = condition.range_kind()?;
+            let column = self.column_index(condition.field())?;
+            let index = self
+                .range_indexes
+                .iter()
+                .find(|index| index.covers(case, column, kind))?;
+            Some((condition, index))
+        })?;
+
+        let mut rows = match (condition, &index.ranges) {
+            (Condition::RangeContains { value, .. }, Ranges::Numbers(ranges)) => {
+                // Only the ranges starting at or below the value can contain it. Working
+                // backwards from the last of them, the search can stop once no earlier range
+                // reaches the value.
+                let end = ranges.partition_point(|((low, _), _, _)| low <= value);
+                ranges[..end]
+                    .iter()
+                    .rev()
+                    .take_while(|(_, max_high, _)| max_high >= value)
+                    .filter(|((_, high), _, _)| high >= value)
+                    .map(|(_, _, idx)| *idx)
+                    .collect::<Vec<_>>()
+            }
+            (Condition::CidrContains { value, .. }, Ranges::Networks(networks)) => networks
+                .iter()
+                .filter(|((v6, _), _)| *v6 == value.is_ipv6())
+                .filter_map(|((_, prefix_len), rows)| {
+                    rows.get(&Cidr::network_of(*value, *prefix_len))
+                })
+                .flatten()
+                .copied()
+                .collect(),
+            (Condition::PrefixOf { value, .. }, Ranges::Prefixes { lengths, rows }) => {
+                let value = match case {
+                    Case::Sensitive => value.as_bytes().to_vec(),
+                    Case::Insensitive => value.to_lowercase().into_bytes(),
+                };
+                lengths
+                    .iter()
+                    .take_while(|len| **len <= value.len())
+                    .filter_map(|len| rows.get(&value[..*len]))
+                    .flatten()
+                    .copied()
+                    .collect()
+            }
+            _ => return None,
+        };
+
+        rows.sort_unstable();
+        Some(rows)
+    }
+
     /// Sequentially searches through the iterator for the given condition.
     fn sequential<'a, I>(
         &'a self,
@@ -379,6 +561,11 @@ impl Table for File {
         select: Option<&'a [String]>,
         index: Option<IndexHandle>,
     ) -> Result<BTreeMap<String, Value>, String> {
+        if let Some(rows) = self.range_indexed(case, condition) {
+            let rows = rows.iter().map(|idx| &self.data[*idx]);
+            return single_or_err(self.sequential(rows, case, condition, select));
+        }
+
         match index {
             None => {
                 // No index has been passed so we need to do a Sequential Scan.
@@ -404,6 +591,11 @@ impl Table for File {
         select: Option<&'a [String]>,
         index: Option<IndexHandle>,
     ) -> Result<Vec<BTreeMap<String, Value>>, String> {
+        if let Some(rows) = self.range_indexed(case, condition) {
+            let rows = rows.iter().map(|idx| &self.data[*idx]);
+            return Ok(self.sequential(rows, case, condition, select).collect());
+        }
+
         match index {
             None => {
                 // No index has been passed so we need to do a Sequential Scan.
@@ -446,6 +638,62 @@ impl Table for File {
             }
         }
     }
+
+    fn add_range_index(&mut self, case: Case, field: &str, kind: RangeKind) -> Result<(), String> {
+        let column = self
+            .column_index(field)
+            .ok_or_else(|| format!("field(s) '{}' missing from dataset", field))?;
+        if !self
+            .range_indexes
+            .iter()
+            .any(|index| index.covers(case, column, kind))
+        {
+            let ranges = self.index_ranges(column, case, kind);
+            self.range_indexes.push(RangeIndex {
+                case,
+                column,
+                kind,
+                ranges,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn needs_reload(&self) -> bool {
+        self.source.as_ref().map_or(false, |source| {
+            modified_time(&source.config.file.path) != source.modified
+        })
+    }
+
+    fn reload(&self) -> Result<Box<dyn Table + Send + Sync>, String> {
+        let source = self
+            .source
+            .as_ref()
+            .ok_or_else(|| "table is not configured to reload".to_string())?;
+        let mut file = source
+            .config
+            .load_file(source.timezone)
+            .map_err(|error| error.to_string())?;
+
+        // Adding the indexes in the same order gives them the same handles.
+        for (case, fields, _) in &self.indexes {
+            let fields = fields
+                .iter()
+                .map(|idx| self.headers[*idx].as_str())
+                .collect::<Vec<_>>();
+            file.add_index(*case, &fields)?;
+        }
+        for index in &self.range_indexes {
+            file.add_range_index(index.case, &self.headers[index.column], index.kind)?;
+        }
+
+        Ok(Box::new(file))
+    }
+
+    fn row_count(&self) -> Option<usize> {
+        Some(self.data.len())
+    }
 }
 
 impl std::fmt::Debug for File {
@@ -798,4 +1046,193 @@ mod tests {
             file.find_table_row(Case::Sensitive, &[condition], None, Some(handle))
         );
     }
+
+    #[test]
+    fn finds_rows_in_ranges() {
+        let mut file = File::new(
+            vec![
+                vec!["1-1023".into(), "system".into()],
+                vec!["8000-8099".into(), "web".into()],
+                vec!["8080".into(), "proxy".into()],
+                vec!["9000-9999".into(), "metrics".into()],
+                vec!["bogus".into(), "bogus".into()],
+            ],
+            vec!["ports".to_string(), "service".to_string()],
+        );
+
+        let find = |file: &File, port| {
+            file.find_table_rows(
+                Case::Sensitive,
+                &[Condition::RangeContains {
+                    field: "ports",
+                    value: port,
+                }],
+                Some(&["service".to_string()]),
+                None,
+            )
+        };
+        let sequential = find(&file, 8080.0);
+        assert_eq!(
+            Ok(vec![
+                btreemap! { "service" => "web" },
+                btreemap! { "service" => "proxy" },
+            ]),
+            sequential
+        );
+
+        file.add_range_index(Case::Sensitive, "ports", RangeKind::Range)
+            .unwrap();
+        assert_eq!(sequential, find(&file, 8080.0));
+        assert_eq!(
+            Ok(vec![btreemap! { "service" => "system" }]),
+            find(&file, 22.0)
+        );
+        assert_eq!(Ok(vec![]), find(&file, 5000.0));
+    }
+
+    #[test]
+    fn finds_rows_in_networks() {
+        let mut file = File::new(
+            vec![
+                vec!["10.0.0.0/8".into(), "platform".into()],
+                vec!["10.1.0.0/16".into(), "payments".into()],
+                vec!["192.168.1.20".into(), "printer".into()],
+                vec!["2001:db8::/32".into(), "edge".into()],
+            ],
+            vec!["network".to_string(), "team".to_string()],
+        );
+        file.add_range_index(Case::Sensitive, "network", RangeKind::Cidr)
+            .unwrap();
+
+        let find = |ip: &str| {
+            file.find_table_rows(
+                Case::Sensitive,
+                &[Condition::CidrContains {
+                    field: "network",
+                    value: ip.parse().unwrap(),
+                }],
+                Some(&["team".to_string()]),
+                None,
+            )
+        };
+        assert_eq!(
+            Ok(vec![
+                btreemap! { "team" => "platform" },
+                btreemap! { "team" => "payments" },
+            ]),
+            find("10.1.2.3")
+        );
+        assert_eq!(
+            Ok(vec![btreemap! { "team" => "printer" }]),
+            find("192.168.1.20")
+        );
+        assert_eq!(
+            Ok(vec![btreemap! { "team" => "edge" }]),
+            find("2001:db8::1")
+        );
+        assert_eq!(Ok(vec![]), find("172.16.0.1"));
+    }
+
+    #[test]
+    fn finds_row_by_prefix() {
+        let mut file = File::new(
+            vec![
+                vec!["/api/".into(), "api".into()],
+                vec!["/static/".into(), "cdn".into()],
+            ],
+            vec!["path".to_string(), "backend".to_string()],
+        );
+        file.add_range_index(Case::Insensitive, "path", RangeKind::Prefix)
+            .unwrap();
+
+        let condition = [Condition::PrefixOf {
+            field: "path",
+            value: "/API/users".to_string(),
+        }];
+        assert_eq!(
+            Ok(btreemap! { "backend" => "api" }),
+            file.find_table_row(
+                Case::Insensitive,
+                &condition,
+                Some(&["backend".to_string()]),
+                None
+            )
+        );
+        // There is no index for case sensitive searches, so the rows are scanned instead.
+        assert_eq!(
+            Err("no rows found".to_string()),
+            file.find_table_row(Case::Sensitive, &condition, None, None)
+        );
+        assert_eq!(
+            Err("field(s) 'route' missing from dataset".to_string()),
+            file.add_range_index(Case::Sensitive, "route", RangeKind::Prefix)
+        );
+    }
+
+    #[test]
+    fn reloads_file_with_indexes() {
+        let path = crate::test_util::temp_file();
+        std::fs::write(&path, "field1,field2\nzip,zup\n").unwrap();
+
+        let config = FileConfig {
+            file: FileC {
+                path: path.clone(),
+                encoding: Encoding::default(),
+            },
+            auto_reload: true,
+            ..Default::default()
+        };
+        let mut file = config
+            .load_file(shared::datetime::TimeZone::default())
+            .unwrap();
+        file.add_index(Case::Sensitive, &["field1"]).unwrap();
+        let handle = file.add_index(Case::Sensitive, &["field2"]).unwrap();
+        assert!(!file.needs_reload());
+
+        // The columns are reordered, but the indexes still refer to the same fields.
+        std::fs::write(&path, "field2,field1\nzurp,zirp\nzoop,zork\n").unwrap();
+        // Pretend the file was modified after it was loaded, regardless of
+        // the resolution of the modification times.
+        file.source.as_mut().unwrap().modified = None;
+        assert!(file.needs_reload());
+
+        let reloaded = file.reload().unwrap();
+        assert!(!reloaded.needs_reload());
+        assert_eq!(Some(2), reloaded.row_count());
+        assert_eq!(
+            Ok(btreemap! {
+                "field1" => "zirp",
+                "field2" => "zurp",
+            }),
+            reloaded.find_table_row(
+                Case::Sensitive,
+                &[Condition::Equals {
+                    field: "field2",
+                    value: Value::from("zurp"),
+                }],
+                None,
+                Some(handle)
+            )
+        );
+    }
+
+    #[test]
+    fn does_not_reload_by_default() {
+        let path = crate::test_util::temp_file();
+        std::fs::write(&path, "field1,field2\nzip,zup\n").unwrap();
+
+        let config = FileConfig {
+            file: FileC {
+                path,
+                encoding: Encoding::default(),
+            },
+            ..Default::default()
+        };
+        let file = config
+            .load_file(shared::datetime::TimeZone::default())
+            .unwrap();
+
+        assert!(!file.needs_reload());
+        assert!(file.reload().is_err());
+    }
 }
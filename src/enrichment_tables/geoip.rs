@@ -0,0 +1,434 @@
+use crate::config::{EnrichmentTableConfig, EnrichmentTableDescription, GenerateConfig};
+use enrichment::{Case, Condition, IndexHandle, Table};
+use maxminddb::{
+    geoip2::{City, ConnectionType, Isp},
+    Reader,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, net::IpAddr, sync::Arc};
+use vrl::Value;
+
+// MaxMind GeoIP database files have a type field we can use to recognize specific
+// products. Any other type is expected to be a City database.
+const ASN_DATABASE_TYPE: &str = "GeoLite2-ASN";
+const ISP_DATABASE_TYPE: &str = "GeoIP2-ISP";
+const CONNECTION_TYPE_DATABASE_TYPE: &str = "GeoIP2-Connection-Type";
+
+/// The only field the table can be searched by.
+const IP_FIELD: &str = "ip";
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum DatabaseKind {
+    Asn,
+    Isp,
+    ConnectionType,
+    City,
+}
+
+impl From<&str> for DatabaseKind {
+    fn from(database_type: &str) -> Self {
+        match database_type {
+            ASN_DATABASE_TYPE => Self::Asn,
+            ISP_DATABASE_TYPE => Self::Isp,
+            CONNECTION_TYPE_DATABASE_TYPE => Self::ConnectionType,
+            _ => Self::City,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct GeoipConfig {
+    /// Path to the MaxMind database file.
+    pub path: String,
+    /// The locale used for the names of cities, countries and regions.
+    #[serde(default = "default_locale")]
+    pub locale: String,
+}
+
+fn default_locale() -> String {
+    "en".to_string()
+}
+
+impl GenerateConfig for GeoipConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            path: "/path/to/GeoLite2-City.mmdb".to_string(),
+            locale: default_locale(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "geoip")]
+impl EnrichmentTableConfig for GeoipConfig {
+    async fn build(
+        &self,
+        _key: &crate::config::ComponentKey,
+        _globals: &crate::config::GlobalOptions,
+    ) -> crate::Result<Box<dyn Table + Send + Sync>> {
+        Ok(Box::new(Geoip::new(self.clone())?))
+    }
+}
+
+inventory::submit! {
+    EnrichmentTableDescription::new::<GeoipConfig>("geoip")
+}
+
+#[derive(Clone)]
+pub struct Geoip {
+    config: GeoipConfig,
+    dbreader: Arc<Reader<Vec<u8>>>,
+    dbkind: DatabaseKind,
+}
+
+impl Geoip {
+    pub fn new(config: GeoipConfig) -> crate::Result<Self> {
+        let dbreader = Arc::new(Reader::open_readfile(config.path.clone())?);
+        let dbkind = DatabaseKind::from(dbreader.metadata.database_type.as_str());
+
+        Ok(Geoip {
+            config,
+            dbreader,
+            dbkind,
+        })
+    }
+
+    /// Looks up the given address, returning `None` when the database holds
+    /// no data for it or the record can't be decoded.
+    fn lookup(&self, ip: IpAddr, select: Option<&[String]>) -> Option<BTreeMap<String, Value>> {
+        let mut map = BTreeMap::new();
+        let mut add_field = |key: &str, value: Value| {
+            if select
+                .map(|fields| fields.iter().any(|field| field == key))
+                .unwrap_or(true)
+            {
+                map.insert(key.to_string(), value);
+            }
+        };
+
+        match self.dbkind {
+            DatabaseKind::Asn | DatabaseKind::Isp => {
+                let data = self.dbreader.lookup::<Isp>(ip).ok()?;
+
+                add_field(
+                    "autonomous_system_number",
+                    data.autonomous_system_number
+                        .map(Value::from)
+                        .unwrap_or(Value::Null),
+                );
+                add_field(
+                    "autonomous_system_organization",
+                    optional_str(data.autonomous_system_organization),
+                );
+                if self.dbkind == DatabaseKind::Isp {
+                    add_field("isp", optional_str(data.isp));
+                    add_field("organization", optional_str(data.organization));
+                }
+            }
+            DatabaseKind::ConnectionType => {
+                let data = self.dbreader.lookup::<ConnectionType>(ip).ok()?;
+
+                add_field("connection_type", optional_str(data.connection_type));
+            }
+            DatabaseKind::City => {
+                let data = self.dbreader.lookup::<City>(ip).ok()?;
+                let locale = self.config.locale.as_str();
+
+                add_field(
+                    "city_name",
+                    optional_str(
+                        data.city
+                            .and_then(|city| city.names)
+                            .and_then(|names| names.get(locale).copied()),
+                    ),
+                );
+
+                let continent = data.continent;
+                add_field(
+                    "continent_code",
+                    optional_str(continent.and_then(|continent| continent.code)),
+                );
+
+                let country = data.country;
+                add_field(
+                    "country_code",
+                    optional_str(country.as_ref().and_then(|country| country.iso_code)),
+                );
+                add_field(
+                    "country_name",
+                    optional_str(
+                        country
+                            .and_then(|country| country.names)
+                            .and_then(|names| names.get(locale).copied()),
+                    ),
+                );
+
+                let subdivision = data
+                    .subdivisions
+                    .and_then(|subdivisions| subdivisions.into_iter().next());
+                add_field(
+                    "region_code",
+                    optional_str(
+                        subdivision
+                            .as_ref()
+                            .and_then(|subdivision| subdivision.iso_code),
+                    ),
+                );
+                add_field(
+                    "region_name",
+                    optional_str(
+                        subdivision
+                            .and_then(|subdivision| subdivision.names)
+                            .and_then(|names| names.get(locale).copied()),
+                    ),
+                );
+
+                let location = data.location;
+                add_field(
+                    "timezone",
+                    optional_str(location.as_ref().and_then(|location| location.time_zone)),
+                );
+                add_field(
+                    "latitude",
+                    location
+                        .as_ref()
+                        .and_then(|location| location.latitude)
+                        .map(Value::from)
+                        .unwrap_or(Value::Null),
+                );
+                add_field(
+                    "longitude",
+                    location
+                        .as_ref()
+                        .and_then(|location| location.longitude)
+                        .map(Value::from)
+                        .unwrap_or(Value::Null),
+                );
+                add_field(
+                    "metro_code",
+                    location
+                        .and_then(|location| location.metro_code)
+                        .map(Value::from)
+                        .unwrap_or(Value::Null),
+                );
+
+                add_field(
+                    "postal_code",
+                    optional_str(data.postal.and_then(|postal| postal.code)),
+                );
+            }
+        }
+
+        Some(map)
+    }
+
+    fn find_ip(&self, condition: &[Condition]) -> Result<IpAddr, String> {
+        match condition {
+            [Condition::Equals { field, value }] if *field == IP_FIELD => value
+                .as_bytes()
+                .and_then(|bytes| std::str::from_utf8(bytes).ok())
+                .and_then(|ip| ip.parse().ok())
+                .ok_or_else(|| "ip must be a valid IP address".to_string()),
+            _ => Err(format!(
+                "only a single `{}` condition is supported by geoip tables",
+                IP_FIELD
+            )),
+        }
+    }
+}
+
+fn optional_str(value: Option<&str>) -> Value {
+    value.map(Value::from).unwrap_or(Value::Null)
+}
+
+impl Table for Geoip {
+    fn find_table_row<'a>(
+        &self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&[String]>,
+        index: Option<IndexHandle>,
+    ) -> Result<BTreeMap<String, Value>, String> {
+        let mut rows = self.find_table_rows(case, condition, select, index)?;
+
+        match rows.pop() {
+            Some(row) if rows.is_empty() => Ok(row),
+            Some(_) => Err("more than one row found".to_string()),
+            None => Err("no rows found".to_string()),
+        }
+    }
+
+    fn find_table_rows<'a>(
+        &self,
+        _case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&[String]>,
+        _index: Option<IndexHandle>,
+    ) -> Result<Vec<BTreeMap<String, Value>>, String> {
+        let ip = self.find_ip(condition)?;
+
+        Ok(self.lookup(ip, select).into_iter().collect())
+    }
+
+    fn add_index(&mut self, _case: Case, fields: &[&str]) -> Result<IndexHandle, String> {
+        match fields {
+            // The database is its own index, so there is nothing to build.
+            [IP_FIELD] => Ok(IndexHandle(0)),
+            _ => Err(format!(
+                "only the `{}` field can be searched in geoip tables",
+                IP_FIELD
+            )),
+        }
+    }
+}
+
+impl std::fmt::Debug for Geoip {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Geoip {} database {}",
+            self.dbreader.metadata.database_type, self.config.path
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::btreemap;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<GeoipConfig>();
+    }
+
+    fn find(ip: &str, database: &str) -> Result<BTreeMap<String, Value>, String> {
+        let geoip = Geoip::new(GeoipConfig {
+            path: database.to_string(),
+            locale: default_locale(),
+        })
+        .unwrap();
+
+        geoip.find_table_row(
+            Case::Insensitive,
+            &[Condition::Equals {
+                field: "ip",
+                value: ip.into(),
+            }],
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn city_lookup() {
+        let values = find("2.125.160.216", "tests/data/GeoIP2-City-Test.mmdb").unwrap();
+
+        assert_eq!(
+            values,
+            btreemap! {
+                "city_name" => "Boxford",
+                "continent_code" => "EU",
+                "country_code" => "GB",
+                "country_name" => "United Kingdom",
+                "region_code" => "ENG",
+                "region_name" => "England",
+                "timezone" => "Europe/London",
+                "latitude" => 51.75,
+                "longitude" => -1.25,
+                "metro_code" => Value::Null,
+                "postal_code" => "OX1",
+            }
+        );
+    }
+
+    #[test]
+    fn city_lookup_partial_results() {
+        let values = find("67.43.156.9", "tests/data/GeoIP2-City-Test.mmdb").unwrap();
+
+        assert_eq!(values["city_name"], Value::Null);
+        assert_eq!(values["country_code"], "BT".into());
+        assert_eq!(values["latitude"], 27.5.into());
+        assert_eq!(values["longitude"], 90.5.into());
+    }
+
+    #[test]
+    fn city_lookup_no_results() {
+        let values = find("10.1.12.1", "tests/data/GeoIP2-City-Test.mmdb");
+
+        assert_eq!(values, Err("no rows found".to_string()));
+    }
+
+    #[test]
+    fn isp_lookup() {
+        let values = find("208.192.1.2", "tests/data/GeoIP2-ISP-Test.mmdb").unwrap();
+
+        assert_eq!(
+            values,
+            btreemap! {
+                "autonomous_system_number" => 701,
+                "autonomous_system_organization" => "MCI Communications Services, Inc. d/b/a Verizon Business",
+                "isp" => "Verizon Business",
+                "organization" => "Verizon Business",
+            }
+        );
+    }
+
+    #[test]
+    fn asn_lookup() {
+        let values = find("2600:7000::1", "tests/data/GeoLite2-ASN-Test.mmdb").unwrap();
+
+        assert_eq!(
+            values,
+            btreemap! {
+                "autonomous_system_number" => 6939,
+                "autonomous_system_organization" => "Hurricane Electric, Inc.",
+            }
+        );
+    }
+
+    #[test]
+    fn selects_fields() {
+        let geoip = Geoip::new(GeoipConfig {
+            path: "tests/data/GeoIP2-City-Test.mmdb".to_string(),
+            locale: default_locale(),
+        })
+        .unwrap();
+
+        let values = geoip.find_table_row(
+            Case::Insensitive,
+            &[Condition::Equals {
+                field: "ip",
+                value: "2.125.160.216".into(),
+            }],
+            Some(&["city_name".to_string(), "latitude".to_string()]),
+            None,
+        );
+
+        assert_eq!(
+            values,
+            Ok(btreemap! {
+                "city_name" => "Boxford",
+                "latitude" => 51.75,
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_conditions() {
+        let mut geoip = Geoip::new(GeoipConfig {
+            path: "tests/data/GeoIP2-City-Test.mmdb".to_string(),
+            locale: default_locale(),
+        })
+        .unwrap();
+
+        assert!(geoip.add_index(Case::Sensitive, &["ip"]).is_ok());
+        assert!(geoip.add_index(Case::Sensitive, &["city_name"]).is_err());
+        assert_eq!(
+            find("not an ip", "tests/data/GeoIP2-City-Test.mmdb"),
+            Err("ip must be a valid IP address".to_string())
+        );
+    }
+}
@@ -0,0 +1,485 @@
+use crate::config::{
+    ComponentKey, EnrichmentTableConfig, EnrichmentTableDescription, GenerateConfig,
+};
+use enrichment::{Case, Condition, IndexHandle, Table};
+use lazy_static::lazy_static;
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex, RwLock, Weak},
+    time::{Duration, Instant},
+};
+use vrl::Value;
+
+lazy_static! {
+    /// The rows of every memory table by name, shared between the table and
+    /// the `enrichment_table` sinks writing to it. The rows are dropped once
+    /// neither uses them.
+    static ref STORES: Mutex<HashMap<String, Weak<Store>>> = Mutex::new(HashMap::new());
+}
+
+/// Returns the rows of the memory table with the given name, if one has been
+/// built.
+pub(crate) fn store(table: &str) -> Option<Arc<Store>> {
+    STORES.lock().unwrap().get(table).and_then(Weak::upgrade)
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct MemoryConfig {
+    /// The field of the events written to the table which identifies the row
+    /// they are written to.
+    pub key_field: String,
+    /// How long a row is kept after it was last written.
+    #[serde(default = "default_ttl_secs")]
+    pub ttl_secs: u64,
+    /// The maximum number of rows. Beyond this, the least recently written
+    /// rows are evicted.
+    #[serde(default = "default_max_rows")]
+    pub max_rows: usize,
+}
+
+const fn default_ttl_secs() -> u64 {
+    600
+}
+
+const fn default_max_rows() -> usize {
+    100_000
+}
+
+impl GenerateConfig for MemoryConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            key_field: "ip".to_string(),
+            ttl_secs: default_ttl_secs(),
+            max_rows: default_max_rows(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "memory")]
+impl EnrichmentTableConfig for MemoryConfig {
+    async fn build(
+        &self,
+        key: &ComponentKey,
+        _globals: &crate::config::GlobalOptions,
+    ) -> crate::Result<Box<dyn Table + Send + Sync>> {
+        // The rows are kept when the table is rebuilt on a config reload, only
+        // the settings change.
+        let mut stores = STORES.lock().unwrap();
+        stores.retain(|_, store| store.strong_count() > 0);
+
+        let name = key.to_string();
+        let store = match stores.get(&name).and_then(Weak::upgrade) {
+            Some(store) => {
+                store.configure(self);
+                store
+            }
+            None => {
+                let store = Arc::new(Store::new(self));
+                stores.insert(name, Arc::downgrade(&store));
+                store
+            }
+        };
+
+        Ok(Box::new(Memory { store }))
+    }
+}
+
+inventory::submit! {
+    EnrichmentTableDescription::new::<MemoryConfig>("memory")
+}
+
+#[derive(Debug)]
+struct Row {
+    written_at: Instant,
+    values: BTreeMap<String, Value>,
+}
+
+#[derive(Debug)]
+struct Rows {
+    key_field: String,
+    ttl: Duration,
+    /// The rows by key, with the least recently written row first in line
+    /// to be evicted.
+    rows: LruCache<String, Row>,
+}
+
+impl Rows {
+    fn is_live(&self, row: &Row, now: Instant) -> bool {
+        now.saturating_duration_since(row.written_at) < self.ttl
+    }
+
+    /// Removes all rows which have outlived the TTL, returning how many were
+    /// removed.
+    fn expire(&mut self, now: Instant) -> usize {
+        let mut expired = 0;
+        while let Some((_, row)) = self.rows.peek_lru() {
+            if self.is_live(row, now) {
+                break;
+            }
+            self.rows.pop_lru();
+            expired += 1;
+        }
+        expired
+    }
+}
+
+/// The outcome of writing a row to a memory table.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Written {
+    /// The number of rows which outlived the TTL and were removed.
+    pub expired: usize,
+    /// The number of rows evicted to stay within `max_rows`.
+    pub evicted: usize,
+    /// The number of rows in the table after the write.
+    pub rows: usize,
+}
+
+/// The rows of a memory table.
+#[derive(Debug)]
+pub struct Store {
+    rows: RwLock<Rows>,
+}
+
+impl Store {
+    fn new(config: &MemoryConfig) -> Self {
+        Self {
+            rows: RwLock::new(Rows {
+                key_field: config.key_field.clone(),
+                ttl: Duration::from_secs(config.ttl_secs),
+                rows: LruCache::new(config.max_rows),
+            }),
+        }
+    }
+
+    fn configure(&self, config: &MemoryConfig) {
+        let mut rows = self.rows.write().unwrap();
+        rows.key_field = config.key_field.clone();
+        rows.ttl = Duration::from_secs(config.ttl_secs);
+        rows.rows.resize(config.max_rows);
+    }
+
+    /// The field which identifies each row.
+    pub(crate) fn key_field(&self) -> String {
+        self.rows.read().unwrap().key_field.clone()
+    }
+
+    /// Writes a row, replacing any row with the same key.
+    pub(crate) fn write(
+        &self,
+        key: String,
+        values: BTreeMap<String, Value>,
+        now: Instant,
+    ) -> Written {
+        let mut rows = self.rows.write().unwrap();
+        let expired = rows.expire(now);
+
+        let evicted = if rows.rows.len() == rows.rows.cap() && !rows.rows.contains(&key) {
+            1
+        } else {
+            0
+        };
+        rows.rows.put(
+            key,
+            Row {
+                written_at: now,
+                values,
+            },
+        );
+
+        Written {
+            expired,
+            evicted,
+            rows: rows.rows.len(),
+        }
+    }
+
+    fn find<'a>(
+        &self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&[String]>,
+        now: Instant,
+    ) -> Vec<BTreeMap<String, Value>> {
+        let rows = self.rows.read().unwrap();
+        let select_columns = |row: &Row| match select {
+            Some(select) => row
+                .values
+                .iter()
+                .filter(|(field, _)| select.contains(field))
+                .map(|(field, value)| (field.clone(), value.clone()))
+                .collect(),
+            None => row.values.clone(),
+        };
+
+        // A case sensitive search on the key field can go straight to the row.
+        let key = condition.iter().find_map(|condition| match condition {
+            Condition::Equals { field, value } if *field == rows.key_field => Some(value),
+            _ => None,
+        });
+        match (case, key) {
+            (Case::Sensitive, Some(key)) => key_of(key)
+                .and_then(|key| rows.rows.peek(&key))
+                .filter(|row| rows.is_live(row, now) && row_matches(case, condition, row))
+                .map(select_columns)
+                .into_iter()
+                .collect(),
+            _ => rows
+                .rows
+                .iter()
+                .map(|(_, row)| row)
+                .filter(|row| rows.is_live(row, now) && row_matches(case, condition, row))
+                .map(select_columns)
+                .collect(),
+        }
+    }
+}
+
+/// Returns the key that a row with the given key field value is stored
+/// under.
+pub(crate) fn key_of(value: &Value) -> Option<String> {
+    match value {
+        Value::Bytes(bytes) => Some(String::from_utf8_lossy(bytes).into_owned()),
+        Value::Integer(_) | Value::Float(_) | Value::Boolean(_) | Value::Timestamp(_) => {
+            Some(value.to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Does the given row match all the conditions specified?
+fn row_matches(case: Case, condition: &[Condition], row: &Row) -> bool {
+    condition.iter().all(|condition| {
+        row.values
+            .get(condition.field())
+            .map_or(false, |value| condition.matches(case, value))
+    })
+}
+
+#[derive(Clone)]
+pub struct Memory {
+    store: Arc<Store>,
+}
+
+impl Table for Memory {
+    fn find_table_row<'a>(
+        &self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&[String]>,
+        _index: Option<IndexHandle>,
+    ) -> Result<BTreeMap<String, Value>, String> {
+        let mut rows = self.store.find(case, condition, select, Instant::now());
+
+        match rows.pop() {
+            Some(row) if rows.is_empty() => Ok(row),
+            Some(_) => Err("more than one row found".to_string()),
+            None => Err("no rows found".to_string()),
+        }
+    }
+
+    fn find_table_rows<'a>(
+        &self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&[String]>,
+        _index: Option<IndexHandle>,
+    ) -> Result<Vec<BTreeMap<String, Value>>, String> {
+        Ok(self.store.find(case, condition, select, Instant::now()))
+    }
+
+    fn add_index(&mut self, _case: Case, _fields: &[&str]) -> Result<IndexHandle, String> {
+        // The rows are only indexed by their key, which is done regardless of
+        // the fields searched.
+        Ok(IndexHandle(0))
+    }
+
+    fn row_count(&self) -> Option<usize> {
+        Some(self.store.rows.read().unwrap().rows.len())
+    }
+}
+
+impl std::fmt::Debug for Memory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Memory {} row(s)", self.row_count().unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::btreemap;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<MemoryConfig>();
+    }
+
+    fn make_store(ttl_secs: u64, max_rows: usize) -> Store {
+        Store::new(&MemoryConfig {
+            key_field: "ip".to_string(),
+            ttl_secs,
+            max_rows,
+        })
+    }
+
+    fn write(store: &Store, ip: &str, host: &str, now: Instant) -> Written {
+        store.write(
+            ip.to_string(),
+            btreemap! {
+                "ip" => ip,
+                "host" => host,
+            },
+            now,
+        )
+    }
+
+    fn find_host(
+        store: &Store,
+        case: Case,
+        ip: &str,
+        now: Instant,
+    ) -> Vec<BTreeMap<String, Value>> {
+        store.find(
+            case,
+            &[Condition::Equals {
+                field: "ip",
+                value: ip.into(),
+            }],
+            Some(&["host".to_string()]),
+            now,
+        )
+    }
+
+    #[test]
+    fn finds_written_rows() {
+        let store = make_store(60, 10);
+        let now = Instant::now();
+        write(&store, "10.0.0.1", "alpha", now);
+        write(&store, "10.0.0.2", "beta", now);
+        write(&store, "10.0.0.1", "gamma", now);
+
+        assert_eq!(
+            vec![btreemap! { "host" => "gamma" }],
+            find_host(&store, Case::Sensitive, "10.0.0.1", now)
+        );
+        assert_eq!(
+            vec![btreemap! { "host" => "beta" }],
+            find_host(&store, Case::Insensitive, "10.0.0.2", now)
+        );
+        assert!(find_host(&store, Case::Sensitive, "10.0.0.3", now).is_empty());
+    }
+
+    #[test]
+    fn expires_rows() {
+        let store = make_store(60, 10);
+        let now = Instant::now();
+        write(&store, "10.0.0.1", "alpha", now);
+        write(&store, "10.0.0.2", "beta", now + Duration::from_secs(30));
+
+        let later = now + Duration::from_secs(60);
+        // Expired rows are never found, even before they are removed.
+        assert!(find_host(&store, Case::Sensitive, "10.0.0.1", later).is_empty());
+        assert_eq!(
+            Written {
+                expired: 1,
+                evicted: 0,
+                rows: 2,
+            },
+            write(&store, "10.0.0.3", "gamma", later)
+        );
+    }
+
+    #[test]
+    fn evicts_least_recently_written_rows() {
+        let store = make_store(60, 2);
+        let now = Instant::now();
+        write(&store, "10.0.0.1", "alpha", now);
+        write(&store, "10.0.0.2", "beta", now);
+        write(&store, "10.0.0.1", "gamma", now);
+
+        assert_eq!(
+            Written {
+                expired: 0,
+                evicted: 1,
+                rows: 2,
+            },
+            write(&store, "10.0.0.3", "delta", now)
+        );
+        assert!(find_host(&store, Case::Sensitive, "10.0.0.2", now).is_empty());
+        assert_eq!(
+            vec![btreemap! { "host" => "gamma" }],
+            find_host(&store, Case::Sensitive, "10.0.0.1", now)
+        );
+    }
+
+    #[tokio::test]
+    async fn shares_rows_between_builds() {
+        let config = MemoryConfig {
+            key_field: "ip".to_string(),
+            ttl_secs: 60,
+            max_rows: 10,
+        };
+        let key = ComponentKey::global("shares_rows_between_builds");
+        let globals = Default::default();
+        let table = config.build(&key, &globals).await.unwrap();
+
+        let store = store("shares_rows_between_builds").unwrap();
+        write(&store, "10.0.0.1", "alpha", Instant::now());
+
+        let rebuilt = config.build(&key, &globals).await.unwrap();
+        for table in &[table, rebuilt] {
+            assert_eq!(
+                Ok(btreemap! { "host" => "alpha", "ip" => "10.0.0.1" }),
+                table.find_table_row(
+                    Case::Sensitive,
+                    &[Condition::Equals {
+                        field: "ip",
+                        value: "10.0.0.1".into(),
+                    }],
+                    None,
+                    None,
+                )
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn drops_rows_of_dropped_tables() {
+        let config = MemoryConfig {
+            key_field: "ip".to_string(),
+            ttl_secs: 60,
+            max_rows: 10,
+        };
+        let key = ComponentKey::global("drops_rows_of_dropped_tables");
+        let globals = Default::default();
+        let table = config.build(&key, &globals).await.unwrap();
+        write(
+            &store("drops_rows_of_dropped_tables").unwrap(),
+            "10.0.0.1",
+            "alpha",
+            Instant::now(),
+        );
+
+        drop(table);
+        assert!(store("drops_rows_of_dropped_tables").is_none());
+
+        let rebuilt = config.build(&key, &globals).await.unwrap();
+        assert!(rebuilt
+            .find_table_rows(
+                Case::Sensitive,
+                &[Condition::Equals {
+                    field: "ip",
+                    value: "10.0.0.1".into(),
+                }],
+                None,
+                None,
+            )
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -1,4 +1,53 @@
 pub use enrichment::{Condition, IndexHandle, Table};
 
+use crate::internal_events::{EnrichmentTableReloadFailed, EnrichmentTableReloaded};
+use enrichment::TableRegistry;
+use std::time::Duration;
+
 #[cfg(feature = "enrichment-tables-file")]
 pub mod file;
+#[cfg(feature = "enrichment-tables-geoip")]
+pub mod geoip;
+#[cfg(feature = "enrichment-tables-memory")]
+pub mod memory;
+#[cfg(feature = "enrichment-tables-sqlite")]
+pub mod sqlite;
+
+/// How often the tables are checked for changes to their source, unless
+/// `enrichment_tables_reload_interval_secs` is set.
+pub const DEFAULT_RELOAD_INTERVAL_SECS: u64 = 10;
+
+/// Reloads the tables whose source has changed since they were loaded, checking every `period`.
+pub async fn reload_changed(tables: TableRegistry, period: Duration) {
+    let mut interval = tokio::time::interval(period);
+    // The first tick completes immediately, when the tables have only just been loaded.
+    interval.tick().await;
+
+    loop {
+        interval.tick().await;
+
+        let registry = tables.clone();
+        // Loading a large table can take a while, so it is kept off the threads running the
+        // topology.
+        let results = match tokio::task::spawn_blocking(move || registry.reload_changed()).await {
+            Ok(results) => results,
+            Err(error) => {
+                error!(message = "Reloading enrichment tables panicked.", %error);
+                continue;
+            }
+        };
+
+        for (table, result) in results {
+            match result {
+                Ok(rows) => emit!(&EnrichmentTableReloaded {
+                    table: &table,
+                    rows
+                }),
+                Err(error) => emit!(&EnrichmentTableReloadFailed {
+                    table: &table,
+                    error: &error
+                }),
+            }
+        }
+    }
+}
@@ -0,0 +1,755 @@
+use crate::config::{EnrichmentTableConfig, EnrichmentTableDescription, GenerateConfig};
+use bytes::Bytes;
+use enrichment::{Case, Condition, IndexHandle, Table};
+use lru::LruCache;
+use rusqlite::{
+    params, params_from_iter,
+    types::{Value as SqlValue, ValueRef},
+    Connection, OpenFlags,
+};
+use serde::{Deserialize, Serialize};
+use shared::{conversion::Conversion, datetime::TimeZone};
+use std::{
+    collections::BTreeMap,
+    net::IpAddr,
+    ops::Deref,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use vrl::Value;
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SqliteConfig {
+    /// Path to the SQLite database file.
+    pub path: PathBuf,
+    /// The table of the database that is searched.
+    pub table: String,
+    /// The number of searches whose results are kept in memory. The results are dropped
+    /// whenever the database changes, which is noticed within a second.
+    #[serde(default = "default_cache_size")]
+    pub cache_size: usize,
+}
+
+const fn default_cache_size() -> usize {
+    10_000
+}
+
+impl GenerateConfig for SqliteConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            path: "/path/to/assets.db".into(),
+            table: "assets".to_string(),
+            cache_size: default_cache_size(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "sqlite")]
+impl EnrichmentTableConfig for SqliteConfig {
+    async fn build(
+        &self,
+        _key: &crate::config::ComponentKey,
+        globals: &crate::config::GlobalOptions,
+    ) -> crate::Result<Box<dyn Table + Send + Sync>> {
+        let pool = Pool::new(self.path.clone());
+        let connection = pool.get()?;
+
+        let columns = connection
+            .prepare("SELECT name, type FROM pragma_table_info(?1)")?
+            .query_map(params![self.table], |row| {
+                Ok(Column {
+                    name: row.get(0)?,
+                    kind: ColumnKind::from_declared_type(&row.get::<_, String>(1)?),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        if columns.is_empty() {
+            return Err(format!(
+                "table {:?} not found in database {:?}",
+                self.table, self.path
+            )
+            .into());
+        }
+
+        drop(connection);
+
+        let cache = Cache {
+            connection: open(&self.path)?,
+            data_version: None,
+            checked_at: None,
+            results: LruCache::new(self.cache_size),
+        };
+
+        Ok(Box::new(Sqlite {
+            table: self.table.clone(),
+            columns,
+            timezone: globals.timezone,
+            pool: Arc::new(pool),
+            cache: Arc::new(Mutex::new(cache)),
+        }))
+    }
+}
+
+inventory::submit! {
+    EnrichmentTableDescription::new::<SqliteConfig>("sqlite")
+}
+
+/// How the values of a column are converted, based on the type it was declared with.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ColumnKind {
+    /// Values are converted to their closest VRL type.
+    Plain,
+    /// Integers are converted to booleans.
+    Boolean,
+    /// Text is parsed as a timestamp, integers are taken as seconds since the Unix epoch.
+    Timestamp,
+    /// SQLite compares numbers stored in these columns numerically.
+    Numeric,
+}
+
+impl ColumnKind {
+    fn from_declared_type(declared: &str) -> Self {
+        let declared = declared.to_uppercase();
+        if declared.contains("BOOL") {
+            Self::Boolean
+        } else if declared.contains("DATE") || declared.contains("TIME") {
+            Self::Timestamp
+        } else if ["INT", "REAL", "FLOA", "DOUB", "NUM", "DEC"]
+            .iter()
+            .any(|numeric| declared.contains(numeric))
+        {
+            Self::Numeric
+        } else {
+            Self::Plain
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Column {
+    name: String,
+    kind: ColumnKind,
+}
+
+/// The most connections kept open for searches that are not running.
+const MAX_IDLE_CONNECTIONS: usize = 4;
+
+/// Opens a connection to the database. The database is only ever read, which also allows it to
+/// be updated by other processes whilst Vector is running.
+fn open(path: &Path) -> rusqlite::Result<Connection> {
+    Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+}
+
+/// Connections to the database, so searches from several threads can run at the same time.
+/// Connections are opened as needed and kept for later searches.
+struct Pool {
+    path: PathBuf,
+    idle: Mutex<Vec<Connection>>,
+}
+
+impl Pool {
+    fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            idle: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn get(&self) -> rusqlite::Result<PooledConnection<'_>> {
+        let connection = match self.idle.lock().unwrap().pop() {
+            Some(connection) => connection,
+            None => open(&self.path)?,
+        };
+
+        Ok(PooledConnection {
+            pool: self,
+            connection: Some(connection),
+        })
+    }
+}
+
+/// A connection taken from the pool, which is given back once dropped.
+struct PooledConnection<'a> {
+    pool: &'a Pool,
+    connection: Option<Connection>,
+}
+
+impl Deref for PooledConnection<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.connection
+            .as_ref()
+            .expect("connection already returned")
+    }
+}
+
+impl Drop for PooledConnection<'_> {
+    fn drop(&mut self) {
+        let mut idle = self.pool.idle.lock().unwrap();
+        if idle.len() < MAX_IDLE_CONNECTIONS {
+            idle.extend(self.connection.take());
+        }
+    }
+}
+
+/// How often the database is checked for changes, as the cached results are only dropped once
+/// a change is noticed.
+const DATA_VERSION_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The results of recent searches by the search they were the result of.
+struct Cache {
+    /// The connection `data_version` is read from. The value only changes between reads by the
+    /// same connection, when another connection has changed the database.
+    connection: Connection,
+    /// The `data_version` of the database the results were read from.
+    data_version: Option<i64>,
+    /// When `data_version` was last read.
+    checked_at: Option<Instant>,
+    results: LruCache<Search, Vec<BTreeMap<String, Value>>>,
+}
+
+/// A search, as the key of its cached results.
+#[derive(Debug, PartialEq, Hash)]
+struct Search {
+    case: Case,
+    condition: Vec<SearchCondition>,
+    select: Option<Vec<String>>,
+    limit: usize,
+}
+
+// The floats of the conditions are compared by their bits, so equality is reflexive.
+impl Eq for Search {}
+
+/// An owned copy of a `Condition`, with its floats as bits so they can be hashed.
+#[derive(Debug, PartialEq, Hash)]
+enum SearchCondition {
+    Equals {
+        field: String,
+        value: Value,
+    },
+    BetweenDates {
+        field: String,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+    },
+    BetweenNumbers {
+        field: String,
+        from: u64,
+        to: u64,
+    },
+    RangeContains {
+        field: String,
+        value: u64,
+    },
+    CidrContains {
+        field: String,
+        value: IpAddr,
+    },
+    PrefixOf {
+        field: String,
+        value: String,
+    },
+}
+
+impl From<&Condition<'_>> for SearchCondition {
+    fn from(condition: &Condition<'_>) -> Self {
+        match condition {
+            Condition::Equals { field, value } => Self::Equals {
+                field: field.to_string(),
+                value: value.clone(),
+            },
+            Condition::BetweenDates { field, from, to } => Self::BetweenDates {
+                field: field.to_string(),
+                from: *from,
+                to: *to,
+            },
+            Condition::BetweenNumbers { field, from, to } => Self::BetweenNumbers {
+                field: field.to_string(),
+                from: from.to_bits(),
+                to: to.to_bits(),
+            },
+            Condition::RangeContains { field, value } => Self::RangeContains {
+                field: field.to_string(),
+                value: value.to_bits(),
+            },
+            Condition::CidrContains { field, value } => Self::CidrContains {
+                field: field.to_string(),
+                value: *value,
+            },
+            Condition::PrefixOf { field, value } => Self::PrefixOf {
+                field: field.to_string(),
+                value: value.clone(),
+            },
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Sqlite {
+    table: String,
+    columns: Vec<Column>,
+    timezone: TimeZone,
+    pool: Arc<Pool>,
+    cache: Arc<Mutex<Cache>>,
+}
+
+/// Quotes the name of a table or column for use in a query.
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+impl Sqlite {
+    fn column(&self, name: &str) -> Option<&Column> {
+        self.columns.iter().find(|column| column.name == name)
+    }
+
+    /// Builds the query for the rows matching the conditions. Conditions that SQLite can evaluate
+    /// the same way as the other tables are included in the query so it can use the indexes of
+    /// the database, the rest are left for `Condition::matches` to check on the rows returned.
+    fn query(&self, case: Case, condition: &[Condition]) -> (String, Vec<SqlValue>) {
+        let mut clauses = Vec::new();
+        let mut params = Vec::new();
+
+        for condition in condition {
+            let column = match self.column(condition.field()) {
+                Some(column) => column,
+                // No row can match a column that doesn't exist.
+                None => {
+                    return (
+                        format!("SELECT * FROM {} WHERE 0", quote(&self.table)),
+                        params,
+                    )
+                }
+            };
+            let name = quote(&column.name);
+
+            match condition {
+                Condition::Equals { value, .. } if column.kind != ColumnKind::Timestamp => {
+                    match value {
+                        Value::Null => clauses.push(format!("{} IS NULL", name)),
+                        // SQLite only folds the case of ASCII characters, so other text is
+                        // left for `Condition::matches` to compare.
+                        Value::Bytes(bytes) if case == Case::Sensitive || bytes.is_ascii() => {
+                            clauses.push(match case {
+                                Case::Insensitive => format!("{} = ? COLLATE NOCASE", name),
+                                Case::Sensitive => format!("{} = ?", name),
+                            });
+                            params
+                                .push(SqlValue::Text(String::from_utf8_lossy(bytes).into_owned()));
+                        }
+                        Value::Integer(integer) => {
+                            clauses.push(format!("{} = ?", name));
+                            params.push(SqlValue::Integer(*integer));
+                        }
+                        Value::Float(float) => {
+                            clauses.push(format!("{} = ?", name));
+                            params.push(SqlValue::Real(float.into_inner()));
+                        }
+                        Value::Boolean(boolean) => {
+                            clauses.push(format!("{} = ?", name));
+                            params.push(SqlValue::Integer(*boolean as i64));
+                        }
+                        _ => (),
+                    }
+                }
+                Condition::BetweenNumbers { from, to, .. }
+                    if column.kind == ColumnKind::Numeric =>
+                {
+                    clauses.push(format!("{} BETWEEN ? AND ?", name));
+                    params.push(SqlValue::Real(*from));
+                    params.push(SqlValue::Real(*to));
+                }
+                _ => (),
+            }
+        }
+
+        let mut query = format!("SELECT * FROM {}", quote(&self.table));
+        if !clauses.is_empty() {
+            query.push_str(" WHERE ");
+            query.push_str(&clauses.join(" AND "));
+        }
+
+        (query, params)
+    }
+
+    /// Converts a value read from the database.
+    fn convert(&self, kind: ColumnKind, value: ValueRef) -> Value {
+        match (kind, value) {
+            (_, ValueRef::Null) => Value::Null,
+            (ColumnKind::Boolean, ValueRef::Integer(integer)) => Value::Boolean(integer != 0),
+            (ColumnKind::Timestamp, ValueRef::Integer(seconds)) => {
+                match chrono::NaiveDateTime::from_timestamp_opt(seconds, 0) {
+                    Some(timestamp) => {
+                        Value::Timestamp(chrono::DateTime::from_utc(timestamp, chrono::Utc))
+                    }
+                    None => Value::Integer(seconds),
+                }
+            }
+            (ColumnKind::Timestamp, ValueRef::Text(text)) => {
+                let text = Bytes::copy_from_slice(text);
+                Conversion::Timestamp(self.timezone)
+                    .convert(text.clone())
+                    .ok()
+                    .or_else(|| {
+                        // Dates without a time, as produced by SQLite's `date` function.
+                        let date = std::str::from_utf8(&text).ok()?;
+                        let date = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+                        Some(Value::Timestamp(chrono::DateTime::from_utc(
+                            date.and_hms(0, 0, 0),
+                            chrono::Utc,
+                        )))
+                    })
+                    .unwrap_or(Value::Bytes(text))
+            }
+            (_, ValueRef::Integer(integer)) => Value::Integer(integer),
+            (_, ValueRef::Real(real)) => Value::from(real),
+            (_, ValueRef::Text(bytes)) | (_, ValueRef::Blob(bytes)) => {
+                Value::Bytes(Bytes::copy_from_slice(bytes))
+            }
+        }
+    }
+
+    /// Returns the cached results of the search, after dropping every cached result if the
+    /// database has changed since they were read. The database is checked for changes at most
+    /// once every `DATA_VERSION_INTERVAL`.
+    fn cached(&self, search: &Search) -> Result<Option<Vec<BTreeMap<String, Value>>>, String> {
+        let mut cache = self.cache.lock().unwrap();
+
+        let due = cache.checked_at.map_or(true, |checked_at| {
+            checked_at.elapsed() >= DATA_VERSION_INTERVAL
+        });
+        if due {
+            let data_version = cache
+                .connection
+                .query_row("PRAGMA data_version", params![], |row| row.get(0))
+                .map_err(|error| error.to_string())?;
+            if cache.data_version != Some(data_version) {
+                cache.results.clear();
+                cache.data_version = Some(data_version);
+            }
+            cache.checked_at = Some(Instant::now());
+        }
+
+        Ok(cache.results.get(search).cloned())
+    }
+
+    /// Searches the database for the rows matching the conditions, stopping once `limit` rows
+    /// have been found.
+    fn search(
+        &self,
+        case: Case,
+        condition: &[Condition],
+        select: Option<&[String]>,
+        limit: usize,
+    ) -> Result<Vec<BTreeMap<String, Value>>, String> {
+        let search = Search {
+            case,
+            condition: condition.iter().map(Into::into).collect(),
+            select: select.map(<[String]>::to_vec),
+            limit,
+        };
+        if let Some(rows) = self.cached(&search)? {
+            return Ok(rows);
+        }
+
+        let (query, params) = self.query(case, condition);
+        let connection = self.pool.get().map_err(|error| error.to_string())?;
+        let mut statement = connection
+            .prepare_cached(&query)
+            .map_err(|error| error.to_string())?;
+        let columns = statement
+            .column_names()
+            .into_iter()
+            .map(|name| {
+                self.column(name).cloned().unwrap_or_else(|| Column {
+                    name: name.to_string(),
+                    kind: ColumnKind::Plain,
+                })
+            })
+            .collect::<Vec<_>>();
+        let mut rows = statement
+            .query(params_from_iter(params.iter()))
+            .map_err(|error| error.to_string())?;
+
+        let mut results = Vec::new();
+        while let Some(row) = rows.next().map_err(|error| error.to_string())? {
+            let values = columns
+                .iter()
+                .enumerate()
+                .map(|(idx, column)| {
+                    let value = row.get_ref(idx).map_err(|error| error.to_string())?;
+                    Ok((column.name.clone(), self.convert(column.kind, value)))
+                })
+                .collect::<Result<BTreeMap<_, _>, String>>()?;
+
+            let matches = condition.iter().all(|condition| {
+                values
+                    .get(condition.field())
+                    .map_or(false, |value| condition.matches(case, value))
+            });
+            if matches {
+                results.push(match select {
+                    Some(select) => values
+                        .into_iter()
+                        .filter(|(column, _)| select.contains(column))
+                        .collect(),
+                    None => values,
+                });
+                if results.len() >= limit {
+                    break;
+                }
+            }
+        }
+
+        self.cache
+            .lock()
+            .unwrap()
+            .results
+            .put(search, results.clone());
+        Ok(results)
+    }
+}
+
+impl Table for Sqlite {
+    fn find_table_row<'a>(
+        &self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&[String]>,
+        _index: Option<IndexHandle>,
+    ) -> Result<BTreeMap<String, Value>, String> {
+        // Searching for a second row is enough to tell whether the row is unique.
+        let mut rows = self.search(case, condition, select, 2)?;
+
+        match rows.pop() {
+            Some(row) if rows.is_empty() => Ok(row),
+            Some(_) => Err("more than one row found".to_string()),
+            None => Err("no rows found".to_string()),
+        }
+    }
+
+    fn find_table_rows<'a>(
+        &self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&[String]>,
+        _index: Option<IndexHandle>,
+    ) -> Result<Vec<BTreeMap<String, Value>>, String> {
+        self.search(case, condition, select, usize::MAX)
+    }
+
+    fn add_index(&mut self, _case: Case, fields: &[&str]) -> Result<IndexHandle, String> {
+        // The searches use the indexes of the database, so only the fields need checking.
+        let missing = fields
+            .iter()
+            .filter(|field| self.column(field).is_none())
+            .copied()
+            .collect::<Vec<_>>();
+        if missing.is_empty() {
+            Ok(IndexHandle(0))
+        } else {
+            Err(format!(
+                "field(s) '{}' missing from dataset",
+                missing.join(", ")
+            ))
+        }
+    }
+
+    fn row_count(&self) -> Option<usize> {
+        let query = format!("SELECT COUNT(*) FROM {}", quote(&self.table));
+        self.pool
+            .get()
+            .ok()?
+            .query_row(&query, params![], |row| row.get::<_, i64>(0))
+            .ok()
+            .map(|count| count as usize)
+    }
+}
+
+impl std::fmt::Debug for Sqlite {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Sqlite table {} with {} column(s)",
+            self.table,
+            self.columns.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::btreemap;
+
+    fn date(year: i32, month: u32, day: u32) -> chrono::DateTime<chrono::Utc> {
+        chrono::TimeZone::ymd(&chrono::Utc, year, month, day).and_hms(0, 0, 0)
+    }
+
+    fn make_database() -> PathBuf {
+        let path = crate::test_util::temp_file();
+        let connection = Connection::open(&path).unwrap();
+        connection
+            .execute_batch(
+                r#"
+                CREATE TABLE assets (
+                    hostname TEXT NOT NULL,
+                    team TEXT,
+                    network TEXT,
+                    rack INTEGER,
+                    decommissioned BOOLEAN,
+                    purchased DATE
+                );
+                CREATE INDEX assets_hostname ON assets (hostname);
+                INSERT INTO assets VALUES
+                    ('web-1', 'frontend', '10.1.0.0/16', 4, 0, '2020-01-20'),
+                    ('web-2', 'frontend', '10.1.0.0/16', 5, 0, '2021-03-02'),
+                    ('db-1', 'storage', '10.2.0.0/16', 5, 1, '2019-11-11');
+                "#,
+            )
+            .unwrap();
+        path
+    }
+
+    async fn make_table(cache_size: usize) -> Box<dyn Table + Send + Sync> {
+        SqliteConfig {
+            path: make_database(),
+            table: "assets".to_string(),
+            cache_size,
+        }
+        .build(&"assets".into(), &Default::default())
+        .await
+        .unwrap()
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SqliteConfig>();
+    }
+
+    #[tokio::test]
+    async fn finds_row() {
+        let table = make_table(10).await;
+
+        assert_eq!(
+            Ok(btreemap! {
+                "hostname" => "db-1",
+                "team" => "storage",
+                "network" => "10.2.0.0/16",
+                "rack" => 5,
+                "decommissioned" => true,
+                "purchased" => Value::Timestamp(date(2019, 11, 11)),
+            }),
+            table.find_table_row(
+                Case::Insensitive,
+                &[Condition::Equals {
+                    field: "hostname",
+                    value: "DB-1".into(),
+                }],
+                None,
+                None
+            )
+        );
+        assert_eq!(
+            Err("more than one row found".to_string()),
+            table.find_table_row(
+                Case::Sensitive,
+                &[Condition::Equals {
+                    field: "team",
+                    value: "frontend".into(),
+                }],
+                None,
+                None
+            )
+        );
+        assert_eq!(Some(3), table.row_count());
+    }
+
+    #[tokio::test]
+    async fn finds_rows_with_conditions_sqlite_cannot_check() {
+        let table = make_table(0).await;
+
+        assert_eq!(
+            Ok(vec![btreemap! { "hostname" => "web-2" }]),
+            table.find_table_rows(
+                Case::Sensitive,
+                &[
+                    Condition::BetweenNumbers {
+                        field: "rack",
+                        from: 5.0,
+                        to: 10.0,
+                    },
+                    Condition::CidrContains {
+                        field: "network",
+                        value: "10.1.2.3".parse().unwrap(),
+                    },
+                    Condition::BetweenDates {
+                        field: "purchased",
+                        from: date(2021, 1, 1),
+                        to: date(2022, 1, 1),
+                    },
+                ],
+                Some(&["hostname".to_string()]),
+                None
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn drops_cached_results_on_change() {
+        let path = make_database();
+        let table = SqliteConfig {
+            path: path.clone(),
+            table: "assets".to_string(),
+            cache_size: 10,
+        }
+        .build(&"assets".into(), &Default::default())
+        .await
+        .unwrap();
+
+        let condition = [Condition::Equals {
+            field: "hostname",
+            value: "web-1".into(),
+        }];
+        let select = ["team".to_string()];
+        let find = || table.find_table_row(Case::Sensitive, &condition, Some(&select), None);
+        assert_eq!(Ok(btreemap! { "team" => "frontend" }), find());
+
+        Connection::open(&path)
+            .unwrap()
+            .execute("UPDATE assets SET team = 'platform'", params![])
+            .unwrap();
+        // The change is only noticed once the database is checked again.
+        assert_eq!(Ok(btreemap! { "team" => "frontend" }), find());
+
+        std::thread::sleep(DATA_VERSION_INTERVAL);
+        assert_eq!(Ok(btreemap! { "team" => "platform" }), find());
+    }
+
+    #[tokio::test]
+    async fn errors_on_missing_table_or_columns() {
+        let config = SqliteConfig {
+            path: make_database(),
+            table: "hosts".to_string(),
+            cache_size: 10,
+        };
+        assert!(config
+            .build(&"hosts".into(), &Default::default())
+            .await
+            .is_err());
+
+        let mut table = make_table(10).await;
+        assert_eq!(
+            Err("field(s) 'owner' missing from dataset".to_string()),
+            table.add_index(Case::Sensitive, &["hostname", "owner"])
+        );
+    }
+}
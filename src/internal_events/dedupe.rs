@@ -15,3 +15,35 @@ impl InternalEvent for DedupeEventDiscarded {
         counter!("events_discarded_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct DedupeCacheLoadFailed<'a> {
+    pub path: &'a std::path::Path,
+    pub error: crate::Error,
+}
+
+impl<'a> InternalEvent for DedupeCacheLoadFailed<'a> {
+    fn emit_logs(&self) {
+        warn!(message = "Failed to load dedupe cache; starting with an empty cache.", path = ?self.path, error = %self.error);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "cache_load_failed");
+    }
+}
+
+#[derive(Debug)]
+pub struct DedupeCacheSaveFailed<'a> {
+    pub path: &'a std::path::Path,
+    pub error: crate::Error,
+}
+
+impl<'a> InternalEvent for DedupeCacheSaveFailed<'a> {
+    fn emit_logs(&self) {
+        error!(message = "Failed to save dedupe cache.", path = ?self.path, error = %self.error, internal_log_rate_secs = 60);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1, "error_type" => "cache_save_failed");
+    }
+}
@@ -0,0 +1,110 @@
+// ## skip check-events ##
+
+use metrics::{counter, gauge};
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct EnrichmentTableReloaded<'a> {
+    pub table: &'a str,
+    pub rows: Option<usize>,
+}
+
+impl<'a> InternalEvent for EnrichmentTableReloaded<'a> {
+    fn emit_logs(&self) {
+        info!(
+            message = "Reloaded enrichment table.",
+            table = %self.table,
+            rows = ?self.rows,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("enrichment_table_reloads_total", 1, "table" => self.table.to_owned());
+        if let Some(rows) = self.rows {
+            gauge!("enrichment_table_rows", rows as f64, "table" => self.table.to_owned());
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct EnrichmentTableReloadFailed<'a> {
+    pub table: &'a str,
+    pub error: &'a str,
+}
+
+impl<'a> InternalEvent for EnrichmentTableReloadFailed<'a> {
+    fn emit_logs(&self) {
+        error!(
+            message = "Failed to reload enrichment table; keeping previous data.",
+            table = %self.table,
+            error = %self.error,
+            internal_log_rate_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("enrichment_table_reload_errors_total", 1, "table" => self.table.to_owned());
+    }
+}
+
+#[derive(Debug)]
+pub struct EnrichmentTableRowWritten<'a> {
+    pub table: &'a str,
+    pub expired: usize,
+    pub evicted: usize,
+    pub rows: usize,
+}
+
+impl<'a> InternalEvent for EnrichmentTableRowWritten<'a> {
+    fn emit_logs(&self) {
+        trace!(
+            message = "Wrote row to enrichment table.",
+            table = %self.table,
+            expired = %self.expired,
+            evicted = %self.evicted,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("enrichment_table_writes_total", 1, "table" => self.table.to_owned());
+        if self.expired > 0 {
+            counter!(
+                "enrichment_table_evictions_total", self.expired as u64,
+                "table" => self.table.to_owned(),
+                "reason" => "expired",
+            );
+        }
+        if self.evicted > 0 {
+            counter!(
+                "enrichment_table_evictions_total", self.evicted as u64,
+                "table" => self.table.to_owned(),
+                "reason" => "max_rows",
+            );
+        }
+        gauge!("enrichment_table_rows", self.rows as f64, "table" => self.table.to_owned());
+    }
+}
+
+#[derive(Debug)]
+pub struct EnrichmentTableKeyMissing<'a> {
+    pub table: &'a str,
+    pub key_field: &'a str,
+}
+
+impl<'a> InternalEvent for EnrichmentTableKeyMissing<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Event is missing the key field; dropping event.",
+            table = %self.table,
+            key_field = %self.key_field,
+            internal_log_rate_secs = 30,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!(
+            "processing_errors_total", 1,
+            "error_type" => "field_missing",
+        );
+    }
+}
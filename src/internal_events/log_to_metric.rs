@@ -45,6 +45,28 @@ impl<'a> InternalEvent for LogToMetricFieldNotFound<'a> {
     }
 }
 
+pub struct LogToMetricFieldValueInvalid<'a> {
+    pub field: &'a str,
+    pub value: &'a str,
+}
+
+impl<'a> InternalEvent for LogToMetricFieldValueInvalid<'a> {
+    fn emit_logs(&self) {
+        warn!(
+            message = "Field has an invalid value.",
+            field = %self.field,
+            value = %self.value,
+            internal_log_rate_secs = 30
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("processing_errors_total", 1,
+                 "error_type" => "invalid_field_value",
+        );
+    }
+}
+
 pub struct LogToMetricParseFloatError<'a> {
     pub field: &'a str,
     pub error: ParseFloatError,
@@ -49,6 +49,7 @@ mod dnstap;
 mod docker_logs;
 mod elasticsearch;
 mod encoding_transcode;
+mod enrichment_tables;
 #[cfg(feature = "sources-eventstoredb_metrics")]
 mod eventstoredb_metrics;
 #[cfg(feature = "sources-exec")]
@@ -102,6 +103,8 @@ mod process;
 #[cfg(any(feature = "sources-prometheus", feature = "sinks-prometheus"))]
 mod prometheus;
 mod pulsar;
+#[cfg(feature = "transforms-recording_rules")]
+mod recording_rules;
 #[cfg(feature = "sinks-redis")]
 mod redis;
 #[cfg(feature = "transforms-reduce")]
@@ -132,6 +135,8 @@ mod syslog;
 mod tag_cardinality_limit;
 mod tcp;
 mod template;
+#[cfg(feature = "transforms-throttle")]
+mod throttle;
 #[cfg(feature = "transforms-tokenizer")]
 mod tokenizer;
 mod udp;
@@ -185,6 +190,7 @@ pub(crate) use self::dnstap::*;
 pub use self::docker_logs::*;
 pub use self::elasticsearch::*;
 pub use self::encoding_transcode::*;
+pub use self::enrichment_tables::*;
 #[cfg(feature = "sources-eventstoredb_metrics")]
 pub use self::eventstoredb_metrics::*;
 #[cfg(feature = "sources-exec")]
@@ -247,6 +253,8 @@ pub use self::process::*;
 #[cfg(any(feature = "sources-prometheus", feature = "sinks-prometheus"))]
 pub(crate) use self::prometheus::*;
 pub use self::pulsar::*;
+#[cfg(feature = "transforms-recording_rules")]
+pub(crate) use self::recording_rules::*;
 #[cfg(feature = "sinks-redis")]
 pub use self::redis::*;
 #[cfg(feature = "transforms-reduce")]
@@ -277,6 +285,8 @@ pub use self::syslog::*;
 pub(crate) use self::tag_cardinality_limit::*;
 pub use self::tcp::*;
 pub use self::template::*;
+#[cfg(feature = "transforms-throttle")]
+pub(crate) use self::throttle::*;
 #[cfg(feature = "transforms-tokenizer")]
 pub(crate) use self::tokenizer::*;
 pub use self::udp::*;
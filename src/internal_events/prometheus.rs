@@ -171,3 +171,24 @@ impl InternalEvent for PrometheusServerRequestComplete {
         counter!("requests_received_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct PrometheusExporterSeriesEvicted<'a> {
+    pub name: &'a str,
+    pub reason: &'static str,
+}
+
+impl<'a> InternalEvent for PrometheusExporterSeriesEvicted<'a> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Series limit reached, evicting least recently updated series.",
+            name = %self.name,
+            reason = %self.reason,
+            internal_log_rate_secs = 10
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("series_evicted_total", 1, "reason" => self.reason);
+    }
+}
@@ -0,0 +1,23 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct RecordingRulesEvaluated {
+    pub rules: usize,
+    pub series: usize,
+}
+
+impl InternalEvent for RecordingRulesEvaluated {
+    fn emit_logs(&self) {
+        trace!(
+            message = "Evaluated recording rules.",
+            rules = %self.rules,
+            series = %self.series
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("recording_rules_evaluations_total", 1);
+        counter!("recording_rules_series_recorded_total", self.series as u64);
+    }
+}
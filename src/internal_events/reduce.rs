@@ -9,3 +9,18 @@ impl InternalEvent for ReduceStaleEventFlushed {
         counter!("stale_events_flushed_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct ReduceGroupFlushed {
+    pub reason: &'static str,
+}
+
+impl InternalEvent for ReduceGroupFlushed {
+    fn emit_logs(&self) {
+        debug!(message = "Reduce limit reached; flushing.", reason = %self.reason, internal_log_rate_secs = 10);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("reduce_groups_flushed_total", 1, "reason" => self.reason);
+    }
+}
@@ -41,6 +41,26 @@ impl<'a> InternalEvent for TagCardinalityLimitRejectingTag<'a> {
     }
 }
 
+pub struct TagCardinalityLimitReplacingTag<'a> {
+    pub tag_key: &'a str,
+    pub tag_value: &'a str,
+}
+
+impl<'a> InternalEvent for TagCardinalityLimitReplacingTag<'a> {
+    fn emit_logs(&self) {
+        debug!(
+            message = "Replacing tag value after hitting configured 'value_limit'.",
+            tag_key = self.tag_key,
+            tag_value = self.tag_value,
+            internal_log_rate_secs = 10,
+        );
+    }
+
+    fn emit_metrics(&self) {
+        counter!("tag_value_limit_exceeded_total", 1);
+    }
+}
+
 pub struct TagCardinalityValueLimitReached<'a> {
     pub key: &'a str,
 }
@@ -0,0 +1,18 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct ThrottleEventDiscarded<'a> {
+    pub key: &'a str,
+}
+
+impl<'a> InternalEvent for ThrottleEventDiscarded<'a> {
+    fn emit_logs(&self) {
+        debug!(message = "Rate limit exceeded; discarding event.", key = %self.key, internal_log_rate_secs = 10);
+    }
+
+    fn emit_metrics(&self) {
+        counter!("events_discarded_total", 1,
+              "reason" => "rate_limited");
+    }
+}
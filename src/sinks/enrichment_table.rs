@@ -0,0 +1,174 @@
+use crate::{
+    buffers::Acker,
+    config::{DataType, GenerateConfig, SinkConfig, SinkContext, SinkDescription},
+    emit,
+    enrichment_tables::memory::{self, Store},
+    internal_events::{EnrichmentTableKeyMissing, EnrichmentTableRowWritten},
+    sinks::util::StreamSink,
+};
+use async_trait::async_trait;
+use futures::{future, stream::BoxStream, FutureExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::{collections::BTreeMap, sync::Arc, time::Instant};
+use vector_core::event::Event;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("No memory enrichment table named {:?}", table))]
+    UnknownTable { table: String },
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct EnrichmentTableSinkConfig {
+    /// The name of the `memory` enrichment table the events are written to.
+    pub table: String,
+}
+
+inventory::submit! {
+    SinkDescription::new::<EnrichmentTableSinkConfig>("enrichment_table")
+}
+
+impl GenerateConfig for EnrichmentTableSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            table: "table".to_string(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "enrichment_table")]
+impl SinkConfig for EnrichmentTableSinkConfig {
+    async fn build(
+        &self,
+        cx: SinkContext,
+    ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        let store = memory::store(&self.table).ok_or_else(|| BuildError::UnknownTable {
+            table: self.table.clone(),
+        })?;
+        let sink = EnrichmentTableSink {
+            table: self.table.clone(),
+            store,
+            acker: cx.acker(),
+        };
+        let healthcheck = future::ok(()).boxed();
+
+        Ok((super::VectorSink::Stream(Box::new(sink)), healthcheck))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "enrichment_table"
+    }
+}
+
+struct EnrichmentTableSink {
+    table: String,
+    store: Arc<Store>,
+    acker: Acker,
+}
+
+impl EnrichmentTableSink {
+    fn write(&self, event: Event) {
+        let (fields, _) = event.into_log().into_parts();
+        let values: BTreeMap<String, vrl::Value> = fields
+            .into_iter()
+            .map(|(field, value)| (field, value.into()))
+            .collect();
+
+        let key_field = self.store.key_field();
+        let key = match values.get(&key_field).and_then(memory::key_of) {
+            Some(key) => key,
+            None => {
+                emit!(&EnrichmentTableKeyMissing {
+                    table: &self.table,
+                    key_field: &key_field,
+                });
+                return;
+            }
+        };
+
+        let written = self.store.write(key, values, Instant::now());
+        emit!(&EnrichmentTableRowWritten {
+            table: &self.table,
+            expired: written.expired,
+            evicted: written.evicted,
+            rows: written.rows,
+        });
+    }
+}
+
+#[async_trait]
+impl StreamSink for EnrichmentTableSink {
+    async fn run(self: Box<Self>, mut input: BoxStream<'_, Event>) -> Result<(), ()> {
+        while let Some(event) = input.next().await {
+            self.write(event);
+            self.acker.ack(1);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::{ComponentKey, EnrichmentTableConfig},
+        enrichment_tables::memory::MemoryConfig,
+        event::LogEvent,
+    };
+    use enrichment::{Case, Condition};
+    use shared::btreemap;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<EnrichmentTableSinkConfig>();
+    }
+
+    #[tokio::test]
+    async fn writes_events_to_table() {
+        let config: MemoryConfig = toml::from_str(r#"key_field = "ip""#).unwrap();
+        let table = config
+            .build(
+                &ComponentKey::global("writes_events_to_table"),
+                &Default::default(),
+            )
+            .await
+            .unwrap();
+
+        let sink = Box::new(EnrichmentTableSink {
+            table: "writes_events_to_table".to_string(),
+            store: memory::store("writes_events_to_table").unwrap(),
+            acker: Acker::Null,
+        });
+        let events = vec![
+            LogEvent::from(btreemap! { "ip" => "10.0.0.1", "host" => "alpha" }),
+            LogEvent::from(btreemap! { "host" => "no ip" }),
+        ];
+        sink.run(Box::pin(futures::stream::iter(events).map(Event::from)))
+            .await
+            .unwrap();
+
+        let condition = [Condition::Equals {
+            field: "ip",
+            value: "10.0.0.1".into(),
+        }];
+        assert_eq!(
+            Ok(btreemap! { "host" => "alpha" }),
+            table.find_table_row(
+                Case::Sensitive,
+                &condition,
+                Some(&["host".to_string()]),
+                None
+            )
+        );
+        assert_eq!(Some(1), table.row_count());
+    }
+}
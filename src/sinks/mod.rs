@@ -31,6 +31,8 @@ pub mod datadog;
 pub mod datadog_archives;
 #[cfg(feature = "sinks-elasticsearch")]
 pub mod elasticsearch;
+#[cfg(feature = "sinks-enrichment_table")]
+pub mod enrichment_table;
 #[cfg(feature = "sinks-file")]
 pub mod file;
 #[cfg(feature = "sinks-gcp")]
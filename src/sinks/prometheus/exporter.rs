@@ -1,9 +1,9 @@
 use crate::{
     buffers::Acker,
     config::{DataType, GenerateConfig, Resource, SinkConfig, SinkContext, SinkDescription},
-    event::metric::{Metric, MetricData, MetricKind, MetricValue},
+    event::metric::{Metric, MetricData, MetricKind, MetricName, MetricValue},
     event::Event,
-    internal_events::PrometheusServerRequestComplete,
+    internal_events::{PrometheusExporterSeriesEvicted, PrometheusServerRequestComplete},
     sinks::{
         util::{statistic::validate_quantiles, StreamSink},
         Healthcheck, VectorSink,
@@ -18,10 +18,11 @@ use hyper::{
     service::{make_service_fn, service_fn},
     Body, Method, Request, Response, Server, StatusCode,
 };
-use indexmap::IndexMap;
+use indexmap::{map::MutableKeys, IndexMap};
 use serde::{Deserialize, Serialize};
 use snafu::Snafu;
 use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
     convert::Infallible,
     hash::{Hash, Hasher},
     mem::discriminant,
@@ -55,6 +56,18 @@ pub struct PrometheusExporterConfig {
     pub quantiles: Vec<f64>,
     #[serde(default = "default_flush_period_secs")]
     pub flush_period_secs: u64,
+    /// The maximum number of series held by the exporter. When exceeded,
+    /// the least recently updated series is evicted.
+    #[serde(default)]
+    pub max_series: Option<usize>,
+    /// The maximum number of series held for any one metric name. When
+    /// exceeded, the least recently updated series with that name is
+    /// evicted.
+    #[serde(default)]
+    pub max_series_per_metric: Option<usize>,
+    /// Overrides of `max_series_per_metric` for specific metric names.
+    #[serde(default)]
+    pub metric_series_limits: BTreeMap<String, usize>,
 }
 
 impl std::default::Default for PrometheusExporterConfig {
@@ -66,6 +79,9 @@ impl std::default::Default for PrometheusExporterConfig {
             buckets: super::default_histogram_buckets(),
             quantiles: super::default_summary_quantiles(),
             flush_period_secs: default_flush_period_secs(),
+            max_series: None,
+            max_series_per_metric: None,
+            metric_series_limits: BTreeMap::new(),
         }
     }
 }
@@ -161,17 +177,150 @@ struct PrometheusExporter {
 }
 
 struct ExpiringMetrics {
-    map: IndexMap<MetricEntry, bool>,
+    map: IndexMap<MetricEntry, EntryState>,
+    /// The index in `map` of every series, by the sequence number of its
+    /// last update, so the least recently updated one is found first.
+    by_sequence: BTreeMap<u64, usize>,
+    /// The sequence numbers of the last update of the series of each name.
+    sequences_per_name: HashMap<MetricName, BTreeSet<u64>>,
+    /// Incremented on every update, used to order series by recency.
+    sequence: u64,
     last_flush_timestamp: i64,
 }
 
+#[derive(Clone, Copy, Debug)]
+struct EntryState {
+    is_incremental_set: bool,
+    last_update: u64,
+}
+
+impl ExpiringMetrics {
+    fn new() -> Self {
+        Self {
+            map: IndexMap::new(),
+            by_sequence: BTreeMap::new(),
+            sequences_per_name: HashMap::new(),
+            sequence: 0,
+            last_flush_timestamp: Utc::now().timestamp(),
+        }
+    }
+
+    /// Replaces the stored value of a series, or merges an incremental
+    /// value into it, inserting the series if it wasn't present yet.
+    fn upsert(&mut self, metric: Metric, config: &PrometheusExporterConfig) {
+        self.sequence += 1;
+        let is_incremental = metric.kind() == MetricKind::Incremental;
+        let entry = MetricEntry(metric.into_absolute());
+        let state = EntryState {
+            is_incremental_set: is_incremental && matches!(entry.value(), MetricValue::Set { .. }),
+            last_update: self.sequence,
+        };
+
+        match self.map.get_full_mut2(&entry) {
+            Some((index, existing, existing_state)) => {
+                if !is_incremental {
+                    *existing = entry;
+                } else if !existing.update(&entry) {
+                    warn!(message = "Metric changed type, dropping old value.", series = %entry.series());
+                    *existing = entry;
+                }
+                let previous = std::mem::replace(existing_state, state).last_update;
+
+                let name = &existing.series().name;
+                if let Some(sequences) = self.sequences_per_name.get_mut(name) {
+                    sequences.remove(&previous);
+                    sequences.insert(state.last_update);
+                }
+                self.by_sequence.remove(&previous);
+                self.by_sequence.insert(state.last_update, index);
+            }
+            None => {
+                let name = &entry.series().name;
+                self.make_room_for(name, config);
+                self.sequences_per_name
+                    .entry(name.clone())
+                    .or_default()
+                    .insert(state.last_update);
+                self.by_sequence.insert(state.last_update, self.map.len());
+                self.map.insert(entry, state);
+            }
+        }
+    }
+
+    /// Evicts series until a new series named `name` can be inserted
+    /// without exceeding the configured limits.
+    fn make_room_for(&mut self, name: &MetricName, config: &PrometheusExporterConfig) {
+        let name_limit = config
+            .metric_series_limits
+            .get(&name.name)
+            .copied()
+            .or(config.max_series_per_metric);
+        if let Some(limit) = name_limit {
+            while self.sequences_per_name.get(name).map_or(0, BTreeSet::len) >= limit.max(1) {
+                if !self.evict_oldest(Some(name), "max_series_per_metric") {
+                    break;
+                }
+            }
+        }
+
+        if let Some(limit) = config.max_series {
+            while self.map.len() >= limit.max(1) {
+                if !self.evict_oldest(None, "max_series") {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Evicts the least recently updated series, only looking at the ones
+    /// named `name` if given, returning `false` if there was nothing to
+    /// evict.
+    fn evict_oldest(&mut self, name: Option<&MetricName>, reason: &'static str) -> bool {
+        let oldest = match name {
+            Some(name) => self
+                .sequences_per_name
+                .get(name)
+                .and_then(|sequences| sequences.iter().next()),
+            None => self.by_sequence.keys().next(),
+        }
+        .copied();
+        let index = match oldest.and_then(|oldest| self.by_sequence.remove(&oldest)) {
+            Some(index) => index,
+            None => return false,
+        };
+
+        let (MetricEntry(metric), state) = match self.map.swap_remove_index(index) {
+            Some(removed) => removed,
+            None => return false,
+        };
+
+        // The last series took the place of the evicted one.
+        if let Some((_, moved)) = self.map.get_index(index) {
+            self.by_sequence.insert(moved.last_update, index);
+        }
+
+        let name = &metric.series().name;
+        if let Some(sequences) = self.sequences_per_name.get_mut(name) {
+            sequences.remove(&state.last_update);
+            if sequences.is_empty() {
+                self.sequences_per_name.remove(name);
+            }
+        }
+        emit!(&PrometheusExporterSeriesEvicted {
+            name: metric.name(),
+            reason,
+        });
+        true
+    }
+}
+
 fn handle(
     req: Request<Body>,
     default_namespace: Option<&str>,
     buckets: &[f64],
     quantiles: &[f64],
     expired: bool,
-    metrics: &IndexMap<MetricEntry, bool>,
+    metrics: &IndexMap<MetricEntry, EntryState>,
 ) -> Response<Body> {
     let mut response = Response::new(Body::empty());
 
@@ -203,10 +352,7 @@ impl PrometheusExporter {
         Self {
             server_shutdown_trigger: None,
             config,
-            metrics: Arc::new(RwLock::new(ExpiringMetrics {
-                map: IndexMap::new(),
-                last_flush_timestamp: Utc::now().timestamp(),
-            })),
+            metrics: Arc::new(RwLock::new(ExpiringMetrics::new())),
             acker,
         }
     }
@@ -304,34 +450,16 @@ impl StreamSink for PrometheusExporter {
                 metrics.map = metrics
                     .map
                     .drain(..)
-                    .map(|(MetricEntry(mut metric), is_incremental_set)| {
-                        if is_incremental_set {
+                    .map(|(MetricEntry(mut metric), state)| {
+                        if state.is_incremental_set {
                             metric.zero();
                         }
-                        (MetricEntry(metric), is_incremental_set)
+                        (MetricEntry(metric), state)
                     })
                     .collect();
             }
 
-            match item.kind() {
-                MetricKind::Incremental => {
-                    let mut entry = MetricEntry(item.into_absolute());
-                    if let Some((MetricEntry(mut existing), _)) = metrics.map.remove_entry(&entry) {
-                        if existing.update(&entry) {
-                            entry = MetricEntry(existing);
-                        } else {
-                            warn!(message = "Metric changed type, dropping old value.", series = %entry.series());
-                        }
-                    }
-                    let is_set = matches!(entry.value(), MetricValue::Set { .. });
-                    metrics.map.insert(entry, is_set);
-                }
-                MetricKind::Absolute => {
-                    let new = MetricEntry(item);
-                    metrics.map.remove(&new);
-                    metrics.map.insert(new, false);
-                }
-            };
+            metrics.upsert(item, &self.config);
 
             self.acker.ack(1);
         }
@@ -634,6 +762,137 @@ mod tests {
             &MetricValue::Counter { value: 33. }
         );
     }
+
+    fn tagged_counter(name: &str, tag: &str) -> Metric {
+        Metric::new(
+            name,
+            MetricKind::Absolute,
+            MetricValue::Counter { value: 1. },
+        )
+        .with_tags(Some(
+            vec![("tag".to_owned(), tag.to_owned())]
+                .into_iter()
+                .collect(),
+        ))
+    }
+
+    async fn run_and_collect(
+        config: PrometheusExporterConfig,
+        metrics: Vec<Metric>,
+    ) -> Vec<Metric> {
+        let cx = SinkContext::new_test();
+        let sink = Box::new(PrometheusExporter::new(config, cx.acker()));
+        let internal_metrics = Arc::clone(&sink.metrics);
+
+        sink.run(Box::pin(futures::stream::iter(
+            metrics.into_iter().map(Event::Metric),
+        )))
+        .await
+        .unwrap();
+
+        let metrics = internal_metrics.read().unwrap();
+        let mut stored = metrics
+            .map
+            .keys()
+            .map(|MetricEntry(metric)| metric.clone())
+            .collect::<Vec<_>>();
+        stored.sort_by(|a, b| a.series().partial_cmp(b.series()).unwrap());
+        stored
+    }
+
+    #[tokio::test]
+    async fn evicts_least_recently_updated_series() {
+        let config = PrometheusExporterConfig {
+            address: next_addr(), // Not actually bound, just needed to fill config
+            max_series: Some(2),
+            ..Default::default()
+        };
+
+        let stored = run_and_collect(
+            config,
+            vec![
+                tagged_counter("requests", "a"),
+                tagged_counter("requests", "b"),
+                tagged_counter("requests", "a"),
+                tagged_counter("errors", "c"),
+            ],
+        )
+        .await;
+
+        assert_eq!(
+            stored,
+            vec![
+                tagged_counter("errors", "c"),
+                tagged_counter("requests", "a")
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn evicts_by_recency_after_series_moved() {
+        let config = PrometheusExporterConfig {
+            address: next_addr(), // Not actually bound, just needed to fill config
+            max_series: Some(3),
+            ..Default::default()
+        };
+
+        // Evicting "a" moves "c" into its place, which must still be
+        // evicted before the more recently updated "b".
+        let stored = run_and_collect(
+            config,
+            vec![
+                tagged_counter("requests", "a"),
+                tagged_counter("requests", "b"),
+                tagged_counter("requests", "c"),
+                tagged_counter("requests", "d"),
+                tagged_counter("requests", "b"),
+                tagged_counter("requests", "e"),
+            ],
+        )
+        .await;
+
+        assert_eq!(
+            stored,
+            vec![
+                tagged_counter("requests", "b"),
+                tagged_counter("requests", "d"),
+                tagged_counter("requests", "e"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn limits_series_per_metric() {
+        let mut metric_series_limits = BTreeMap::new();
+        metric_series_limits.insert("errors".to_owned(), 2);
+        let config = PrometheusExporterConfig {
+            address: next_addr(), // Not actually bound, just needed to fill config
+            max_series_per_metric: Some(1),
+            metric_series_limits,
+            ..Default::default()
+        };
+
+        let stored = run_and_collect(
+            config,
+            vec![
+                tagged_counter("requests", "a"),
+                tagged_counter("errors", "a"),
+                tagged_counter("errors", "b"),
+                tagged_counter("requests", "b"),
+                tagged_counter("errors", "c"),
+            ],
+        )
+        .await;
+
+        assert_eq!(
+            stored,
+            vec![
+                tagged_counter("errors", "b"),
+                tagged_counter("errors", "c"),
+                tagged_counter("requests", "b"),
+            ]
+        );
+    }
 }
 
 #[cfg(all(test, feature = "prometheus-integration-tests"))]
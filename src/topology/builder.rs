@@ -42,7 +42,7 @@ pub async fn load_enrichment_tables<'a>(
         .iter()
         .filter(|(name, _)| diff.enrichment_tables.contains_new(name))
     {
-        let table = match table.inner.build(&config.global).await {
+        let table = match table.inner.build(name, &config.global).await {
             Ok(table) => table,
             Err(error) => {
                 errors.push(format!("Enrichment Table \"{}\": {}", name, error));
@@ -141,11 +141,6 @@ pub async fn build_pieces(
         source_tasks.insert(key.clone(), server);
     }
 
-    let context = TransformContext {
-        globals: config.global.clone(),
-        enrichment_tables: enrichment_tables.clone(),
-    };
-
     // Build transforms
     for (key, transform) in config
         .transforms
@@ -156,6 +151,12 @@ pub async fn build_pieces(
 
         let typetag = transform.inner.transform_type();
 
+        let context = TransformContext {
+            key: Some(key.id().to_owned()),
+            globals: config.global.clone(),
+            enrichment_tables: enrichment_tables.clone(),
+        };
+
         let input_type = transform.inner.input_type();
         let transform = match transform.inner.build(&context).await {
             Err(error) => {
@@ -65,6 +65,7 @@ pub async fn start_validated(
         return None;
     }
     running_topology.connect_diff(&diff, &mut pieces).await;
+    running_topology.spawn_enrichment_table_reloads(pieces.enrichment_tables.clone());
     running_topology.spawn_diff(&diff, pieces);
 
     Some((running_topology, abort_rx))
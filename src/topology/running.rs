@@ -34,6 +34,7 @@ pub struct RunningTopology {
     pub(crate) config: Config,
     abort_tx: mpsc::UnboundedSender<()>,
     watch: (WatchTx, WatchRx),
+    enrichment_table_reloads: Option<tokio::task::JoinHandle<()>>,
 }
 
 impl RunningTopology {
@@ -48,6 +49,7 @@ impl RunningTopology {
             tasks: HashMap::new(),
             abort_tx,
             watch: watch::channel(HashMap::new()),
+            enrichment_table_reloads: None,
         }
     }
 
@@ -76,6 +78,10 @@ impl RunningTopology {
     /// dropped then everything from this RunningTopology instance is fully
     /// dropped.
     pub fn stop(self) -> impl Future<Output = ()> {
+        if let Some(reloads) = &self.enrichment_table_reloads {
+            reloads.abort();
+        }
+
         // Create handy handles collections of all tasks for the subsequent
         // operations.
         let mut wait_handles = Vec::new();
@@ -473,6 +479,18 @@ impl RunningTopology {
         }
     }
 
+    /// Starts reloading the enrichment tables whenever their source changes.
+    pub(crate) fn spawn_enrichment_table_reloads(&mut self, tables: enrichment::TableRegistry) {
+        let interval = self
+            .config
+            .global
+            .enrichment_tables_reload_interval_secs
+            .unwrap_or(crate::enrichment_tables::DEFAULT_RELOAD_INTERVAL_SECS);
+        self.enrichment_table_reloads = Some(tokio::spawn(
+            crate::enrichment_tables::reload_changed(tables, Duration::from_secs(interval.max(1))),
+        ));
+    }
+
     /// Starts new and changed pieces of topology.
     pub(crate) fn spawn_diff(&mut self, diff: &ConfigDiff, mut new_pieces: Pieces) {
         // Sources
@@ -0,0 +1,311 @@
+use crate::{
+    conditions::{AnyCondition, Condition},
+    config::{DataType, GenerateConfig, TransformConfig, TransformContext, TransformDescription},
+    event::Event,
+    internal_events::SampleEventDiscarded,
+    transforms::{FunctionTransform, Transform},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AdaptiveSampleConfig {
+    /// The number of events to keep in each window, across all keys.
+    pub target_events: u64,
+    #[serde(default = "default_window_secs")]
+    pub window_secs: u64,
+    pub key_field: Option<String>,
+    pub exclude: Option<AnyCondition>,
+}
+
+const fn default_window_secs() -> u64 {
+    30
+}
+
+inventory::submit! {
+    TransformDescription::new::<AdaptiveSampleConfig>("adaptive_sample")
+}
+
+impl GenerateConfig for AdaptiveSampleConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            target_events: 1000,
+            window_secs: default_window_secs(),
+            key_field: None,
+            exclude: None::<AnyCondition>,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "adaptive_sample")]
+impl TransformConfig for AdaptiveSampleConfig {
+    async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
+        Ok(Transform::function(AdaptiveSample::new(
+            self.target_events,
+            Duration::from_secs(self.window_secs),
+            self.key_field.clone(),
+            self.exclude
+                .as_ref()
+                .map(|condition| condition.build(&context.enrichment_tables))
+                .transpose()?,
+        )))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "adaptive_sample"
+    }
+}
+
+#[derive(Clone)]
+pub struct AdaptiveSample {
+    target_events: u64,
+    window: Duration,
+    key_field: Option<String>,
+    exclude: Option<Box<dyn Condition>>,
+    window_started_at: Option<Instant>,
+    /// The rate each key is sampled at in the current window.
+    rates: HashMap<String, u64>,
+    /// The events seen for each key in the current window.
+    counts: HashMap<String, u64>,
+}
+
+impl AdaptiveSample {
+    pub fn new(
+        target_events: u64,
+        window: Duration,
+        key_field: Option<String>,
+        exclude: Option<Box<dyn Condition>>,
+    ) -> Self {
+        Self {
+            target_events,
+            window,
+            key_field,
+            exclude,
+            window_started_at: None,
+            rates: HashMap::new(),
+            counts: HashMap::new(),
+        }
+    }
+
+    /// Starts a new window if the current one has ended, sampling each key
+    /// according to how many events were seen for it in the ended window.
+    fn roll_window(&mut self, now: Instant) {
+        match self.window_started_at {
+            Some(started_at) if now.duration_since(started_at) < self.window => {}
+            Some(started_at) => {
+                let counts = std::mem::take(&mut self.counts);
+                // After a window without events, the counts are stale.
+                self.rates = if now.duration_since(started_at) < self.window * 2 {
+                    rates(self.target_events, counts)
+                } else {
+                    HashMap::new()
+                };
+                self.window_started_at = Some(now);
+            }
+            None => self.window_started_at = Some(now),
+        }
+    }
+
+    fn transform_at(&mut self, output: &mut Vec<Event>, mut event: Event, now: Instant) {
+        if let Some(condition) = self.exclude.as_ref() {
+            if condition.check(&event) {
+                output.push(event);
+                return;
+            }
+        }
+
+        self.roll_window(now);
+
+        let key = self
+            .key_field
+            .as_ref()
+            .and_then(|key_field| event.as_log().get(key_field))
+            .map(|value| value.to_string_lossy())
+            .unwrap_or_default();
+
+        let rate = self.rates.get(&key).copied().unwrap_or(1);
+        let count = self.counts.entry(key).or_default();
+        let keep = *count % rate == 0;
+        *count += 1;
+
+        if keep {
+            event.as_mut_log().insert("sample_rate", rate as i64);
+            output.push(event);
+        } else {
+            emit!(&SampleEventDiscarded);
+        }
+    }
+}
+
+impl FunctionTransform for AdaptiveSample {
+    fn transform(&mut self, output: &mut Vec<Event>, event: Event) {
+        self.transform_at(output, event, Instant::now())
+    }
+}
+
+/// Works out the rate to sample each key at so that, if the keys see as many
+/// events as they did in the last window, about `target_events` are kept.
+///
+/// The target is shared out between the keys starting with the least
+/// frequent ones, which are kept in full if their share allows. Whatever
+/// they leave of their share goes to the more frequent keys, which are
+/// sampled down to their share.
+fn rates(target_events: u64, counts: HashMap<String, u64>) -> HashMap<String, u64> {
+    let mut counts = counts.into_iter().collect::<Vec<_>>();
+    counts.sort_by_key(|(_, count)| *count);
+
+    let mut remaining = target_events;
+    let mut keys_left = counts.len() as u64;
+    counts
+        .into_iter()
+        .map(|(key, count)| {
+            let share = (remaining / keys_left).max(1);
+            let rate = if count <= share {
+                1
+            } else {
+                (count + share - 1) / share
+            };
+            remaining = remaining.saturating_sub(count / rate);
+            keys_left -= 1;
+            (key, rate)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        conditions::{ConditionConfig, VrlConfig},
+        event::{LogEvent, Value},
+    };
+    use shared::btreemap;
+
+    fn events(service: &str, count: usize) -> Vec<Event> {
+        vec![LogEvent::from(btreemap! { "service" => service }).into(); count]
+    }
+
+    fn sample(sampler: &mut AdaptiveSample, events: Vec<Event>, now: Instant) -> Vec<Event> {
+        let mut output = Vec::new();
+        for event in events {
+            sampler.transform_at(&mut output, event, now);
+        }
+        output
+    }
+
+    fn kept(sampled: &[Event], service: &str) -> usize {
+        sampled
+            .iter()
+            .filter(|event| event.as_log()["service"] == service.into())
+            .count()
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<AdaptiveSampleConfig>();
+    }
+
+    #[test]
+    fn samples_frequent_keys_towards_target() {
+        let mut sampler = AdaptiveSample::new(
+            110,
+            Duration::from_secs(10),
+            Some("service".to_string()),
+            None,
+        );
+        let now = Instant::now();
+        let traffic = || [events("noisy", 1000), events("quiet", 10)].concat();
+
+        // Without a previous window, everything is kept.
+        let sampled = sample(&mut sampler, traffic(), now);
+        assert_eq!(1010, sampled.len());
+
+        let sampled = sample(&mut sampler, traffic(), now + Duration::from_secs(10));
+        assert_eq!(100, kept(&sampled, "noisy"));
+        assert_eq!(10, kept(&sampled, "quiet"));
+        for event in sampled {
+            let expected = if event.as_log()["service"] == "noisy".into() {
+                10
+            } else {
+                1
+            };
+            assert_eq!(Value::from(expected), event.as_log()["sample_rate"]);
+        }
+    }
+
+    #[test]
+    fn forgets_rates_after_idle_windows() {
+        let mut sampler = AdaptiveSample::new(10, Duration::from_secs(10), None, None);
+        let now = Instant::now();
+
+        sample(&mut sampler, events("a", 100), now);
+        let sampled = sample(
+            &mut sampler,
+            events("a", 100),
+            now + Duration::from_secs(10),
+        );
+        assert_eq!(10, sampled.len());
+
+        let sampled = sample(
+            &mut sampler,
+            events("a", 100),
+            now + Duration::from_secs(30),
+        );
+        assert_eq!(100, sampled.len());
+    }
+
+    #[test]
+    fn excluded_events_are_not_sampled() {
+        let exclude = VrlConfig {
+            source: r#".service == "audit""#.to_string(),
+        }
+        .build(&Default::default())
+        .unwrap();
+        let mut sampler = AdaptiveSample::new(
+            1,
+            Duration::from_secs(10),
+            Some("service".to_string()),
+            Some(exclude),
+        );
+        let now = Instant::now();
+
+        sample(&mut sampler, events("audit", 100), now);
+        let sampled = sample(
+            &mut sampler,
+            events("audit", 100),
+            now + Duration::from_secs(10),
+        );
+        assert_eq!(100, sampled.len());
+        assert!(sampled
+            .iter()
+            .all(|event| !event.as_log().contains("sample_rate")));
+    }
+
+    #[test]
+    fn shares_target_between_keys() {
+        let counts = vec![("a", 1000), ("b", 300), ("c", 5)]
+            .into_iter()
+            .map(|(key, count)| (key.to_string(), count))
+            .collect();
+
+        let rates = rates(105, counts);
+        assert_eq!(Some(&1), rates.get("c"));
+        // c leaves 30 of its share of 35, so a and b get 50 each.
+        assert_eq!(Some(&6), rates.get("b"));
+        assert_eq!(Some(&20), rates.get("a"));
+    }
+}
@@ -4,14 +4,29 @@ use crate::{
         TransformDescription,
     },
     event::{Event, Value},
-    internal_events::DedupeEventDiscarded,
+    internal_events::{DedupeCacheLoadFailed, DedupeCacheSaveFailed, DedupeEventDiscarded},
     transforms::{TaskTransform, Transform},
 };
+use async_stream::stream;
 use bytes::Bytes;
+use chrono::{DateTime, Utc};
 use futures::{Stream, StreamExt};
 use lru::LruCache;
 use serde::{Deserialize, Serialize};
-use std::{future::ready, pin::Pin};
+use std::{
+    fs::{self, File},
+    future::ready,
+    io::{BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    pin::Pin,
+    time::{Duration, Instant},
+};
+
+/// The file the cache is saved to, in the transform's data directory.
+const CACHE_FILENAME: &str = "cache.json";
+
+/// How often the cache is saved, besides when the transform stops.
+const SAVE_INTERVAL: Duration = Duration::from_secs(60);
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
@@ -26,6 +41,18 @@ pub enum FieldMatchConfig {
 #[serde(deny_unknown_fields)]
 pub struct CacheConfig {
     pub num_events: usize,
+    /// How long events are remembered after they were first seen. Without it,
+    /// events are remembered until newer events evict them.
+    #[serde(default)]
+    pub ttl_secs: Option<u64>,
+    /// The approximate maximum memory used by the remembered events. Beyond
+    /// this, the least recently seen events are evicted.
+    #[serde(default)]
+    pub max_bytes: Option<usize>,
+    /// Whether to save the remembered events to the data directory, so they
+    /// are remembered when Vector restarts.
+    #[serde(default)]
+    pub persist: bool,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -35,10 +62,18 @@ pub struct DedupeConfig {
     pub fields: Option<FieldMatchConfig>,
     #[serde(default = "default_cache_config")]
     pub cache: CacheConfig,
+    /// The directory the cache is saved in, when `cache.persist` is enabled.
+    #[serde(default)]
+    pub data_dir: Option<PathBuf>,
 }
 
 const fn default_cache_config() -> CacheConfig {
-    CacheConfig { num_events: 5000 }
+    CacheConfig {
+        num_events: 5000,
+        ttl_secs: None,
+        max_bytes: None,
+        persist: false,
+    }
 }
 
 impl DedupeConfig {
@@ -60,7 +95,9 @@ impl DedupeConfig {
 
 pub struct Dedupe {
     fields: FieldMatchConfig,
-    cache: LruCache<CacheEntry, bool>,
+    cache: Cache,
+    /// The file the cache is saved to, if it is persisted.
+    cache_path: Option<PathBuf>,
 }
 
 inventory::submit! {
@@ -72,6 +109,7 @@ impl GenerateConfig for DedupeConfig {
         toml::Value::try_from(Self {
             fields: None,
             cache: default_cache_config(),
+            data_dir: None,
         })
         .unwrap()
     }
@@ -80,8 +118,21 @@ impl GenerateConfig for DedupeConfig {
 #[async_trait::async_trait]
 #[typetag::serde(name = "dedupe")]
 impl TransformConfig for DedupeConfig {
-    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
-        Ok(Transform::task(Dedupe::new(self.clone())))
+    async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
+        let mut dedupe = Dedupe::new(self.clone());
+
+        if self.cache.persist {
+            // Transforms built outside of a topology, such as for unit tests,
+            // have nowhere to keep their cache.
+            if let Some(key) = &context.key {
+                let data_dir = context
+                    .globals
+                    .resolve_and_make_data_subdir(self.data_dir.as_ref(), key)?;
+                dedupe.persist_to(data_dir.join(CACHE_FILENAME));
+            }
+        }
+
+        Ok(Transform::task(dedupe))
     }
 
     fn input_type(&self) -> DataType {
@@ -122,12 +173,172 @@ type TypeId = u8;
 /// iterating over the fields of the incoming Events, we know that the
 /// CacheEntries for 2 equivalent events will always contain the fields in the
 /// same order.
-#[derive(PartialEq, Eq, Hash)]
+#[derive(PartialEq, Eq, Hash, Deserialize, Serialize)]
 enum CacheEntry {
     Match(Vec<Option<(TypeId, Bytes)>>),
     Ignore(Vec<(String, TypeId, Bytes)>),
 }
 
+impl CacheEntry {
+    /// The approximate memory used to remember the entry.
+    fn size(&self) -> usize {
+        let fields = match self {
+            CacheEntry::Match(fields) => fields
+                .iter()
+                .map(|field| {
+                    std::mem::size_of::<Option<(TypeId, Bytes)>>()
+                        + field.as_ref().map_or(0, |(_, bytes)| bytes.len())
+                })
+                .sum::<usize>(),
+            CacheEntry::Ignore(fields) => fields
+                .iter()
+                .map(|(name, _, bytes)| {
+                    std::mem::size_of::<(String, TypeId, Bytes)>() + name.len() + bytes.len()
+                })
+                .sum(),
+        };
+        std::mem::size_of::<(CacheEntry, Seen)>() + fields
+    }
+}
+
+/// When an entry was first seen, and the memory used to remember it.
+#[derive(Clone, Copy)]
+struct Seen {
+    at: Instant,
+    size: usize,
+}
+
+/// The events seen recently, bounded by number, age and memory.
+struct Cache {
+    entries: LruCache<CacheEntry, Seen>,
+    ttl: Option<Duration>,
+    max_bytes: Option<usize>,
+    bytes: usize,
+}
+
+impl Cache {
+    fn new(config: &CacheConfig) -> Self {
+        Self {
+            entries: LruCache::new(config.num_events),
+            ttl: config.ttl_secs.map(Duration::from_secs),
+            max_bytes: config.max_bytes,
+            bytes: 0,
+        }
+    }
+
+    fn is_expired(&self, seen_at: Instant, now: Instant) -> bool {
+        self.ttl
+            .map_or(false, |ttl| now.saturating_duration_since(seen_at) >= ttl)
+    }
+
+    /// Remembers the entry, returning whether it was already remembered.
+    fn check_and_insert(&mut self, entry: CacheEntry, now: Instant) -> bool {
+        self.remove_expired(now);
+
+        let seen_at = self.entries.get(&entry).map(|seen| seen.at);
+        match seen_at {
+            Some(seen_at) if !self.is_expired(seen_at, now) => true,
+            _ => {
+                self.insert(entry, now);
+                false
+            }
+        }
+    }
+
+    fn insert(&mut self, entry: CacheEntry, seen_at: Instant) {
+        let size = entry.size();
+        if self.entries.len() == self.entries.cap() && !self.entries.contains(&entry) {
+            self.remove_lru();
+        }
+        if let Some(replaced) = self.entries.put(entry, Seen { at: seen_at, size }) {
+            self.bytes -= replaced.size;
+        }
+        self.bytes += size;
+
+        if let Some(max_bytes) = self.max_bytes {
+            // The entry just inserted is kept even if it alone is too large.
+            while self.bytes > max_bytes && self.entries.len() > 1 {
+                self.remove_lru();
+            }
+        }
+    }
+
+    fn remove_lru(&mut self) {
+        if let Some((_, seen)) = self.entries.pop_lru() {
+            self.bytes -= seen.size;
+        }
+    }
+
+    /// Removes the expired entries that are least recently seen. Expired
+    /// entries seen more recently are removed when they come up.
+    fn remove_expired(&mut self, now: Instant) {
+        while let Some((_, seen)) = self.entries.peek_lru() {
+            if !self.is_expired(seen.at, now) {
+                break;
+            }
+            self.remove_lru();
+        }
+    }
+
+    fn save(&self, path: &Path, now: Instant) -> crate::Result<()> {
+        let snapshot = Snapshot {
+            written_at: Utc::now(),
+            // Most recently seen first.
+            entries: self
+                .entries
+                .iter()
+                .map(|(entry, seen)| SnapshotEntry {
+                    age_ms: now.saturating_duration_since(seen.at).as_millis() as u64,
+                    entry,
+                })
+                .collect(),
+        };
+
+        let temp_path = path.with_extension("json.tmp");
+        let mut writer = BufWriter::new(File::create(&temp_path)?);
+        serde_json::to_writer(&mut writer, &snapshot)?;
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+        fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+
+    fn load(&mut self, path: &Path, now: Instant) -> crate::Result<()> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(error) => return Err(error.into()),
+        };
+        let snapshot: Snapshot<CacheEntry> = serde_json::from_reader(BufReader::new(file))?;
+
+        // The entries kept aging while Vector was stopped.
+        let downtime = (Utc::now() - snapshot.written_at)
+            .to_std()
+            .unwrap_or_default();
+        for SnapshotEntry { entry, age_ms } in snapshot.entries.into_iter().rev() {
+            let age = Duration::from_millis(age_ms) + downtime;
+            let seen_at = now.checked_sub(age).unwrap_or(now);
+            if self.ttl.map_or(true, |ttl| age < ttl) {
+                self.insert(entry, seen_at);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The cache as saved to the data directory.
+#[derive(Deserialize, Serialize)]
+struct Snapshot<E> {
+    written_at: DateTime<Utc>,
+    entries: Vec<SnapshotEntry<E>>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct SnapshotEntry<E> {
+    entry: E,
+    age_ms: u64,
+}
+
 /// Assigns a unique number to each of the types supported by Event::Value.
 const fn type_id_for_value(val: &Value) -> TypeId {
     match val {
@@ -144,17 +355,34 @@ const fn type_id_for_value(val: &Value) -> TypeId {
 
 impl Dedupe {
     pub fn new(config: DedupeConfig) -> Self {
-        let num_entries = config.cache.num_events;
         let fields = config.fill_default_fields_match();
         Self {
             fields,
-            cache: LruCache::new(num_entries),
+            cache: Cache::new(&config.cache),
+            cache_path: None,
+        }
+    }
+
+    /// Restores the cache from the file, and saves it there periodically and
+    /// when the transform stops.
+    pub fn persist_to(&mut self, path: PathBuf) {
+        if let Err(error) = self.cache.load(&path, Instant::now()) {
+            emit!(&DedupeCacheLoadFailed { path: &path, error });
+        }
+        self.cache_path = Some(path);
+    }
+
+    fn save_cache(&self) {
+        if let Some(path) = &self.cache_path {
+            if let Err(error) = self.cache.save(path, Instant::now()) {
+                emit!(&DedupeCacheSaveFailed { path, error });
+            }
         }
     }
 
     fn transform_one(&mut self, event: Event) -> Option<Event> {
         let cache_entry = build_cache_entry(&event, &self.fields);
-        if self.cache.put(cache_entry, true).is_some() {
+        if self.cache.check_and_insert(cache_entry, Instant::now()) {
             emit!(&DedupeEventDiscarded { event });
             None
         } else {
@@ -202,7 +430,32 @@ impl TaskTransform for Dedupe {
         Self: 'static,
     {
         let mut inner = self;
-        Box::pin(task.filter_map(move |v| ready(inner.transform_one(v))))
+        if inner.cache_path.is_none() {
+            return Box::pin(task.filter_map(move |v| ready(inner.transform_one(v))));
+        }
+
+        let mut input_rx = task;
+        let mut save_interval = tokio::time::interval(SAVE_INTERVAL);
+        Box::pin(stream! {
+            loop {
+                let output = tokio::select! {
+                    _ = save_interval.tick() => {
+                        inner.save_cache();
+                        None
+                    }
+                    maybe_event = input_rx.next() => match maybe_event {
+                        None => {
+                            inner.save_cache();
+                            break;
+                        }
+                        Some(event) => inner.transform_one(event),
+                    }
+                };
+                if let Some(event) = output {
+                    yield event;
+                }
+            }
+        })
     }
 }
 
@@ -220,8 +473,12 @@ mod tests {
 
     fn make_match_transform(num_events: usize, fields: Vec<String>) -> Dedupe {
         Dedupe::new(DedupeConfig {
-            cache: CacheConfig { num_events },
+            cache: CacheConfig {
+                num_events,
+                ..default_cache_config()
+            },
             fields: Some(FieldMatchConfig::MatchFields(fields)),
+            data_dir: None,
         })
     }
 
@@ -231,8 +488,12 @@ mod tests {
         fields.extend(given_fields);
 
         Dedupe::new(DedupeConfig {
-            cache: CacheConfig { num_events },
+            cache: CacheConfig {
+                num_events,
+                ..default_cache_config()
+            },
             fields: Some(FieldMatchConfig::IgnoreFields(fields)),
+            data_dir: None,
         })
     }
 
@@ -472,4 +733,67 @@ mod tests {
         let new_event = transform.transform_one(event2.clone()).unwrap();
         assert_eq!(new_event, event2);
     }
+
+    fn entry(value: &'static str) -> CacheEntry {
+        CacheEntry::Match(vec![Some((0, Bytes::from_static(value.as_bytes())))])
+    }
+
+    fn make_cache(ttl_secs: Option<u64>, max_bytes: Option<usize>) -> Cache {
+        Cache::new(&CacheConfig {
+            num_events: 10,
+            ttl_secs,
+            max_bytes,
+            persist: false,
+        })
+    }
+
+    #[test]
+    fn dedupe_cache_expires_entries() {
+        let mut cache = make_cache(Some(600), None);
+        let now = Instant::now();
+
+        assert!(!cache.check_and_insert(entry("a"), now));
+        // Seeing a duplicate doesn't extend the window.
+        let later = now + Duration::from_secs(300);
+        assert!(cache.check_and_insert(entry("a"), later));
+        let window_end = now + Duration::from_secs(600);
+        assert!(!cache.check_and_insert(entry("a"), window_end));
+        assert!(cache.check_and_insert(entry("a"), window_end));
+    }
+
+    #[test]
+    fn dedupe_cache_bounds_memory() {
+        let size = entry("a").size();
+        let mut cache = make_cache(None, Some(size * 2));
+        let now = Instant::now();
+
+        assert!(!cache.check_and_insert(entry("a"), now));
+        assert!(!cache.check_and_insert(entry("b"), now));
+        assert!(!cache.check_and_insert(entry("c"), now));
+        assert_eq!(2, cache.entries.len());
+        assert_eq!(size * 2, cache.bytes);
+        assert!(!cache.check_and_insert(entry("a"), now));
+        assert!(cache.check_and_insert(entry("c"), now));
+    }
+
+    #[test]
+    fn dedupe_cache_persists() {
+        let path = crate::test_util::temp_file();
+        let now = Instant::now();
+
+        let mut cache = make_cache(Some(600), None);
+        cache.check_and_insert(entry("old"), now);
+        cache.check_and_insert(entry("new"), now + Duration::from_secs(599));
+        cache.save(&path, now + Duration::from_secs(601)).unwrap();
+
+        let mut restored = make_cache(Some(600), None);
+        restored.load(&path, now).unwrap();
+        assert_eq!(1, restored.entries.len());
+        assert!(restored.check_and_insert(entry("new"), now));
+
+        // Nothing to restore the first time the cache is persisted.
+        let mut empty = make_cache(Some(600), None);
+        empty.load(&crate::test_util::temp_file(), now).unwrap();
+        assert_eq!(0, empty.entries.len());
+    }
 }
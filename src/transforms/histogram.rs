@@ -0,0 +1,472 @@
+use crate::{
+    config::{DataType, TransformConfig, TransformContext, TransformDescription},
+    event::{
+        metric::{Bucket, MetricValue, Quantile, Sample},
+        Event,
+    },
+    sinks::util::statistic::{validate_quantiles, DistributionStatistic},
+    transforms::{FunctionTransform, Transform},
+};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("At least one bucket must be configured"))]
+    NoBuckets,
+    #[snafu(display("Buckets must be finite and in strictly ascending order"))]
+    UnorderedBuckets,
+    #[snafu(display("Quantiles must be in range [0.0,1.0]"))]
+    QuantileOutOfRange,
+}
+
+/// The representation histogram-like metrics are converted into.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum HistogramTarget {
+    /// Emit an `AggregatedHistogram` with the configured `buckets`.
+    Histogram,
+    /// Emit an `AggregatedSummary` with the configured `quantiles`.
+    Summary,
+}
+
+impl Default for HistogramTarget {
+    fn default() -> Self {
+        Self::Histogram
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct HistogramConfig {
+    #[serde(default)]
+    pub target: HistogramTarget,
+    /// The upper limits of the buckets to emit when `target` is `histogram`.
+    #[serde(default = "default_buckets")]
+    pub buckets: Vec<f64>,
+    /// The quantiles to emit when `target` is `summary`.
+    #[serde(default = "default_quantiles")]
+    pub quantiles: Vec<f64>,
+}
+
+fn default_buckets() -> Vec<f64> {
+    vec![
+        0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+    ]
+}
+
+fn default_quantiles() -> Vec<f64> {
+    vec![0.5, 0.75, 0.9, 0.95, 0.99]
+}
+
+impl Default for HistogramConfig {
+    fn default() -> Self {
+        Self {
+            target: HistogramTarget::default(),
+            buckets: default_buckets(),
+            quantiles: default_quantiles(),
+        }
+    }
+}
+
+inventory::submit! {
+    TransformDescription::new::<HistogramConfig>("histogram")
+}
+
+impl_generate_config_from_default!(HistogramConfig);
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "histogram")]
+impl TransformConfig for HistogramConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        Histogram::new(self).map(Transform::function)
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Metric
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Metric
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "histogram"
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Histogram {
+    target: HistogramTarget,
+    buckets: Vec<f64>,
+    quantiles: Vec<f64>,
+}
+
+impl Histogram {
+    pub fn new(config: &HistogramConfig) -> crate::Result<Self> {
+        match config.target {
+            HistogramTarget::Histogram => {
+                if config.buckets.is_empty() {
+                    return Err(Box::new(BuildError::NoBuckets));
+                }
+                if config.buckets.iter().any(|limit| !limit.is_finite())
+                    || config.buckets.windows(2).any(|pair| pair[0] >= pair[1])
+                {
+                    return Err(Box::new(BuildError::UnorderedBuckets));
+                }
+            }
+            HistogramTarget::Summary => {
+                if validate_quantiles(&config.quantiles).is_err() {
+                    return Err(Box::new(BuildError::QuantileOutOfRange));
+                }
+            }
+        }
+
+        Ok(Self {
+            target: config.target,
+            buckets: config.buckets.clone(),
+            quantiles: config.quantiles.clone(),
+        })
+    }
+
+    fn convert(&self, value: &MetricValue) -> Option<MetricValue> {
+        match (value, self.target) {
+            (MetricValue::Distribution { samples, .. }, HistogramTarget::Histogram) => {
+                Some(samples_to_histogram(samples, &self.buckets))
+            }
+            (MetricValue::Distribution { samples, .. }, HistogramTarget::Summary) => {
+                Some(samples_to_summary(samples, &self.quantiles))
+            }
+            (
+                MetricValue::AggregatedHistogram {
+                    buckets,
+                    count,
+                    sum,
+                },
+                HistogramTarget::Histogram,
+            ) => Some(MetricValue::AggregatedHistogram {
+                buckets: rebucket(buckets, *count, &self.buckets),
+                count: *count,
+                sum: *sum,
+            }),
+            (
+                MetricValue::AggregatedHistogram {
+                    buckets,
+                    count,
+                    sum,
+                },
+                HistogramTarget::Summary,
+            ) => Some(MetricValue::AggregatedSummary {
+                quantiles: self
+                    .quantiles
+                    .iter()
+                    .map(|&q| Quantile {
+                        upper_limit: q,
+                        value: histogram_quantile(buckets, *count, q),
+                    })
+                    .collect(),
+                count: *count,
+                sum: *sum,
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl FunctionTransform for Histogram {
+    fn transform(&mut self, output: &mut Vec<Event>, event: Event) {
+        let metric = event.into_metric();
+        let event = match self.convert(metric.value()) {
+            Some(value) => Event::Metric(metric.with_value(value)),
+            None => Event::Metric(metric),
+        };
+        output.push(event);
+    }
+}
+
+fn samples_to_histogram(samples: &[Sample], limits: &[f64]) -> MetricValue {
+    let mut counts = vec![0u32; limits.len()];
+    let mut count = 0u32;
+    let mut sum = 0.0;
+    for sample in samples {
+        // Samples above the last limit are only accounted for in `count`,
+        // the same as the implicit `+Inf` bucket.
+        if let Some(index) = limits.iter().position(|&limit| sample.value <= limit) {
+            counts[index] += sample.rate;
+        }
+        count += sample.rate;
+        sum += sample.value * sample.rate as f64;
+    }
+
+    MetricValue::AggregatedHistogram {
+        buckets: limits
+            .iter()
+            .zip(counts)
+            .map(|(&upper_limit, count)| Bucket { upper_limit, count })
+            .collect(),
+        count,
+        sum,
+    }
+}
+
+fn samples_to_summary(samples: &[Sample], quantiles: &[f64]) -> MetricValue {
+    match DistributionStatistic::from_samples(samples, quantiles) {
+        Some(statistic) => MetricValue::AggregatedSummary {
+            quantiles: statistic
+                .quantiles
+                .into_iter()
+                .map(|(upper_limit, value)| Quantile { upper_limit, value })
+                .collect(),
+            count: statistic.count as u32,
+            sum: statistic.sum,
+        },
+        None => MetricValue::AggregatedSummary {
+            quantiles: Vec::new(),
+            count: 0,
+            sum: 0.0,
+        },
+    }
+}
+
+/// Returns the lower bound of the bucket at `index`. The first bucket
+/// starts at zero, unless its upper limit is not positive, in which case
+/// it is treated as having no width.
+fn lower_limit(buckets: &[Bucket], index: usize) -> f64 {
+    match index {
+        0 => buckets[0].upper_limit.min(0.0),
+        _ => buckets[index - 1].upper_limit,
+    }
+}
+
+/// Estimates the cumulative count of observations less than or equal to
+/// `limit`, assuming observations are evenly spread within each bucket.
+/// Observations above the last bucket can't be located and are never
+/// included.
+fn cumulative_count(buckets: &[Bucket], limit: f64) -> f64 {
+    let mut cumulative = 0.0;
+    for (index, bucket) in buckets.iter().enumerate() {
+        if limit >= bucket.upper_limit {
+            cumulative += bucket.count as f64;
+            continue;
+        }
+        let lower = lower_limit(buckets, index);
+        if limit > lower {
+            let fraction = (limit - lower) / (bucket.upper_limit - lower);
+            cumulative += bucket.count as f64 * fraction;
+        }
+        break;
+    }
+    cumulative
+}
+
+/// Redistributes the observations in `buckets` over a new set of bucket
+/// limits. `count` and `sum` of the histogram are left untouched, so any
+/// observations which fall above the last new limit end up in the
+/// implicit `+Inf` bucket.
+fn rebucket(buckets: &[Bucket], count: u32, limits: &[f64]) -> Vec<Bucket> {
+    let mut previous = 0u32;
+    limits
+        .iter()
+        .map(|&upper_limit| {
+            // Round the cumulative counts rather than the individual buckets,
+            // so rounding errors can't accumulate across buckets.
+            let cumulative = (cumulative_count(buckets, upper_limit).round() as u32).min(count);
+            let bucket = Bucket {
+                upper_limit,
+                count: cumulative.saturating_sub(previous),
+            };
+            previous = previous.max(cumulative);
+            bucket
+        })
+        .collect()
+}
+
+/// Estimates the value of quantile `q` from the buckets of a histogram,
+/// following the same linear interpolation as Prometheus'
+/// `histogram_quantile` function.
+fn histogram_quantile(buckets: &[Bucket], count: u32, q: f64) -> f64 {
+    if buckets.is_empty() || count == 0 {
+        return 0.0;
+    }
+
+    let rank = q * count as f64;
+    let mut cumulative = 0.0;
+    for (index, bucket) in buckets.iter().enumerate() {
+        let previous = cumulative;
+        cumulative += bucket.count as f64;
+        if cumulative >= rank && bucket.count > 0 {
+            let lower = lower_limit(buckets, index);
+            let fraction = (rank - previous) / bucket.count as f64;
+            return lower + (bucket.upper_limit - lower) * fraction;
+        }
+    }
+
+    // The quantile falls into the implicit `+Inf` bucket, so the best
+    // estimate available is the highest finite limit.
+    buckets[buckets.len() - 1].upper_limit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        event::metric::{Metric, MetricKind, StatisticKind},
+        transforms::test::transform_one,
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<HistogramConfig>();
+    }
+
+    fn transform(config: HistogramConfig, value: MetricValue) -> MetricValue {
+        let mut histogram = Histogram::new(&config).unwrap();
+        let event = Event::Metric(Metric::new("latency", MetricKind::Absolute, value));
+        transform_one(&mut histogram, event)
+            .unwrap()
+            .into_metric()
+            .value()
+            .clone()
+    }
+
+    fn histogram_config(buckets: Vec<f64>) -> HistogramConfig {
+        HistogramConfig {
+            target: HistogramTarget::Histogram,
+            buckets,
+            ..Default::default()
+        }
+    }
+
+    fn summary_config(quantiles: Vec<f64>) -> HistogramConfig {
+        HistogramConfig {
+            target: HistogramTarget::Summary,
+            quantiles,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_config() {
+        assert!(Histogram::new(&histogram_config(vec![])).is_err());
+        assert!(Histogram::new(&histogram_config(vec![1.0, 1.0])).is_err());
+        assert!(Histogram::new(&histogram_config(vec![2.0, 1.0])).is_err());
+        assert!(Histogram::new(&summary_config(vec![0.5, 1.5])).is_err());
+    }
+
+    #[test]
+    fn distribution_to_histogram() {
+        let value = transform(
+            histogram_config(vec![1.0, 2.0, 4.0]),
+            MetricValue::Distribution {
+                samples: vector_core::samples![0.5 => 2, 1.0 => 1, 3.0 => 4, 10.0 => 1],
+                statistic: StatisticKind::Histogram,
+            },
+        );
+        assert_eq!(
+            value,
+            MetricValue::AggregatedHistogram {
+                buckets: vector_core::buckets![1.0 => 3, 2.0 => 0, 4.0 => 4],
+                count: 8,
+                sum: 24.0,
+            }
+        );
+    }
+
+    #[test]
+    fn distribution_to_summary() {
+        let value = transform(
+            summary_config(vec![0.5, 1.0]),
+            MetricValue::Distribution {
+                samples: vector_core::samples![1.0 => 1, 2.0 => 2, 3.0 => 1],
+                statistic: StatisticKind::Summary,
+            },
+        );
+        assert_eq!(
+            value,
+            MetricValue::AggregatedSummary {
+                quantiles: vector_core::quantiles![0.5 => 2.0, 1.0 => 3.0],
+                count: 4,
+                sum: 8.0,
+            }
+        );
+    }
+
+    #[test]
+    fn histogram_rebucketed() {
+        let value = transform(
+            histogram_config(vec![1.0, 2.0, 3.0, 4.0, 8.0]),
+            MetricValue::AggregatedHistogram {
+                buckets: vector_core::buckets![2.0 => 4, 4.0 => 10],
+                count: 16,
+                sum: 40.0,
+            },
+        );
+        assert_eq!(
+            value,
+            MetricValue::AggregatedHistogram {
+                buckets: vector_core::buckets![1.0 => 2, 2.0 => 2, 3.0 => 5, 4.0 => 5, 8.0 => 0],
+                count: 16,
+                sum: 40.0,
+            }
+        );
+    }
+
+    #[test]
+    fn histogram_merged_buckets() {
+        let value = transform(
+            histogram_config(vec![2.0]),
+            MetricValue::AggregatedHistogram {
+                buckets: vector_core::buckets![1.0 => 3, 2.0 => 4, 4.0 => 5],
+                count: 12,
+                sum: 20.0,
+            },
+        );
+        assert_eq!(
+            value,
+            MetricValue::AggregatedHistogram {
+                buckets: vector_core::buckets![2.0 => 7],
+                count: 12,
+                sum: 20.0,
+            }
+        );
+    }
+
+    #[test]
+    fn histogram_to_summary() {
+        let value = transform(
+            summary_config(vec![0.25, 0.5, 1.0]),
+            MetricValue::AggregatedHistogram {
+                buckets: vector_core::buckets![1.0 => 2, 2.0 => 2, 4.0 => 0],
+                count: 5,
+                sum: 8.0,
+            },
+        );
+        assert_eq!(
+            value,
+            MetricValue::AggregatedSummary {
+                quantiles: vector_core::quantiles![0.25 => 0.625, 0.5 => 1.25, 1.0 => 4.0],
+                count: 5,
+                sum: 8.0,
+            }
+        );
+    }
+
+    #[test]
+    fn passes_through_other_metrics() {
+        let counter = MetricValue::Counter { value: 1.0 };
+        assert_eq!(
+            transform(HistogramConfig::default(), counter.clone()),
+            counter
+        );
+
+        let summary = MetricValue::AggregatedSummary {
+            quantiles: vector_core::quantiles![0.5 => 1.0],
+            count: 1,
+            sum: 1.0,
+        };
+        assert_eq!(
+            transform(summary_config(vec![0.5]), summary.clone()),
+            summary
+        );
+    }
+}
@@ -6,8 +6,8 @@ use crate::{
     event::metric::{Metric, MetricKind, MetricValue, StatisticKind},
     event::{Event, Value},
     internal_events::{
-        LogToMetricFieldNotFound, LogToMetricFieldNull, LogToMetricParseFloatError,
-        LogToMetricTemplateParseError, TemplateRenderingFailed,
+        LogToMetricFieldNotFound, LogToMetricFieldNull, LogToMetricFieldValueInvalid,
+        LogToMetricParseFloatError, LogToMetricTemplateParseError, TemplateRenderingFailed,
     },
     template::{Template, TemplateParseError, TemplateRenderingError},
     transforms::{FunctionTransform, Transform},
@@ -66,6 +66,27 @@ pub struct SummaryConfig {
     tags: Option<IndexMap<String, String>>,
 }
 
+/// A metric whose type, kind and tags are read from the event itself,
+/// for logs which already describe a metric.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct DynamicConfig {
+    field: String,
+    name: Option<String>,
+    namespace: Option<String>,
+    /// The field holding the metric type, one of `counter`, `gauge`,
+    /// `set`, `histogram` or `summary`.
+    type_field: String,
+    /// The field holding the metric kind, either `incremental` or
+    /// `absolute`. Falls back to `kind`, then the default kind of the
+    /// metric type, if the field is missing.
+    kind_field: Option<String>,
+    kind: Option<MetricKind>,
+    /// A field whose nested fields are all added as tags, using their
+    /// path below this field as the tag name.
+    tags_field: Option<String>,
+    tags: Option<IndexMap<String, String>>,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum MetricConfig {
@@ -74,6 +95,7 @@ pub enum MetricConfig {
     Gauge(GaugeConfig),
     Set(SetConfig),
     Summary(SummaryConfig),
+    Dynamic(DynamicConfig),
 }
 
 impl MetricConfig {
@@ -84,6 +106,7 @@ impl MetricConfig {
             MetricConfig::Gauge(GaugeConfig { field, .. }) => field,
             MetricConfig::Set(SetConfig { field, .. }) => field,
             MetricConfig::Summary(SummaryConfig { field, .. }) => field,
+            MetricConfig::Dynamic(DynamicConfig { field, .. }) => field,
         }
     }
 }
@@ -155,6 +178,10 @@ enum TransformError {
         field: String,
         error: ParseFloatError,
     },
+    InvalidFieldValue {
+        field: String,
+        value: String,
+    },
 }
 
 fn render_template(s: &str, event: &Event) -> Result<String, TransformError> {
@@ -196,6 +223,131 @@ fn render_tags(
     })
 }
 
+/// Collects all fields nested below `value` as tags, naming each tag by
+/// its path relative to `value`. Null fields are skipped.
+fn collect_tags(prefix: Option<String>, value: &Value, tags: &mut BTreeMap<String, String>) {
+    match value {
+        Value::Map(map) => {
+            for (key, value) in map {
+                let path = match &prefix {
+                    Some(prefix) => format!("{}.{}", prefix, key),
+                    None => key.clone(),
+                };
+                collect_tags(Some(path), value, tags);
+            }
+        }
+        Value::Null => {}
+        value => {
+            if let Some(prefix) = prefix {
+                tags.insert(prefix, value.to_string_lossy());
+            }
+        }
+    }
+}
+
+fn parse_field_value<T>(
+    field: &str,
+    value: &Value,
+    parse: impl Fn(&str) -> Option<T>,
+) -> Result<T, TransformError> {
+    let value = value.to_string_lossy();
+    parse(&value).ok_or_else(|| TransformError::InvalidFieldValue {
+        field: field.to_owned(),
+        value,
+    })
+}
+
+#[derive(Clone, Copy)]
+enum DynamicType {
+    Counter,
+    Gauge,
+    Set,
+    Histogram,
+    Summary,
+}
+
+fn to_dynamic_metric(
+    config: &DynamicConfig,
+    value: &Value,
+    event: &Event,
+) -> Result<(MetricKind, MetricValue), TransformError> {
+    let log = event.as_log();
+
+    let metric_type = log
+        .get(&config.type_field)
+        .ok_or_else(|| TransformError::FieldNotFound {
+            field: config.type_field.clone(),
+        })?;
+    let metric_type = parse_field_value(&config.type_field, metric_type, |metric_type| {
+        Some(match metric_type {
+            "counter" => DynamicType::Counter,
+            "gauge" => DynamicType::Gauge,
+            "set" => DynamicType::Set,
+            "histogram" => DynamicType::Histogram,
+            "summary" => DynamicType::Summary,
+            _ => return None,
+        })
+    })?;
+
+    let kind = match config.kind_field.as_ref().and_then(|field| {
+        log.get(field)
+            .filter(|kind| !matches!(kind, Value::Null))
+            .map(|kind| (field, kind))
+    }) {
+        Some((field, kind)) => Some(parse_field_value(field, kind, |kind| match kind {
+            "incremental" => Some(MetricKind::Incremental),
+            "absolute" => Some(MetricKind::Absolute),
+            _ => None,
+        })?),
+        None => config.kind,
+    };
+
+    let parse_float = || {
+        value
+            .to_string_lossy()
+            .parse()
+            .map_err(|error| TransformError::ParseFloatError {
+                field: config.field.clone(),
+                error,
+            })
+    };
+
+    Ok(match metric_type {
+        DynamicType::Counter => (
+            kind.unwrap_or(MetricKind::Incremental),
+            MetricValue::Counter {
+                value: parse_float()?,
+            },
+        ),
+        DynamicType::Gauge => (
+            kind.unwrap_or(MetricKind::Absolute),
+            MetricValue::Gauge {
+                value: parse_float()?,
+            },
+        ),
+        DynamicType::Set => (
+            kind.unwrap_or(MetricKind::Incremental),
+            MetricValue::Set {
+                values: std::iter::once(value.to_string_lossy()).collect(),
+            },
+        ),
+        DynamicType::Histogram => (
+            kind.unwrap_or(MetricKind::Incremental),
+            MetricValue::Distribution {
+                samples: vector_core::samples![parse_float()? => 1],
+                statistic: StatisticKind::Histogram,
+            },
+        ),
+        DynamicType::Summary => (
+            kind.unwrap_or(MetricKind::Incremental),
+            MetricValue::Distribution {
+                samples: vector_core::samples![parse_float()? => 1],
+                statistic: StatisticKind::Summary,
+            },
+        ),
+    })
+}
+
 fn to_metric(config: &MetricConfig, event: &Event) -> Result<Metric, TransformError> {
     let log = event.as_log();
 
@@ -365,6 +517,30 @@ fn to_metric(config: &MetricConfig, event: &Event) -> Result<Metric, TransformEr
             .with_tags(tags)
             .with_timestamp(timestamp))
         }
+        MetricConfig::Dynamic(dynamic) => {
+            let (kind, value) = to_dynamic_metric(dynamic, value, event)?;
+
+            let name = dynamic.name.as_ref().unwrap_or(&dynamic.field);
+            let name = render_template(name, event)?;
+
+            let namespace = dynamic.namespace.as_ref();
+            let namespace = namespace
+                .map(|namespace| render_template(namespace, event))
+                .transpose()?;
+
+            let mut tags = BTreeMap::new();
+            if let Some(field_tags) = dynamic.tags_field.as_ref().and_then(|field| log.get(field)) {
+                collect_tags(None, field_tags, &mut tags);
+            }
+            if let Some(rendered) = render_tags(&dynamic.tags, event)? {
+                tags.extend(rendered);
+            }
+
+            Ok(Metric::new_with_metadata(name, kind, value, metadata)
+                .with_namespace(namespace)
+                .with_tags(if tags.is_empty() { None } else { Some(tags) })
+                .with_timestamp(timestamp))
+        }
     }
 }
 
@@ -397,6 +573,12 @@ impl FunctionTransform for LogToMetric {
                 Err(TransformError::TemplateParseError(error)) => {
                     emit!(&LogToMetricTemplateParseError { error })
                 }
+                Err(TransformError::InvalidFieldValue { field, value }) => {
+                    emit!(&LogToMetricFieldValueInvalid {
+                        field: field.as_ref(),
+                        value: value.as_ref(),
+                    })
+                }
             }
         }
     }
@@ -854,4 +1036,102 @@ mod tests {
             .with_timestamp(Some(ts()))
         );
     }
+
+    #[test]
+    fn dynamic_metric_from_fields() {
+        let config = parse_config(
+            r#"
+            [[metrics]]
+            type = "dynamic"
+            field = "metric.value"
+            name = "{{metric.name}}"
+            type_field = "metric.type"
+            kind_field = "metric.kind"
+            tags_field = "metric.labels"
+            tags = {host = "{{host}}"}
+            "#,
+        );
+
+        let mut event = create_event("metric.value", 12.5);
+        event.as_mut_log().insert("metric.name", "queue_depth");
+        event.as_mut_log().insert("metric.type", "gauge");
+        event.as_mut_log().insert("metric.kind", "incremental");
+        event.as_mut_log().insert("metric.labels.queue", "jobs");
+        event.as_mut_log().insert("metric.labels.shard.id", 3);
+        event.as_mut_log().insert("host", "localhost");
+        let metadata = event.metadata().clone();
+
+        let mut transform = LogToMetric::new(config);
+        let metric = transform_one(&mut transform, event).unwrap();
+
+        assert_eq!(
+            metric.into_metric(),
+            Metric::new_with_metadata(
+                "queue_depth",
+                MetricKind::Incremental,
+                MetricValue::Gauge { value: 12.5 },
+                metadata,
+            )
+            .with_tags(Some(
+                vec![
+                    ("host".to_owned(), "localhost".to_owned()),
+                    ("queue".to_owned(), "jobs".to_owned()),
+                    ("shard.id".to_owned(), "3".to_owned()),
+                ]
+                .into_iter()
+                .collect(),
+            ))
+            .with_timestamp(Some(ts()))
+        );
+    }
+
+    #[test]
+    fn dynamic_metric_default_kind() {
+        let config = parse_config(
+            r#"
+            [[metrics]]
+            type = "dynamic"
+            field = "value"
+            name = "requests_total"
+            type_field = "type"
+            kind_field = "kind"
+            "#,
+        );
+
+        let mut event = create_event("value", 3);
+        event.as_mut_log().insert("type", "counter");
+        let metadata = event.metadata().clone();
+
+        let mut transform = LogToMetric::new(config);
+        let metric = transform_one(&mut transform, event).unwrap();
+
+        assert_eq!(
+            metric.into_metric(),
+            Metric::new_with_metadata(
+                "requests_total",
+                MetricKind::Incremental,
+                MetricValue::Counter { value: 3.0 },
+                metadata,
+            )
+            .with_timestamp(Some(ts()))
+        );
+    }
+
+    #[test]
+    fn dynamic_metric_invalid_type() {
+        let config = parse_config(
+            r#"
+            [[metrics]]
+            type = "dynamic"
+            field = "value"
+            type_field = "type"
+            "#,
+        );
+
+        let mut event = create_event("value", 3);
+        event.as_mut_log().insert("type", "timer");
+
+        let mut transform = LogToMetric::new(config);
+        assert_eq!(transform_one(&mut transform, event), None);
+    }
 }
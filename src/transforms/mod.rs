@@ -2,6 +2,8 @@ use snafu::Snafu;
 
 #[cfg(feature = "transforms-add_fields")]
 pub mod add_fields;
+#[cfg(feature = "transforms-adaptive_sample")]
+pub mod adaptive_sample;
 #[cfg(feature = "transforms-add_tags")]
 pub mod add_tags;
 #[cfg(feature = "transforms-aggregate")]
@@ -28,6 +30,8 @@ pub mod filter;
 pub mod geoip;
 #[cfg(feature = "transforms-grok_parser")]
 pub mod grok_parser;
+#[cfg(feature = "transforms-histogram")]
+pub mod histogram;
 #[cfg(feature = "transforms-json_parser")]
 pub mod json_parser;
 #[cfg(feature = "transforms-key_value_parser")]
@@ -42,6 +46,8 @@ pub mod lua;
 pub mod merge;
 #[cfg(feature = "transforms-metric_to_log")]
 pub mod metric_to_log;
+#[cfg(feature = "transforms-recording_rules")]
+pub mod recording_rules;
 #[cfg(feature = "transforms-reduce")]
 pub mod reduce;
 #[cfg(feature = "transforms-regex_parser")]
@@ -62,6 +68,8 @@ pub mod sample;
 pub mod split;
 #[cfg(feature = "transforms-tag_cardinality_limit")]
 pub mod tag_cardinality_limit;
+#[cfg(feature = "transforms-throttle")]
+pub mod throttle;
 #[cfg(feature = "transforms-tokenizer")]
 pub mod tokenizer;
 
@@ -0,0 +1,440 @@
+use super::expr::{AggregateOp, BinaryOp, Expr, Function, Grouping, Selector};
+use crate::event::metric::{Metric, MetricKind, MetricName, MetricSeries, MetricTags, MetricValue};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+/// How far back an instant selector looks for the latest sample of a
+/// series, the same as the default Prometheus lookback delta.
+pub const LOOKBACK: std::time::Duration = std::time::Duration::from_secs(5 * 60);
+
+/// The result of evaluating an expression.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Scalar(f64),
+    Vector(Vec<(MetricTags, f64)>),
+}
+
+/// Holds the recent samples of the counter and gauge series selected by
+/// the rules of the transform. Incremental values are accumulated into
+/// absolute ones as they are recorded.
+#[derive(Debug)]
+pub struct SeriesStore {
+    /// The metric names selected by the rules, series named otherwise
+    /// are never looked at.
+    names: HashSet<String>,
+    series: HashMap<MetricSeries, VecDeque<(DateTime<Utc>, f64)>>,
+}
+
+impl SeriesStore {
+    pub fn new<'a>(exprs: impl IntoIterator<Item = &'a Expr>) -> Self {
+        let names = exprs
+            .into_iter()
+            .flat_map(Expr::selector_names)
+            .map(Into::into)
+            .collect();
+
+        Self {
+            names,
+            series: HashMap::new(),
+        }
+    }
+
+    pub fn record(&mut self, metric: &Metric, timestamp: DateTime<Utc>) {
+        if !self.is_selected(&metric.series().name) {
+            return;
+        }
+
+        let value = match metric.value() {
+            MetricValue::Counter { value } | MetricValue::Gauge { value } => *value,
+            _ => return,
+        };
+
+        let samples = self.series.entry(metric.series().clone()).or_default();
+        // Samples older than the last one of their series arrived out of
+        // order, they are dropped so the samples stay sorted by time.
+        if matches!(samples.back(), Some((last, _)) if timestamp < *last) {
+            return;
+        }
+        let value = match (metric.kind(), samples.back()) {
+            (MetricKind::Incremental, Some((_, last))) => last + value,
+            _ => value,
+        };
+        samples.push_back((timestamp, value));
+    }
+
+    fn is_selected(&self, name: &MetricName) -> bool {
+        self.names.contains(&name.name)
+            || name.namespace.as_ref().map_or(false, |namespace| {
+                self.names.contains(&format!("{}_{}", namespace, name.name))
+            })
+    }
+
+    /// Drops all samples older than `retention`, and any series left
+    /// without samples.
+    pub fn prune(&mut self, now: DateTime<Utc>, retention: std::time::Duration) {
+        let cutoff = since(now, retention);
+        self.series.retain(|_, samples| {
+            while matches!(samples.front(), Some((timestamp, _)) if *timestamp < cutoff) {
+                samples.pop_front();
+            }
+            !samples.is_empty()
+        });
+    }
+
+    fn select<'a>(
+        &'a self,
+        selector: &'a Selector,
+    ) -> impl Iterator<Item = (&'a MetricSeries, &'a VecDeque<(DateTime<Utc>, f64)>)> + 'a {
+        self.series.iter().filter(move |(series, _)| {
+            let name = &series.name;
+            let name_matches = name.name == selector.name
+                || name.namespace.as_ref().map_or(false, |namespace| {
+                    selector
+                        .name
+                        .strip_prefix(namespace.as_str())
+                        .and_then(|rest| rest.strip_prefix('_'))
+                        == Some(name.name.as_str())
+                });
+            name_matches && selector.matches(series.tags.as_ref())
+        })
+    }
+}
+
+/// Returns the point in time `duration` before `now`.
+fn since(now: DateTime<Utc>, duration: std::time::Duration) -> DateTime<Utc> {
+    Duration::from_std(duration)
+        .ok()
+        .and_then(|duration| now.checked_sub_signed(duration))
+        .unwrap_or(chrono::MIN_DATETIME)
+}
+
+pub fn evaluate(expr: &Expr, store: &SeriesStore, now: DateTime<Utc>) -> Value {
+    match expr {
+        Expr::Number(number) => Value::Scalar(*number),
+        Expr::Selector(selector) => {
+            let cutoff = since(now, LOOKBACK);
+            Value::Vector(
+                store
+                    .select(selector)
+                    .filter_map(|(series, samples)| {
+                        samples
+                            .iter()
+                            .rev()
+                            .find(|(timestamp, _)| *timestamp <= now)
+                            .filter(|(timestamp, _)| *timestamp >= cutoff)
+                            .map(|(_, value)| (tags_of(series), *value))
+                    })
+                    .collect(),
+            )
+        }
+        Expr::Function {
+            function,
+            selector,
+            range,
+        } => {
+            let start = since(now, *range);
+            Value::Vector(
+                store
+                    .select(selector)
+                    .filter_map(|(series, samples)| {
+                        let window = samples
+                            .iter()
+                            .filter(|(timestamp, _)| *timestamp >= start && *timestamp <= now)
+                            .copied()
+                            .collect::<Vec<_>>();
+                        apply_function(*function, &window, *range)
+                            .map(|value| (tags_of(series), value))
+                    })
+                    .collect(),
+            )
+        }
+        Expr::Aggregate { op, grouping, expr } => match evaluate(expr, store, now) {
+            Value::Scalar(value) => Value::Scalar(value),
+            Value::Vector(vector) => Value::Vector(aggregate(*op, grouping, vector)),
+        },
+        Expr::Binary { op, lhs, rhs } => {
+            binary(*op, evaluate(lhs, store, now), evaluate(rhs, store, now))
+        }
+        Expr::Negate(expr) => match evaluate(expr, store, now) {
+            Value::Scalar(value) => Value::Scalar(-value),
+            Value::Vector(vector) => Value::Vector(
+                vector
+                    .into_iter()
+                    .map(|(tags, value)| (tags, -value))
+                    .collect(),
+            ),
+        },
+    }
+}
+
+fn tags_of(series: &MetricSeries) -> MetricTags {
+    series.tags.clone().unwrap_or_default()
+}
+
+/// Returns the increase of a counter over the samples, accounting for
+/// counter resets.
+fn counter_increase(window: &[(DateTime<Utc>, f64)]) -> f64 {
+    window
+        .windows(2)
+        .map(|pair| {
+            let (previous, current) = (pair[0].1, pair[1].1);
+            if current < previous {
+                current
+            } else {
+                current - previous
+            }
+        })
+        .sum()
+}
+
+fn apply_function(
+    function: Function,
+    window: &[(DateTime<Utc>, f64)],
+    range: std::time::Duration,
+) -> Option<f64> {
+    let values = window.iter().map(|(_, value)| *value);
+    match function {
+        Function::Rate | Function::Increase => {
+            if window.len() < 2 {
+                return None;
+            }
+            let elapsed = window[window.len() - 1].0 - window[0].0;
+            let elapsed = elapsed.num_milliseconds() as f64 / 1000.0;
+            if elapsed <= 0.0 {
+                return None;
+            }
+            let rate = counter_increase(window) / elapsed;
+            Some(match function {
+                Function::Rate => rate,
+                _ => rate * range.as_secs_f64(),
+            })
+        }
+        Function::Delta => match (window.first(), window.last()) {
+            (Some(first), Some(last)) if window.len() >= 2 => Some(last.1 - first.1),
+            _ => None,
+        },
+        Function::AvgOverTime if !window.is_empty() => {
+            Some(values.sum::<f64>() / window.len() as f64)
+        }
+        Function::MinOverTime => values.fold(None, |min: Option<f64>, value| {
+            Some(min.map_or(value, |min| min.min(value)))
+        }),
+        Function::MaxOverTime => values.fold(None, |max: Option<f64>, value| {
+            Some(max.map_or(value, |max| max.max(value)))
+        }),
+        Function::SumOverTime if !window.is_empty() => Some(values.sum()),
+        Function::CountOverTime if !window.is_empty() => Some(window.len() as f64),
+        _ => None,
+    }
+}
+
+fn aggregate(
+    op: AggregateOp,
+    grouping: &Grouping,
+    vector: Vec<(MetricTags, f64)>,
+) -> Vec<(MetricTags, f64)> {
+    let mut groups: BTreeMap<MetricTags, Vec<f64>> = BTreeMap::new();
+    for (tags, value) in vector {
+        let key = match grouping {
+            Grouping::By(labels) => tags
+                .into_iter()
+                .filter(|(label, _)| labels.contains(label))
+                .collect(),
+            Grouping::Without(labels) => tags
+                .into_iter()
+                .filter(|(label, _)| !labels.contains(label))
+                .collect(),
+        };
+        groups.entry(key).or_default().push(value);
+    }
+
+    groups
+        .into_iter()
+        .map(|(tags, values)| {
+            let value = match op {
+                AggregateOp::Sum => values.iter().sum(),
+                AggregateOp::Avg => values.iter().sum::<f64>() / values.len() as f64,
+                AggregateOp::Min => values.iter().copied().fold(f64::INFINITY, f64::min),
+                AggregateOp::Max => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+                AggregateOp::Count => values.len() as f64,
+            };
+            (tags, value)
+        })
+        .collect()
+}
+
+fn binary(op: BinaryOp, lhs: Value, rhs: Value) -> Value {
+    match (lhs, rhs) {
+        (Value::Scalar(lhs), Value::Scalar(rhs)) => Value::Scalar(op.apply(lhs, rhs)),
+        (Value::Vector(lhs), Value::Scalar(rhs)) => Value::Vector(
+            lhs.into_iter()
+                .map(|(tags, value)| (tags, op.apply(value, rhs)))
+                .collect(),
+        ),
+        (Value::Scalar(lhs), Value::Vector(rhs)) => Value::Vector(
+            rhs.into_iter()
+                .map(|(tags, value)| (tags, op.apply(lhs, value)))
+                .collect(),
+        ),
+        (Value::Vector(lhs), Value::Vector(rhs)) => {
+            // Series are matched one-to-one on their full set of tags.
+            let rhs = rhs.into_iter().collect::<HashMap<_, _>>();
+            Value::Vector(
+                lhs.into_iter()
+                    .filter_map(|(tags, lhs)| {
+                        let rhs = *rhs.get(&tags)?;
+                        Some((tags, op.apply(lhs, rhs)))
+                    })
+                    .collect(),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transforms::recording_rules::expr::parse;
+
+    fn counter(name: &str, service: &str, value: f64) -> Metric {
+        Metric::new(name, MetricKind::Absolute, MetricValue::Counter { value }).with_tags(Some(
+            vec![("service".to_owned(), service.to_owned())]
+                .into_iter()
+                .collect(),
+        ))
+    }
+
+    fn tags(service: &str) -> MetricTags {
+        vec![("service".to_owned(), service.to_owned())]
+            .into_iter()
+            .collect()
+    }
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        chrono::TimeZone::timestamp(&Utc, 1_600_000_000 + secs, 0)
+    }
+
+    #[test]
+    fn rate_handles_counter_resets() {
+        let expr = parse("rate(requests[1m])").unwrap();
+        let mut store = SeriesStore::new(std::iter::once(&expr));
+        store.record(&counter("requests", "api", 10.0), at(0));
+        store.record(&counter("requests", "api", 30.0), at(10));
+        store.record(&counter("requests", "api", 5.0), at(20));
+
+        assert_eq!(
+            evaluate(&expr, &store, at(20)),
+            Value::Vector(vec![(tags("api"), 1.25)])
+        );
+    }
+
+    #[test]
+    fn incremental_values_accumulate() {
+        let expr = parse("requests").unwrap();
+        let mut store = SeriesStore::new(std::iter::once(&expr));
+        let metric = Metric::new(
+            "requests",
+            MetricKind::Incremental,
+            MetricValue::Counter { value: 2.0 },
+        );
+        store.record(&metric, at(0));
+        store.record(&metric, at(1));
+
+        assert_eq!(
+            evaluate(&expr, &store, at(1)),
+            Value::Vector(vec![(MetricTags::new(), 4.0)])
+        );
+    }
+
+    #[test]
+    fn drops_out_of_order_samples() {
+        let expr = parse("increase(requests[1m])").unwrap();
+        let mut store = SeriesStore::new(std::iter::once(&expr));
+        store.record(&counter("requests", "api", 1.0), at(0));
+        store.record(&counter("requests", "api", 5.0), at(20));
+        store.record(&counter("requests", "api", 100.0), at(10));
+        store.record(&counter("requests", "api", 7.0), at(30));
+
+        assert_eq!(
+            store.series.values().next().unwrap(),
+            &vec![(at(0), 1.0), (at(20), 5.0), (at(30), 7.0)]
+                .into_iter()
+                .collect::<VecDeque<_>>()
+        );
+    }
+
+    #[test]
+    fn ratio_of_sums() {
+        let ratio = parse("sum by (service) (errors) / sum by (service) (requests)").unwrap();
+        let doubled = parse("sum(requests) * 2").unwrap();
+        let mut store = SeriesStore::new(vec![&ratio, &doubled]);
+        for (service, errors, requests) in &[("api", 1.0, 4.0), ("web", 0.0, 10.0)] {
+            store.record(&counter("errors", service, *errors), at(0));
+            store.record(&counter("requests", service, *requests), at(0));
+        }
+        store.record(&counter("requests", "web", 10.0).with_name("other"), at(0));
+
+        assert_eq!(
+            evaluate(&ratio, &store, at(0)),
+            Value::Vector(vec![(tags("api"), 0.25), (tags("web"), 0.0)])
+        );
+
+        assert_eq!(
+            evaluate(&doubled, &store, at(0)),
+            Value::Vector(vec![(MetricTags::new(), 28.0)])
+        );
+    }
+
+    #[test]
+    fn stale_samples_are_ignored() {
+        let expr = parse("requests").unwrap();
+        let mut store = SeriesStore::new(std::iter::once(&expr));
+        store.record(&counter("requests", "api", 1.0), at(0));
+
+        assert_eq!(evaluate(&expr, &store, at(600)), Value::Vector(Vec::new()));
+
+        store.prune(at(600), LOOKBACK);
+        assert!(store.series.is_empty());
+    }
+
+    #[test]
+    fn matches_namespaced_names() {
+        for query in &["requests", "vector_requests"] {
+            let expr = parse(query).unwrap();
+            let mut store = SeriesStore::new(std::iter::once(&expr));
+            store.record(
+                &counter("requests", "api", 1.0).with_namespace(Some("vector")),
+                at(0),
+            );
+
+            assert_eq!(
+                evaluate(&expr, &store, at(0)),
+                Value::Vector(vec![(tags("api"), 1.0)])
+            );
+        }
+    }
+
+    #[test]
+    fn only_records_selected_series() {
+        let expr = parse("sum(errors) / sum(vector_requests)").unwrap();
+        let mut store = SeriesStore::new(std::iter::once(&expr));
+        store.record(&counter("errors", "api", 1.0), at(0));
+        store.record(
+            &counter("requests", "api", 4.0).with_namespace(Some("vector")),
+            at(0),
+        );
+        store.record(&counter("requests", "api", 4.0), at(0));
+        store.record(&counter("other", "api", 1.0), at(0));
+
+        let mut recorded = store
+            .series
+            .keys()
+            .map(|series| (series.name.namespace.as_deref(), series.name.name.as_str()))
+            .collect::<Vec<_>>();
+        recorded.sort();
+        assert_eq!(
+            recorded,
+            vec![(None, "errors"), (Some("vector"), "requests")]
+        );
+    }
+}
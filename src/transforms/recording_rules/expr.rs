@@ -0,0 +1,732 @@
+//! A parser for the subset of PromQL supported by recording rules.
+//!
+//! The supported grammar covers numeric literals, instant and range
+//! selectors with label matchers, the `*_over_time`, `rate`, `increase`
+//! and `delta` range functions, the `sum`, `avg`, `min`, `max` and
+//! `count` aggregations with `by`/`without` grouping, and the `+`, `-`,
+//! `*` and `/` arithmetic operators.
+
+use regex::Regex;
+use snafu::Snafu;
+use std::{iter::Peekable, str::CharIndices, time::Duration};
+
+#[derive(Debug, PartialEq, Snafu)]
+pub enum ParseError {
+    #[snafu(display("Unexpected {} at position {}", found, position))]
+    Unexpected { found: String, position: usize },
+    #[snafu(display("Unexpected end of expression, expected {}", expected))]
+    UnexpectedEnd { expected: &'static str },
+    #[snafu(display("Invalid duration {:?}", duration))]
+    InvalidDuration { duration: String },
+    #[snafu(display("Invalid regular expression {:?}: {}", regex, message))]
+    InvalidRegex { regex: String, message: String },
+    #[snafu(display("Function {:?} requires a range selector argument", function))]
+    RangeRequired { function: String },
+    #[snafu(display("Range selector for {:?} must be used with a range function", name))]
+    RangeNotAllowed { name: String },
+}
+
+#[derive(Clone, Debug)]
+pub enum Expr {
+    Number(f64),
+    Selector(Selector),
+    Function {
+        function: Function,
+        selector: Selector,
+        range: Duration,
+    },
+    Aggregate {
+        op: AggregateOp,
+        grouping: Grouping,
+        expr: Box<Expr>,
+    },
+    Binary {
+        op: BinaryOp,
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+    Negate(Box<Expr>),
+}
+
+impl Expr {
+    /// The longest range over which this expression looks back.
+    pub fn max_range(&self) -> Duration {
+        match self {
+            Self::Number(_) | Self::Selector(_) => Duration::from_secs(0),
+            Self::Function { range, .. } => *range,
+            Self::Aggregate { expr, .. } | Self::Negate(expr) => expr.max_range(),
+            Self::Binary { lhs, rhs, .. } => lhs.max_range().max(rhs.max_range()),
+        }
+    }
+
+    /// The metric names selected anywhere in this expression.
+    pub fn selector_names(&self) -> Vec<&str> {
+        match self {
+            Self::Number(_) => Vec::new(),
+            Self::Selector(selector) | Self::Function { selector, .. } => {
+                vec![selector.name.as_str()]
+            }
+            Self::Aggregate { expr, .. } | Self::Negate(expr) => expr.selector_names(),
+            Self::Binary { lhs, rhs, .. } => {
+                let mut names = lhs.selector_names();
+                names.extend(rhs.selector_names());
+                names
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Selector {
+    pub name: String,
+    pub matchers: Vec<Matcher>,
+}
+
+impl Selector {
+    pub fn matches(&self, tags: Option<&crate::event::metric::MetricTags>) -> bool {
+        self.matchers.iter().all(|matcher| {
+            let value = tags
+                .and_then(|tags| tags.get(&matcher.label))
+                .map(String::as_str)
+                .unwrap_or("");
+            matcher.op.matches(value)
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Matcher {
+    pub label: String,
+    pub op: MatchOp,
+}
+
+#[derive(Clone, Debug)]
+pub enum MatchOp {
+    Equal(String),
+    NotEqual(String),
+    Regex(Regex),
+    NotRegex(Regex),
+}
+
+impl MatchOp {
+    fn matches(&self, value: &str) -> bool {
+        match self {
+            Self::Equal(expected) => value == expected,
+            Self::NotEqual(expected) => value != expected,
+            Self::Regex(regex) => regex.is_match(value),
+            Self::NotRegex(regex) => !regex.is_match(value),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Function {
+    Rate,
+    Increase,
+    Delta,
+    AvgOverTime,
+    MinOverTime,
+    MaxOverTime,
+    SumOverTime,
+    CountOverTime,
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "rate" => Self::Rate,
+            "increase" => Self::Increase,
+            "delta" => Self::Delta,
+            "avg_over_time" => Self::AvgOverTime,
+            "min_over_time" => Self::MinOverTime,
+            "max_over_time" => Self::MaxOverTime,
+            "sum_over_time" => Self::SumOverTime,
+            "count_over_time" => Self::CountOverTime,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AggregateOp {
+    Sum,
+    Avg,
+    Min,
+    Max,
+    Count,
+}
+
+impl AggregateOp {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "sum" => Self::Sum,
+            "avg" => Self::Avg,
+            "min" => Self::Min,
+            "max" => Self::Max,
+            "count" => Self::Count,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Grouping {
+    By(Vec<String>),
+    Without(Vec<String>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BinaryOp {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+impl BinaryOp {
+    pub fn apply(self, lhs: f64, rhs: f64) -> f64 {
+        match self {
+            Self::Add => lhs + rhs,
+            Self::Subtract => lhs - rhs,
+            Self::Multiply => lhs * rhs,
+            Self::Divide => lhs / rhs,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(f64),
+    Str(String),
+    Duration(Duration),
+    Punct(&'static str),
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Self::Ident(ident) => format!("identifier {:?}", ident),
+            Self::Number(number) => format!("number {}", number),
+            Self::Str(string) => format!("string {:?}", string),
+            Self::Duration(duration) => format!("duration {:?}", duration),
+            Self::Punct(punct) => format!("{:?}", punct),
+        }
+    }
+}
+
+const PUNCTUATION: &[&str] = &[
+    "!=", "=~", "!~", "=", "(", ")", "{", "}", ",", "+", "-", "*", "/",
+];
+
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some(&(position, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_alphabetic() || c == '_' || c == ':' {
+            let ident = take_while(&mut chars, |c| {
+                c.is_ascii_alphanumeric() || c == '_' || c == ':'
+            });
+            tokens.push((position, Token::Ident(ident)));
+        } else if c.is_ascii_digit() || c == '.' {
+            let number = take_while(&mut chars, |c| c.is_ascii_alphanumeric() || c == '.');
+            let number = number.parse().map_err(|_| ParseError::Unexpected {
+                found: format!("{:?}", number),
+                position,
+            })?;
+            tokens.push((position, Token::Number(number)));
+        } else if c == '"' || c == '\'' {
+            chars.next();
+            let mut string = String::new();
+            loop {
+                match chars.next() {
+                    Some((_, '\\')) => match chars.next() {
+                        Some((_, 'n')) => string.push('\n'),
+                        Some((_, 't')) => string.push('\t'),
+                        Some((_, escaped)) => string.push(escaped),
+                        None => return Err(ParseError::UnexpectedEnd { expected: "string" }),
+                    },
+                    Some((_, end)) if end == c => break,
+                    Some((_, other)) => string.push(other),
+                    None => return Err(ParseError::UnexpectedEnd { expected: "string" }),
+                }
+            }
+            tokens.push((position, Token::Str(string)));
+        } else if c == '[' {
+            chars.next();
+            let duration = take_while(&mut chars, |c| c != ']');
+            if chars.next().is_none() {
+                return Err(ParseError::UnexpectedEnd { expected: "\"]\"" });
+            }
+            tokens.push((position, Token::Duration(parse_duration(duration.trim())?)));
+        } else {
+            let rest = &input[position..];
+            let punct = PUNCTUATION
+                .iter()
+                .find(|punct| rest.starts_with(*punct))
+                .ok_or_else(|| ParseError::Unexpected {
+                    found: format!("{:?}", c),
+                    position,
+                })?;
+            for _ in 0..punct.len() {
+                chars.next();
+            }
+            tokens.push((position, Token::Punct(punct)));
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn take_while(chars: &mut Peekable<CharIndices>, predicate: impl Fn(char) -> bool) -> String {
+    let mut taken = String::new();
+    while let Some(&(_, c)) = chars.peek() {
+        if !predicate(c) {
+            break;
+        }
+        taken.push(c);
+        chars.next();
+    }
+    taken
+}
+
+/// Parses a Prometheus style duration such as `30s`, `5m` or `1h30m`.
+fn parse_duration(input: &str) -> Result<Duration, ParseError> {
+    let invalid = || ParseError::InvalidDuration {
+        duration: input.to_owned(),
+    };
+
+    let mut total = Duration::from_secs(0);
+    let mut rest = input;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(invalid)?;
+        let value: u64 = rest[..digits].parse().map_err(|_| invalid())?;
+        rest = &rest[digits..];
+        let unit = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or_else(|| rest.len());
+        let millis = match &rest[..unit] {
+            "ms" => 1,
+            "s" => 1000,
+            "m" => 60 * 1000,
+            "h" => 60 * 60 * 1000,
+            "d" => 24 * 60 * 60 * 1000,
+            "w" => 7 * 24 * 60 * 60 * 1000,
+            _ => return Err(invalid()),
+        };
+        total += Duration::from_millis(value * millis);
+        rest = &rest[unit..];
+    }
+
+    if total == Duration::from_secs(0) {
+        Err(invalid())
+    } else {
+        Ok(total)
+    }
+}
+
+pub fn parse(input: &str) -> Result<Expr, ParseError> {
+    let mut parser = Parser {
+        tokens: tokenize(input)?,
+        position: 0,
+    };
+    let expr = parser.expr()?;
+    match parser.tokens.get(parser.position) {
+        Some((position, token)) => Err(ParseError::Unexpected {
+            found: token.describe(),
+            position: *position,
+        }),
+        None => Ok(expr),
+    }
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(_, token)| token)
+    }
+
+    fn next(&mut self, expected: &'static str) -> Result<(usize, Token), ParseError> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .cloned()
+            .ok_or(ParseError::UnexpectedEnd { expected })?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn eat(&mut self, punct: &str) -> bool {
+        match self.peek() {
+            Some(Token::Punct(found)) if *found == punct => {
+                self.position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect(&mut self, punct: &'static str) -> Result<(), ParseError> {
+        match self.next(punct)? {
+            (_, Token::Punct(found)) if found == punct => Ok(()),
+            (position, token) => Err(ParseError::Unexpected {
+                found: token.describe(),
+                position,
+            }),
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, ParseError> {
+        let mut lhs = self.term()?;
+        loop {
+            let op = if self.eat("+") {
+                BinaryOp::Add
+            } else if self.eat("-") {
+                BinaryOp::Subtract
+            } else {
+                return Ok(lhs);
+            };
+            let rhs = self.term()?;
+            lhs = Expr::Binary {
+                op,
+                lhs: Box::new(lhs),
+                rhs: Box::new(rhs),
+            };
+        }
+    }
+
+    fn term(&mut self) -> Result<Expr, ParseError> {
+        let mut lhs = self.unary()?;
+        loop {
+            let op = if self.eat("*") {
+                BinaryOp::Multiply
+            } else if self.eat("/") {
+                BinaryOp::Divide
+            } else {
+                return Ok(lhs);
+            };
+            let rhs = self.unary()?;
+            lhs = Expr::Binary {
+                op,
+                lhs: Box::new(lhs),
+                rhs: Box::new(rhs),
+            };
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        if self.eat("-") {
+            Ok(Expr::Negate(Box::new(self.unary()?)))
+        } else if self.eat("+") {
+            self.unary()
+        } else {
+            self.primary()
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, ParseError> {
+        match self.next("expression")? {
+            (_, Token::Number(number)) => Ok(Expr::Number(number)),
+            (_, Token::Punct("(")) => {
+                let expr = self.expr()?;
+                self.expect(")")?;
+                Ok(expr)
+            }
+            (_, Token::Ident(ident)) => {
+                if let Some(op) = AggregateOp::from_name(&ident) {
+                    let grouped = match self.peek() {
+                        Some(Token::Punct("(")) => true,
+                        Some(Token::Ident(next)) => next == "by" || next == "without",
+                        _ => false,
+                    };
+                    if grouped {
+                        return self.aggregate(op);
+                    }
+                }
+                if let Some(function) = Function::from_name(&ident) {
+                    if self.peek() == Some(&Token::Punct("(")) {
+                        return self.function(function, ident);
+                    }
+                }
+                match self.selector(ident)? {
+                    (selector, None) => Ok(Expr::Selector(selector)),
+                    (selector, Some(_)) => Err(ParseError::RangeNotAllowed {
+                        name: selector.name,
+                    }),
+                }
+            }
+            (position, token) => Err(ParseError::Unexpected {
+                found: token.describe(),
+                position,
+            }),
+        }
+    }
+
+    fn aggregate(&mut self, op: AggregateOp) -> Result<Expr, ParseError> {
+        let mut grouping = self.grouping()?;
+        self.expect("(")?;
+        let expr = self.expr()?;
+        self.expect(")")?;
+        if grouping.is_none() {
+            grouping = self.grouping()?;
+        }
+        Ok(Expr::Aggregate {
+            op,
+            grouping: grouping.unwrap_or_else(|| Grouping::By(Vec::new())),
+            expr: Box::new(expr),
+        })
+    }
+
+    fn grouping(&mut self) -> Result<Option<Grouping>, ParseError> {
+        let by = match self.peek() {
+            Some(Token::Ident(ident)) if ident == "by" => true,
+            Some(Token::Ident(ident)) if ident == "without" => false,
+            _ => return Ok(None),
+        };
+        self.position += 1;
+        self.expect("(")?;
+        let mut labels = Vec::new();
+        while !self.eat(")") {
+            match self.next("label name")? {
+                (_, Token::Ident(label)) => labels.push(label),
+                (position, token) => {
+                    return Err(ParseError::Unexpected {
+                        found: token.describe(),
+                        position,
+                    })
+                }
+            }
+            if !self.eat(",") {
+                self.expect(")")?;
+                break;
+            }
+        }
+        Ok(Some(if by {
+            Grouping::By(labels)
+        } else {
+            Grouping::Without(labels)
+        }))
+    }
+
+    fn function(&mut self, function: Function, name: String) -> Result<Expr, ParseError> {
+        self.expect("(")?;
+        let ident = match self.next("selector")? {
+            (_, Token::Ident(ident)) => ident,
+            (position, token) => {
+                return Err(ParseError::Unexpected {
+                    found: token.describe(),
+                    position,
+                })
+            }
+        };
+        let (selector, range) = self.selector(ident)?;
+        self.expect(")")?;
+        match range {
+            Some(range) => Ok(Expr::Function {
+                function,
+                selector,
+                range,
+            }),
+            None => Err(ParseError::RangeRequired { function: name }),
+        }
+    }
+
+    fn selector(&mut self, name: String) -> Result<(Selector, Option<Duration>), ParseError> {
+        let mut matchers = Vec::new();
+        if self.eat("{") {
+            while !self.eat("}") {
+                matchers.push(self.matcher()?);
+                if !self.eat(",") {
+                    self.expect("}")?;
+                    break;
+                }
+            }
+        }
+
+        let range = match self.peek() {
+            Some(Token::Duration(duration)) => {
+                let duration = *duration;
+                self.position += 1;
+                Some(duration)
+            }
+            _ => None,
+        };
+
+        Ok((Selector { name, matchers }, range))
+    }
+
+    fn matcher(&mut self) -> Result<Matcher, ParseError> {
+        let label = match self.next("label matcher")? {
+            (_, Token::Ident(label)) => label,
+            (position, token) => {
+                return Err(ParseError::Unexpected {
+                    found: token.describe(),
+                    position,
+                })
+            }
+        };
+        let op = match self.next("matcher operator")? {
+            (_, Token::Punct(op)) if ["=", "!=", "=~", "!~"].contains(&op) => op,
+            (position, token) => {
+                return Err(ParseError::Unexpected {
+                    found: token.describe(),
+                    position,
+                })
+            }
+        };
+        let value = match self.next("label value")? {
+            (_, Token::Str(value)) => value,
+            (position, token) => {
+                return Err(ParseError::Unexpected {
+                    found: token.describe(),
+                    position,
+                })
+            }
+        };
+
+        let op = match op {
+            "=" => MatchOp::Equal(value),
+            "!=" => MatchOp::NotEqual(value),
+            _ => {
+                // Like Prometheus, regular expressions are fully anchored.
+                let regex = Regex::new(&format!("^(?:{})$", value)).map_err(|error| {
+                    ParseError::InvalidRegex {
+                        regex: value,
+                        message: error.to_string(),
+                    }
+                })?;
+                if op == "=~" {
+                    MatchOp::Regex(regex)
+                } else {
+                    MatchOp::NotRegex(regex)
+                }
+            }
+        };
+
+        Ok(Matcher { label, op })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("5m"), Ok(Duration::from_secs(300)));
+        assert_eq!(parse_duration("1h30m"), Ok(Duration::from_secs(5400)));
+        assert_eq!(parse_duration("250ms"), Ok(Duration::from_millis(250)));
+        assert!(parse_duration("5").is_err());
+        assert!(parse_duration("5y").is_err());
+        assert!(parse_duration("0s").is_err());
+    }
+
+    #[test]
+    fn parses_selectors() {
+        let expr = parse(r#"http_requests_total{code="200", method!~"GET|HEAD"}"#).unwrap();
+        match expr {
+            Expr::Selector(selector) => {
+                assert_eq!(selector.name, "http_requests_total");
+                assert_eq!(selector.matchers.len(), 2);
+                let tags = vec![
+                    ("code".to_owned(), "200".to_owned()),
+                    ("method".to_owned(), "POST".to_owned()),
+                ]
+                .into_iter()
+                .collect();
+                assert!(selector.matches(Some(&tags)));
+                assert!(!selector.matches(None));
+            }
+            other => panic!("unexpected expression {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_ratio_of_aggregations() {
+        let expr = parse(
+            "sum by (service) (rate(errors_total[5m])) / sum(rate(requests_total[1m])) by (service)",
+        )
+        .unwrap();
+        assert_eq!(expr.max_range(), Duration::from_secs(300));
+        match expr {
+            Expr::Binary {
+                op: BinaryOp::Divide,
+                lhs,
+                rhs,
+            } => {
+                for side in &[lhs, rhs] {
+                    match side.as_ref() {
+                        Expr::Aggregate {
+                            op: AggregateOp::Sum,
+                            grouping,
+                            expr,
+                        } => {
+                            assert_eq!(grouping, &Grouping::By(vec!["service".to_owned()]));
+                            assert!(matches!(
+                                expr.as_ref(),
+                                Expr::Function {
+                                    function: Function::Rate,
+                                    ..
+                                }
+                            ));
+                        }
+                        other => panic!("unexpected expression {:?}", other),
+                    }
+                }
+            }
+            other => panic!("unexpected expression {:?}", other),
+        }
+    }
+
+    #[test]
+    fn respects_precedence() {
+        match parse("1 + 2 * 3").unwrap() {
+            Expr::Binary {
+                op: BinaryOp::Add,
+                rhs,
+                ..
+            } => assert!(matches!(
+                *rhs,
+                Expr::Binary {
+                    op: BinaryOp::Multiply,
+                    ..
+                }
+            )),
+            other => panic!("unexpected expression {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        assert_eq!(
+            parse("rate(requests_total)").unwrap_err(),
+            ParseError::RangeRequired {
+                function: "rate".to_owned()
+            }
+        );
+        assert_eq!(
+            parse("requests_total[5m]").unwrap_err(),
+            ParseError::RangeNotAllowed {
+                name: "requests_total".to_owned()
+            }
+        );
+        assert!(matches!(
+            parse("sum(requests_total"),
+            Err(ParseError::UnexpectedEnd { .. })
+        ));
+        assert!(matches!(
+            parse("requests_total requests_total"),
+            Err(ParseError::Unexpected { position: 15, .. })
+        ));
+    }
+}
@@ -0,0 +1,372 @@
+use crate::{
+    config::{DataType, GenerateConfig, TransformConfig, TransformContext, TransformDescription},
+    event::{
+        metric::{Metric, MetricKind, MetricValue},
+        Event,
+    },
+    internal_events::RecordingRulesEvaluated,
+    transforms::{TaskTransform, Transform},
+};
+use async_stream::stream;
+use chrono::{DateTime, Utc};
+use futures::{Stream, StreamExt};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::{pin::Pin, time::Duration};
+
+mod eval;
+mod expr;
+
+use eval::{evaluate, SeriesStore, Value, LOOKBACK};
+use expr::Expr;
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Invalid expression for rule {:?}: {}", record, source))]
+    InvalidExpression {
+        record: String,
+        source: expr::ParseError,
+    },
+    #[snafu(display("At least one rule must be configured"))]
+    NoRules,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RecordingRulesConfig {
+    /// The interval between rule evaluations in seconds.
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+    pub rules: Vec<RuleConfig>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RuleConfig {
+    /// The name of the metric the result of the expression is recorded as.
+    pub record: String,
+    pub namespace: Option<String>,
+    /// The expression to evaluate.
+    pub expr: String,
+    /// Tags added to every series produced by the rule.
+    #[serde(default)]
+    pub tags: IndexMap<String, String>,
+}
+
+const fn default_interval_secs() -> u64 {
+    10
+}
+
+inventory::submit! {
+    TransformDescription::new::<RecordingRulesConfig>("recording_rules")
+}
+
+impl GenerateConfig for RecordingRulesConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"interval_secs = 10
+
+            [[rules]]
+            record = "requests:rate1m"
+            expr = "sum by (service) (rate(requests_total[1m]))""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "recording_rules")]
+impl TransformConfig for RecordingRulesConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        RecordingRules::new(self).map(Transform::task)
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Metric
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Metric
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "recording_rules"
+    }
+}
+
+#[derive(Debug)]
+struct Rule {
+    record: String,
+    namespace: Option<String>,
+    expr: Expr,
+    tags: IndexMap<String, String>,
+}
+
+#[derive(Debug)]
+pub struct RecordingRules {
+    interval: Duration,
+    rules: Vec<Rule>,
+    /// How long samples are kept around for, enough to cover the longest
+    /// range used by any rule.
+    retention: Duration,
+    store: SeriesStore,
+}
+
+impl RecordingRules {
+    pub fn new(config: &RecordingRulesConfig) -> crate::Result<Self> {
+        if config.rules.is_empty() {
+            return Err(Box::new(BuildError::NoRules));
+        }
+
+        let rules = config
+            .rules
+            .iter()
+            .map(|rule| {
+                let expr =
+                    expr::parse(&rule.expr).map_err(|source| BuildError::InvalidExpression {
+                        record: rule.record.clone(),
+                        source,
+                    })?;
+                Ok(Rule {
+                    record: rule.record.clone(),
+                    namespace: rule.namespace.clone(),
+                    expr,
+                    tags: rule.tags.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>, BuildError>>()?;
+
+        let retention = rules
+            .iter()
+            .map(|rule| rule.expr.max_range())
+            .fold(LOOKBACK, Duration::max);
+        let store = SeriesStore::new(rules.iter().map(|rule| &rule.expr));
+
+        Ok(Self {
+            interval: Duration::from_secs(config.interval_secs),
+            rules,
+            retention,
+            store,
+        })
+    }
+
+    /// Records the samples of the metric at its timestamp, or at `now` if
+    /// it has none.
+    fn record(&mut self, event: &Event, now: DateTime<Utc>) {
+        if let Event::Metric(metric) = event {
+            self.store.record(metric, metric.timestamp().unwrap_or(now));
+        }
+    }
+
+    fn evaluate_into(&mut self, now: DateTime<Utc>, output: &mut Vec<Event>) {
+        self.store.prune(now, self.retention);
+
+        let before = output.len();
+        for rule in &self.rules {
+            let vector = match evaluate(&rule.expr, &self.store, now) {
+                Value::Scalar(value) => vec![(Default::default(), value)],
+                Value::Vector(vector) => vector,
+            };
+
+            for (mut tags, value) in vector {
+                // Divisions by zero and similar produce values which can't
+                // be represented by most sinks, so they are left out.
+                if !value.is_finite() {
+                    continue;
+                }
+                for (key, tag) in &rule.tags {
+                    tags.insert(key.clone(), tag.clone());
+                }
+                let metric = Metric::new(
+                    rule.record.clone(),
+                    MetricKind::Absolute,
+                    MetricValue::Gauge { value },
+                )
+                .with_namespace(rule.namespace.clone())
+                .with_tags(if tags.is_empty() { None } else { Some(tags) })
+                .with_timestamp(Some(now));
+                output.push(Event::Metric(metric));
+            }
+        }
+
+        emit!(&RecordingRulesEvaluated {
+            rules: self.rules.len(),
+            series: output.len() - before,
+        });
+    }
+}
+
+impl TaskTransform for RecordingRules {
+    fn transform(
+        mut self: Box<Self>,
+        mut input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let mut evaluation_stream = tokio::time::interval(self.interval);
+
+        Box::pin(stream! {
+            let mut output = Vec::new();
+            let mut done = false;
+            while !done {
+                tokio::select! {
+                    _ = evaluation_stream.tick() => {
+                        self.evaluate_into(Utc::now(), &mut output);
+                    },
+                    maybe_event = input_rx.next() => {
+                        match maybe_event {
+                            None => done = true,
+                            Some(event) => {
+                                self.record(&event, Utc::now());
+                                output.push(event);
+                            }
+                        }
+                    }
+                };
+                for event in output.drain(..) {
+                    yield event;
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::metric::MetricTags;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<RecordingRulesConfig>();
+    }
+
+    fn parse_config(s: &str) -> RecordingRulesConfig {
+        toml::from_str(s).unwrap()
+    }
+
+    fn counter(name: &str, service: &str, value: f64) -> Event {
+        Event::Metric(
+            Metric::new(name, MetricKind::Absolute, MetricValue::Counter { value }).with_tags(
+                Some(
+                    vec![("service".to_owned(), service.to_owned())]
+                        .into_iter()
+                        .collect(),
+                ),
+            ),
+        )
+    }
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        chrono::TimeZone::timestamp(&Utc, 1_600_000_000 + secs, 0)
+    }
+
+    #[test]
+    fn rejects_invalid_rules() {
+        let config = parse_config(
+            r#"
+            [[rules]]
+            record = "broken"
+            expr = "sum(requests"
+            "#,
+        );
+        let error = RecordingRules::new(&config).unwrap_err().to_string();
+        assert!(error.starts_with(r#"Invalid expression for rule "broken""#));
+
+        let config = parse_config("rules = []");
+        assert!(RecordingRules::new(&config).is_err());
+    }
+
+    #[test]
+    fn records_error_ratio() {
+        let config = parse_config(
+            r#"
+            [[rules]]
+            record = "error_ratio"
+            namespace = "slo"
+            expr = "sum by (service) (rate(errors[1m])) / sum by (service) (rate(requests[1m]))"
+            tags.source = "edge"
+            "#,
+        );
+        let mut rules = RecordingRules::new(&config).unwrap();
+
+        for (secs, errors, requests) in &[(0, 0.0, 0.0), (10, 1.0, 10.0), (20, 5.0, 20.0)] {
+            rules.record(&counter("errors", "api", *errors), at(*secs));
+            rules.record(&counter("requests", "api", *requests), at(*secs));
+        }
+
+        let mut output = Vec::new();
+        rules.evaluate_into(at(20), &mut output);
+
+        let mut tags = MetricTags::new();
+        tags.insert("service".to_owned(), "api".to_owned());
+        tags.insert("source".to_owned(), "edge".to_owned());
+        assert_eq!(
+            output,
+            vec![Event::Metric(
+                Metric::new(
+                    "error_ratio",
+                    MetricKind::Absolute,
+                    MetricValue::Gauge { value: 0.25 }
+                )
+                .with_namespace(Some("slo"))
+                .with_tags(Some(tags))
+                .with_timestamp(Some(at(20)))
+            )]
+        );
+    }
+
+    #[test]
+    fn skips_non_finite_results() {
+        let config = parse_config(
+            r#"
+            [[rules]]
+            record = "ratio"
+            expr = "errors / requests"
+            "#,
+        );
+        let mut rules = RecordingRules::new(&config).unwrap();
+        rules.record(&counter("errors", "api", 1.0), at(0));
+        rules.record(&counter("requests", "api", 0.0), at(0));
+
+        let mut output = Vec::new();
+        rules.evaluate_into(at(0), &mut output);
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn records_samples_at_their_timestamp() {
+        let config = parse_config(
+            r#"
+            [[rules]]
+            record = "requests:increase1m"
+            expr = "sum(increase(requests[1m]))"
+            "#,
+        );
+        let mut rules = RecordingRules::new(&config).unwrap();
+
+        // The samples arrive late and out of order, the one older than the
+        // latest sample of its series is dropped.
+        for (secs, value) in &[(0, 1.0), (20, 5.0), (10, 100.0), (30, 7.0)] {
+            let event = counter("requests", "api", *value);
+            let event = Event::Metric(event.into_metric().with_timestamp(Some(at(*secs))));
+            rules.record(&event, at(50));
+        }
+
+        let mut output = Vec::new();
+        rules.evaluate_into(at(50), &mut output);
+        assert_eq!(
+            output,
+            vec![Event::Metric(
+                Metric::new(
+                    "requests:increase1m",
+                    MetricKind::Absolute,
+                    MetricValue::Gauge { value: 12.0 }
+                )
+                .with_timestamp(Some(at(50)))
+            )]
+        );
+    }
+}
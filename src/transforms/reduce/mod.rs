@@ -1,19 +1,22 @@
 use crate::{
     conditions::{AnyCondition, Condition},
     config::{DataType, TransformConfig, TransformContext, TransformDescription},
-    event::{discriminant::Discriminant, Event, EventMetadata, LogEvent},
-    internal_events::ReduceStaleEventFlushed,
+    event::{discriminant::Discriminant, Event, EventMetadata, LogEvent, Value, VrlTarget},
+    internal_events::{ReduceGroupFlushed, ReduceStaleEventFlushed},
     transforms::{TaskTransform, Transform},
 };
 use async_stream::stream;
 use futures::{stream, Stream, StreamExt};
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
+use shared::TimeZone;
 use std::{
-    collections::{hash_map, HashMap},
+    collections::{hash_map, BTreeMap, HashMap},
     pin::Pin,
     time::{Duration, Instant},
 };
+use vector_core::ByteSizeOf;
+use vrl::{diagnostic::Formatter, Program, Runtime};
 
 mod merge_strategy;
 
@@ -36,6 +39,24 @@ pub struct ReduceConfig {
     #[serde(default)]
     pub merge_strategies: IndexMap<String, MergeStrategy>,
 
+    /// A VRL program merging each event into the event accumulated for its
+    /// reduce, in place of `merge_strategies`. The program is given the
+    /// accumulated event as `.accumulated` and the incoming event as
+    /// `.incoming`, and updates `.accumulated`.
+    pub merge: Option<String>,
+
+    /// The maximum number of events merged into a reduce before it is
+    /// flushed.
+    pub max_events: Option<usize>,
+
+    /// The maximum size in bytes of the events merged into a reduce before it
+    /// is flushed.
+    pub max_bytes: Option<usize>,
+
+    /// The maximum number of reduces kept at once. Beyond this, the reduce
+    /// that has been stale the longest is flushed.
+    pub max_groups: Option<usize>,
+
     /// An optional condition that determines when an event is the end of a
     /// reduce.
     pub ends_when: Option<AnyCondition>,
@@ -68,74 +89,167 @@ impl TransformConfig for ReduceConfig {
     }
 }
 
+/// How events are merged into the event accumulated for their reduce.
+enum Merge {
+    Strategies(IndexMap<String, MergeStrategy>),
+    Vrl(Program),
+}
+
+impl Merge {
+    /// Runs the `merge` program, leaving the accumulated fields unchanged if
+    /// it fails.
+    fn run_program(
+        program: &Program,
+        accumulated: &mut BTreeMap<String, Value>,
+        incoming: BTreeMap<String, Value>,
+    ) -> Result<(), String> {
+        let mut target = LogEvent::default();
+        target.insert("accumulated", Value::Map(accumulated.clone()));
+        target.insert("incoming", Value::Map(incoming));
+        let mut target = VrlTarget::new(target.into());
+
+        Runtime::default()
+            .resolve(&mut target, program, &TimeZone::default())
+            .map_err(|error| error.to_string())?;
+
+        let merged = target
+            .into_events()
+            .next()
+            .and_then(|event| event.into_log().remove("accumulated"));
+        match merged {
+            Some(Value::Map(merged)) => {
+                *accumulated = merged;
+                Ok(())
+            }
+            _ => Err("`merge` program must leave `.accumulated` an object".to_owned()),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Accumulated {
+    /// The values of each field, merged according to their merge strategy.
+    Fields(HashMap<String, Box<dyn ReduceValueMerger>>),
+    /// The fields the `merge` program merges events into.
+    Event(BTreeMap<String, Value>),
+}
+
 #[derive(Debug)]
 struct ReduceState {
-    fields: HashMap<String, Box<dyn ReduceValueMerger>>,
+    accumulated: Accumulated,
     stale_since: Instant,
     metadata: EventMetadata,
+    events: usize,
+    bytes: usize,
 }
 
 impl ReduceState {
-    fn new(e: LogEvent, strategies: &IndexMap<String, MergeStrategy>) -> Self {
+    fn new(e: LogEvent, merge: &Merge) -> Self {
+        let bytes = e.size_of();
         let (fields, metadata) = e.into_parts();
-        Self {
-            stale_since: Instant::now(),
-            fields: fields
-                .into_iter()
-                .filter_map(|(k, v)| {
-                    if let Some(strat) = strategies.get(&k) {
-                        match get_value_merger(v, strat) {
-                            Ok(m) => Some((k, m)),
-                            Err(error) => {
-                                warn!(message = "Failed to create merger.", field = ?k, %error);
-                                None
+        let accumulated = match merge {
+            Merge::Strategies(strategies) => Accumulated::Fields(
+                fields
+                    .into_iter()
+                    .filter_map(|(k, v)| {
+                        if let Some(strat) = strategies.get(&k) {
+                            match get_value_merger(v, strat) {
+                                Ok(m) => Some((k, m)),
+                                Err(error) => {
+                                    warn!(message = "Failed to create merger.", field = ?k, %error);
+                                    None
+                                }
                             }
+                        } else {
+                            Some((k, v.into()))
                         }
-                    } else {
-                        Some((k, v.into()))
-                    }
-                })
-                .collect(),
+                    })
+                    .collect(),
+            ),
+            Merge::Vrl(_) => Accumulated::Event(fields),
+        };
+
+        Self {
+            accumulated,
+            stale_since: Instant::now(),
             metadata,
+            events: 1,
+            bytes,
         }
     }
 
-    fn add_event(&mut self, e: LogEvent, strategies: &IndexMap<String, MergeStrategy>) {
+    fn add_event(&mut self, e: LogEvent, merge: &Merge) {
+        self.bytes += e.size_of();
+        self.events += 1;
         let (fields, metadata) = e.into_parts();
         self.metadata.merge(metadata);
 
-        for (k, v) in fields.into_iter() {
-            let strategy = strategies.get(&k);
-            match self.fields.entry(k) {
-                hash_map::Entry::Vacant(entry) => {
-                    if let Some(strat) = strategy {
-                        match get_value_merger(v, strat) {
-                            Ok(m) => {
-                                entry.insert(m);
+        match (&mut self.accumulated, merge) {
+            (Accumulated::Fields(accumulated), Merge::Strategies(strategies)) => {
+                for (k, v) in fields.into_iter() {
+                    let strategy = strategies.get(&k);
+                    match accumulated.entry(k) {
+                        hash_map::Entry::Vacant(entry) => {
+                            if let Some(strat) = strategy {
+                                match get_value_merger(v, strat) {
+                                    Ok(m) => {
+                                        entry.insert(m);
+                                    }
+                                    Err(error) => {
+                                        warn!(message = "Failed to merge value.", %error);
+                                    }
+                                }
+                            } else {
+                                entry.insert(v.clone().into());
                             }
-                            Err(error) => {
+                        }
+                        hash_map::Entry::Occupied(mut entry) => {
+                            if let Err(error) = entry.get_mut().add(v.clone()) {
                                 warn!(message = "Failed to merge value.", %error);
                             }
                         }
-                    } else {
-                        entry.insert(v.clone().into());
                     }
                 }
-                hash_map::Entry::Occupied(mut entry) => {
-                    if let Err(error) = entry.get_mut().add(v.clone()) {
-                        warn!(message = "Failed to merge value.", %error);
-                    }
+            }
+            (Accumulated::Event(accumulated), Merge::Vrl(program)) => {
+                if let Err(error) = Merge::run_program(program, accumulated, fields) {
+                    warn!(message = "Failed to merge event.", %error);
                 }
             }
+            _ => unreachable!("reduce states are created with the transform's merge"),
         }
         self.stale_since = Instant::now();
     }
 
-    fn flush(mut self) -> LogEvent {
+    /// Returns the limit the reduce has reached, if any.
+    fn limit_reached(
+        &self,
+        max_events: Option<usize>,
+        max_bytes: Option<usize>,
+    ) -> Option<&'static str> {
+        if max_events.map_or(false, |max_events| self.events >= max_events) {
+            Some("max_events")
+        } else if max_bytes.map_or(false, |max_bytes| self.bytes >= max_bytes) {
+            Some("max_bytes")
+        } else {
+            None
+        }
+    }
+
+    fn flush(self) -> LogEvent {
         let mut event = LogEvent::new_with_metadata(self.metadata);
-        for (k, v) in self.fields.drain() {
-            if let Err(error) = v.insert_into(k, &mut event) {
-                warn!(message = "Failed to merge values for field.", %error);
+        match self.accumulated {
+            Accumulated::Fields(fields) => {
+                for (k, v) in fields {
+                    if let Err(error) = v.insert_into(k, &mut event) {
+                        warn!(message = "Failed to merge values for field.", %error);
+                    }
+                }
+            }
+            Accumulated::Event(fields) => {
+                for (k, v) in fields {
+                    event.insert_flat(k, v);
+                }
             }
         }
         event
@@ -148,7 +262,10 @@ pub struct Reduce {
     expire_after: Duration,
     flush_period: Duration,
     group_by: Vec<String>,
-    merge_strategies: IndexMap<String, MergeStrategy>,
+    merge: Merge,
+    max_events: Option<usize>,
+    max_bytes: Option<usize>,
+    max_groups: Option<usize>,
     reduce_merge_states: HashMap<Discriminant, ReduceState>,
     ends_when: Option<Box<dyn Condition>>,
     starts_when: Option<Box<dyn Condition>>,
@@ -174,12 +291,32 @@ impl Reduce {
             .map(|c| c.build(enrichment_tables))
             .transpose()?;
         let group_by = config.group_by.clone().into_iter().collect();
+        let merge = match &config.merge {
+            Some(_) if !config.merge_strategies.is_empty() => {
+                return Err("only one of `merge` and `merge_strategies` can be provided".into());
+            }
+            Some(source) => {
+                let mut functions = vrl_stdlib::all();
+                functions.append(&mut enrichment::vrl_functions());
+                let program = vrl::compile(
+                    source,
+                    &functions,
+                    Some(Box::new(enrichment_tables.clone())),
+                )
+                .map_err(|diagnostics| Formatter::new(source, diagnostics).colored().to_string())?;
+                Merge::Vrl(program)
+            }
+            None => Merge::Strategies(config.merge_strategies.clone()),
+        };
 
         Ok(Reduce {
             expire_after: Duration::from_millis(config.expire_after_ms.unwrap_or(30000)),
             flush_period: Duration::from_millis(config.flush_period_ms.unwrap_or(1000)),
             group_by,
-            merge_strategies: config.merge_strategies.clone(),
+            merge,
+            max_events: config.max_events,
+            max_bytes: config.max_bytes,
+            max_groups: config.max_groups,
             reduce_merge_states: HashMap::new(),
             ends_when,
             starts_when,
@@ -207,13 +344,55 @@ impl Reduce {
             .for_each(|(_, s)| output.push(Event::from(s.flush())));
     }
 
-    fn push_or_new_reduce_state(&mut self, event: LogEvent, discriminant: Discriminant) {
-        match self.reduce_merge_states.entry(discriminant) {
-            hash_map::Entry::Vacant(entry) => {
-                entry.insert(ReduceState::new(event, &self.merge_strategies));
+    /// Flushes the reduce that has been stale the longest if there are as
+    /// many reduces as allowed.
+    fn make_room_into(&mut self, output: &mut Vec<Event>) {
+        let max_groups = match self.max_groups {
+            Some(max_groups) => max_groups.max(1),
+            None => return,
+        };
+        while self.reduce_merge_states.len() >= max_groups {
+            let stalest = self
+                .reduce_merge_states
+                .iter()
+                .min_by_key(|(_, state)| state.stale_since)
+                .map(|(discriminant, _)| discriminant.clone());
+            match stalest.and_then(|stalest| self.reduce_merge_states.remove(&stalest)) {
+                Some(state) => {
+                    emit!(&ReduceGroupFlushed {
+                        reason: "max_groups"
+                    });
+                    output.push(state.flush().into());
+                }
+                None => break,
             }
+        }
+    }
+
+    fn push_or_new_reduce_state(
+        &mut self,
+        output: &mut Vec<Event>,
+        event: LogEvent,
+        discriminant: Discriminant,
+    ) {
+        if !self.reduce_merge_states.contains_key(&discriminant) {
+            self.make_room_into(output);
+        }
+
+        let limit_reached = match self.reduce_merge_states.entry(discriminant.clone()) {
+            hash_map::Entry::Vacant(entry) => entry
+                .insert(ReduceState::new(event, &self.merge))
+                .limit_reached(self.max_events, self.max_bytes),
             hash_map::Entry::Occupied(mut entry) => {
-                entry.get_mut().add_event(event, &self.merge_strategies);
+                entry.get_mut().add_event(event, &self.merge);
+                entry.get().limit_reached(self.max_events, self.max_bytes)
+            }
+        };
+
+        if let Some(reason) = limit_reached {
+            if let Some(state) = self.reduce_merge_states.remove(&discriminant) {
+                emit!(&ReduceGroupFlushed { reason });
+                output.push(state.flush().into());
             }
         }
     }
@@ -238,19 +417,17 @@ impl Reduce {
                 output.push(state.flush().into());
             }
 
-            self.push_or_new_reduce_state(event, discriminant)
+            self.push_or_new_reduce_state(output, event, discriminant)
         } else if ends_here {
             output.push(match self.reduce_merge_states.remove(&discriminant) {
                 Some(mut state) => {
-                    state.add_event(event, &self.merge_strategies);
+                    state.add_event(event, &self.merge);
                     state.flush().into()
                 }
-                None => ReduceState::new(event, &self.merge_strategies)
-                    .flush()
-                    .into(),
+                None => ReduceState::new(event, &self.merge).flush().into(),
             })
         } else {
-            self.push_or_new_reduce_state(event, discriminant)
+            self.push_or_new_reduce_state(output, event, discriminant)
         }
 
         self.flush_into(output);
@@ -561,4 +738,136 @@ merge_strategies.bar = "concat"
         assert_eq!(output_2["bar"], json!([2, 4, 6, 8, "done"]).into());
         assert_eq!(output_2.metadata(), &metadata_2);
     }
+
+    #[tokio::test]
+    async fn reduce_with_vrl_merge() {
+        let reduce = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+merge = """
+.accumulated.bytes = (to_int(.accumulated.bytes) ?? 0) + (to_int(.incoming.bytes) ?? 0)
+.accumulated.message = .incoming.message
+"""
+
+[ends_when]
+  type = "check_fields"
+  "test_end.exists" = true
+"#,
+        )
+        .unwrap()
+        .build(&TransformContext::default())
+        .await
+        .unwrap();
+        let reduce = reduce.into_task();
+
+        let mut e_1 = LogEvent::from("test message 1");
+        e_1.insert("bytes", 10);
+        e_1.insert("request_id", "1");
+
+        let mut e_2 = LogEvent::from("test message 2");
+        e_2.insert("bytes", 20);
+        e_2.insert("request_id", "1");
+
+        let mut e_3 = LogEvent::from("test message 3");
+        e_3.insert("bytes", "not a number");
+        e_3.insert("request_id", "1");
+        e_3.insert("test_end", "yep");
+
+        let inputs = vec![e_1.into(), e_2.into(), e_3.into()];
+        let in_stream = Box::pin(stream::iter(inputs));
+        let mut out_stream = reduce.transform(in_stream);
+
+        let output = out_stream.next().await.unwrap().into_log();
+        assert_eq!(output["message"], "test message 3".into());
+        assert_eq!(output["bytes"], Value::from(30));
+        assert_eq!(output["request_id"], "1".into());
+    }
+
+    #[tokio::test]
+    async fn merge_and_merge_strategies_conflict() {
+        let error = toml::from_str::<ReduceConfig>(
+            r#"
+merge = ".accumulated = .incoming"
+merge_strategies.foo = "concat"
+"#,
+        )
+        .unwrap()
+        .build(&TransformContext::default())
+        .await
+        .map(|_| ())
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "only one of `merge` and `merge_strategies` can be provided"
+        );
+    }
+
+    #[tokio::test]
+    async fn flushes_at_max_events() {
+        let reduce = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+max_events = 2
+"#,
+        )
+        .unwrap()
+        .build(&TransformContext::default())
+        .await
+        .unwrap();
+        let reduce = reduce.into_task();
+
+        let inputs = (1..=3)
+            .map(|counter| {
+                let mut e = LogEvent::from(format!("test message {}", counter));
+                e.insert("counter", counter);
+                e.insert("request_id", "1");
+                e.into()
+            })
+            .collect::<Vec<Event>>();
+        let in_stream = Box::pin(stream::iter(inputs));
+        let mut out_stream = reduce.transform(in_stream);
+
+        let output_1 = out_stream.next().await.unwrap().into_log();
+        assert_eq!(output_1["message"], "test message 1".into());
+        assert_eq!(output_1["counter"], Value::from(3));
+
+        let output_2 = out_stream.next().await.unwrap().into_log();
+        assert_eq!(output_2["message"], "test message 3".into());
+        assert_eq!(output_2["counter"], Value::from(3));
+    }
+
+    #[tokio::test]
+    async fn flushes_stalest_at_max_groups() {
+        let reduce = toml::from_str::<ReduceConfig>(
+            r#"
+group_by = [ "request_id" ]
+max_groups = 1
+"#,
+        )
+        .unwrap()
+        .build(&TransformContext::default())
+        .await
+        .unwrap();
+        let reduce = reduce.into_task();
+
+        let inputs = ["1", "1", "2"]
+            .iter()
+            .map(|request_id| {
+                let mut e = LogEvent::from("test message");
+                e.insert("counter", 1);
+                e.insert("request_id", *request_id);
+                e.into()
+            })
+            .collect::<Vec<Event>>();
+        let in_stream = Box::pin(stream::iter(inputs));
+        let mut out_stream = reduce.transform(in_stream);
+
+        let output_1 = out_stream.next().await.unwrap().into_log();
+        assert_eq!(output_1["request_id"], "1".into());
+        assert_eq!(output_1["counter"], Value::from(2));
+
+        let output_2 = out_stream.next().await.unwrap().into_log();
+        assert_eq!(output_2["request_id"], "2".into());
+        assert_eq!(output_2["counter"], Value::from(1));
+    }
 }
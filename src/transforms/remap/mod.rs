@@ -13,7 +13,11 @@ use std::fs::File;
 use std::io::{self, Read};
 use std::path::PathBuf;
 use vrl::diagnostic::Formatter;
-use vrl::{Program, Runtime, Terminate};
+use vrl::{Program, Runtime, Terminate, Vm, VrlRuntime};
+
+mod state;
+
+pub use state::StateConfig;
 
 #[derive(Deserialize, Serialize, Debug, Clone, Derivative)]
 #[serde(deny_unknown_fields, default)]
@@ -26,6 +30,15 @@ pub struct RemapConfig {
     pub drop_on_error: bool,
     #[serde(default = "crate::serde::default_true")]
     pub drop_on_abort: bool,
+    /// Keeps state across events, accessed from the program with the
+    /// `get_state`, `set_state`, `incr_state` and `delete_state` functions.
+    pub state: Option<StateConfig>,
+    /// The directories modules imported by the program are looked up in, in
+    /// order.
+    pub module_paths: Vec<PathBuf>,
+    /// How the program is run, either by resolving its expressions (`ast`)
+    /// or by compiling it to bytecode first (`vm`).
+    pub runtime: VrlRuntime,
 }
 
 inventory::submit! {
@@ -57,6 +70,7 @@ impl TransformConfig for RemapConfig {
 #[derive(Debug)]
 pub struct Remap {
     program: Program,
+    vm: Option<Vm>,
     runtime: Runtime,
     timezone: TimeZone,
     drop_on_error: bool,
@@ -85,16 +99,25 @@ impl Remap {
 
         let mut functions = vrl_stdlib::all();
         functions.append(&mut enrichment::vrl_functions());
+        if let Some(state) = &config.state {
+            functions.append(&mut state::vrl_functions(&state.build()?));
+        }
 
-        let program = vrl::compile(
-            &source,
-            &functions,
-            Some(Box::new(enrichment_tables.clone())),
-        )
-        .map_err(|diagnostics| Formatter::new(&source, diagnostics).colored().to_string())?;
+        let mut state = vrl::state::Compiler::new();
+        state.set_external_context(Some(Box::new(enrichment_tables.clone())));
+        state.set_module_paths(config.module_paths.clone());
+
+        let program = vrl::compile_with_state(&source, &functions, &mut state)
+            .map_err(|diagnostics| Formatter::new(&source, diagnostics).colored().to_string())?;
+
+        let vm = match config.runtime {
+            VrlRuntime::Ast => None,
+            VrlRuntime::Vm => Some(program.compile_to_vm()?),
+        };
 
         Ok(Remap {
             program,
+            vm,
             runtime: Runtime::default(),
             timezone: config.timezone,
             drop_on_error: config.drop_on_error,
@@ -112,6 +135,7 @@ impl Clone for Remap {
     fn clone(&self) -> Self {
         Self {
             program: self.program.clone(),
+            vm: self.vm.clone(),
             runtime: Runtime::default(),
             timezone: self.timezone,
             drop_on_error: self.drop_on_error,
@@ -141,9 +165,12 @@ impl FunctionTransform for Remap {
 
         let mut target: VrlTarget = event.into();
 
-        let result = self
-            .runtime
-            .resolve(&mut target, &self.program, &self.timezone);
+        let result = match &self.vm {
+            Some(vm) => self.runtime.run_vm(&mut target, vm, &self.timezone),
+            None => self
+                .runtime
+                .resolve(&mut target, &self.program, &self.timezone),
+        };
         self.runtime.clear();
 
         match result {
@@ -184,6 +211,18 @@ pub enum BuildError {
     FileOpenFailed { path: PathBuf, source: io::Error },
     #[snafu(display("Could not read vrl program {:?}: {}", path, source))]
     FileReadFailed { path: PathBuf, source: io::Error },
+
+    #[snafu(display(
+        "state {:?} is already shared with max_keys = {} and ttl_secs = {:?}",
+        name,
+        max_keys,
+        ttl_secs
+    ))]
+    ConflictingState {
+        name: String,
+        max_keys: usize,
+        ttl_secs: Option<u64>,
+    },
 }
 
 #[cfg(test)]
@@ -251,6 +290,9 @@ mod tests {
             timezone: TimeZone::default(),
             drop_on_error: true,
             drop_on_abort: false,
+            state: None,
+            module_paths: vec![],
+            runtime: VrlRuntime::Ast,
         };
         let mut tform = Remap::new(conf, &Default::default()).unwrap();
         assert!(tform.runtime().is_empty());
@@ -300,6 +342,9 @@ mod tests {
             timezone: TimeZone::default(),
             drop_on_error: true,
             drop_on_abort: false,
+            state: None,
+            module_paths: vec![],
+            runtime: VrlRuntime::Ast,
         };
         let mut tform = Remap::new(conf, &Default::default()).unwrap();
 
@@ -335,6 +380,9 @@ mod tests {
             timezone: TimeZone::default(),
             drop_on_error: true,
             drop_on_abort: false,
+            state: None,
+            module_paths: vec![],
+            runtime: VrlRuntime::Ast,
         };
         let mut tform = Remap::new(conf, &Default::default()).unwrap();
 
@@ -365,6 +413,9 @@ mod tests {
             timezone: TimeZone::default(),
             drop_on_error: false,
             drop_on_abort: false,
+            state: None,
+            module_paths: vec![],
+            runtime: VrlRuntime::Ast,
         };
         let mut tform = Remap::new(conf, &Default::default()).unwrap();
 
@@ -393,6 +444,9 @@ mod tests {
             timezone: TimeZone::default(),
             drop_on_error: true,
             drop_on_abort: false,
+            state: None,
+            module_paths: vec![],
+            runtime: VrlRuntime::Ast,
         };
         let mut tform = Remap::new(conf, &Default::default()).unwrap();
 
@@ -416,6 +470,9 @@ mod tests {
             timezone: TimeZone::default(),
             drop_on_error: false,
             drop_on_abort: false,
+            state: None,
+            module_paths: vec![],
+            runtime: VrlRuntime::Ast,
         };
         let mut tform = Remap::new(conf, &Default::default()).unwrap();
 
@@ -444,6 +501,9 @@ mod tests {
             timezone: TimeZone::default(),
             drop_on_error: false,
             drop_on_abort: false,
+            state: None,
+            module_paths: vec![],
+            runtime: VrlRuntime::Ast,
         };
         let mut tform = Remap::new(conf, &Default::default()).unwrap();
 
@@ -472,12 +532,49 @@ mod tests {
             timezone: TimeZone::default(),
             drop_on_error: false,
             drop_on_abort: true,
+            state: None,
+            module_paths: vec![],
+            runtime: VrlRuntime::Ast,
         };
         let mut tform = Remap::new(conf, &Default::default()).unwrap();
 
         assert!(transform_one(&mut tform, event).is_none())
     }
 
+    #[test]
+    fn check_remap_vm_runtime() {
+        let source = formatdoc! {r#"
+            .foo = "foo"
+            .count = to_int(.count) ?? 0
+            if .count > 1 {{
+                .plural = true
+            }}
+            .not_an_int = int!(.bar)
+        "#};
+        let conf = |runtime| RemapConfig {
+            source: Some(source.clone()),
+            drop_on_error: false,
+            runtime,
+            ..Default::default()
+        };
+        let mut ast = Remap::new(conf(VrlRuntime::Ast), &Default::default()).unwrap();
+        let mut vm = Remap::new(conf(VrlRuntime::Vm), &Default::default()).unwrap();
+
+        for (count, bar) in &[("2", Value::from(5)), ("1", Value::from("is a string"))] {
+            let event = LogEvent::from(btreemap! { "count" => *count, "bar" => bar.clone() });
+
+            assert_eq!(
+                transform_one(&mut ast, event.clone().into()),
+                transform_one(&mut vm, event.into())
+            );
+        }
+
+        let event = LogEvent::from(btreemap! { "count" => "3", "bar" => 5 });
+        let result = transform_one(&mut vm, event.into()).unwrap();
+        assert_eq!(result.as_log().get("plural"), Some(&Value::from(true)));
+        assert_eq!(result.as_log().get("not_an_int"), Some(&Value::from(5)));
+    }
+
     #[test]
     fn check_remap_metric() {
         let metric = Event::Metric(Metric::new(
@@ -499,6 +596,9 @@ mod tests {
             timezone: TimeZone::default(),
             drop_on_error: true,
             drop_on_abort: false,
+            state: None,
+            module_paths: vec![],
+            runtime: VrlRuntime::Ast,
         };
         let mut tform = Remap::new(conf, &Default::default()).unwrap();
 
@@ -521,4 +621,108 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn check_remap_keeps_state_between_events() {
+        let conf = RemapConfig {
+            source: Some(
+                indoc! {r#"
+                .count = incr_state!(.user)
+                .previous = get_state("last_message")
+                set_state("last_message", .message)
+            "#}
+                .to_string(),
+            ),
+            state: Some(StateConfig {
+                shared_as: None,
+                max_keys: 10,
+                ttl_secs: None,
+            }),
+            ..Default::default()
+        };
+        let mut tform = Remap::new(conf, &Default::default()).unwrap();
+
+        let mut transform = |user: &str, message: &str| {
+            let event = LogEvent::from(btreemap! { "user" => user, "message" => message });
+            let result = transform_one(&mut tform, event.into()).unwrap();
+            (
+                result.as_log().get("count").cloned(),
+                result.as_log().get("previous").cloned(),
+            )
+        };
+
+        assert_eq!(
+            (Some(1.into()), Some(Value::Null)),
+            transform("alice", "one")
+        );
+        assert_eq!(
+            (Some(1.into()), Some("one".into())),
+            transform("bob", "two")
+        );
+        assert_eq!(
+            (Some(2.into()), Some("two".into())),
+            transform("alice", "three")
+        );
+    }
+
+    #[test]
+    fn check_remap_shares_named_state() {
+        let state = Some(StateConfig {
+            shared_as: Some("check_remap_shares_named_state".to_string()),
+            max_keys: 10,
+            ttl_secs: Some(60),
+        });
+        let mut first = Remap::new(
+            RemapConfig {
+                source: Some(r#"set_state!(.user, .session)"#.to_string()),
+                state: state.clone(),
+                ..Default::default()
+            },
+            &Default::default(),
+        )
+        .unwrap();
+        let mut second = Remap::new(
+            RemapConfig {
+                source: Some(r#".session = get_state!(.user)"#.to_string()),
+                state,
+                ..Default::default()
+            },
+            &Default::default(),
+        )
+        .unwrap();
+
+        let event = LogEvent::from(btreemap! { "user" => "alice", "session" => "abc" });
+        transform_one(&mut first, event.into()).unwrap();
+
+        let event = LogEvent::from(btreemap! { "user" => "alice" });
+        let result = transform_one(&mut second, event.into()).unwrap();
+        assert_eq!(get_field_string(&result, "session"), "abc");
+    }
+
+    #[test]
+    fn check_remap_imports_modules() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("greetings.vrl"),
+            r#"fn greet(name) { "hello " + (string(name) ?? "stranger") }"#,
+        )
+        .unwrap();
+
+        let conf = RemapConfig {
+            source: Some(
+                indoc! {r#"
+                import "greetings"
+                .message = greet(.name)
+            "#}
+                .to_string(),
+            ),
+            module_paths: vec![dir.path().to_owned()],
+            ..Default::default()
+        };
+        let mut tform = Remap::new(conf, &Default::default()).unwrap();
+
+        let event = LogEvent::from(btreemap! { "name" => "alice" });
+        let result = transform_one(&mut tform, event.into()).unwrap();
+        assert_eq!(get_field_string(&result, "message"), "hello alice");
+    }
 }
@@ -0,0 +1,601 @@
+//! State kept by `remap` transforms across events, accessed from VRL with
+//! the `get_state`, `set_state`, `incr_state` and `delete_state` functions.
+use super::BuildError;
+use lazy_static::lazy_static;
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, Weak},
+    time::{Duration, Instant},
+};
+use vrl::prelude::*;
+
+lazy_static! {
+    /// The state shared between transforms, by the name it is shared as. A
+    /// state is dropped once no transform uses it.
+    static ref SHARED: Mutex<HashMap<String, Weak<State>>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct StateConfig {
+    /// The name the state is shared as. All `remap` transforms using the same
+    /// name share their state, and must use the same `max_keys` and
+    /// `ttl_secs`. The state is kept when the transforms are reloaded.
+    /// Without a name, the state is only available to the transform itself.
+    pub shared_as: Option<String>,
+    /// The maximum number of keys kept. Beyond this, the least recently used
+    /// keys are dropped.
+    #[serde(default = "default_max_keys")]
+    pub max_keys: usize,
+    /// How long keys are kept after they were last set, unless a TTL is given
+    /// when setting them. Without a TTL, keys are kept until they are deleted
+    /// or dropped to stay within `max_keys`.
+    pub ttl_secs: Option<u64>,
+}
+
+const fn default_max_keys() -> usize {
+    10_000
+}
+
+impl StateConfig {
+    /// Returns the state the transform uses, failing if it is shared with
+    /// transforms that configured it differently.
+    pub fn build(&self) -> Result<Arc<State>, BuildError> {
+        let name = match &self.shared_as {
+            Some(name) => name,
+            None => return Ok(Arc::new(State::new(self))),
+        };
+
+        let mut shared = SHARED.lock().unwrap();
+        shared.retain(|_, state| state.strong_count() > 0);
+
+        match shared.get(name).and_then(Weak::upgrade) {
+            Some(state) if state.max_keys == self.max_keys && state.ttl_secs == self.ttl_secs => {
+                Ok(state)
+            }
+            Some(state) => Err(BuildError::ConflictingState {
+                name: name.clone(),
+                max_keys: state.max_keys,
+                ttl_secs: state.ttl_secs,
+            }),
+            None => {
+                let state = Arc::new(State::new(self));
+                shared.insert(name.clone(), Arc::downgrade(&state));
+                Ok(state)
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    value: Value,
+    expires_at: Option<Instant>,
+}
+
+#[derive(Debug)]
+struct Entries {
+    ttl: Option<Duration>,
+    entries: LruCache<String, Entry>,
+}
+
+impl Entries {
+    /// Returns the live entry for the key, removing it if it has expired.
+    fn get_mut(&mut self, key: &String, now: Instant) -> Option<&mut Entry> {
+        let expired = match self.entries.peek(key)?.expires_at {
+            Some(expires_at) => expires_at <= now,
+            None => false,
+        };
+        if expired {
+            self.entries.pop(key);
+            None
+        } else {
+            self.entries.get_mut(key)
+        }
+    }
+
+    fn expires_at(&self, ttl: Option<Duration>, now: Instant) -> Option<Instant> {
+        ttl.or(self.ttl).map(|ttl| now + ttl)
+    }
+}
+
+/// Values kept by key across events.
+#[derive(Debug)]
+pub struct State {
+    max_keys: usize,
+    ttl_secs: Option<u64>,
+    entries: Mutex<Entries>,
+}
+
+impl State {
+    fn new(config: &StateConfig) -> Self {
+        Self {
+            max_keys: config.max_keys,
+            ttl_secs: config.ttl_secs,
+            entries: Mutex::new(Entries {
+                ttl: config.ttl_secs.map(Duration::from_secs),
+                entries: LruCache::new(config.max_keys),
+            }),
+        }
+    }
+
+    pub fn get(&self, key: String, now: Instant) -> Option<Value> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.get_mut(&key, now).map(|entry| entry.value.clone())
+    }
+
+    /// Sets the value of the key, which expires after the given TTL or else
+    /// the configured one.
+    pub fn set(&self, key: String, value: Value, ttl: Option<Duration>, now: Instant) {
+        let mut entries = self.entries.lock().unwrap();
+        let expires_at = entries.expires_at(ttl, now);
+        entries.entries.put(key, Entry { value, expires_at });
+    }
+
+    /// Adds to the integer value of the key, returning the new value. Keys
+    /// without a value start at zero, and keep the expiry they were given when
+    /// they were first incremented, so counts are kept for fixed windows.
+    pub fn increment(
+        &self,
+        key: String,
+        by: i64,
+        ttl: Option<Duration>,
+        now: Instant,
+    ) -> std::result::Result<i64, String> {
+        let mut entries = self.entries.lock().unwrap();
+        if let Some(entry) = entries.get_mut(&key, now) {
+            let count = match entry.value {
+                Value::Integer(count) => count.saturating_add(by),
+                ref value => {
+                    return Err(format!(
+                        r#"state {:?}: expected "integer", got {}"#,
+                        key,
+                        value.kind()
+                    ))
+                }
+            };
+            entry.value = Value::Integer(count);
+            return Ok(count);
+        }
+
+        let expires_at = entries.expires_at(ttl, now);
+        entries.entries.put(
+            key,
+            Entry {
+                value: Value::Integer(by),
+                expires_at,
+            },
+        );
+        Ok(by)
+    }
+
+    /// Removes the key, returning its value.
+    pub fn delete(&self, key: String, now: Instant) -> Option<Value> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.get_mut(&key, now)?;
+        entries.entries.pop(&key).map(|entry| entry.value)
+    }
+}
+
+/// Returns the VRL functions accessing the state.
+pub fn vrl_functions(state: &Arc<State>) -> Vec<Box<dyn Function>> {
+    vec![
+        Box::new(GetState(Arc::clone(state))) as Box<dyn Function>,
+        Box::new(SetState(Arc::clone(state))) as Box<dyn Function>,
+        Box::new(IncrState(Arc::clone(state))) as Box<dyn Function>,
+        Box::new(DeleteState(Arc::clone(state))) as Box<dyn Function>,
+    ]
+}
+
+fn resolve_key(
+    key: &dyn Expression,
+    ctx: &mut Context,
+) -> std::result::Result<String, ExpressionError> {
+    Ok(key.resolve(ctx)?.try_bytes_utf8_lossy()?.into_owned())
+}
+
+fn resolve_ttl(
+    ttl_secs: &Option<Box<dyn Expression>>,
+    ctx: &mut Context,
+) -> std::result::Result<Option<Duration>, ExpressionError> {
+    match ttl_secs {
+        Some(ttl_secs) => {
+            let ttl_secs = ttl_secs.resolve(ctx)?.try_integer()?;
+            Ok(Some(Duration::from_secs(ttl_secs.max(0) as u64)))
+        }
+        None => Ok(None),
+    }
+}
+
+#[derive(Debug)]
+struct GetState(Arc<State>);
+
+impl Function for GetState {
+    fn identifier(&self) -> &'static str {
+        "get_state"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "key",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let key = arguments.required("key");
+
+        Ok(Box::new(GetStateFn {
+            state: Arc::clone(&self.0),
+            key,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct GetStateFn {
+    state: Arc<State>,
+    key: Box<dyn Expression>,
+}
+
+impl Expression for GetStateFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let key = resolve_key(&*self.key, ctx)?;
+
+        Ok(self.state.get(key, Instant::now()).unwrap_or(Value::Null))
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().unknown()
+    }
+}
+
+#[derive(Debug)]
+struct SetState(Arc<State>);
+
+impl Function for SetState {
+    fn identifier(&self) -> &'static str {
+        "set_state"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "key",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "value",
+                kind: kind::ANY,
+                required: true,
+            },
+            Parameter {
+                keyword: "ttl_secs",
+                kind: kind::INTEGER,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let key = arguments.required("key");
+        let value = arguments.required("value");
+        let ttl_secs = arguments.optional("ttl_secs");
+
+        Ok(Box::new(SetStateFn {
+            state: Arc::clone(&self.0),
+            key,
+            value,
+            ttl_secs,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SetStateFn {
+    state: Arc<State>,
+    key: Box<dyn Expression>,
+    value: Box<dyn Expression>,
+    ttl_secs: Option<Box<dyn Expression>>,
+}
+
+impl Expression for SetStateFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let key = resolve_key(&*self.key, ctx)?;
+        let value = self.value.resolve(ctx)?;
+        let ttl = resolve_ttl(&self.ttl_secs, ctx)?;
+
+        self.state.set(key, value, ttl, Instant::now());
+        Ok(Value::Null)
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().infallible().null()
+    }
+}
+
+#[derive(Debug)]
+struct IncrState(Arc<State>);
+
+impl Function for IncrState {
+    fn identifier(&self) -> &'static str {
+        "incr_state"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "key",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "by",
+                kind: kind::INTEGER,
+                required: false,
+            },
+            Parameter {
+                keyword: "ttl_secs",
+                kind: kind::INTEGER,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let key = arguments.required("key");
+        let by = arguments.optional("by");
+        let ttl_secs = arguments.optional("ttl_secs");
+
+        Ok(Box::new(IncrStateFn {
+            state: Arc::clone(&self.0),
+            key,
+            by,
+            ttl_secs,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct IncrStateFn {
+    state: Arc<State>,
+    key: Box<dyn Expression>,
+    by: Option<Box<dyn Expression>>,
+    ttl_secs: Option<Box<dyn Expression>>,
+}
+
+impl Expression for IncrStateFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let key = resolve_key(&*self.key, ctx)?;
+        let by = match &self.by {
+            Some(by) => by.resolve(ctx)?.try_integer()?,
+            None => 1,
+        };
+        let ttl = resolve_ttl(&self.ttl_secs, ctx)?;
+
+        self.state
+            .increment(key, by, ttl, Instant::now())
+            .map(Value::Integer)
+            .map_err(Into::into)
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        // The key may hold a value that isn't an integer.
+        TypeDef::new().fallible().integer()
+    }
+}
+
+#[derive(Debug)]
+struct DeleteState(Arc<State>);
+
+impl Function for DeleteState {
+    fn identifier(&self) -> &'static str {
+        "delete_state"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "key",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let key = arguments.required("key");
+
+        Ok(Box::new(DeleteStateFn {
+            state: Arc::clone(&self.0),
+            key,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DeleteStateFn {
+    state: Arc<State>,
+    key: Box<dyn Expression>,
+}
+
+impl Expression for DeleteStateFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let key = resolve_key(&*self.key, ctx)?;
+
+        Ok(self
+            .state
+            .delete(key, Instant::now())
+            .unwrap_or(Value::Null))
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::new().unknown()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_state(max_keys: usize, ttl_secs: Option<u64>) -> State {
+        State::new(&StateConfig {
+            shared_as: None,
+            max_keys,
+            ttl_secs,
+        })
+    }
+
+    #[test]
+    fn expires_keys() {
+        let state = make_state(10, Some(60));
+        let now = Instant::now();
+        state.set("default".to_string(), "a".into(), None, now);
+        state.set(
+            "short".to_string(),
+            "b".into(),
+            Some(Duration::from_secs(10)),
+            now,
+        );
+
+        let later = now + Duration::from_secs(10);
+        assert_eq!(Some("a".into()), state.get("default".to_string(), later));
+        assert_eq!(None, state.get("short".to_string(), later));
+        assert_eq!(
+            None,
+            state.get("default".to_string(), now + Duration::from_secs(60))
+        );
+    }
+
+    #[test]
+    fn counts_in_fixed_windows() {
+        let state = make_state(10, None);
+        let now = Instant::now();
+        let ttl = Some(Duration::from_secs(60));
+
+        assert_eq!(Ok(1), state.increment("error".to_string(), 1, ttl, now));
+        let later = now + Duration::from_secs(30);
+        assert_eq!(Ok(3), state.increment("error".to_string(), 2, ttl, later));
+        // Incrementing doesn't extend the window.
+        let window_end = now + Duration::from_secs(60);
+        assert_eq!(
+            Ok(1),
+            state.increment("error".to_string(), 1, ttl, window_end)
+        );
+
+        state.set("text".to_string(), "a".into(), None, now);
+        assert_eq!(
+            Err(r#"state "text": expected "integer", got "string""#.to_string()),
+            state.increment("text".to_string(), 1, None, now)
+        );
+    }
+
+    #[test]
+    fn drops_least_recently_used_keys() {
+        let state = make_state(2, None);
+        let now = Instant::now();
+        state.set("a".to_string(), 1.into(), None, now);
+        state.set("b".to_string(), 2.into(), None, now);
+        state.get("a".to_string(), now);
+        state.set("c".to_string(), 3.into(), None, now);
+
+        assert_eq!(Some(1.into()), state.get("a".to_string(), now));
+        assert_eq!(None, state.get("b".to_string(), now));
+        assert_eq!(Some(3.into()), state.delete("c".to_string(), now));
+        assert_eq!(None, state.get("c".to_string(), now));
+    }
+
+    #[test]
+    fn shares_named_state() {
+        let config = StateConfig {
+            shared_as: Some("shares_named_state".to_string()),
+            max_keys: 10,
+            ttl_secs: None,
+        };
+        let now = Instant::now();
+        let state = config.build().unwrap();
+        state.set("key".to_string(), "value".into(), None, now);
+        assert_eq!(
+            Some("value".into()),
+            config.build().unwrap().get("key".to_string(), now)
+        );
+
+        // The state is dropped along with its last user.
+        drop(state);
+        assert_eq!(None, config.build().unwrap().get("key".to_string(), now));
+
+        let config = StateConfig {
+            shared_as: None,
+            ..config
+        };
+        let state = config.build().unwrap();
+        state.set("key".to_string(), "value".into(), None, now);
+        assert_eq!(None, config.build().unwrap().get("key".to_string(), now));
+    }
+
+    #[test]
+    fn rejects_conflicting_shared_state() {
+        let config = StateConfig {
+            shared_as: Some("rejects_conflicting_shared_state".to_string()),
+            max_keys: 10,
+            ttl_secs: None,
+        };
+        let _state = config.build().unwrap();
+
+        let conflicting = StateConfig {
+            ttl_secs: Some(60),
+            ..config.clone()
+        };
+        assert!(matches!(
+            conflicting.build(),
+            Err(BuildError::ConflictingState { .. })
+        ));
+        let conflicting = StateConfig {
+            max_keys: 20,
+            ..config.clone()
+        };
+        assert!(matches!(
+            conflicting.build(),
+            Err(BuildError::ConflictingState { .. })
+        ));
+        assert!(config.build().is_ok());
+    }
+}
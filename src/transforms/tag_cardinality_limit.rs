@@ -4,7 +4,7 @@ use crate::{
     event::Event,
     internal_events::{
         TagCardinalityLimitRejectingEvent, TagCardinalityLimitRejectingTag,
-        TagCardinalityValueLimitReached,
+        TagCardinalityLimitReplacingTag, TagCardinalityValueLimitReached,
     },
     transforms::Transform,
 };
@@ -13,7 +13,7 @@ use futures::{Stream, StreamExt};
 use serde::{Deserialize, Serialize};
 use std::{
     borrow::{Borrow, Cow},
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     fmt,
     future::ready,
     pin::Pin,
@@ -29,6 +29,28 @@ pub struct TagCardinalityLimitConfig {
     #[serde(default = "default_limit_exceeded_action")]
     pub limit_exceeded_action: LimitExceededAction,
 
+    /// The value rejected tag values are replaced with when
+    /// `limit_exceeded_action` is `replace_value`.
+    #[serde(default = "default_replacement_value")]
+    pub replacement_value: String,
+
+    /// Overrides `value_limit` for individual tag keys.
+    #[serde(default)]
+    pub per_tag_limits: BTreeMap<String, u32>,
+
+    /// Tag keys whose values are never limited.
+    #[serde(default)]
+    pub allowed_tags: Vec<String>,
+
+    /// Tag keys whose values are always rejected.
+    #[serde(default)]
+    pub denied_tags: Vec<String>,
+
+    /// Whether the accepted values of each tag key are tracked separately
+    /// for every metric name rather than across all metrics.
+    #[serde(default)]
+    pub per_metric: bool,
+
     #[serde(flatten)]
     pub mode: Mode,
 }
@@ -46,23 +68,30 @@ pub struct BloomFilterConfig {
     pub cache_size_per_key: usize,
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
-#[serde(tag = "limit_exceeded_action", rename_all = "snake_case")]
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
 pub enum LimitExceededAction {
     DropTag,
     DropEvent,
+    ReplaceValue,
 }
 
 #[derive(Debug)]
 pub struct TagCardinalityLimit {
     config: TagCardinalityLimitConfig,
-    accepted_tags: HashMap<String, TagValueSet>,
+    /// The accepted values of each tag key, grouped by metric name when
+    /// `per_metric` is set and under an empty name otherwise.
+    accepted_tags: HashMap<String, HashMap<String, TagValueSet>>,
 }
 
 const fn default_limit_exceeded_action() -> LimitExceededAction {
     LimitExceededAction::DropTag
 }
 
+fn default_replacement_value() -> String {
+    "__other__".to_string()
+}
+
 const fn default_value_limit() -> u32 {
     500
 }
@@ -81,6 +110,11 @@ impl GenerateConfig for TagCardinalityLimitConfig {
             mode: Mode::Exact,
             value_limit: default_value_limit(),
             limit_exceeded_action: default_limit_exceeded_action(),
+            replacement_value: default_replacement_value(),
+            per_tag_limits: BTreeMap::new(),
+            allowed_tags: Vec::new(),
+            denied_tags: Vec::new(),
+            per_metric: false,
         })
         .unwrap()
     }
@@ -187,14 +221,47 @@ impl TagCardinalityLimit {
     /// for the key and returns true, otherwise returns false.  A false return
     /// value indicates to the caller that the value is not accepted for this
     /// key, and the configured limit_exceeded_action should be taken.
-    fn try_accept_tag(&mut self, key: &str, value: Cow<'_, String>) -> bool {
-        if !self.accepted_tags.contains_key(key) {
-            self.accepted_tags.insert(
+    ///
+    /// Keys in `allowed_tags` are always accepted and keys in `denied_tags`
+    /// are always rejected. The replacement value is always accepted, so it
+    /// does not count towards the limit.
+    fn try_accept_tag(&mut self, metric_name: &str, key: &str, value: Cow<'_, String>) -> bool {
+        if self.config.allowed_tags.iter().any(|tag| tag == key) {
+            return true;
+        }
+        if self.config.denied_tags.iter().any(|tag| tag == key) {
+            return false;
+        }
+        if self.config.limit_exceeded_action == LimitExceededAction::ReplaceValue
+            && *value == self.config.replacement_value
+        {
+            return true;
+        }
+
+        let value_limit = self
+            .config
+            .per_tag_limits
+            .get(key)
+            .copied()
+            .unwrap_or(self.config.value_limit);
+
+        let scope = if self.config.per_metric {
+            metric_name
+        } else {
+            ""
+        };
+        if !self.accepted_tags.contains_key(scope) {
+            self.accepted_tags.insert(scope.to_string(), HashMap::new());
+        }
+        let accepted_tags = self.accepted_tags.get_mut(scope).unwrap();
+
+        if !accepted_tags.contains_key(key) {
+            accepted_tags.insert(
                 key.to_string(),
-                TagValueSet::new(self.config.value_limit, &self.config.mode),
+                TagValueSet::new(value_limit, &self.config.mode),
             );
         }
-        let tag_value_set = self.accepted_tags.get_mut(key).unwrap();
+        let tag_value_set = accepted_tags.get_mut(key).unwrap();
 
         if tag_value_set.contains(value.clone()) {
             // Tag value has already been accepted, nothing more to do.
@@ -202,11 +269,11 @@ impl TagCardinalityLimit {
         }
 
         // Tag value not yet part of the accepted set.
-        if tag_value_set.len() < self.config.value_limit as usize {
+        if tag_value_set.len() < value_limit as usize {
             // accept the new value
             tag_value_set.insert(value);
 
-            if tag_value_set.len() == self.config.value_limit as usize {
+            if tag_value_set.len() == value_limit as usize {
                 emit!(&TagCardinalityValueLimitReached { key });
             }
 
@@ -220,10 +287,11 @@ impl TagCardinalityLimit {
     fn transform_one(&mut self, mut event: Event) -> Option<Event> {
         let metric = event.as_mut_metric();
         if let Some(tags_map) = metric.tags() {
+            let metric_name = metric.name();
             match self.config.limit_exceeded_action {
                 LimitExceededAction::DropEvent => {
                     for (key, value) in tags_map {
-                        if !self.try_accept_tag(key, Cow::Borrowed(value)) {
+                        if !self.try_accept_tag(metric_name, key, Cow::Borrowed(value)) {
                             emit!(&TagCardinalityLimitRejectingEvent {
                                 tag_key: key,
                                 tag_value: value,
@@ -235,7 +303,7 @@ impl TagCardinalityLimit {
                 LimitExceededAction::DropTag => {
                     let mut to_delete = Vec::new();
                     for (key, value) in tags_map {
-                        if !self.try_accept_tag(key, Cow::Borrowed(value)) {
+                        if !self.try_accept_tag(metric_name, key, Cow::Borrowed(value)) {
                             emit!(&TagCardinalityLimitRejectingTag {
                                 tag_key: key,
                                 tag_value: value,
@@ -247,6 +315,21 @@ impl TagCardinalityLimit {
                         metric.remove_tag(&key);
                     }
                 }
+                LimitExceededAction::ReplaceValue => {
+                    let mut to_replace = Vec::new();
+                    for (key, value) in tags_map {
+                        if !self.try_accept_tag(metric_name, key, Cow::Borrowed(value)) {
+                            emit!(&TagCardinalityLimitReplacingTag {
+                                tag_key: key,
+                                tag_value: value,
+                            });
+                            to_replace.push(key.clone());
+                        }
+                    }
+                    for key in to_replace {
+                        metric.insert_tag(key, self.config.replacement_value.clone());
+                    }
+                }
             }
         }
         Some(event)
@@ -271,17 +354,39 @@ mod tests {
     use super::*;
     use crate::transforms::tag_cardinality_limit::{default_cache_size, BloomFilterConfig, Mode};
     use crate::{event::metric, event::Event, event::Metric};
-    use std::collections::BTreeMap;
 
     #[test]
     fn generate_config() {
         crate::test_util::test_generate_config::<TagCardinalityLimitConfig>();
     }
 
+    #[test]
+    fn parse_limit_exceeded_action() {
+        for (action, expected) in &[
+            ("drop_tag", LimitExceededAction::DropTag),
+            ("drop_event", LimitExceededAction::DropEvent),
+            ("replace_value", LimitExceededAction::ReplaceValue),
+        ] {
+            let config: TagCardinalityLimitConfig = toml::from_str(&format!(
+                r#"
+                mode = "exact"
+                limit_exceeded_action = "{}"
+                "#,
+                action
+            ))
+            .unwrap();
+            assert_eq!(config.limit_exceeded_action, *expected);
+        }
+    }
+
     fn make_metric(tags: BTreeMap<String, String>) -> Event {
+        make_named_metric("event", tags)
+    }
+
+    fn make_named_metric(name: &str, tags: BTreeMap<String, String>) -> Event {
         Event::Metric(
             Metric::new(
-                "event",
+                name,
                 metric::MetricKind::Incremental,
                 metric::MetricValue::Counter { value: 1.0 },
             )
@@ -307,6 +412,11 @@ mod tests {
         TagCardinalityLimit::new(TagCardinalityLimitConfig {
             value_limit,
             limit_exceeded_action,
+            replacement_value: default_replacement_value(),
+            per_tag_limits: BTreeMap::new(),
+            allowed_tags: Vec::new(),
+            denied_tags: Vec::new(),
+            per_metric: false,
             mode: Mode::Probabilistic(BloomFilterConfig {
                 cache_size_per_key: default_cache_size(),
             }),
@@ -442,4 +552,63 @@ mod tests {
         assert_eq!(new_event2, event2);
         assert_eq!(new_event3, event3);
     }
+
+    fn make_tags(tags: &[(&str, &str)]) -> BTreeMap<String, String> {
+        tags.iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn tag_cardinality_limit_replace_value() {
+        let mut transform = make_transform_hashset(1, LimitExceededAction::ReplaceValue);
+
+        let event1 = make_metric(make_tags(&[("tag1", "val1")]));
+        let event2 = make_metric(make_tags(&[("tag1", "val2")]));
+        let event3 = make_metric(make_tags(&[("tag1", "__other__")]));
+
+        assert_eq!(transform.transform_one(event1.clone()).unwrap(), event1);
+        // The replacement value does not count towards the limit.
+        assert_eq!(transform.transform_one(event2).unwrap(), event3);
+        assert_eq!(transform.transform_one(event3.clone()).unwrap(), event3);
+    }
+
+    #[test]
+    fn tag_cardinality_limit_per_tag_limits() {
+        let mut config = make_transform_hashset(1, LimitExceededAction::DropTag).config;
+        config.per_tag_limits.insert("tag2".into(), 2);
+        config.allowed_tags.push("tag3".into());
+        config.denied_tags.push("tag4".into());
+        let mut transform = TagCardinalityLimit::new(config);
+
+        for value in &["val1", "val2", "val3"] {
+            let event = make_metric(make_tags(&[
+                ("tag1", *value),
+                ("tag2", *value),
+                ("tag3", *value),
+                ("tag4", *value),
+            ]));
+            let event = transform.transform_one(event).unwrap();
+            let tags = event.as_metric().tags().unwrap();
+            assert_eq!(tags.contains_key("tag1"), *value == "val1");
+            assert_eq!(tags.contains_key("tag2"), *value != "val3");
+            assert!(tags.contains_key("tag3"));
+            assert!(!tags.contains_key("tag4"));
+        }
+    }
+
+    #[test]
+    fn tag_cardinality_limit_per_metric() {
+        let mut config = make_transform_hashset(1, LimitExceededAction::DropEvent).config;
+        config.per_metric = true;
+        let mut transform = TagCardinalityLimit::new(config);
+
+        let event1 = make_named_metric("metric1", make_tags(&[("tag1", "val1")]));
+        let event2 = make_named_metric("metric2", make_tags(&[("tag1", "val2")]));
+        let event3 = make_named_metric("metric1", make_tags(&[("tag1", "val2")]));
+
+        assert_eq!(transform.transform_one(event1.clone()), Some(event1));
+        assert_eq!(transform.transform_one(event2.clone()), Some(event2));
+        assert_eq!(transform.transform_one(event3), None);
+    }
 }
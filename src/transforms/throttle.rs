@@ -0,0 +1,262 @@
+use crate::{
+    conditions::{AnyCondition, Condition},
+    config::{DataType, GenerateConfig, TransformConfig, TransformContext, TransformDescription},
+    event::Event,
+    internal_events::{TemplateRenderingFailed, ThrottleEventDiscarded},
+    template::Template,
+    transforms::{FunctionTransform, Transform},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ThrottleConfig {
+    /// The number of events allowed for each key in each window.
+    pub threshold: u32,
+    /// The length of the windows.
+    pub window_secs: u64,
+    /// A template for the key events are throttled by, such as `{{ service }}`.
+    /// Without it, all events are throttled together.
+    pub key_field: Option<Template>,
+    /// Events matching this condition are never throttled.
+    pub exclude: Option<AnyCondition>,
+}
+
+inventory::submit! {
+    TransformDescription::new::<ThrottleConfig>("throttle")
+}
+
+impl GenerateConfig for ThrottleConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            threshold: 100,
+            window_secs: 1,
+            key_field: None,
+            exclude: None::<AnyCondition>,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "throttle")]
+impl TransformConfig for ThrottleConfig {
+    async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
+        Ok(Transform::function(Throttle::new(
+            self.threshold,
+            Duration::from_secs(self.window_secs),
+            self.key_field.clone(),
+            self.exclude
+                .as_ref()
+                .map(|condition| condition.build(&context.enrichment_tables))
+                .transpose()?,
+        )))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "throttle"
+    }
+}
+
+/// The events counted for a key in the current window.
+#[derive(Clone, Debug)]
+struct Window {
+    started_at: Instant,
+    count: u32,
+}
+
+#[derive(Clone)]
+pub struct Throttle {
+    threshold: u32,
+    window: Duration,
+    key_field: Option<Template>,
+    exclude: Option<Box<dyn Condition>>,
+    windows: HashMap<String, Window>,
+    last_pruned_at: Option<Instant>,
+}
+
+impl Throttle {
+    pub fn new(
+        threshold: u32,
+        window: Duration,
+        key_field: Option<Template>,
+        exclude: Option<Box<dyn Condition>>,
+    ) -> Self {
+        Self {
+            threshold,
+            window,
+            key_field,
+            exclude,
+            windows: HashMap::new(),
+            last_pruned_at: None,
+        }
+    }
+
+    fn key(&self, event: &Event) -> String {
+        match &self.key_field {
+            Some(template) => template.render_string(event).unwrap_or_else(|error| {
+                // Events without a key are throttled together.
+                emit!(&TemplateRenderingFailed {
+                    error,
+                    field: Some("key_field"),
+                    drop_event: false,
+                });
+                String::new()
+            }),
+            None => String::new(),
+        }
+    }
+
+    /// Counts the event towards its key's window, returning whether the
+    /// event is within the threshold.
+    fn allow(&mut self, key: &str, now: Instant) -> bool {
+        self.prune(now);
+
+        let window = self.window;
+        match self.windows.get_mut(key) {
+            Some(current) if now.duration_since(current.started_at) < window => {
+                current.count = current.count.saturating_add(1);
+                current.count <= self.threshold
+            }
+            _ => {
+                self.windows.insert(
+                    key.to_owned(),
+                    Window {
+                        started_at: now,
+                        count: 1,
+                    },
+                );
+                self.threshold > 0
+            }
+        }
+    }
+
+    /// Forgets the keys whose window has ended, once per window, so keys
+    /// that are no longer seen don't accumulate.
+    fn prune(&mut self, now: Instant) {
+        match self.last_pruned_at {
+            Some(last_pruned_at) if now.duration_since(last_pruned_at) < self.window => {}
+            _ => {
+                let window = self.window;
+                self.windows
+                    .retain(|_, current| now.duration_since(current.started_at) < window);
+                self.last_pruned_at = Some(now);
+            }
+        }
+    }
+
+    fn transform_at(&mut self, output: &mut Vec<Event>, event: Event, now: Instant) {
+        if let Some(condition) = self.exclude.as_ref() {
+            if condition.check(&event) {
+                output.push(event);
+                return;
+            }
+        }
+
+        let key = self.key(&event);
+        if self.allow(&key, now) {
+            output.push(event);
+        } else {
+            emit!(&ThrottleEventDiscarded { key: &key });
+        }
+    }
+}
+
+impl FunctionTransform for Throttle {
+    fn transform(&mut self, output: &mut Vec<Event>, event: Event) {
+        self.transform_at(output, event, Instant::now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        conditions::{ConditionConfig, VrlConfig},
+        event::LogEvent,
+    };
+    use shared::btreemap;
+    use std::convert::TryFrom;
+
+    fn event(service: &str) -> Event {
+        LogEvent::from(btreemap! { "service" => service, "message" => "hello" }).into()
+    }
+
+    fn passed(throttle: &mut Throttle, events: Vec<Event>, now: Instant) -> usize {
+        let mut output = Vec::new();
+        for event in events {
+            throttle.transform_at(&mut output, event, now);
+        }
+        output.len()
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<ThrottleConfig>();
+    }
+
+    #[test]
+    fn throttles_events_per_window() {
+        let mut throttle = Throttle::new(2, Duration::from_secs(10), None, None);
+        let now = Instant::now();
+
+        assert_eq!(2, passed(&mut throttle, vec![event("a"); 3], now));
+        let later = now + Duration::from_secs(5);
+        assert_eq!(0, passed(&mut throttle, vec![event("a")], later));
+        let next_window = now + Duration::from_secs(10);
+        assert_eq!(2, passed(&mut throttle, vec![event("a"); 3], next_window));
+    }
+
+    #[test]
+    fn throttles_events_per_key() {
+        let key_field = Template::try_from("{{ service }}").unwrap();
+        let mut throttle = Throttle::new(1, Duration::from_secs(10), Some(key_field), None);
+        let now = Instant::now();
+
+        let events = vec![event("a"), event("b"), event("a"), event("c")];
+        assert_eq!(3, passed(&mut throttle, events, now));
+        assert_eq!(0, passed(&mut throttle, vec![event("a"), event("b")], now));
+    }
+
+    #[test]
+    fn forgets_ended_windows() {
+        let key_field = Template::try_from("{{ service }}").unwrap();
+        let mut throttle = Throttle::new(1, Duration::from_secs(10), Some(key_field), None);
+        let now = Instant::now();
+
+        passed(&mut throttle, vec![event("a"), event("b")], now);
+        assert_eq!(2, throttle.windows.len());
+        passed(
+            &mut throttle,
+            vec![event("c")],
+            now + Duration::from_secs(10),
+        );
+        assert_eq!(1, throttle.windows.len());
+    }
+
+    #[test]
+    fn excluded_events_are_not_throttled() {
+        let exclude = VrlConfig {
+            source: r#".service == "audit""#.to_string(),
+        }
+        .build(&Default::default())
+        .unwrap();
+        let mut throttle = Throttle::new(1, Duration::from_secs(10), None, Some(exclude));
+        let now = Instant::now();
+
+        let events = vec![event("a"), event("audit"), event("audit"), event("b")];
+        assert_eq!(3, passed(&mut throttle, events, now));
+    }
+}
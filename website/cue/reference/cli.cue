@@ -464,6 +464,44 @@ cli: {
 				}
 			}
 		}
+
+		"vrl test": {
+			description: """
+				Run the VRL programs of a directory against their test cases, and report the
+				branches of the programs' `if` statements the test cases don't take.
+
+				The test cases of a program `foo.vrl` are the `foo.input.json` or
+				`foo.<case>.input.json` files next to it, holding the event object to run the
+				program on. Each is paired with the expected outcome: the event object after the
+				program ran in `foo.<case>.output.json`, the message of the error the program fails
+				with in `foo.<case>.error`, or an empty `foo.<case>.abort` file if the program is
+				expected to abort.
+				"""
+
+			flags: _default_flags
+
+			options: {
+				"module-path": {
+					description: """
+						A directory the modules imported by the programs are looked up in. Can be
+						given multiple times, in which case the directories are searched in order.
+						"""
+					type: "string"
+				}
+				"timezone": {
+					_short:      "tz"
+					description: "The timezone used to parse dates."
+					type:        "string"
+				}
+			}
+
+			args: {
+				dir: {
+					description: "The directory to look up programs and their test cases in, recursively."
+					type:        "string"
+				}
+			}
+		}
 	}
 
 	// Helpers
@@ -0,0 +1,80 @@
+package metadata
+
+components: sinks: enrichment_table: {
+	title: "Enrichment Table"
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		development:   "beta"
+		egress_method: "stream"
+		service_providers: []
+		stateful: true
+	}
+
+	features: {
+		buffer: enabled:      false
+		healthcheck: enabled: false
+		send: {
+			compression: enabled: false
+			encoding: enabled:    false
+			request: enabled:     false
+			tls: enabled:         false
+		}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		table: {
+			common:      true
+			description: "The name of the `memory` enrichment table to write the events to. Each event is written as a row keyed by the table's `key_field`, replacing any row with the same key; events without the key field are dropped."
+			required:    true
+			warnings: []
+			type: string: {
+				examples: ["recent_logins"]
+				syntax: "literal"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		memory_tables: {
+			title: "Memory enrichment tables"
+			body: """
+				A `memory` enrichment table starts out empty and is filled by this sink, so lookups with
+				`get_enrichment_table_record` and `find_enrichment_table_records` in other components see
+				the latest event written for each key. Rows which are not written to for `ttl_secs` are
+				no longer returned and are removed, and once the table holds `max_rows` rows the least
+				recently written row is evicted to make room. The rows survive configuration reloads, but
+				as they are only held in memory they are lost when Vector restarts.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		enrichment_table_evictions_total: components.sources.internal_metrics.output.metrics.enrichment_table_evictions_total
+		enrichment_table_rows:            components.sources.internal_metrics.output.metrics.enrichment_table_rows
+		enrichment_table_writes_total:    components.sources.internal_metrics.output.metrics.enrichment_table_writes_total
+		processing_errors_total:          components.sources.internal_metrics.output.metrics.processing_errors_total
+	}
+}
@@ -101,6 +101,49 @@ components: sinks: prometheus_exporter: {
 				unit:    "seconds"
 			}
 		}
+		max_series: {
+			common:      false
+			description: """
+				The maximum number of series to hold in memory. When a new series would exceed
+				this limit, the least recently updated series is evicted.
+				"""
+			required:    false
+			warnings: []
+			type: uint: {
+				default:  null
+				examples: [10000]
+				unit:     null
+			}
+		}
+		max_series_per_metric: {
+			common:      false
+			description: """
+				The maximum number of series to hold in memory for any single metric name. When a
+				new series would exceed this limit, the least recently updated series with the same
+				name is evicted.
+				"""
+			required:    false
+			warnings: []
+			type: uint: {
+				default:  null
+				examples: [1000]
+				unit:     null
+			}
+		}
+		metric_series_limits: {
+			common:      false
+			description: "Overrides of `max_series_per_metric` for individual metric names."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [
+					{
+						"http_requests_total": 5000
+					},
+				]
+				options: {}
+			}
+		}
 		default_namespace: {
 			common:      true
 			description: """
@@ -375,5 +418,20 @@ components: sinks: prometheus_exporter: {
 				frequently.
 				"""
 		}
+		series_limits: {
+			title: "Series Limits"
+			body: """
+				Every distinct combination of metric name and tags is held in memory until Vector
+				is restarted, so a single high cardinality tag can grow memory usage without bound.
+				The `max_series`, `max_series_per_metric` and `metric_series_limits` options put an
+				upper bound on the number of series held. Once a limit is reached, the least
+				recently updated series is evicted to make room for the new one, and the
+				`series_evicted_total` internal metric is incremented.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		series_evicted_total: components.sources.internal_metrics.output.metrics.series_evicted_total
 	}
 }
@@ -191,6 +191,45 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _internal_metrics_tags
 		}
+		enrichment_table_evictions_total: {
+			description:       "The total number of rows removed from a memory enrichment table to make room for new rows or because they outlived their TTL."
+			type:              "counter"
+			default_namespace: "vector"
+			tags: _enrichment_table_tags & {
+				reason: {
+					description: "Why the rows were removed."
+					required:    true
+					enum: {
+						expired:  "The row was not written to for longer than the TTL."
+						max_rows: "The table held its maximum number of rows."
+					}
+				}
+			}
+		}
+		enrichment_table_reload_errors_total: {
+			description:       "The total number of errors encountered when reloading an enrichment table after its source changed."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _enrichment_table_tags
+		}
+		enrichment_table_reloads_total: {
+			description:       "The total number of times an enrichment table has been reloaded after its source changed."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _enrichment_table_tags
+		}
+		enrichment_table_rows: {
+			description:       "The number of rows held by an enrichment table as of its last reload or write."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _enrichment_table_tags
+		}
+		enrichment_table_writes_total: {
+			description:       "The total number of rows written to a memory enrichment table."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _enrichment_table_tags
+		}
 		quit_total: {
 			description:       "The total number of times the Vector instance has quit."
 			type:              "counter"
@@ -858,6 +897,18 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		recording_rules_evaluations_total: {
+			description:       "The total number of times the recording rules have been evaluated."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		recording_rules_series_recorded_total: {
+			description:       "The total number of series produced by evaluating recording rules."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		request_automatic_decode_errors_total: {
 			description:       "The total number of request errors for this component when it attempted to automatically discover and handle the content-encoding of incoming request data."
 			type:              "counter"
@@ -876,6 +927,37 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		reduce_groups_flushed_total: {
+			description:       "The total number of transactions the `reduce` transform flushed because a limit was reached."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				reason: {
+					description: "The limit that was reached."
+					required:    true
+					enum: {
+						max_bytes:  "The `max_bytes` limit of the transaction was reached."
+						max_events: "The `max_events` limit of the transaction was reached."
+						max_groups: "The `max_groups` limit was reached."
+					}
+				}
+			}
+		}
+		series_evicted_total: {
+			description:       "The total number of series evicted because a series limit was reached."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				reason: {
+					description: "The limit that caused the eviction."
+					required:    true
+					enum: {
+						max_series:            "The `max_series` limit was reached."
+						max_series_per_metric: "The limit for the metric name was reached."
+					}
+				}
+			}
+		}
 		send_errors_total: {
 			description:       "The total number of errors sending messages."
 			type:              "counter"
@@ -1068,6 +1150,14 @@ components: sources: internal_metrics: {
 			component_type:  _component_type
 		}
 
+		_enrichment_table_tags: _internal_metrics_tags & {
+			table: {
+				description: "The name of the enrichment table."
+				required:    true
+				examples: ["ip_owners"]
+			}
+		}
+
 		// All available tags
 		_collector: {
 			description: "Which collector this metric comes from."
@@ -1171,6 +1261,7 @@ components: sources: internal_metrics: {
 			enum: {
 				"out_of_order": "The event was out of order."
 				"oversized":    "The event was too large."
+				"rate_limited": "The event exceeded a rate limit."
 			}
 		}
 	}
@@ -0,0 +1,116 @@
+package metadata
+
+components: transforms: adaptive_sample: {
+	title: "Adaptive Sample"
+
+	description: """
+		Samples events towards a target throughput, keeping every event for rare keys and
+		sampling frequent keys progressively more.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		filter: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		exclude: {
+			common: true
+			description: """
+				The set of logical conditions to exclude events from sampling.
+				"""
+			required: false
+			warnings: []
+			type: string: {
+				default: null
+				examples: [
+					#".status_code != 200 && !includes(["info", "debug"], .severity)"#,
+				]
+				syntax: "remap_boolean_expression"
+			}
+		}
+		key_field: {
+			common: true
+			description: """
+				The name of the log field whose value events are sampled by. If left unspecified, or if
+				the event doesn't have `key_field`, events are sampled together.
+				"""
+			required: false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["service"]
+				syntax: "literal"
+			}
+		}
+		target_events: {
+			description: """
+				The number of events to keep in each window, across all keys. The target is shared
+				between the keys seen in the previous window: keys with fewer events than their share
+				are kept in full, and what they leave of their share goes to the more frequent keys.
+				"""
+			required: true
+			warnings: []
+			type: uint: {
+				examples: [1000]
+				unit: null
+			}
+		}
+		window_secs: {
+			common: false
+			description: """
+				The length of the windows. The rate each key is sampled at is worked out from the
+				events seen in the previous window, so all events are kept in the first window.
+				"""
+			required: false
+			warnings: []
+			type: uint: {
+				default: 30
+				unit:    "seconds"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		sample_rate: {
+			title: "Sample Rate"
+			body: """
+				Kept events are annotated with a `sample_rate` field holding the rate they were
+				sampled at: an event with a `sample_rate` of 10 stands for 10 events. Counts can be
+				re-weighted by counting this field, for example with a `log_to_metric` counter with
+				`increment_by_value` enabled.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
+	}
+}
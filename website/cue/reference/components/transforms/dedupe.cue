@@ -42,6 +42,17 @@ components: transforms: dedupe: {
 			warnings: []
 			type: object: {
 				options: {
+					max_bytes: {
+						common:      false
+						description: "The approximate maximum memory used by the cache. Beyond this, the least recently seen Events are evicted."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: null
+							examples: [10485760]
+							unit: "bytes"
+						}
+					}
 					num_events: {
 						common:      true
 						description: "The number of recent Events to cache and compare new incoming Events against."
@@ -52,9 +63,38 @@ components: transforms: dedupe: {
 							unit:    null
 						}
 					}
+					persist: {
+						common:      false
+						description: "Whether to save the cache to the data directory, so that Events seen before Vector restarts are still considered. The cache is saved every minute and when Vector stops."
+						required:    false
+						warnings: []
+						type: bool: default: false
+					}
+					ttl_secs: {
+						common:      false
+						description: "How long Events are cached after they were first seen. Duplicates of an Event are dropped only within this window. By default, Events are cached until newer Events evict them."
+						required:    false
+						warnings: []
+						type: uint: {
+							default: null
+							examples: [600]
+							unit: "seconds"
+						}
+					}
 				}
 			}
 		}
+		data_dir: {
+			common:      false
+			description: "The directory the cache is saved in when `cache.persist` is enabled. By default, the global `data_dir` option is used. Please make sure the Vector project has write permissions to this dir."
+			required:    false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["/var/lib/vector"]
+				syntax: "file_system_path"
+			}
+		}
 		fields: {
 			description: "Options controlling what fields to match against."
 			required:    true
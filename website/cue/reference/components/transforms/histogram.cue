@@ -0,0 +1,104 @@
+package metadata
+
+components: transforms: histogram: {
+	title: "Histogram"
+
+	description: """
+		Converts distribution and histogram metrics into a histogram with a
+		chosen set of buckets, or into a summary with a chosen set of quantiles.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		convert: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		target: {
+			common:      true
+			description: "The metric type to convert distributions and histograms into."
+			required:    false
+			warnings: []
+			type: string: {
+				default: "histogram"
+				enum: {
+					histogram: "An [aggregated histogram](\(urls.vector_metric)#histogram) with the configured `buckets`."
+					summary:   "An [aggregated summary](\(urls.vector_metric)#summary) with the configured `quantiles`."
+				}
+				syntax: "literal"
+			}
+		}
+		buckets: {
+			common:      true
+			description: """
+				The upper limits of the buckets to emit when `target` is `histogram`. Must be in
+				strictly ascending order.
+				"""
+			required:    false
+			warnings: []
+			type: array: {
+				default: [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]
+				items: type: float: examples: [0.005, 0.01]
+			}
+		}
+		quantiles: {
+			common:      true
+			description: "The quantiles to emit when `target` is `summary`."
+			required:    false
+			warnings: []
+			type: array: {
+				default: [0.5, 0.75, 0.9, 0.95, 0.99]
+				items: type: float: examples: [0.5, 0.75, 0.9, 0.95, 0.99]
+			}
+		}
+	}
+
+	input: {
+		logs: false
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+	}
+
+	how_it_works: {
+		conversions: {
+			title: "Conversions"
+			body: """
+				Distributions are counted into the configured buckets, or have their quantiles
+				calculated from the samples. Histograms are rebucketed or converted into quantiles
+				by assuming observations are spread evenly within each bucket, the same way as
+				Prometheus' `histogram_quantile` function. Observations above the highest bucket
+				limit are only reflected in the `count` of the histogram.
+
+				All other metric types, including summaries, are passed through unchanged.
+				"""
+		}
+	}
+}
@@ -75,6 +75,33 @@ components: transforms: log_to_metric: {
 							syntax:  "template"
 						}
 					}
+					kind: {
+						description: "The metric kind to use when `kind_field` is not set or missing from the event. Defaults to `absolute` for gauges and `incremental` for all other types."
+						required:    false
+						common:      false
+						warnings: []
+						relevant_when: #"type = "dynamic""#
+						type: string: {
+							default: null
+							enum: {
+								absolute:    "The metric value is absolute."
+								incremental: "The metric value is an increment over the previous value."
+							}
+							syntax: "literal"
+						}
+					}
+					kind_field: {
+						description: "The log field holding the metric kind, either `incremental` or `absolute`."
+						required:    false
+						common:      false
+						warnings: []
+						relevant_when: #"type = "dynamic""#
+						type: string: {
+							default: null
+							examples: ["metric.kind"]
+							syntax: "literal"
+						}
+					}
 					namespace: {
 						description: "The namespace of the metric."
 						required:    false
@@ -112,6 +139,28 @@ components: transforms: log_to_metric: {
 							}
 						}
 					}
+					tags_field: {
+						description: "A log field whose nested fields are all added as tags. Each tag is named after the path of the field below `tags_field`. Tags of the same name set by `tags` take precedence."
+						required:    false
+						common:      false
+						warnings: []
+						relevant_when: #"type = "dynamic""#
+						type: string: {
+							default: null
+							examples: ["metric.labels"]
+							syntax: "literal"
+						}
+					}
+					type_field: {
+						description: "The log field holding the metric type, one of `counter`, `gauge`, `set`, `histogram` or `summary`."
+						required:    true
+						warnings: []
+						relevant_when: #"type = "dynamic""#
+						type: string: {
+							examples: ["metric.type"]
+							syntax: "literal"
+						}
+					}
 					type: {
 						description: "The metric type."
 						required:    true
@@ -119,6 +168,7 @@ components: transforms: log_to_metric: {
 						type: string: {
 							enum: {
 								counter:   "A [counter metric type](\(urls.vector_metric)#counter)."
+								dynamic:   "A metric whose type is read from the `type_field` of each event."
 								gauge:     "A [gauge metric type](\(urls.vector_metric)#gauge)."
 								histogram: "A [distribution metric type](\(urls.vector_metric)#histogram) with histogram statistic."
 								set:       "A [set metric type](\(urls.vector_metric)#set)."
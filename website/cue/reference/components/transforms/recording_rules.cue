@@ -0,0 +1,149 @@
+package metadata
+
+components: transforms: recording_rules: {
+	title: "Recording Rules"
+
+	description: """
+		Periodically evaluates PromQL-like expressions over the metrics passing through
+		the transform and records their results as new gauge metrics.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		aggregate: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		interval_secs: {
+			common:      true
+			description: "The interval between rule evaluations, in seconds."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: 10
+				unit:    "seconds"
+			}
+		}
+		rules: {
+			description: "The rules to evaluate."
+			required:    true
+			warnings: []
+			type: array: items: type: object: {
+				examples: []
+				options: {
+					record: {
+						description: "The name of the metric the result of `expr` is recorded as."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: ["service:errors:ratio_rate1m"]
+							syntax: "literal"
+						}
+					}
+					namespace: {
+						description: "The namespace of the recorded metric."
+						required:    false
+						common:      false
+						warnings: []
+						type: string: {
+							default: null
+							examples: ["slo"]
+							syntax: "literal"
+						}
+					}
+					expr: {
+						description: "The expression to evaluate. See [supported expressions](#supported-expressions)."
+						required:    true
+						warnings: []
+						type: string: {
+							examples: [
+								"sum by (service) (rate(errors_total[1m])) / sum by (service) (rate(requests_total[1m]))",
+							]
+							syntax: "literal"
+						}
+					}
+					tags: {
+						description: "Tags added to every series recorded by the rule."
+						required:    false
+						common:      false
+						warnings: []
+						type: object: {
+							examples: [{"source": "edge"}]
+							options: {}
+						}
+					}
+				}
+			}
+		}
+	}
+
+	input: {
+		logs: false
+		metrics: {
+			counter:      true
+			distribution: true
+			gauge:        true
+			histogram:    true
+			set:          true
+			summary:      true
+		}
+	}
+
+	how_it_works: {
+		supported_expressions: {
+			title: "Supported Expressions"
+			body: """
+				Expressions are a subset of PromQL. Instant selectors (`requests_total{code="200"}`)
+				return the latest value of each matching series seen within the last five minutes,
+				and range selectors (`requests_total[5m]`) may be used as the argument of `rate`,
+				`increase`, `delta`, `avg_over_time`, `min_over_time`, `max_over_time`,
+				`sum_over_time` and `count_over_time`. The `sum`, `avg`, `min`, `max` and `count`
+				aggregations support `by` and `without` grouping, and the `+`, `-`, `*` and `/`
+				operators may be applied between scalars and vectors, or between vectors with
+				identical tag sets.
+
+				Selectors match either the metric name, or the namespace and name joined with an
+				underscore.
+				"""
+		}
+
+		evaluation: {
+			title: "Evaluation"
+			body: """
+				Only `counter` and `gauge` metrics are used for evaluation. Samples are recorded at
+				the timestamp of their metric, or when they arrive at the transform if they have none;
+				samples older than the latest one of their series are dropped. `incremental` metrics
+				are summed into a running total. All incoming metrics are passed through unchanged, and on every
+				interval each rule produces one `absolute` `gauge` per resulting series. Results which
+				are not finite, such as divisions by zero, are dropped.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		recording_rules_evaluations_total:     components.sources.internal_metrics.output.metrics.recording_rules_evaluations_total
+		recording_rules_series_recorded_total: components.sources.internal_metrics.output.metrics.recording_rules_series_recorded_total
+	}
+}
@@ -85,6 +85,60 @@ components: transforms: reduce: {
 				}
 			}
 		}
+		max_bytes: {
+			common:      false
+			description: "The maximum size in bytes of the events merged into a transaction. Once reached, the transaction is flushed."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: null
+				examples: [1048576]
+				unit: "bytes"
+			}
+		}
+		max_events: {
+			common:      false
+			description: "The maximum number of events merged into a transaction. Once reached, the transaction is flushed."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: null
+				examples: [1000]
+				unit: null
+			}
+		}
+		max_groups: {
+			common:      false
+			description: "The maximum number of transactions kept at once. Once reached, the transaction that has been stale the longest is flushed to make room for a new one."
+			required:    false
+			warnings: []
+			type: uint: {
+				default: null
+				examples: [10000]
+				unit: null
+			}
+		}
+		merge: {
+			common: false
+			description: """
+				A VRL program merging each event into its transaction, in place of `merge_strategies`. The
+				program is given the event accumulated so far as `.accumulated` and the incoming event as
+				`.incoming`, and updates `.accumulated`. The first event of a transaction is the initial
+				accumulated event. If the program fails, the accumulated event is left unchanged.
+				"""
+			required: false
+			warnings: []
+			type: string: {
+				default: null
+				examples: [
+					#"""
+						.accumulated.bytes = (to_int(.accumulated.bytes) ?? 0) + (to_int(.incoming.bytes) ?? 0)
+						.accumulated.message = .incoming.message
+						"""#,
+				]
+				syntax: "remap_program"
+			}
+		}
 		merge_strategies: {
 			common: false
 			description: """
@@ -272,6 +326,7 @@ components: transforms: reduce: {
 	]
 
 	telemetry: metrics: {
+		reduce_groups_flushed_total: components.sources.internal_metrics.output.metrics.reduce_groups_flushed_total
 		stale_events_flushed_total: components.sources.internal_metrics.output.metrics.stale_events_flushed_total
 	}
 }
@@ -101,6 +101,91 @@ components: transforms: "remap": {
 				"""
 			type: bool: default: true
 		}
+		module_paths: {
+			common:   false
+			required: false
+			description: """
+				The directories the modules [imported](\(urls.vrl_expressions)#import) by the program are
+				looked up in, in order.
+
+				If a relative path is provided, its root is the current working directory.
+				"""
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["./vrl/modules"]
+					syntax: "literal"
+				}
+			}
+		}
+		runtime: {
+			common:   false
+			required: false
+			description: """
+				How the program is run for each event. The program is compiled the same way in both
+				cases, so it behaves the same regardless of the runtime.
+				"""
+			type: string: {
+				default: "ast"
+				enum: {
+					ast: "Run the program by resolving each of its expressions in turn."
+					vm:  "Compile the program to bytecode once and run it in a virtual machine, which is faster for most programs."
+				}
+				syntax: "literal"
+			}
+		}
+		state: {
+			common:   false
+			required: false
+			description: """
+				Keeps state across events, such as counters or the last value seen for a key, which
+				the program accesses with the `get_state`, `set_state`, `incr_state` and
+				`delete_state` functions. Without this option, these functions aren't available.
+				"""
+			type: object: {
+				examples: []
+				options: {
+					shared_as: {
+						common:   false
+						required: false
+						description: """
+							The name the state is shared as. All `remap` transforms using the same name
+							share their state, and must use the same `max_keys` and `ttl_secs`. The state
+							is kept when the transforms are reloaded, so these can't be changed by a reload
+							whilst the state is in use, and is dropped once no transform uses it. Without a
+							name, the state is only available to this transform.
+							"""
+						type: string: {
+							default: null
+							examples: ["sessions"]
+						}
+					}
+					max_keys: {
+						common:      false
+						required:    false
+						description: "The maximum number of keys kept. Beyond this, the least recently used keys are dropped."
+						type: uint: {
+							default: 10000
+							unit:    null
+						}
+					}
+					ttl_secs: {
+						common:   false
+						required: false
+						description: """
+							How long keys are kept after they were last set, unless the function setting
+							them is given a `ttl_secs`. Incrementing a key with `incr_state` doesn't extend
+							its TTL, so counts are kept for fixed windows.
+							"""
+						type: uint: {
+							default: null
+							examples: [3600]
+							unit: "seconds"
+						}
+					}
+				}
+			}
+		}
 	}
 
 	input: {
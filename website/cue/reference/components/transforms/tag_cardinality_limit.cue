@@ -37,6 +37,19 @@ components: transforms: tag_cardinality_limit: {
 	}
 
 	configuration: {
+		allowed_tags: {
+			common:      false
+			description: "Tag keys whose values are never limited. These tags are always passed through unchanged."
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["host", "region"]
+					syntax: "literal"
+				}
+			}
+		}
 		cache_size_per_tag: {
 			common:        false
 			description:   "The size of the cache in bytes to use to detect duplicate tags. The bigger the cache the less likely it is to have a 'false positive' or a case where we allow a new value for tag even after we have reached the configured limits."
@@ -48,6 +61,19 @@ components: transforms: tag_cardinality_limit: {
 				unit:    "bytes"
 			}
 		}
+		denied_tags: {
+			common:      false
+			description: "Tag keys whose values are always rejected, as if their limit had been exceeded."
+			required:    false
+			warnings: []
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["request_id"]
+					syntax: "literal"
+				}
+			}
+		}
 		limit_exceeded_action: {
 			common:      true
 			description: "Controls what should happen when a metric comes in with a tag that would exceed the configured limit on cardinality."
@@ -57,7 +83,8 @@ components: transforms: tag_cardinality_limit: {
 				default: "drop_tag"
 				enum: {
 					drop_tag:   "Remove tags that would exceed the configured limit from the incoming metric"
-					drop_event: "Drop any metric events that contain tags that would exceed the configured limit"
+					drop_event:    "Drop any metric events that contain tags that would exceed the configured limit"
+					replace_value: "Replace the values of tags that would exceed the configured limit with `replacement_value`"
 				}
 				syntax: "literal"
 			}
@@ -74,9 +101,48 @@ components: transforms: tag_cardinality_limit: {
 				syntax: "literal"
 			}
 		}
+		per_metric: {
+			common:      false
+			description: "Whether to track the distinct values of each key separately for every metric name, rather than across all metrics."
+			required:    false
+			warnings: []
+			type: bool: default: false
+		}
+		per_tag_limits: {
+			common:      false
+			description: "Overrides `value_limit` for individual tag keys."
+			required:    false
+			warnings: []
+			type: object: {
+				examples: [
+					{
+						user_id: 100
+					},
+				]
+				options: {
+					"*": {
+						description: "The number of distinct values to accept for the key."
+						required:    true
+						warnings: []
+						type: uint: unit: null
+					}
+				}
+			}
+		}
+		replacement_value: {
+			common:        false
+			description:   "The value that rejected tag values are replaced with. This value is always accepted and does not count towards the limit."
+			relevant_when: "limit_exceeded_action = \"replace_value\""
+			required:      false
+			warnings: []
+			type: string: {
+				default: "__other__"
+				syntax:  "literal"
+			}
+		}
 		value_limit: {
 			common:      true
-			description: "How many distinct values to accept for any given key."
+			description: "How many distinct values to accept for any given key, unless overridden in `per_tag_limits`."
 			required:    false
 			warnings: []
 			type: uint: {
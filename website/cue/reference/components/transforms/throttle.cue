@@ -0,0 +1,103 @@
+package metadata
+
+components: transforms: throttle: {
+	title: "Throttle"
+
+	description: """
+		Rate limits events, allowing a number of events per key in each time window and
+		discarding the rest.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      true
+	}
+
+	features: {
+		filter: {}
+	}
+
+	support: {
+		targets: {
+			"aarch64-unknown-linux-gnu":      true
+			"aarch64-unknown-linux-musl":     true
+			"armv7-unknown-linux-gnueabihf":  true
+			"armv7-unknown-linux-musleabihf": true
+			"x86_64-apple-darwin":            true
+			"x86_64-pc-windows-msv":          true
+			"x86_64-unknown-linux-gnu":       true
+			"x86_64-unknown-linux-musl":      true
+		}
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		exclude: {
+			common: true
+			description: """
+				The set of logical conditions to exclude events from throttling. Excluded events are
+				always passed, and don't count towards the threshold.
+				"""
+			required: false
+			warnings: []
+			type: string: {
+				default: null
+				examples: [
+					#".status_code != 200 && !includes(["info", "debug"], .severity)"#,
+				]
+				syntax: "remap_boolean_expression"
+			}
+		}
+		key_field: {
+			common: false
+			description: """
+				The key events are throttled by, so each key is allowed `threshold` events in each
+				window. If left unspecified, all events are throttled together.
+				"""
+			required: false
+			warnings: []
+			type: string: {
+				default: null
+				examples: ["{{ service }}", "{{ hostname }}"]
+				syntax: "template"
+			}
+		}
+		threshold: {
+			description: """
+				The number of events allowed for each key in each window. Further events are
+				discarded until the window ends.
+				"""
+			required: true
+			warnings: []
+			type: uint: {
+				examples: [100, 10000]
+				unit: null
+			}
+		}
+		window_secs: {
+			description: """
+				The length of the time windows. Each key's window starts with the first event
+				seen for the key.
+				"""
+			required: true
+			warnings: []
+			type: uint: {
+				examples: [1, 60]
+				unit: "seconds"
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	telemetry: metrics: {
+		events_discarded_total: components.sources.internal_metrics.output.metrics.events_discarded_total
+	}
+}
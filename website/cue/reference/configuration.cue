@@ -23,6 +23,21 @@ configuration: {
 			}
 		}
 
+		enrichment_tables_reload_interval_secs: {
+			common: false
+			description: """
+				How often enrichment tables are checked for changes to their source, such as
+				the file of a `file` table, and reloaded if they changed.
+				"""
+			required: false
+			warnings: []
+			type: uint: {
+				default: 10
+				examples: [10, 300]
+				unit: "seconds"
+			}
+		}
+
 		log_schema: {
 			common: false
 			description: """
@@ -0,0 +1,26 @@
+package metadata
+
+remap: errors: "112": {
+	title:       "Function closure arity mismatch"
+	description: """
+		A [function closure](\(urls.vrl_expressions)#closures) defines a different number of variables than the
+		function calls it with.
+		"""
+	resolution: """
+		Define the variables the function documents for its closure. Variables you don't use can be prefixed with
+		`_`.
+		"""
+
+	examples: [
+		{
+			"title": title
+			source: #"""
+				filter(.tags) -> |value| { value != "debug" }
+				"""#
+			diff: #"""
+				-filter(.tags) -> |value| { value != "debug" }
+				+filter(.tags) -> |_index, value| { value != "debug" }
+				"""#
+		},
+	]
+}
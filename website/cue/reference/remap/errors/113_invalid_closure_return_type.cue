@@ -0,0 +1,26 @@
+package metadata
+
+remap: errors: "113": {
+	title:       "Invalid function closure return type"
+	description: """
+		A [function closure](\(urls.vrl_expressions)#closures) resolves to a type the function doesn't accept.
+		"""
+	rationale:   remap._fail_safe_blurb
+	resolution:  """
+		Make the closure resolve to the type the function documents, for example by using the appropriate
+		[type](\(urls.vrl_functions)/#type-functions) or [coercion](\(urls.vrl_functions)/#coerce-functions) function.
+		"""
+
+	examples: [
+		{
+			"title": title
+			source: #"""
+				filter(.tags) -> |_index, value| { value }
+				"""#
+			diff: #"""
+				-filter(.tags) -> |_index, value| { value }
+				+filter(.tags) -> |_index, value| { to_bool(value) ?? false }
+				"""#
+		},
+	]
+}
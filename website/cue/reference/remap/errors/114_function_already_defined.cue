@@ -0,0 +1,25 @@
+package metadata
+
+remap: errors: "114": {
+	title:       "Function already defined"
+	description: """
+		A [function definition](\(urls.vrl_expressions)#function-definition) uses the name of a built-in function,
+		or of a function defined before it.
+		"""
+	resolution: """
+		Give the function a name that isn't used by any other function.
+		"""
+
+	examples: [
+		{
+			"title": title
+			source: #"""
+				fn upcase(value) { "custom" }
+				"""#
+			diff: #"""
+				-fn upcase(value) { "custom" }
+				+fn custom_upcase(value) { "custom" }
+				"""#
+		},
+	]
+}
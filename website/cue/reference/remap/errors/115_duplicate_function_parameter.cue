@@ -0,0 +1,24 @@
+package metadata
+
+remap: errors: "115": {
+	title:       "Duplicate function parameter"
+	description: """
+		A [function definition](\(urls.vrl_expressions)#function-definition) names more than one parameter the same.
+		"""
+	resolution: """
+		Give each parameter of the function a different name.
+		"""
+
+	examples: [
+		{
+			"title": title
+			source: #"""
+				fn join_pair(value, value) { value }
+				"""#
+			diff: #"""
+				-fn join_pair(value, value) { value }
+				+fn join_pair(left, right) { left }
+				"""#
+		},
+	]
+}
@@ -0,0 +1,25 @@
+package metadata
+
+remap: errors: "116": {
+	title:       "Module not found"
+	description: """
+		An [import](\(urls.vrl_expressions)#import) names a module that can't be found or read in any of the
+		configured module paths.
+		"""
+	resolution: """
+		Correct the name of the module, or add the directory that holds it to the module paths.
+		"""
+
+	examples: [
+		{
+			"title": "\(title) (typo)"
+			source: #"""
+				import "strngs"
+				"""#
+			diff: #"""
+				-import "strngs"
+				+import "strings"
+				"""#
+		},
+	]
+}
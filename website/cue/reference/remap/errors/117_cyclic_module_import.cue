@@ -0,0 +1,26 @@
+package metadata
+
+remap: errors: "117": {
+	title:       "Cyclic module import"
+	description: """
+		A module [imports](\(urls.vrl_expressions)#import) a module that, directly or through other modules, imports
+		it back.
+		"""
+	resolution: """
+		Move the functions both modules need into a separate module that they both import.
+		"""
+
+	examples: [
+		{
+			"title": title
+			source: #"""
+				# a.vrl
+				import "b"
+				"""#
+			diff: #"""
+				-import "b"
+				+import "shared"
+				"""#
+		},
+	]
+}
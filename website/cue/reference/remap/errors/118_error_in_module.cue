@@ -0,0 +1,26 @@
+package metadata
+
+remap: errors: "118": {
+	title:       "Error in module"
+	description: """
+		An [imported](\(urls.vrl_expressions)#import) module fails to compile. The error lists each error found in
+		the module, with the line and column it's found at.
+		"""
+	resolution: """
+		Fix the errors in the module. Compiling the module on its own shows the full diagnostics.
+		"""
+
+	examples: [
+		{
+			"title": title
+			source: #"""
+				# strings.vrl
+				fn shout(value) { upcase(vaule) }
+				"""#
+			diff: #"""
+				-fn shout(value) { upcase(vaule) }
+				+fn shout(value) { upcase(value) }
+				"""#
+		},
+	]
+}
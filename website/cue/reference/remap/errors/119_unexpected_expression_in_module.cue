@@ -0,0 +1,26 @@
+package metadata
+
+remap: errors: "119": {
+	title:       "Unexpected expression in module"
+	description: """
+		An [imported](\(urls.vrl_expressions)#import) module contains an expression other than a function definition
+		or an import. Modules only define functions, they can't run expressions of their own.
+		"""
+	resolution: """
+		Move the expression into a function, or into the program importing the module.
+		"""
+
+	examples: [
+		{
+			"title": title
+			source: #"""
+				# tags.vrl
+				.tagged = true
+				"""#
+			diff: #"""
+				-.tagged = true
+				+fn tag() { .tagged = true }
+				"""#
+		},
+	]
+}
@@ -0,0 +1,25 @@
+package metadata
+
+remap: errors: "120": {
+	title:       "Function closure missing"
+	description: """
+		A [function call expression](\(urls.vrl_expressions)#closures) doesn't pass a closure to a function that
+		requires one.
+		"""
+	resolution: """
+		Add a closure to the function call, as documented by the function.
+		"""
+
+	examples: [
+		{
+			"title": title
+			source: #"""
+				map_values(.tags)
+				"""#
+			diff: #"""
+				-map_values(.tags)
+				+map_values(.tags) -> |value| { upcase!(value) }
+				"""#
+		},
+	]
+}
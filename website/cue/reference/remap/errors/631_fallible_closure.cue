@@ -0,0 +1,30 @@
+package metadata
+
+remap: errors: "631": {
+	title: "Fallible closure"
+	description: """
+		You've passed a fallible [closure](\(urls.vrl_expressions)#closures) to a function.
+		"""
+
+	rationale: """
+		In VRL, closures that you pass to functions need to be infallible themselves. Otherwise, the function could
+		fail halfway through its elements.
+		"""
+
+	resolution: """
+		Make the closure infallible, by handling its errors using `??` or via some other method.
+		"""
+
+	examples: [
+		{
+			"title": "\(title)"
+			source: #"""
+				map_values(.tags) -> |value| { upcase(value) }
+				"""#
+			diff: #"""
+				-map_values(.tags) -> |value| { upcase(value) }
+				+map_values(.tags) -> |value| { upcase(value) ?? value }
+				"""#
+		},
+	]
+}
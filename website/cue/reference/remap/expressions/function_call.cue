@@ -15,7 +15,7 @@ remap: expressions: function_call: {
 
 	grammar: {
 		source: """
-			function ~ abort? ~ "(" ~ arguments? ~ ")"
+			function ~ abort? ~ "(" ~ arguments? ~ ")" ~ closure?
 			"""
 		definitions: {
 			function: {
@@ -95,6 +95,49 @@ remap: expressions: function_call: {
 					}
 				}
 			}
+			closure: {
+				description: """
+					The `closure` is a block of expressions that some functions, such as `map_values` or `filter`,
+					run for each element of the array or object they are given. It is preceded by `->` and the
+					comma-delimited closure variables, surrounded by `|`:
+
+					```vrl
+					map_values(.tags) -> |value| { upcase(value) ?? value }
+					```
+
+					The function documents which variables its closure takes, such as the key (or index) and
+					value of each element, and the type the closure has to resolve to.
+					"""
+
+				characteristics: {
+					variables: {
+						title: "Closure variables"
+						description: """
+							The closure variables are only defined inside the closure. Any variable of the same name
+							outside of the closure keeps its value once the closure has run.
+							"""
+					}
+					type_safety: {
+						title:       "Closure type safety"
+						description: """
+							The types of the closure variables are known from the argument the function iterates over,
+							and the closure is type checked like any other argument. A closure that can fail is
+							rejected at compile time, so errors must be handled inside the closure:
+
+							```vrl
+							map_values(.values) -> |value| { to_int(value) ?? 0 }
+							```
+							"""
+					}
+					termination: {
+						title:       "Termination"
+						description: """
+							Closures only run once for each element of the value passed to the function, so
+							programs using them are still guaranteed to terminate.
+							"""
+					}
+				}
+			}
 		}
 	}
 
@@ -120,5 +163,12 @@ remap: expressions: function_call: {
 				"""#
 			return: ["hello", "world!"]
 		},
+		{
+			title: "Function invocation with a closure"
+			source: #"""
+				filter([1, 2, 3]) -> |_index, value| { value != 2 }
+				"""#
+			return: [1, 3]
+		},
 	]
 }
@@ -0,0 +1,92 @@
+package metadata
+
+remap: expressions: function_definition: {
+	title:       "Function definition"
+	description: """
+		A _function definition_ expression defines a function that can be called like the
+		[built-in functions](\(urls.vrl_functions)), anywhere after its definition.
+		"""
+	return: """
+		Does not return a value, function definitions can only appear at the root of the program.
+
+		Calling the function returns the value its block resolves to. If the block can fail, calls to the function
+		are fallible, and the error must be [handled](\(urls.vrl_errors_reference)).
+		"""
+
+	grammar: {
+		source: """
+			"fn" ~ function ~ "(" ~ parameters? ~ ")" ~ block
+			"""
+		definitions: {
+			function: {
+				description: """
+					`function` represents the name of the function. It can't be the name of a built-in function, or
+					of a function defined before it.
+					"""
+			}
+			parameters: {
+				description: """
+					The `parameters` are the comma-delimited names of the function arguments. Arguments can be given
+					positionally, or by naming the parameter, like those of built-in functions.
+					"""
+			}
+			block: {
+				description: """
+					The `block` holds the expressions the function runs when called.
+					"""
+
+				characteristics: {
+					variables: {
+						title: "Function variables"
+						description: """
+							The block only sees the function parameters and the variables it assigns. The variables of
+							the caller are not in scope, and are left untouched by the call.
+							"""
+					}
+					type_safety: {
+						title:       "Function type safety"
+						description: """
+							The block is type checked once, where the function is defined. As the function can be
+							called with any value, nothing is known about the type of its parameters, or about the
+							event:
+
+							```vrl
+							fn shout(value) {
+								upcase(string(value) ?? "")
+							}
+							```
+
+							Calling a function that modifies the event drops what is known about the event at that
+							point of the program.
+							"""
+					}
+					termination: {
+						title:       "Termination"
+						description: """
+							A function can only call the functions defined before it, so it can't call itself, and
+							programs using functions are still guaranteed to terminate.
+							"""
+					}
+				}
+			}
+		}
+	}
+
+	examples: [
+		{
+			title: "Define and call a function"
+			input: log: {
+				first: "Jane"
+				last:  "Doe"
+			}
+			source: #"""
+				fn full_name(first, last) {
+					first + " " + last
+				}
+
+				.name = full_name(string!(.first), string!(.last))
+				"""#
+			return: "Jane Doe"
+		},
+	]
+}
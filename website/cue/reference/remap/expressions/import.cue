@@ -0,0 +1,58 @@
+package metadata
+
+remap: expressions: import: {
+	title:       "Import"
+	description: """
+		An _import_ expression makes the functions defined in a module available to the program. A module is a VRL
+		file that only contains [function definitions](#function-definition) and imports of other modules.
+		"""
+	return: """
+		Does not return a value, imports can only appear at the root of the program or of a module.
+		"""
+
+	grammar: {
+		source: """
+			"import" ~ module
+			"""
+		definitions: {
+			module: {
+				description: """
+					`module` is a string literal naming the module file. The `.vrl` extension can be left out. The
+					file is looked up in the configured module paths, in order, such as the `module_paths` option
+					of the [`remap` transform](\(urls.vector_remap_transform)).
+					"""
+
+				characteristics: {
+					once: {
+						title: "Imported once"
+						description: """
+							A module is only compiled once, however many times it's imported by the program or other
+							modules. Modules can't import each other in a cycle.
+							"""
+					}
+					compilation: {
+						title:       "Compile-time errors"
+						description: """
+							Modules are compiled with the program, so a module that can't be found or contains an
+							error makes the program fail to compile, with the location of the error in the module.
+							"""
+					}
+				}
+			}
+		}
+	}
+
+	examples: [
+		{
+			title: "Import a module"
+			input: log: message: "  Hello World  "
+			source: #"""
+				# strings.vrl: fn normalize(value) { downcase(strip_whitespace(value)) }
+				import "strings"
+
+				.message = normalize(.message) ?? .message
+				"""#
+			return: "hello world"
+		},
+	]
+}
@@ -12,7 +12,7 @@ remap: expressions: path: {
 
 	grammar: {
 		source: """
-			"." ~ path_segments
+			("." | "%") ~ path_segments
 			"""
 		definitions: {
 			"\".\"": {
@@ -21,6 +21,25 @@ remap: expressions: path: {
 					character, and `.` alone is a valid path.
 					"""
 			}
+			"\"%\"": {
+				description: """
+					The `"%"` character represents the root of the event metadata, such as the context a source
+					attaches to the event. Metadata travels with the event without being part of it, so it isn't
+					sent by sinks. It can be used to carry transient data, like routing information, between
+					transforms:
+
+					```vrl
+					%route = "archive"
+					```
+
+					Metadata isn't encoded with the event, so it's lost when the event goes through a disk buffer or
+					is sent to another Vector instance by the `vector` sink. It's only available to the components that
+					follow in the same Vector instance, as long as they use memory buffers.
+
+					A metadata path must have at least one segment. Nothing is known about the type of metadata
+					values at compile time.
+					"""
+			}
 			path_segments: {
 				description: """
 					`path_segments` denote a segment of a nested path. Each segment must be delimited by a `.` character
@@ -26,7 +26,7 @@ remap: {
 		examples?: [remap.#Example, ...remap.#Example]
 	}
 
-	#FunctionCategory: "Array" | "Codec" | "Coerce" | "Convert" | "Debug" | "Enumerate" | "Path" | "Hash" | "IP" | "Number" | "Object" | "Parse" | "Random" | "String" | "System" | "Timestamp" | "Type"
+	#FunctionCategory: "Array" | "Codec" | "Coerce" | "Convert" | "Cryptography" | "Debug" | "Enrichment" | "Enumerate" | "Path" | "Hash" | "IP" | "Number" | "Object" | "Parse" | "Random" | "State" | "String" | "System" | "Timestamp" | "Type"
 
 	// A helper array for generating docs. At some point, we should generate this from the
 	// #FunctionCategory enum if CUE adds support for that.
@@ -35,7 +35,9 @@ remap: {
 		"Codec",
 		"Coerce",
 		"Convert",
+		"Cryptography",
 		"Debug",
+		"Enrichment",
 		"Enumerate",
 		"Path",
 		"Hash",
@@ -44,6 +46,7 @@ remap: {
 		"Object",
 		"Parse",
 		"Random",
+		"State",
 		"String",
 		"System",
 		"Timestamp",
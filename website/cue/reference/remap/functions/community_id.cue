@@ -0,0 +1,82 @@
+package metadata
+
+remap: functions: community_id: {
+	category: "IP"
+	description: """
+		Calculates the [Community ID](\(urls.community_id)) of a network flow, a hash of its endpoints and protocol
+		that is the same for both directions of the flow. Tools such as Zeek and Suricata add it to their logs, so
+		it can be used to correlate their events with other flow records.
+		"""
+
+	arguments: [
+		{
+			name:        "source_ip"
+			description: "The IP address of the source of the flow."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "destination_ip"
+			description: "The IP address of the destination of the flow."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "protocol"
+			description: "The IP protocol number of the flow, such as `6` for TCP or `17` for UDP."
+			required:    true
+			type: ["integer"]
+		},
+		{
+			name:        "source_port"
+			description: "The source port of the flow. For ICMP and ICMPv6, this is the message type."
+			required:    false
+			type: ["integer"]
+		},
+		{
+			name:        "destination_port"
+			description: "The destination port of the flow. For ICMP and ICMPv6, this is the message code."
+			required:    false
+			type: ["integer"]
+		},
+		{
+			name:        "seed"
+			description: "The seed to calculate the hash with, which must be the same for flows to be correlated."
+			required:    false
+			default:     0
+			type: ["integer"]
+		},
+	]
+	internal_failure_reasons: [
+		"`source_ip` or `destination_ip` isn't a valid IP address",
+		"`source_ip` and `destination_ip` are of different IP versions",
+		"`protocol` isn't between 0 and 255",
+		"`source_port`, `destination_port` or `seed` isn't between 0 and 65535",
+		"only one of `source_port` and `destination_port` is given",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Calculate the Community ID of a TCP flow"
+			source: #"""
+				community_id!(source_ip: "128.232.110.120", destination_ip: "66.35.250.204", protocol: 6, source_port: 34855, destination_port: 80)
+				"""#
+			return: "1:LQU9qZlK+B5F3KDmev6m5PMibrg="
+		},
+		{
+			title: "Calculate the Community ID of the reverse flow"
+			source: #"""
+				community_id!(source_ip: "66.35.250.204", destination_ip: "128.232.110.120", protocol: 6, source_port: 80, destination_port: 34855)
+				"""#
+			return: "1:LQU9qZlK+B5F3KDmev6m5PMibrg="
+		},
+		{
+			title: "Calculate the Community ID with a seed"
+			source: #"""
+				community_id!(source_ip: "128.232.110.120", destination_ip: "66.35.250.204", protocol: 6, source_port: 34855, destination_port: 80, seed: 1)
+				"""#
+			return: "1:3V71V58M3Ksw/yuFALMcW0LAHvc="
+		},
+	]
+}
@@ -0,0 +1,29 @@
+package metadata
+
+remap: functions: crc32: {
+	category: "Hash"
+	description: """
+		Calculates a [CRC-32](\(urls.crc)) checksum of the `value`.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to calculate the checksum for."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: []
+	return: types: ["integer"]
+
+	examples: [
+		{
+			title: "Calculate CRC-32 checksum"
+			source: #"""
+				crc32("foobar")
+				"""#
+			return: 2666930069
+		},
+	]
+}
@@ -0,0 +1,43 @@
+package metadata
+
+remap: functions: decode_gzip: {
+	category:    "Codec"
+	description: """
+		Decompresses the `value`, compressed with [Gzip](\(urls.gzip)).
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The Gzip data to decompress."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "max_size"
+			description: """
+				The maximum size, in bytes, of the decompressed data. Decompression stops and fails once the data
+				grows past it, which protects against small values that decompress to very large ones.
+				"""
+			required:    false
+			default:     10485760
+			type: ["integer"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't valid Gzip data.",
+		"The decompressed data is larger than `max_size`.",
+		"`max_size` is negative.",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Decompress Gzip data"
+			source: #"""
+				decode_gzip!(decode_base64!("H4sIAAAAAAAC/yvISU0sTlVISU3OT0lVyE0FAJsZ870QAAAA"))
+				"""#
+			return: "please decode me"
+		},
+	]
+}
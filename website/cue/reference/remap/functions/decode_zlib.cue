@@ -0,0 +1,43 @@
+package metadata
+
+remap: functions: decode_zlib: {
+	category:    "Codec"
+	description: """
+		Decompresses the `value`, compressed with [Zlib](\(urls.zlib)).
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The Zlib data to decompress."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "max_size"
+			description: """
+				The maximum size, in bytes, of the decompressed data. Decompression stops and fails once the data
+				grows past it, which protects against small values that decompress to very large ones.
+				"""
+			required:    false
+			default:     10485760
+			type: ["integer"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't valid Zlib data.",
+		"The decompressed data is larger than `max_size`.",
+		"`max_size` is negative.",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Decompress Zlib data"
+			source: #"""
+				decode_zlib!(decode_base64!("eJwryElNLE5VSElNzk9JVchNBQAz9AXx"))
+				"""#
+			return: "please decode me"
+		},
+	]
+}
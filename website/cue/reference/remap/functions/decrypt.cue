@@ -0,0 +1,83 @@
+package metadata
+
+remap: functions: decrypt: {
+	category: "Cryptography"
+	description: """
+		Decrypts the `ciphertext` with the given symmetric `algorithm`, `key` and `iv`.
+		"""
+	notices: [
+		"""
+			Keys can only be written in the program or read from environment variables with
+			[`get_env_var`](#get_env_var); there's no other way to pass them from the configuration. Avoid writing
+			them in the program.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "ciphertext"
+			description: "The binary data to decrypt."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "algorithm"
+			description: "The encryption algorithm to use."
+			enum: {
+				"AES-128-GCM":       "[AES](\(urls.aes)) with a 16-byte key, in [GCM](\(urls.gcm)) mode"
+				"AES-256-GCM":       "[AES](\(urls.aes)) with a 32-byte key, in [GCM](\(urls.gcm)) mode"
+				"AES-128-CBC-PKCS7": "[AES](\(urls.aes)) with a 16-byte key, in [CBC](\(urls.cbc)) mode with PKCS#7 padding"
+				"AES-192-CBC-PKCS7": "[AES](\(urls.aes)) with a 24-byte key, in [CBC](\(urls.cbc)) mode with PKCS#7 padding"
+				"AES-256-CBC-PKCS7": "[AES](\(urls.aes)) with a 32-byte key, in [CBC](\(urls.cbc)) mode with PKCS#7 padding"
+			}
+			required: true
+			type: ["string"]
+		},
+		{
+			name:        "key"
+			description: "The secret key. Its length must match the `algorithm`."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "iv"
+			description: "The initialization vector, 12 bytes long for GCM modes and 16 bytes long for CBC modes."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`key` isn't of the length required by `algorithm`.",
+		"`iv` isn't of the length required by `algorithm`.",
+		"`ciphertext` wasn't encrypted with the given `algorithm`, `key` and `iv`.",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Decrypt with AES-256 in CBC mode"
+			source: #"""
+				decrypt!(decode_base64!("J7OCxo4Ea3CMMbkQclCgtQ=="), "AES-256-CBC-PKCS7", key: "01234567890123456789012345678912", iv: "1234567890123456")
+				"""#
+			return: "data"
+		},
+		{
+			title: "Decrypt with AES-256 in GCM mode"
+			source: #"""
+				decrypt!(decode_base64!("tAutH6N8KnTNP3fy5ZKNPboN3Fw="), "AES-256-GCM", key: "01234567890123456789012345678912", iv: "123456789012")
+				"""#
+			return: "data"
+		},
+		{
+			title: "Decrypt a ciphertext stored with its IV"
+			input: log: {
+				iv:         "MTIzNDU2Nzg5MDEy"
+				ciphertext: "tAutH6N8KnTNP3fy5ZKNPboN3Fw="
+			}
+			source: #"""
+				decrypt!(decode_base64!(.ciphertext), "AES-256-GCM", key: "01234567890123456789012345678912", iv: decode_base64!(.iv))
+				"""#
+			return: "data"
+		},
+	]
+}
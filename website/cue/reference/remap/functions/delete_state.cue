@@ -0,0 +1,41 @@
+package metadata
+
+remap: functions: delete_state: {
+	category: "State"
+	description: """
+		Removes the value kept in the state of the transform under the `key`.
+		"""
+
+	arguments: [
+		{
+			name:        "key"
+			description: "The key of the value to remove."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: []
+	notices: [
+		"""
+			This function is only available to `remap` transforms with the `state` option set, and
+			accesses the state configured there.
+			""",
+	]
+	return: {
+		types: ["any"]
+		rules: [
+			"Returns the value that was removed, or `null` if no value was kept for the `key`.",
+		]
+	}
+
+	examples: [
+		{
+			title: "End the session of a user"
+			source: #"""
+				delete_state(.user)
+				"""#
+			return:    null
+			skip_test: true
+		},
+	]
+}
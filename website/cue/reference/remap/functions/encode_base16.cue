@@ -0,0 +1,29 @@
+package metadata
+
+remap: functions: encode_base16: {
+	category:    "Codec"
+	description: """
+		Encodes the `value` to [Base16](\(urls.base16)), as a lowercase hexadecimal string.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to encode."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: []
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Encode to Base16"
+			source: """
+				encode_base16("some string value")
+				"""
+			return: "736f6d6520737472696e672076616c7565"
+		},
+	]
+}
@@ -0,0 +1,52 @@
+package metadata
+
+remap: functions: encode_csv: {
+	category: "Codec"
+	description: """
+		Encodes the `value` as a single CSV formatted row.
+		"""
+	notices: [
+		"""
+			Fields are quoted only when necessary. Timestamps are encoded as RFC 3339 strings and `null` as an
+			empty field.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The fields of the row to encode."
+			required:    true
+			type: ["array"]
+		},
+		{
+			name:        "delimiter"
+			description: "The field delimiter to use when encoding. Must be a single-byte utf8 character."
+			required:    false
+			default:     ","
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"delimiter must be a single-byte utf8 character",
+		"`value` contains an array or object",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Encode a single CSV formatted row"
+			source: #"""
+				encode_csv!(["foo", 1, true, "foo \", bar"])
+				"""#
+			return: #"foo,1,true,"foo "", bar""#
+		},
+		{
+			title: "Encode a single CSV formatted row with a custom delimiter"
+			source: #"""
+				encode_csv!(["foo", "bar"], delimiter: "|")
+				"""#
+			return: "foo|bar"
+		},
+	]
+}
@@ -0,0 +1,55 @@
+package metadata
+
+remap: functions: encode_protobuf: {
+	category: "Codec"
+	description: """
+		Encodes the `value` as a [protocol buffers](\(urls.protobuf)) message of the given `message_type`,
+		using the message definitions of a descriptor set file.
+		"""
+	notices: [
+		"""
+			The descriptor set file is read when the program is compiled. It can be generated with
+			`protoc --include_imports --descriptor_set_out=<desc_file> <proto files>`.
+			""",
+		"""
+			Enum fields accept both names and integers. Fields set to `null` are left out of the message.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The object to encode."
+			required:    true
+			type: ["object"]
+		},
+		{
+			name:        "desc_file"
+			description: "The path of the descriptor set file containing the message definitions."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "message_type"
+			description: "The fully qualified name of the message type, including its package."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` contains a field that isn't defined in the `message_type`",
+		"`value` contains a field whose type doesn't match its definition",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Encode to a protocol buffers message"
+			source: #"""
+				encode_base64(encode_protobuf!({"name": "Someone", "id": 1234, "email": "someone@example.com"}, "resources/protobuf_descriptor_set.desc", "test_protobuf.Person"))
+				"""#
+			return:    "CgdTb21lb25lENIJGhNzb21lb25lQGV4YW1wbGUuY29t"
+			skip_test: true
+		},
+	]
+}
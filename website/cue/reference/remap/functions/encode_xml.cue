@@ -0,0 +1,64 @@
+package metadata
+
+remap: functions: encode_xml: {
+	category: "Codec"
+	description: """
+		Encodes the `value` to XML. This is the inverse of `parse_xml`: keys prefixed with `attr_prefix` are
+		encoded as attributes, the `text_key` key as the text of its element, and arrays as repeated elements.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The object to convert to an XML string."
+			required:    true
+			type: ["object"]
+		},
+		{
+			name: "root_name"
+			description: """
+				Name of the root element that wraps the `value`. If not set, the `value` must have a single key
+				that is used as the root element.
+				"""
+			required: false
+			type: ["string"]
+		},
+		{
+			name:        "attr_prefix"
+			description: "String prefix of the keys to encode as XML tag attributes."
+			required:    false
+			default:     "@"
+			type: ["string"]
+		},
+		{
+			name:        "text_key"
+			description: "Key name to encode as the text of its element."
+			required:    false
+			default:     "text"
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`root_name` isn't set and `value` doesn't have a single key",
+		"`value` contains a key that isn't a valid XML name",
+		"`value` contains a nested array, or an array or object as text or attribute value",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Encode to XML"
+			source: #"""
+				encode_xml!({"book": {"@category": "CHILDREN", "title": {"@lang": "en", "text": "Harry Potter"}, "year": 2005}})
+				"""#
+			return: #"<book category="CHILDREN"><title lang="en">Harry Potter</title><year>2005</year></book>"#
+		},
+		{
+			title: "Encode to XML with a root element"
+			source: #"""
+				encode_xml!({"item": ["a", "b"], "total": 2}, root_name: "list")
+				"""#
+			return: "<list><item>a</item><item>b</item><total>2</total></list>"
+		},
+	]
+}
@@ -0,0 +1,34 @@
+package metadata
+
+remap: functions: encode_yaml: {
+	category: "Codec"
+	description: """
+		Encodes the `value` to [YAML](\(urls.yaml)).
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The value to convert to a YAML string."
+			required:    true
+			type: ["any"]
+		},
+	]
+	internal_failure_reasons: []
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Encode to YAML"
+			source: #"""
+				.payload = encode_yaml({"field": "value", "list": [1, true]})
+				"""#
+			return: """
+				field: value
+				list:
+				  - 1
+				  - true
+				"""
+		},
+	]
+}
@@ -0,0 +1,37 @@
+package metadata
+
+remap: functions: encode_zlib: {
+	category:    "Codec"
+	description: """
+		Compresses the `value` with [Zlib](\(urls.zlib)).
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to compress."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "compression_level"
+			description: "The compression level, from `0` (no compression) to `9` (best compression). Defaults to `6`."
+			required:    false
+			type: ["integer"]
+		},
+	]
+	internal_failure_reasons: [
+		"`compression_level` isn't between `0` and `9`.",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Compress with Zlib"
+			source: #"""
+				encode_base64(encode_zlib("please encode me"))
+				"""#
+			return: "eJwryElNLE5VSM1Lzk9JVchNBQA0RQX7"
+		},
+	]
+}
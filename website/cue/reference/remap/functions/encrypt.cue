@@ -0,0 +1,84 @@
+package metadata
+
+remap: functions: encrypt: {
+	category: "Cryptography"
+	description: """
+		Encrypts the `plaintext` with the given symmetric `algorithm`, `key` and `iv`.
+		"""
+	notices: [
+		"""
+			Keys can only be written in the program or read from environment variables with
+			[`get_env_var`](#get_env_var); there's no other way to pass them from the configuration. Avoid writing
+			them in the program.
+			""",
+		"""
+			The `iv` must never be reused with the same key, otherwise the encryption can be broken. Generate a new
+			one for each value with [`random_bytes`](#random_bytes), and keep it with the ciphertext, for example in
+			another field, as it's needed to decrypt the value.
+			""",
+		"""
+			The ciphertext is binary data; use [`encode_base64`](#encode_base64) to turn it into printable text.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "plaintext"
+			description: "The string to encrypt."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "algorithm"
+			description: "The encryption algorithm to use."
+			enum: {
+				"AES-128-GCM":       "[AES](\(urls.aes)) with a 16-byte key, in [GCM](\(urls.gcm)) mode"
+				"AES-256-GCM":       "[AES](\(urls.aes)) with a 32-byte key, in [GCM](\(urls.gcm)) mode"
+				"AES-128-CBC-PKCS7": "[AES](\(urls.aes)) with a 16-byte key, in [CBC](\(urls.cbc)) mode with PKCS#7 padding"
+				"AES-192-CBC-PKCS7": "[AES](\(urls.aes)) with a 24-byte key, in [CBC](\(urls.cbc)) mode with PKCS#7 padding"
+				"AES-256-CBC-PKCS7": "[AES](\(urls.aes)) with a 32-byte key, in [CBC](\(urls.cbc)) mode with PKCS#7 padding"
+			}
+			required: true
+			type: ["string"]
+		},
+		{
+			name:        "key"
+			description: "The secret key. Its length must match the `algorithm`."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "iv"
+			description: "The initialization vector, 12 bytes long for GCM modes and 16 bytes long for CBC modes."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`key` isn't of the length required by `algorithm`.",
+		"`iv` isn't of the length required by `algorithm`.",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Encrypt with AES-256 in GCM mode"
+			source: #"""
+				iv = random_bytes!(12)
+				ciphertext = encrypt!("data", "AES-256-GCM", key: "01234567890123456789012345678912", iv: iv)
+				decrypt!(ciphertext, "AES-256-GCM", key: "01234567890123456789012345678912", iv: iv)
+				"""#
+			return: "data"
+		},
+		{
+			title: "Encrypt with AES-256 in CBC mode, keeping the IV with the ciphertext"
+			source: #"""
+				iv = random_bytes!(16)
+				.iv = encode_base64(iv)
+				.ciphertext = encode_base64(encrypt!("data", "AES-256-CBC-PKCS7", key: "01234567890123456789012345678912", iv: iv))
+				decrypt!(decode_base64!(.ciphertext), "AES-256-CBC-PKCS7", key: "01234567890123456789012345678912", iv: decode_base64!(.iv))
+				"""#
+			return: "data"
+		},
+	]
+}
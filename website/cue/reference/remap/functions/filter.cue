@@ -0,0 +1,43 @@
+package metadata
+
+remap: functions: filter: {
+	category: "Enumerate"
+	description: """
+		Filters the elements of the `value` using the given [closure](\(urls.vrl_expressions)#closures).
+
+		The closure is called with the key (or index) and value of each element, and has to resolve to a boolean.
+		Elements for which it resolves to `false` are removed.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The object or array to filter."
+			required:    true
+			type: ["array", "object"]
+		},
+	]
+	internal_failure_reasons: []
+	return: {
+		types: ["array", "object"]
+		rules: [
+			"The return type matches the `value` type.",
+		]
+	}
+	examples: [
+		{
+			title: "Filter an object"
+			source: #"""
+				filter({ "a": 1, "b": 2, "c": 3 }) -> |key, _value| { key != "b" }
+				"""#
+			return: {a: 1, c: 3}
+		},
+		{
+			title: "Filter an array"
+			source: #"""
+				filter([1, 2, 3, 4]) -> |_index, value| { value > 2 }
+				"""#
+			return: [3, 4]
+		},
+	]
+}
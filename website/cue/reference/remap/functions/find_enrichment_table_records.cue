@@ -0,0 +1,86 @@
+package metadata
+
+remap: functions: find_enrichment_table_records: {
+	category: "Enrichment"
+	description: """
+		Searches the enrichment table named by `table` for the rows matching every field of the
+		`condition` object, returning them as an array of objects.
+		"""
+	notices: [
+		"""
+			By default, the value of a field in `condition` must equal the value the table holds for
+			that field. The following object literals search for a range of values instead:
+
+			* `{ "from": <timestamp>, "to": <timestamp> }` matches the timestamps held by the table
+			  between `from` and `to`, inclusive.
+			* `{ "from": <number>, "to": <number> }` matches the numbers held by the table between `from`
+			  and `to`, inclusive.
+			* `{ "range_contains": <number> }` matches the ranges of numbers held by the table, such as
+			  `8000-8099` or `22`, that contain the number.
+			* `{ "cidr_contains": <ip> }` matches the networks in CIDR notation held by the table, such as
+			  `192.168.0.0/16`, that contain the IP address.
+			* `{ "prefix_of": <string> }` matches the strings held by the table that the string starts
+			  with, such as `+44` for `+442071234567`.
+			""",
+		"""
+			The kind of each condition is decided when the program is compiled, so the table can build
+			indexes for it in advance. Range conditions must be written as object literals in
+			`condition`: an object from a variable or a path, such as `{ "ip": .network }`, is
+			compared for equality like any other value.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "table"
+			description: "The name of the enrichment table to search."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "condition"
+			description: "The fields of the table to search, and the values or ranges they must match."
+			required:    true
+			type: ["object"]
+		},
+		{
+			name:        "select"
+			description: "The fields of the table to return, returning all of them if not given."
+			required:    false
+			type: ["array"]
+		},
+		{
+			name:        "case_sensitive"
+			description: "Whether strings are compared case sensitively."
+			required:    false
+			default:     true
+			type: ["boolean"]
+		},
+	]
+	internal_failure_reasons: [
+		"The table can't be searched",
+		"A `from` and `to` condition isn't two timestamps or two numbers",
+		"A `range_contains` condition isn't a number",
+		"A `cidr_contains` condition isn't a valid IP address",
+		"A `prefix_of` condition isn't a string",
+	]
+	return: types: ["array"]
+
+	examples: [
+		{
+			title: "Find the networks containing an IP address"
+			input: log: ip: "192.168.10.32"
+			source: #"""
+				find_enrichment_table_records!("networks", { "cidr": { "cidr_contains": .ip } })
+				"""#
+			return: [{"cidr": "192.168.0.0/16", "name": "office"}]
+		},
+		{
+			title: "Find the rows in a range of numbers"
+			source: #"""
+				find_enrichment_table_records!("ports", { "port": { "from": 8000, "to": 8099 } }, select: ["service"])
+				"""#
+			return: [{"service": "http-alt"}]
+		},
+	]
+}
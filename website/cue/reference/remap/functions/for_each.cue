@@ -0,0 +1,33 @@
+package metadata
+
+remap: functions: for_each: {
+	category: "Enumerate"
+	description: """
+		Runs the given [closure](\(urls.vrl_expressions)#closures) for each element of the `value`.
+
+		The closure is called with the key (or index) and value of each element. It can assign to variables defined
+		outside of it, and to the event.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The object or array to iterate over."
+			required:    true
+			type: ["array", "object"]
+		},
+	]
+	internal_failure_reasons: []
+	return: types: ["null"]
+	examples: [
+		{
+			title: "Sum the values of an object"
+			source: #"""
+				sum = 0
+				for_each({ "a": 1, "b": 2 }) -> |_key, value| { sum = sum + value }
+				sum
+				"""#
+			return: 3
+		},
+	]
+}
@@ -0,0 +1,60 @@
+package metadata
+
+remap: functions: get_enrichment_table_record: {
+	category: "Enrichment"
+	description: """
+		Searches the enrichment table named by `table` for the row matching every field of the
+		`condition` object, returning it as an object.
+		"""
+	notices: [
+		"""
+			The conditions are the same as for `find_enrichment_table_records`, including the range
+			conditions, which must be written as object literals in `condition`.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "table"
+			description: "The name of the enrichment table to search."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "condition"
+			description: "The fields of the table to search, and the values or ranges they must match."
+			required:    true
+			type: ["object"]
+		},
+		{
+			name:        "select"
+			description: "The fields of the table to return, returning all of them if not given."
+			required:    false
+			type: ["array"]
+		},
+		{
+			name:        "case_sensitive"
+			description: "Whether strings are compared case sensitively."
+			required:    false
+			default:     true
+			type: ["boolean"]
+		},
+	]
+	internal_failure_reasons: [
+		"No row, or more than one row, matches the condition",
+		"The table can't be searched",
+		"A range condition isn't of the right type",
+	]
+	return: types: ["object"]
+
+	examples: [
+		{
+			title: "Get the row for a port"
+			input: log: port: 22
+			source: #"""
+				get_enrichment_table_record!("ports", { "port": { "range_contains": .port } })
+				"""#
+			return: {"port": "22", "service": "ssh"}
+		},
+	]
+}
@@ -0,0 +1,45 @@
+package metadata
+
+remap: functions: get_state: {
+	category: "State"
+	description: """
+		Returns the value kept in the state of the transform for the `key`.
+		"""
+
+	arguments: [
+		{
+			name:        "key"
+			description: "The key the value is kept under."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: []
+	notices: [
+		"""
+			This function is only available to `remap` transforms with the `state` option set, and
+			accesses the state configured there.
+			""",
+	]
+	return: {
+		types: ["any"]
+		rules: [
+			"Returns `null` if no value is kept for the `key`, or it has expired.",
+		]
+	}
+
+	examples: [
+		{
+			title: "Get the last message of a host"
+			input: log: host: "web-1"
+			source: #"""
+				.previous_message = get_state(.host)
+				"""#
+			output: log: {
+				host:             "web-1"
+				previous_message: null
+			}
+			skip_test: true
+		},
+	]
+}
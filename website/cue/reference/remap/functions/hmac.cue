@@ -0,0 +1,62 @@
+package metadata
+
+remap: functions: hmac: {
+	category: "Cryptography"
+	description: """
+		Calculates an [HMAC](\(urls.hmac)) of the `value` using the given `key`, as a hexadecimal string.
+		"""
+	notices: [
+		"""
+			Avoid writing keys in the program, for example by reading them from the environment with
+			[`get_env_var`](#get_env_var) instead.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to calculate the HMAC for."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "key"
+			description: "The secret key."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "algorithm"
+			description: "The hash algorithm to use."
+			enum: {
+				"SHA1":    "SHA-1 algorithm"
+				"SHA-224": "SHA-224 algorithm"
+				"SHA-256": "SHA-256 algorithm"
+				"SHA-384": "SHA-384 algorithm"
+				"SHA-512": "SHA-512 algorithm"
+			}
+			required: false
+			default:  "SHA-256"
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: []
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Calculate HMAC"
+			source: #"""
+				hmac("The quick brown fox jumps over the lazy dog", "key")
+				"""#
+			return: "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+		},
+		{
+			title: "Calculate HMAC with SHA-1"
+			source: #"""
+				hmac("The quick brown fox jumps over the lazy dog", "key", algorithm: "SHA1")
+				"""#
+			return: "de7c9b85b8b78aa6bc8a7a36f70a90701c9db4d9"
+		},
+	]
+}
@@ -0,0 +1,59 @@
+package metadata
+
+remap: functions: incr_state: {
+	category: "State"
+	description: """
+		Adds `by` to the integer kept in the state of the transform under the `key`, and returns
+		the new value. Keys without a value start at zero.
+
+		The expiry of a key is set when it is first incremented, and isn't extended by
+		incrementing it again, so counts are kept for fixed windows.
+		"""
+
+	arguments: [
+		{
+			name:        "key"
+			description: "The key of the count."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "by"
+			description: "The amount to add to the count."
+			required:    false
+			default:     1
+			type: ["integer"]
+		},
+		{
+			name:        "ttl_secs"
+			description: "How long the count is kept for once started, instead of the `ttl_secs` of the state."
+			required:    false
+			type: ["integer"]
+		},
+	]
+	internal_failure_reasons: [
+		"The value kept under the `key` isn't an integer.",
+	]
+	notices: [
+		"""
+			This function is only available to `remap` transforms with the `state` option set, and
+			accesses the state configured there.
+			""",
+	]
+	return: types: ["integer"]
+
+	examples: [
+		{
+			title: "Count the errors of each host per minute"
+			input: log: host: "web-1"
+			source: #"""
+				.errors = incr_state!("errors." + .host, ttl_secs: 60)
+				"""#
+			output: log: {
+				host:   "web-1"
+				errors: 1
+			}
+			skip_test: true
+		},
+	]
+}
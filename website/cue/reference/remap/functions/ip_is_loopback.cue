@@ -0,0 +1,43 @@
+package metadata
+
+remap: functions: ip_is_loopback: {
+	category: "IP"
+	description: """
+		Determines whether the `value` is a loopback address, in the `127.0.0.0/8` IPv4 range or the `::1` IPv6 address.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The IP address to check."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a valid IP address",
+	]
+	return: {
+		types: ["boolean"]
+		rules: [
+			"Returns `false` for IPv4-mapped IPv6 addresses, such as `::ffff:127.0.0.1`.",
+		]
+	}
+
+	examples: [
+		{
+			title: "Loopback address"
+			source: #"""
+				ip_is_loopback!("127.0.0.1")
+				"""#
+			return: true
+		},
+		{
+			title: "Other address"
+			source: #"""
+				ip_is_loopback!("192.168.0.1")
+				"""#
+			return: false
+		},
+	]
+}
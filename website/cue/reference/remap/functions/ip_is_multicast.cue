@@ -0,0 +1,43 @@
+package metadata
+
+remap: functions: ip_is_multicast: {
+	category: "IP"
+	description: """
+		Determines whether the `value` is a multicast address, in the `224.0.0.0/4` IPv4 range or the `ff00::/8` IPv6 range.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The IP address to check."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a valid IP address",
+	]
+	return: {
+		types: ["boolean"]
+		rules: [
+			"Returns `false` for IPv4-mapped IPv6 addresses, such as `::ffff:224.0.0.1`.",
+		]
+	}
+
+	examples: [
+		{
+			title: "Multicast address"
+			source: #"""
+				ip_is_multicast!("224.0.0.251")
+				"""#
+			return: true
+		},
+		{
+			title: "Other address"
+			source: #"""
+				ip_is_multicast!("192.168.0.1")
+				"""#
+			return: false
+		},
+	]
+}
@@ -0,0 +1,43 @@
+package metadata
+
+remap: functions: ip_is_private: {
+	category: "IP"
+	description: """
+		Determines whether the `value` is a private address, in the `10.0.0.0/8`, `172.16.0.0/12` or `192.168.0.0/16` IPv4 ranges or the `fc00::/7` IPv6 unique local range.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The IP address to check."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a valid IP address",
+	]
+	return: {
+		types: ["boolean"]
+		rules: [
+			"Returns `true` for IPv6 unique local addresses, which are the IPv6 equivalent of private addresses.",
+		]
+	}
+
+	examples: [
+		{
+			title: "Private address"
+			source: #"""
+				ip_is_private!("192.168.10.32")
+				"""#
+			return: true
+		},
+		{
+			title: "Public address"
+			source: #"""
+				ip_is_private!("8.8.8.8")
+				"""#
+			return: false
+		},
+	]
+}
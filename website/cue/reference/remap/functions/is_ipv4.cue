@@ -0,0 +1,42 @@
+package metadata
+
+remap: functions: is_ipv4: {
+	category: "IP"
+	description: """
+		Check if the `value` is a string containing a valid IPv4 address or not.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: #"The value to check"#
+			required:    true
+			type: ["any"]
+		},
+	]
+	internal_failure_reasons: []
+	return: {
+		types: ["boolean"]
+		rules: [
+			#"Returns `true` if `value` is a valid IPv4 address."#,
+			#"Returns `false` if `value` is anything else."#,
+		]
+	}
+
+	examples: [
+		{
+			title: "Valid IPv4 address"
+			source: """
+				is_ipv4("192.168.0.1")
+				"""
+			return: true
+		},
+		{
+			title: "Other IP version"
+			source: """
+				is_ipv4("2001:db8::1")
+				"""
+			return: false
+		},
+	]
+}
@@ -0,0 +1,31 @@
+package metadata
+
+remap: functions: map_keys: {
+	category: "Enumerate"
+	description: """
+		Maps the keys of the `value` using the given [closure](\(urls.vrl_expressions)#closures).
+
+		The closure is called with each key, and has to resolve to a string, which replaces the key. If several keys
+		are mapped to the same key, the value of the last one in key order is kept.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The object to map the keys of."
+			required:    true
+			type: ["object"]
+		},
+	]
+	internal_failure_reasons: []
+	return: types: ["object"]
+	examples: [
+		{
+			title: "Upcase the keys of an object"
+			source: #"""
+				map_keys({ "a": 1, "b": 2 }) -> |key| { upcase(key) }
+				"""#
+			return: {A: 1, B: 2}
+		},
+	]
+}
@@ -0,0 +1,43 @@
+package metadata
+
+remap: functions: map_values: {
+	category: "Enumerate"
+	description: """
+		Maps the values of the elements of the `value` using the given [closure](\(urls.vrl_expressions)#closures).
+
+		The closure is called with the value of each element, and the value it resolves to replaces the element's
+		value.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The object or array to map the values of."
+			required:    true
+			type: ["array", "object"]
+		},
+	]
+	internal_failure_reasons: []
+	return: {
+		types: ["array", "object"]
+		rules: [
+			"The return type matches the `value` type.",
+		]
+	}
+	examples: [
+		{
+			title: "Map the values of an object"
+			source: #"""
+				map_values({ "a": 1, "b": 2 }) -> |value| { value * 10 }
+				"""#
+			return: {a: 10, b: 20}
+		},
+		{
+			title: "Map the values of an array"
+			source: #"""
+				map_values(["foo", "bar"]) -> |value| { upcase(value) }
+				"""#
+			return: ["FOO", "BAR"]
+		},
+	]
+}
@@ -0,0 +1,74 @@
+package metadata
+
+remap: functions: parse_cef: {
+	category:    "Parse"
+	description: """
+		Parses the `value` in the [Common Event Format](\(urls.cef)) (CEF), optionally preceded by a syslog header.
+		The fields of the header and of the extension are returned as strings, with their escaping removed.
+		"""
+	notices: [
+		"""
+			The fields of the header are named `cefVersion`, `deviceVendor`, `deviceProduct`, `deviceVersion`,
+			`deviceEventClassId`, `name` and `severity`; the fields of the extension keep their keys.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to parse."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "translate_custom_fields"
+			description: "Rename the custom fields, such as `cs1`, to the value of their label, such as `cs1Label`, and remove the label."
+			required:    false
+			default:     false
+			type: ["boolean"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` doesn't contain a `CEF:` prefix",
+		"`value` is missing header fields",
+	]
+	return: types: ["object"]
+
+	examples: [
+		{
+			title: "Parse CEF"
+			source: #"""
+				parse_cef!(s'CEF:0|Security|threatmanager|1.0|100|worm successfully stopped|10|src=10.0.0.1 dst=2.1.2.2 spt=1232 msg=Detected a threat. No action needed.')
+				"""#
+			return: {
+				cefVersion:         "0"
+				deviceEventClassId: "100"
+				deviceProduct:      "threatmanager"
+				deviceVendor:       "Security"
+				deviceVersion:      "1.0"
+				dst:                "2.1.2.2"
+				msg:                "Detected a threat. No action needed."
+				name:               "worm successfully stopped"
+				severity:           "10"
+				spt:                "1232"
+				src:                "10.0.0.1"
+			}
+		},
+		{
+			title: "Parse CEF with custom fields"
+			source: #"""
+				parse_cef!(s'CEF:0|Dev|firewall|2.1|4000|Blocked|5|cs1Label=policy cs1=default deny', translate_custom_fields: true)
+				"""#
+			return: {
+				cefVersion:         "0"
+				deviceEventClassId: "4000"
+				deviceProduct:      "firewall"
+				deviceVendor:       "Dev"
+				deviceVersion:      "2.1"
+				name:               "Blocked"
+				policy:             "default deny"
+				severity:           "5"
+			}
+		},
+	]
+}
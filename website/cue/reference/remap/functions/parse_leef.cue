@@ -0,0 +1,70 @@
+package metadata
+
+remap: functions: parse_leef: {
+	category:    "Parse"
+	description: """
+		Parses the `value` in the [Log Event Extended Format](\(urls.leef)) (LEEF), version 1.0 or 2.0, optionally
+		preceded by a syslog header. The fields of the header and the event attributes are returned as strings.
+		"""
+	notices: [
+		"""
+			The fields of the header are named `leefVersion`, `vendor`, `productName`, `productVersion` and `eventId`;
+			the event attributes keep their keys.
+			""",
+		"""
+			The event attributes are separated by tabs, or by the delimiter given in the header for LEEF 2.0,
+			either as a character or as its hexadecimal code such as `x5E`.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to parse."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` doesn't contain a `LEEF:` prefix",
+		"`value` is missing header fields",
+		"the delimiter of a LEEF 2.0 header isn't a character or a hexadecimal character code",
+	]
+	return: types: ["object"]
+
+	examples: [
+		{
+			title: "Parse LEEF 1.0"
+			source: #"""
+				parse_leef!("LEEF:1.0|Microsoft|MSExchange|4.0 SP1|15345|src=192.0.2.0\tdst=172.50.123.1\tsev=5\tcat=anomaly")
+				"""#
+			return: {
+				cat:            "anomaly"
+				dst:            "172.50.123.1"
+				eventId:        "15345"
+				leefVersion:    "1.0"
+				productName:    "MSExchange"
+				productVersion: "4.0 SP1"
+				sev:            "5"
+				src:            "192.0.2.0"
+				vendor:         "Microsoft"
+			}
+		},
+		{
+			title: "Parse LEEF 2.0 with a custom delimiter"
+			source: #"""
+				parse_leef!("LEEF:2.0|Lancope|StealthWatch|1.0|41|^|src=10.0.1.8^dst=10.0.0.5^proto=6")
+				"""#
+			return: {
+				dst:            "10.0.0.5"
+				eventId:        "41"
+				leefVersion:    "2.0"
+				productName:    "StealthWatch"
+				productVersion: "1.0"
+				proto:          "6"
+				src:            "10.0.1.8"
+				vendor:         "Lancope"
+			}
+		},
+	]
+}
@@ -0,0 +1,70 @@
+package metadata
+
+remap: functions: parse_port_name: {
+	category: "Parse"
+	description: """
+		Parses the port number of a well-known [service name](\(urls.iana_service_names)), such as `https` or
+		`ssh`, as used in `/etc/services`.
+		"""
+	notices: [
+		"""
+			Only a curated subset of the IANA registry is known, covering common services. Names are
+			those assigned by the registry, so aliases such as `dns` or `rdp` aren't known; use
+			`domain` or `ms-wbt-server` instead.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The service name or port number to parse."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "protocol"
+			description: "The transport protocol of the service."
+			enum: {
+				"tcp": "The TCP port of the service."
+				"udp": "The UDP port of the service."
+			}
+			required: false
+			default:  "tcp"
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a known service name for the `protocol` or a port number",
+	]
+	return: {
+		types: ["integer"]
+		rules: [
+			"Service names are matched case-insensitively.",
+			"Port numbers are returned as is, so that fields containing either can be parsed.",
+		]
+	}
+
+	examples: [
+		{
+			title: "Parse a service name"
+			source: #"""
+				parse_port_name!("https")
+				"""#
+			return: 443
+		},
+		{
+			title: "Parse a UDP service name"
+			source: #"""
+				parse_port_name!("ntp", protocol: "udp")
+				"""#
+			return: 123
+		},
+		{
+			title: "Parse a port number"
+			source: #"""
+				parse_port_name!("8443")
+				"""#
+			return: 8443
+		},
+	]
+}
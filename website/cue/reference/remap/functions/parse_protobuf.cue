@@ -0,0 +1,59 @@
+package metadata
+
+remap: functions: parse_protobuf: {
+	category: "Parse"
+	description: """
+		Parses the `value` as a [protocol buffers](\(urls.protobuf)) message of the given `message_type`,
+		using the message definitions of a descriptor set file.
+		"""
+	notices: [
+		"""
+			The descriptor set file is read when the program is compiled. It can be generated with
+			`protoc --include_imports --descriptor_set_out=<desc_file> <proto files>`.
+			""",
+		"""
+			Enum values are returned as their names, or as integers if they are unknown. Map fields are
+			returned as objects, and unknown fields are ignored.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The binary protocol buffers message to parse."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "desc_file"
+			description: "The path of the descriptor set file containing the message definitions."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "message_type"
+			description: "The fully qualified name of the message type, including its package."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a valid protocol buffers message of the `message_type`",
+	]
+	return: types: ["object"]
+
+	examples: [
+		{
+			title: "Parse a protocol buffers message"
+			source: #"""
+				parse_protobuf!(decode_base64!("CgdTb21lb25lENIJGhNzb21lb25lQGV4YW1wbGUuY29t"), "resources/protobuf_descriptor_set.desc", "test_protobuf.Person")
+				"""#
+			return: {
+				email: "someone@example.com"
+				id:    1234
+				name:  "Someone"
+			}
+			skip_test: true
+		},
+	]
+}
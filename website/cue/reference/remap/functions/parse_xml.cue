@@ -67,6 +67,16 @@ remap: functions: parse_xml: {
 			default:     true
 			type: ["boolean"]
 		},
+		{
+			name:        "windows_event"
+			description: """
+				Parse the `value` as a [Windows event](\(urls.windows_event_schema)), keying the `Data` elements
+				of its `EventData` by their `Name` attribute.
+				"""
+			required: false
+			default:  false
+			type: ["boolean"]
+		},
 	]
 	internal_failure_reasons: [
 		"`value` isn't a valid XML document",
@@ -93,5 +103,28 @@ remap: functions: parse_xml: {
 				}
 			}
 		},
+		{
+			title: "Parse Windows event XML"
+			source: #"""
+				value = s'<Event xmlns="http://schemas.microsoft.com/win/2004/08/events/event"><System><Provider Name="Microsoft-Windows-Security-Auditing"/><EventID>4624</EventID><Channel>Security</Channel></System><EventData><Data Name="TargetUserName">bob</Data><Data Name="LogonType">3</Data></EventData></Event>';
+
+				parse_xml!(value, windows_event: true)
+				"""#
+			return: {
+				"Event": {
+					"EventData": {
+						"LogonType":      3
+						"TargetUserName": "bob"
+					}
+					"System": {
+						"Channel": "Security"
+						"EventID": 4624
+						"Provider": {
+							"@Name": "Microsoft-Windows-Security-Auditing"
+						}
+					}
+				}
+			}
+		},
 	]
 }
@@ -0,0 +1,40 @@
+package metadata
+
+remap: functions: parse_yaml: {
+	category: "Parse"
+	description: """
+		Parses the `value` as [YAML](\(urls.yaml)).
+		"""
+	notices: [
+		"""
+			Streams containing more than one YAML document aren't supported.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string representation of the YAML to parse."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a valid YAML formatted payload",
+		"`value` contains more than one YAML document",
+	]
+	return: types: ["boolean", "integer", "float", "string", "object", "array", "null"]
+
+	examples: [
+		{
+			title: "Parse YAML"
+			source: #"""
+				parse_yaml!("field: value\nlist:\n  - 1\n  - true")
+				"""#
+			return: {
+				field: "value"
+				list: [1, true]
+			}
+		},
+	]
+}
@@ -0,0 +1,32 @@
+package metadata
+
+remap: functions: random_bytes: {
+	category: "Random"
+	description: """
+		Generates `length` cryptographically secure random bytes, such as the initialization vector of
+		[`encrypt`](#encrypt).
+		"""
+
+	arguments: [
+		{
+			name:        "length"
+			description: "The number of bytes to generate, up to 65536."
+			required:    true
+			type: ["integer"]
+		},
+	]
+	internal_failure_reasons: [
+		"`length` is negative or larger than 65536.",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Generate an initialization vector"
+			source: #"""
+				length(random_bytes!(16))
+				"""#
+			return: 16
+		},
+	]
+}
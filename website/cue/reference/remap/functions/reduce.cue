@@ -0,0 +1,52 @@
+package metadata
+
+remap: functions: reduce: {
+	category: "Enumerate"
+	description: """
+		Reduces the elements of the `value` to a single value using the given
+		[closure](\(urls.vrl_expressions)#closures).
+
+		The closure is called with the accumulated value, followed by the key (or index) and value of each element.
+		The accumulated value starts out as `initial`, and is replaced by the value the closure resolves to for each
+		element, which has to be of the same type as `initial`.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The object or array to reduce."
+			required:    true
+			type: ["array", "object"]
+		},
+		{
+			name:        "initial"
+			description: "The value to start accumulating from."
+			required:    true
+			type: ["any"]
+		},
+	]
+	internal_failure_reasons: []
+	return: {
+		types: ["any"]
+		rules: [
+			"The return type matches the `initial` type.",
+			"Returns `initial` if the `value` is empty.",
+		]
+	}
+	examples: [
+		{
+			title: "Sum an array"
+			source: #"""
+				reduce([1, 2, 3], 0) -> |sum, _index, value| { sum + value }
+				"""#
+			return: 6
+		},
+		{
+			title: "Join the keys and values of an object"
+			source: #"""
+				reduce({ "a": "1", "b": "2" }, "") -> |joined, key, value| { joined + key + value }
+				"""#
+			return: "a1b2"
+		},
+	]
+}
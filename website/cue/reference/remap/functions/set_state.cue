@@ -0,0 +1,49 @@
+package metadata
+
+remap: functions: set_state: {
+	category: "State"
+	description: """
+		Keeps the `value` in the state of the transform under the `key`, replacing the value kept
+		under it before.
+		"""
+
+	arguments: [
+		{
+			name:        "key"
+			description: "The key to keep the value under."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "value"
+			description: "The value to keep."
+			required:    true
+			type: ["any"]
+		},
+		{
+			name:        "ttl_secs"
+			description: "How long the value is kept for, instead of the `ttl_secs` of the state."
+			required:    false
+			type: ["integer"]
+		},
+	]
+	internal_failure_reasons: []
+	notices: [
+		"""
+			This function is only available to `remap` transforms with the `state` option set, and
+			accesses the state configured there.
+			""",
+	]
+	return: types: ["null"]
+
+	examples: [
+		{
+			title: "Keep the last message of a host"
+			source: #"""
+				set_state(.host, .message, ttl_secs: 3600)
+				"""#
+			return:    null
+			skip_test: true
+		},
+	]
+}
@@ -4,6 +4,7 @@ urls: {
 	azure_blob_storage:                                       "https://azure.microsoft.com/en-us/services/storage/blobs/"
 	affine_type_system:                                       "\(wikipedia)/wiki/Substructural_type_system#Affine_type_systems"
 	adaptive_request_concurrency_post:                        "/blog/adaptive-request-concurrency/"
+	aes:                                                      "\(wikipedia)/wiki/Advanced_Encryption_Standard"
 	amazon_linux:                                             "https://aws.amazon.com/amazon-linux-ami/"
 	ansi_escape_codes:                                        "\(wikipedia)/wiki/ANSI_escape_code"
 	apache:                                                   "https://httpd.apache.org"
@@ -93,7 +94,10 @@ urls: {
 	big_query_streaming:                                      "https://cloud.google.com/bigquery/streaming-data-into-bigquery"
 	bind_dnstap:                                              "https://kb.isc.org/docs/aa-01342"
 	b_tree_map:                                               "https://doc.rust-lang.org/std/collections/struct.BTreeMap.html"
+	base16:                                                   "https://tools.ietf.org/html/rfc4648#section-8"
 	cargo_audit:                                              "\(github)/RustSec/cargo-audit"
+	cbc:                                                      "\(wikipedia)/wiki/Block_cipher_mode_of_operation#Cipher_block_chaining_(CBC)"
+	cef:                                                      "https://www.microfocus.com/documentation/arcsight/arcsight-smartconnectors/pdfdoc/common-event-format-v25/common-event-format-v25.pdf"
 	centos:                                                   "https://www.centos.org/"
 	chrono_time_formats:                                      "https://docs.rs/chrono/latest/chrono/format/strftime/index.html#specifiers"
 	cgroups_limit_resources:                                  "https://the.binbashtheory.com/control-resources-cgroups/"
@@ -102,6 +106,7 @@ urls: {
 	cloudsmith:                                               "https://cloudsmith.io/~timber/repos/vector/packages/"
 	cloudsmith_apt:                                           "https://cloudsmith.io/~timber/repos/vector/setup/#formats-deb"
 	cloudsmith_yum:                                           "https://cloudsmith.io/~timber/repos/vector/setup/#formats-rpm"
+	community_id:                                             "\(github)/corelight/community-id-spec"
 	console:                                                  "\(wikipedia)/wiki/System_console"
 	conventional_commits:                                     "https://www.conventionalcommits.org"
 	contributing:                                             "\(vector_repo)/blob/master/CONTRIBUTING.md#setup"
@@ -184,6 +189,7 @@ urls: {
 	fluentbit:                                                "https://fluentbit.io/"
 	freebsd:                                                  "https://www.freebsd.org/"
 	gcp:                                                      "https://cloud.google.com"
+	gcm:                                                      "\(wikipedia)/wiki/Galois/Counter_Mode"
 	gcp_authentication:                                       "\(gcp)/docs/authentication/"
 	gcp_authentication_api_key:                               "\(gcp)/docs/authentication/api-keys"
 	gcp_authentication_server_to_server:                      "\(gcp)/docs/authentication/production"
@@ -222,6 +228,7 @@ urls: {
 	heroku:                                                   "https://www.heroku.com"
 	heroku_http_log_drain:                                    "https://devcenter.heroku.com/articles/log-drains#https-drains"
 	heroku_start:                                             "https://devcenter.heroku.com/start"
+	hmac:                                                     "\(wikipedia)/wiki/HMAC"
 	homebrew:                                                 "https://brew.sh/"
 	homebrew_services:                                        "\(github)/Homebrew/homebrew-services"
 	honeycomb:                                                "https://honeycomb.io"
@@ -235,6 +242,7 @@ urls: {
 	humio_hec:                                                "https://docs.humio.com/integrations/data-shippers/hec/"
 	humio_hec_format_of_data:                                 "https://docs.humio.com/integrations/data-shippers/hec/#format-of-data"
 	iam_instance_profile:                                     "\(aws_docs)/IAM/latest/UserGuide/id_roles_use_switch-role-ec2_instance-profiles.html"
+	iana_service_names:                                       "https://www.iana.org/assignments/service-names-port-numbers/service-names-port-numbers.xhtml"
 	iana_time_zone_format:                                    "\(wikipedia)/wiki/Tz_database#Names_of_time_zones"
 	iana_time_zones:                                          "\(wikipedia)/wiki/List_of_tz_database_time_zones"
 	ieee_754:                                                 "\(wikipedia)/wiki/IEEE_754"
@@ -274,6 +282,7 @@ urls: {
 	kubernetes_rbac:                                          "\(kubernetes)/docs/reference/access-authn-authz/rbac/"
 	kubernetes_request_verbs:                                 "\(kubernetes)/docs/reference/access-authn-authz/authorization/#determine-the-request-verb"
 	kubernetes_watch_api:                                     "\(kubernetes)/docs/reference/generated/kubernetes-api/v1.10/#watch-30"
+	leef:                                                     "https://www.ibm.com/docs/en/dsm?topic=leef-overview"
 	leveldb:                                                  "\(github)/google/leveldb"
 	leveldb_sys_2:                                            "https://crates.io/crates/leveldb-sys"
 	leveldb_sys_3:                                            "\(github)/vectordotdev/leveldb-sys/tree/v3.0.0"
@@ -600,9 +609,11 @@ urls: {
 	wasm_languages:                                           "\(github)/appcypher/awesome-wasm-langs"
 	wikipedia:                                                "https://en.wikipedia.org"
 	windows:                                                  "https://www.microsoft.com/en-us/windows"
+	windows_event_schema:                                     "https://docs.microsoft.com/en-us/windows/win32/wes/eventschema-schema"
 	windows_installer:                                        "\(wikipedia)/wiki/Windows_Installer"
 	windows_service:                                          "https://docs.microsoft.com/en-us/powershell/module/microsoft.powershell.management/new-service"
 	woothee:                                                  "https://github.com/woothee/woothee"
+	xxhash:                                                   "https://cyan4973.github.io/xxHash/"
 	yaml:                                                     "https://yaml.org/"
 	yum:                                                      "\(wikipedia)/wiki/Yum_(software)"
 	zlib:                                                     "https://www.zlib.net"